@@ -0,0 +1,54 @@
+//! Optional parts of the game a saved or written down match can depend on, like a rule that
+//! only some builds have. A match names the ones it uses, and a build lacking any of them
+//! says which instead of failing to read the match.
+
+use super::game::Rule;
+
+/// The optional features this build has, none yet as every rule is always built in
+const AVAILABLE: &[&str] = &[];
+
+/// Fails with the features the match uses that this build lacks
+pub fn check(used: &[String]) -> Result<(), String> {
+  let missing = used
+    .iter()
+    .filter(|feature| !AVAILABLE.contains(&feature.as_str()))
+    .cloned()
+    .collect::<Vec<_>>();
+  if missing.is_empty() {
+    Ok(())
+  } else {
+    Err(format!(
+      "the match needs features this build lacks: {}",
+      missing.join(", ")
+    ))
+  }
+}
+
+/// The rule of a match, which may be one only a build with more features has
+pub fn parse_rule(name: &str) -> Result<Rule, String> {
+  name.parse().map_err(|_| {
+    format!(
+      "the match is played by the {} rule, which this build lacks",
+      name
+    )
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_features_check() {
+    assert_eq!(check(&[]), Ok(()));
+    assert_eq!(
+      check(&["torpedoes".into(), "salvo".into()]),
+      Err("the match needs features this build lacks: torpedoes, salvo".into())
+    );
+    assert_eq!(parse_rule("fury"), Ok(Rule::Fury));
+    assert_eq!(
+      parse_rule("Torpedoes"),
+      Err("the match is played by the Torpedoes rule, which this build lacks".into())
+    );
+  }
+}
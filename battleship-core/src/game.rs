@@ -0,0 +1,6012 @@
+//! A match of battleship: both players' fleets and what each knows of the other's, the
+//! rules the shots are fired by and the computer player.
+
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fmt::{self, Display},
+  str::FromStr,
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use structopt::clap::arg_enum;
+use uuid::Uuid;
+
+use super::strategy::{BoardView, HuntTarget, Probability, Random, Strategy};
+
+pub const ROWS: usize = 10;
+pub const COLS: usize = 10;
+pub(crate) const POS_ADDITION: [i32; 5] = [-2, -1, 0, 1, 2];
+const ROTATIONS: [u16; 4] = [90, 180, 270, 360];
+/// Random places a ship is tried in before the whole fleet is searched for room
+const PLACEMENT_ATTEMPTS: usize = 1000;
+/// Places the search for room tries before it gives up on a fleet
+const SEARCH_STEPS: usize = 100_000;
+/// Cells away from a shot of an earlier turn that still count as firing in its region
+const DRIFT_REGION: usize = 2;
+/// Chance in percent a shot drifts for every turn in a row fired in its region before
+const DRIFT_STEP: usize = 20;
+const DRIFT_MAX: usize = 60;
+/// Mines every player hides among the empty cells of their board under the mines rule
+pub const MINES: usize = 2;
+/// Mines adrift in the waters of every player under the hazards rule
+const HAZARDS: usize = 3;
+/// Every how many rounds night falls under the night rule
+const NIGHT_EVERY: usize = 3;
+/// Turns the results of a night turn stay hidden for
+const NIGHT_DELAY: usize = 2;
+/// Hits an armored cell takes under the armored rule
+const ARMOR_HIT_POINTS: u8 = 2;
+/// What a turn is refused with when the answer to it is for another kind of turn
+const MISMATCHED_ANSWER: &str = "The answer doesn't match the turn";
+/// Cells a sonar reaches around the one it is used on, a 3x3 area
+const SONAR_RANGE: usize = 1;
+/// Turns the computer fires before it falls back on its sonar
+const BOT_SONAR_AFTER: usize = 3;
+/// Sonars every player gets for a match
+const SONARS: usize = 1;
+/// Ships a player sinks to earn an airstrike
+const AIRSTRIKE_SINKS: usize = 2;
+/// Turns between two strikes of a carrier, the one it strikes on included
+const STRIKE_COOLDOWN: usize = 5;
+/// Cells a strike reaches either way of the one it is aimed at, a 1x3 line
+const STRIKE_REACH: usize = 1;
+/// Turns between two pings of a submarine, the one it pings on included
+const PING_COOLDOWN: usize = 4;
+pub const POWER_UPS: [PowerUp; 6] = [
+  PowerUp::Sonar,
+  PowerUp::Airstrike(Line::Row),
+  PowerUp::Airstrike(Line::Column),
+  PowerUp::Strike(Line::Row),
+  PowerUp::Strike(Line::Column),
+  PowerUp::Ping,
+];
+
+pub type Coordinate = (usize, usize);
+type ShipShape = Vec<Vec<Status>>;
+pub type FiringResponse = BTreeMap<Coordinate, Status>;
+
+/// What the randomness of a match is drawn for. Each gets a stream of its own derived from
+/// the seed of the match, so drawing more or less from one, like a new taunt, leaves the
+/// fleets and the bot's shots of a seed as they were.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Stream {
+  Placement = 1,
+  Bot = 2,
+  /// taunts and anything else that doesn't change the outcome
+  Cosmetic = 3,
+}
+
+pub fn stream_rng(seed: u64, stream: Stream) -> StdRng {
+  // spread the stream over the whole seed so neighbouring seeds don't share streams
+  StdRng::seed_from_u64(seed ^ (stream as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+}
+
+arg_enum! {
+    /// How many shots a turn allows and what else is in play
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Rule {
+      Default, // single shots
+      Fury,    // not more than total number of ships alive
+      Charge,  // not more than number of killed ships + 1
+      Salvo,   // one shot for every ship alive, all of them fired at once
+      Chain,   // single shots, firing again after every hit until a miss
+      Drift,   // single shots, which may drift to a cell next door when fired near the last ones
+      Mines,   // single shots, and a shot on one of the other player's mines costs a ship cell
+      Critical, // single shots, and a shot on the center cell of a ship sinks all of it
+      Armored, // single shots, and the center cell of every ship takes two hits
+      Hazards, // single shots, and mines adrift hit the ships they drift onto
+      Night,   // single shots, whose results every third round stay hidden for two turns
+      Flagship, // single shots, and sinking the other side's hidden flagship wins at once
+    }
+}
+
+impl Rule {
+  /// How many shots a turn the rule allows, in words
+  pub fn shot_limit(&self) -> &'static str {
+    match self {
+      Rule::Fury => "a shot for every ship of yours afloat",
+      Rule::Charge => "a shot for every ship you sank, plus one",
+      Rule::Salvo => "a shot for every ship of yours afloat, all at once",
+      Rule::Chain => "one shot, and another after every hit",
+      _ => "one shot a turn",
+    }
+  }
+}
+
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum First {
+        Random, // a coin toss before every match
+        Me,     // the user fires first
+        Bot,    // the computer fires first
+    }
+}
+
+impl First {
+  /// The index of the player who fires first, tossing the coin for a random start
+  pub fn player<R: Rng>(&self, rng: &mut R) -> usize {
+    match self {
+      First::Random => rng.gen_range(0..2),
+      First::Me => 0,
+      First::Bot => 1,
+    }
+  }
+}
+
+arg_enum! {
+    /// How the computer picks its shots
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Difficulty {
+        Easy, // computer generates random shots without previous ones
+        Hard, // computer generates shots based on analysis of hit/miss  data
+        Expert, // computer fires where the ships left most likely are
+    }
+}
+
+impl Difficulty {
+  /// The strategy the computer picks its shots by at the difficulty
+  pub fn strategy(&self) -> Box<dyn Strategy> {
+    match self {
+      Difficulty::Easy => Box::new(Random),
+      Difficulty::Hard => Box::new(HuntTarget),
+      Difficulty::Expert => Box::new(Probability),
+    }
+  }
+}
+
+/// The ships both fleets are made of
+#[derive(PartialEq, Debug, Clone)]
+pub enum FleetType {
+  /// the X, V, H and I shapes
+  Standard,
+  /// straight ships of 5, 4, 3, 3 and 2 cells
+  Classic,
+  /// ships of the user's own shapes
+  Custom(Vec<ShipType>),
+}
+
+impl FromStr for FleetType {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "standard" => Ok(FleetType::Standard),
+      "classic" => Ok(FleetType::Classic),
+      _ => Err(format!("unknown fleet {}", s)),
+    }
+  }
+}
+
+impl FleetType {
+  /// The fleets that can be picked by name
+  pub fn variants() -> [&'static str; 2] {
+    ["Standard", "Classic"]
+  }
+
+  /// A fleet of the given ships, as long as they leave room to place them at random
+  pub fn custom(ships: Vec<ShipType>) -> Result<Self, String> {
+    if ships.is_empty() {
+      return Err("a fleet needs at least one ship".into());
+    }
+    // every ship is placed with its whole outline clear
+    let area = ships
+      .iter()
+      .map(|s| {
+        let (rows, cols) = s.size(ROTATIONS[0]);
+        rows * cols
+      })
+      .sum::<usize>();
+    if area > ROWS * COLS / 2 {
+      return Err(format!(
+        "the ships take up {} cells with their outlines, more than half of the board",
+        area
+      ));
+    }
+    // the ships are placed one after the other, each clear of the ones before it
+    let positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    let (mut rng, mut steps) = (StdRng::seed_from_u64(0), SEARCH_STEPS);
+    if search_fleet(&ships, &positions, &mut rng, &mut steps).is_none() {
+      return Err("the ships can't all be placed on the board together".into());
+    }
+    Ok(FleetType::Custom(ships))
+  }
+
+  pub fn ships(&self) -> Vec<ShipType> {
+    match self {
+      FleetType::Standard => vec![ShipType::X, ShipType::V, ShipType::H, ShipType::I],
+      FleetType::Classic => vec![
+        ShipType::A,
+        ShipType::B,
+        ShipType::C,
+        ShipType::S,
+        ShipType::P,
+      ],
+      FleetType::Custom(ships) => ships.clone(),
+    }
+  }
+
+  /// Whether both are made of the same ships, in whatever order
+  pub fn matches(&self, other: &FleetType) -> bool {
+    let mut others = other.ships();
+    self.ships().len() == others.len()
+      && self
+        .ships()
+        .iter()
+        .all(|ship| match others.iter().position(|other| other == ship) {
+          Some(i) => {
+            others.remove(i);
+            true
+          }
+          None => false,
+        })
+  }
+
+  /// The type of fleet made up of the ships, known by its name when it has one
+  pub fn of_ships(ships: Vec<ShipType>) -> Self {
+    let custom = FleetType::Custom(ships);
+    vec![FleetType::Standard, FleetType::Classic]
+      .into_iter()
+      .find(|fleet_type| fleet_type.matches(&custom))
+      .unwrap_or(custom)
+  }
+
+  /// The type of fleet the ships belong to
+  pub fn of(placements: &[Placement]) -> Self {
+    let classic = FleetType::Classic.ships();
+    if placements
+      .iter()
+      .any(|p| matches!(p.ship_type, ShipType::Custom(_)))
+    {
+      FleetType::Custom(placements.iter().map(|p| p.ship_type.clone()).collect())
+    } else if placements.iter().any(|p| classic.contains(&p.ship_type)) {
+      FleetType::Classic
+    } else {
+      FleetType::Standard
+    }
+  }
+}
+
+/// Why a cell can't be fired upon
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum IllegalShot {
+  OutOfBounds,
+  Resolved(Status),
+  /// a cell of a ship sunk already, which nothing fired at it can change
+  Sunk,
+}
+
+impl Display for IllegalShot {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      IllegalShot::OutOfBounds => write!(f, "out of the board"),
+      IllegalShot::Resolved(status) => write!(f, "already a {}", status.describe()),
+      IllegalShot::Sunk => write!(f, "part of a ship sunk already"),
+    }
+  }
+}
+
+/// Why a salvo can't be fired
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SalvoError {
+  Empty,
+  /// more shots than the budget of the turn
+  OverBudget(usize),
+  /// fewer shots than the rule asks for in the turn
+  UnderBudget(usize),
+  /// the same cell requested more than once
+  Duplicate(Coordinate),
+  Illegal(Coordinate, IllegalShot),
+}
+
+impl Display for SalvoError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SalvoError::Empty => write!(f, "no shots selected"),
+      SalvoError::OverBudget(budget) => write!(f, "only {} shots allowed this turn", budget),
+      SalvoError::UnderBudget(budget) => write!(f, "all {} shots must be fired this turn", budget),
+      SalvoError::Duplicate(c) => write!(f, "{} is fired upon more than once", a1_notation(*c)),
+      SalvoError::Illegal(c, reason) => write!(f, "{} is {}", a1_notation(*c), reason),
+    }
+  }
+}
+
+/// Something notable that happened in the game, for the frontend to react to
+#[derive(PartialEq, Debug, Clone)]
+pub enum GameEvent {
+  ShipSunk {
+    by: usize,
+    ship_type: ShipType,
+  },
+  /// a ship sunk all at once by a shot on its center
+  CriticalSink {
+    by: usize,
+    ship_type: ShipType,
+  },
+  /// the flagship sunk under the flagship rule, which ends the match
+  FlagshipSunk {
+    by: usize,
+    ship_type: ShipType,
+  },
+}
+
+impl GameEvent {
+  /// The ship the event happened to
+  pub fn ship_type(&self) -> &ShipType {
+    match self {
+      GameEvent::ShipSunk { ship_type, .. }
+      | GameEvent::CriticalSink { ship_type, .. }
+      | GameEvent::FlagshipSunk { ship_type, .. } => ship_type,
+    }
+  }
+}
+
+/// A whole row or column of the board
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum Line {
+  Row,
+  Column,
+}
+
+impl Line {
+  pub fn name(&self) -> &'static str {
+    match self {
+      Line::Row => "row",
+      Line::Column => "column",
+    }
+  }
+
+  pub fn turned(&self) -> Self {
+    match self {
+      Line::Row => Line::Column,
+      Line::Column => Line::Row,
+    }
+  }
+
+  /// The cells of the line through the cell
+  pub fn cells(&self, (row, col): Coordinate) -> Vec<Coordinate> {
+    match self {
+      Line::Row => (0..COLS).map(|c| (row, c)).collect(),
+      Line::Column => (0..ROWS).map(|r| (r, col)).collect(),
+    }
+  }
+
+  /// The cells of the line within the reach of the cell, cut off at the edges
+  pub fn cells_near(&self, cell: Coordinate, reach: usize) -> Vec<Coordinate> {
+    self
+      .cells(cell)
+      .into_iter()
+      .filter(|c| c.0.abs_diff(cell.0) + c.1.abs_diff(cell.1) <= reach)
+      .collect()
+  }
+}
+
+/// Something a player may use once in a while in place of firing
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum PowerUp {
+  /// tells whether the 3x3 area around a cell holds any ship cells, without damaging them
+  Sonar,
+  /// fires upon every cell of the line through a cell at once, earned by sinking ships
+  Airstrike(Line),
+  /// fires upon a cell and its neighbours along the line, launched every few turns by a
+  /// ship with the strike ability while it is afloat
+  Strike(Line),
+  /// tells how far the nearest ship cell not hit yet is from a cell, sent out every few
+  /// turns by a ship with the ping ability while it is afloat
+  Ping,
+}
+
+impl PowerUp {
+  pub fn name(&self) -> &'static str {
+    match self {
+      PowerUp::Sonar => "sonar",
+      PowerUp::Airstrike(_) => "airstrike",
+      PowerUp::Strike(_) => "strike",
+      PowerUp::Ping => "ping",
+    }
+  }
+
+  /// The ability of a ship the power-up comes with, if any
+  pub fn ability(&self) -> Option<Ability> {
+    match self {
+      PowerUp::Strike(_) => Some(Ability::Strike),
+      PowerUp::Ping => Some(Ability::Ping),
+      PowerUp::Sonar | PowerUp::Airstrike(_) => None,
+    }
+  }
+
+  /// The cells the power-up covers when used on the cell
+  pub fn cells(&self, cell: Coordinate) -> Vec<Coordinate> {
+    match self {
+      PowerUp::Sonar => sonar_area(cell),
+      PowerUp::Airstrike(line) => line.cells(cell),
+      PowerUp::Strike(line) => line.cells_near(cell, STRIKE_REACH),
+      PowerUp::Ping => vec![cell],
+    }
+  }
+
+  /// The line the power-up is aimed along, if it is aimed along one
+  pub fn line(&self) -> Option<Line> {
+    match self {
+      PowerUp::Airstrike(line) | PowerUp::Strike(line) => Some(*line),
+      PowerUp::Sonar | PowerUp::Ping => None,
+    }
+  }
+
+  /// The power-up aimed along the other line
+  pub fn turned(&self) -> Self {
+    match self {
+      PowerUp::Airstrike(line) => PowerUp::Airstrike(line.turned()),
+      PowerUp::Strike(line) => PowerUp::Strike(line.turned()),
+      power_up => *power_up,
+    }
+  }
+
+  /// Whether both are the same power-up, whichever way they're aimed
+  pub fn is_like(&self, other: &PowerUp) -> bool {
+    std::mem::discriminant(self) == std::mem::discriminant(other)
+  }
+
+  /// Mark after the cell it was used on when a match is written down, `C4?`
+  fn mark(&self) -> char {
+    match self {
+      PowerUp::Sonar => '?',
+      PowerUp::Airstrike(Line::Row) => '-',
+      PowerUp::Airstrike(Line::Column) => '|',
+      PowerUp::Strike(Line::Row) => '_',
+      PowerUp::Strike(Line::Column) => ':',
+      PowerUp::Ping => '#',
+    }
+  }
+}
+
+impl Display for PowerUp {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PowerUp::Sonar => write!(f, "sonar"),
+      PowerUp::Airstrike(line) => write!(f, "airstrike-{}", line.name()),
+      PowerUp::Strike(line) => write!(f, "strike-{}", line.name()),
+      PowerUp::Ping => write!(f, "ping"),
+    }
+  }
+}
+
+impl FromStr for PowerUp {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    POWER_UPS
+      .iter()
+      .copied()
+      .find(|p| p.to_string() == s)
+      .ok_or_else(|| format!("unknown power-up {}", s))
+  }
+}
+
+/// Formats a power-up used on a cell as the cell followed by its mark, `C4?`
+pub fn write_power_up((power_up, cell): (PowerUp, Coordinate)) -> String {
+  format!("{}{}", a1_notation(cell), power_up.mark())
+}
+
+/// Reads a power-up used on a cell back from its notation, `C4?`
+pub fn parse_power_up(text: &str) -> Option<(PowerUp, Coordinate)> {
+  let mark = text.chars().last()?;
+  let power_up = POWER_UPS.iter().copied().find(|p| p.mark() == mark)?;
+  Some((power_up, parse_a1(&text[..text.len() - mark.len_utf8()])?))
+}
+
+/// What is left of a fleet, for rules and the computer to weigh their shots
+#[derive(PartialEq, Debug, Clone)]
+pub struct FleetView {
+  /// ships still afloat
+  pub alive: usize,
+  /// types of the ships sunk, in fleet order
+  pub sunk: Vec<ShipType>,
+  /// ship cells not hit yet
+  pub cells_remaining: usize,
+}
+
+impl FleetView {
+  pub fn size(&self) -> usize {
+    self.alive + self.sunk.len()
+  }
+}
+
+/// What a player does on their turn, as the side keeping the opponent's fleet is told
+#[derive(PartialEq, Debug, Clone)]
+pub enum Play {
+  Salvo(BTreeSet<Coordinate>),
+  PowerUp(PowerUp, Coordinate),
+}
+
+/// What a turn found on the fleet it was played upon, all the player needs to settle it
+/// without seeing the fleet
+#[derive(PartialEq, Debug, Clone)]
+pub enum Answer {
+  Salvo {
+    response: FiringResponse,
+    /// every ship the shots sank
+    sunk: Vec<ShipType>,
+    /// ships sunk all at once by a shot on their center
+    criticals: Vec<ShipType>,
+    /// the flagship, when the shots sank it
+    flagship: Option<ShipType>,
+    /// the last ship of the fleet went down
+    lost: bool,
+  },
+  /// whether the sonar picked up a ship
+  Sonar(bool),
+  /// how far the nearest ship cell not hit yet is, if any is left
+  Ping(Option<usize>),
+}
+
+/// Formats ships as their letters, `X,V`
+pub fn write_ships(ships: &[ShipType]) -> String {
+  ships
+    .iter()
+    .map(|s| s.to_string())
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Reads ships back from their letters, `X,V`
+pub fn parse_ships(text: &str) -> Result<Vec<ShipType>, String> {
+  text.split(',').map(str::parse).collect()
+}
+
+/// Formats the answer as the cells a salvo found followed by what it sank,
+/// `RESULT B7=. C3=x SUNK=V`, what a sonar found, `SONAR yes`, or how far a ping found the
+/// nearest ship, `PING 3` or `PING none`
+impl Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Answer::Salvo {
+        response,
+        sunk,
+        criticals,
+        flagship,
+        lost,
+      } => {
+        let mut tokens = response
+          .iter()
+          .map(|(c, status)| format!("{}={}", a1_notation(*c), status.as_char()))
+          .collect::<Vec<_>>();
+        if !sunk.is_empty() {
+          tokens.push(format!("SUNK={}", write_ships(sunk)));
+        }
+        if !criticals.is_empty() {
+          tokens.push(format!("CRIT={}", write_ships(criticals)));
+        }
+        if let Some(flagship) = flagship {
+          tokens.push(format!("FLAGSHIP={}", flagship));
+        }
+        if *lost {
+          tokens.push("LOST".into());
+        }
+        write!(f, "RESULT {}", tokens.join(" "))
+      }
+      Answer::Sonar(found) => write!(f, "SONAR {}", if *found { "yes" } else { "no" }),
+      Answer::Ping(Some(distance)) => write!(f, "PING {}", distance),
+      Answer::Ping(None) => write!(f, "PING none"),
+    }
+  }
+}
+
+impl FromStr for Answer {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (kind, rest) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+    match (kind, rest) {
+      ("RESULT", _) => parse_result(rest),
+      ("SONAR", "yes") => Ok(Answer::Sonar(true)),
+      ("SONAR", "no") => Ok(Answer::Sonar(false)),
+      ("PING", "none") => Ok(Answer::Ping(None)),
+      ("PING", _) => rest
+        .parse()
+        .map(|distance| Answer::Ping(Some(distance)))
+        .map_err(|_| format!("invalid ping {}", rest)),
+      _ => Err(format!("invalid answer {}", s.trim())),
+    }
+  }
+}
+
+/// The answer to a salvo, the cells it found followed by what it sank
+fn parse_result(rest: &str) -> Result<Answer, String> {
+  let (mut response, mut sunk, mut criticals, mut flagship, mut lost) =
+    (FiringResponse::new(), vec![], vec![], None, false);
+  for token in rest.split_whitespace() {
+    let invalid = || format!("invalid result {}", token);
+    match token.split_once('=') {
+      Some(("SUNK", ships)) => sunk = parse_ships(ships)?,
+      Some(("CRIT", ships)) => criticals = parse_ships(ships)?,
+      Some(("FLAGSHIP", ship)) => flagship = Some(ship.parse()?),
+      None if token == "LOST" => lost = true,
+      Some((c, status)) => {
+        let c = parse_a1(c).ok_or_else(invalid)?;
+        let status = status.chars().next().ok_or_else(invalid)?;
+        response.insert(c, Status::from_char(status)?);
+      }
+      None => return Err(invalid()),
+    }
+  }
+  Ok(Answer::Salvo {
+    response,
+    sunk,
+    criticals,
+    flagship,
+    lost,
+  })
+}
+
+/// A turn as it was played, recorded in order on the game timeline
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Turn {
+  pub player: usize,
+  pub shots: BTreeSet<Coordinate>,
+  pub response: FiringResponse,
+  /// cells of the opponent's fleet the turn changed
+  pub fleet_changes: Vec<CellChange>,
+  /// cells of the player's view of the opponent the turn changed
+  pub view_changes: Vec<CellChange>,
+  /// shots that landed on another cell than the one they were aimed at, aimed and landed
+  pub drifts: Vec<(Coordinate, Coordinate)>,
+  /// cells of the player's own fleet blown up by the mines the turn fired upon
+  pub blasts: FiringResponse,
+  /// ships sunk all at once by a shot on their center
+  pub criticals: Vec<ShipType>,
+  /// the power-up used in place of firing and the cell it was used on, the shots are those
+  /// an airstrike fired
+  pub power_up: Option<(PowerUp, Coordinate)>,
+  /// how far from the cell pinged the nearest ship cell not hit yet was
+  pub ping: Option<usize>,
+  /// what the turn found as told to the player, which settles it again where the fleet it
+  /// was played upon is kept elsewhere
+  pub answer: Option<Answer>,
+}
+
+impl Turn {
+  /// The play the turn was taken with
+  pub fn play(&self) -> Play {
+    match self.power_up {
+      Some((power_up, cell)) => Play::PowerUp(power_up, cell),
+      None => Play::Salvo(self.shots.clone()),
+    }
+  }
+
+  /// The cells the shots landed on, where they were aimed unless they drifted
+  pub fn landed(&self) -> BTreeSet<Coordinate> {
+    self
+      .shots
+      .iter()
+      .map(|shot| {
+        self
+          .drifts
+          .iter()
+          .find(|(aimed, _)| aimed == shot)
+          .map_or(*shot, |(_, landed)| *landed)
+      })
+      .collect()
+  }
+}
+
+/// A cell whose status was changed by a turn
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct CellChange {
+  pub coordinate: Coordinate,
+  pub from: Status,
+  pub to: Status,
+}
+
+impl Display for CellChange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} {} → {}",
+      a1_notation(self.coordinate),
+      self.from.describe(),
+      self.to.describe()
+    )
+  }
+}
+
+/// A match between the user, player 0, and the computer, player 1, from the fleets dealt
+/// to the winner
+#[derive(Clone)]
+pub struct Game {
+  pub rule: Rule,
+  difficulty: Difficulty,
+  fleet_type: FleetType,
+  players: [Player; 2],
+  winner: Option<usize>,
+  turn: usize,
+  /// the player who fired the first shot, or fires it
+  first: usize,
+  /// the player firing second gets a shot more in their first turn
+  balanced: bool,
+  timeline: Vec<Turn>,
+  events: Vec<GameEvent>,
+  /// the night turns whose results the shooter hasn't seen yet and the ships they sank
+  in_the_dark: Vec<(usize, Vec<ShipType>)>,
+  /// the computer never fires back, the user plays against the clock
+  solo: bool,
+  placement_rng: StdRng,
+  /// the seed the match was dealt from, unless it was rebuilt from elsewhere
+  seed: Option<u64>,
+  /// what the user wrote down about the match, kept with its save and notation
+  note: Option<String>,
+  bot_rng: StdRng,
+  cosmetic_rng: StdRng,
+}
+
+impl Game {
+  /// A game where the player of the index fires first
+  pub fn new(rule: Rule, difficulty: Difficulty, first: usize) -> Self {
+    let mut game = Self::seeded(rule, difficulty, FleetType::Standard, rand::random());
+    game.set_first(first);
+    game
+  }
+
+  /// A game whose fleets and bot play all follow from the seed
+  pub fn seeded(rule: Rule, difficulty: Difficulty, fleet_type: FleetType, seed: u64) -> Self {
+    let mut placement_rng = stream_rng(seed, Stream::Placement);
+    let mut game = Self {
+      turn: 0,
+      first: 0,
+      balanced: false,
+      winner: None,
+      players: [
+        Player::new(false, &fleet_type, &mut placement_rng),
+        Player::new(true, &fleet_type, &mut placement_rng),
+      ],
+      rule,
+      difficulty,
+      fleet_type,
+      timeline: vec![],
+      events: vec![],
+      in_the_dark: vec![],
+      solo: false,
+      placement_rng,
+      seed: Some(seed),
+      note: None,
+      bot_rng: stream_rng(seed, Stream::Bot),
+      cosmetic_rng: stream_rng(seed, Stream::Cosmetic),
+    };
+    game.rig_boards();
+    game
+  }
+
+  /// A game where only the user fires, to sink the computer's fleet in as few shots as possible
+  pub fn solo(rule: Rule, difficulty: Difficulty) -> Self {
+    Self {
+      solo: true,
+      ..Self::new(rule, difficulty, 0)
+    }
+  }
+
+  /// Deals the match again from the seed, keeping the rule, difficulty and mode
+  pub fn reseed(&mut self, seed: u64) {
+    let first = self.first;
+    *self = Self {
+      solo: self.solo,
+      balanced: self.balanced,
+      ..Self::seeded(self.rule, self.difficulty, self.fleet_type.clone(), seed)
+    };
+    self.set_first(first);
+  }
+
+  /// The match again from the first turn, with both fleets where they were and the
+  /// computer's shots drawn from the same seed, so it fires as it did as long as the user
+  /// does too
+  pub fn run_back(&self) -> Self {
+    let fleets = [
+      self.player().player_board().placements(),
+      self.computer().player_board().placements(),
+    ];
+    let game = match self.seed {
+      Some(seed) => Self::restore_seeded(self.rule, self.difficulty, seed, fleets, &[]),
+      None => Self::restore(self.rule, self.difficulty, fleets, &[]),
+    };
+    let mut game = Self {
+      solo: self.solo,
+      balanced: self.balanced,
+      ..game
+    }
+    .with_setup_of(self);
+    game.set_first(self.first);
+    game
+  }
+
+  /// The game with the flagships and mines the players of the other one picked, for a game
+  /// restored from its fleets before replaying its turns
+  pub fn with_setup_of(mut self, other: &Game) -> Self {
+    for (player, other) in self.players.iter_mut().zip(other.players.iter()) {
+      let board = player.player_board_mut();
+      board.flagship = other.player_board().flagship;
+      board.mines = other.player_board().mines.clone();
+    }
+    self
+  }
+
+  /// A match against a fleet of the type kept elsewhere, with the user's fleet or, for a
+  /// spectator, neither. The turns played upon a fleet kept elsewhere are settled by what
+  /// its side answers them with.
+  pub fn versus(
+    rule: Rule,
+    difficulty: Difficulty,
+    fleet: Option<&[Placement]>,
+    fleet_type: &FleetType,
+  ) -> Self {
+    let fleets = [fleet.map_or_else(Vec::new, <[Placement]>::to_vec), vec![]];
+    let mut game = Self::restore(rule, difficulty, fleets, &[]);
+    game.fleet_type = fleet_type.clone();
+    game.players[1].boards[0] = Board::unseen(fleet_type);
+    game.players[1].memory = BotMemory::new(fleet_type);
+    if fleet.is_none() {
+      game.players[0].boards[0] = Board::unseen(fleet_type);
+    }
+    game
+  }
+
+  /// The match played against fleets kept elsewhere, with both fleets in the open. Fails
+  /// unless the fleets are ones the match could be played with and every turn finds on
+  /// them what it was answered with. A match given up keeps its winner.
+  pub fn reveal(&self, fleets: [Vec<Placement>; 2]) -> Result<Self, String> {
+    for fleet in &fleets {
+      check_fleet(fleet)?;
+      if !FleetType::of(fleet).matches(&self.fleet_type) {
+        return Err("The fleet isn't made of the ships of the match".into());
+      }
+    }
+    let game = Self::restore(self.rule, self.difficulty, fleets, &self.timeline);
+    let found = |game: &Self| {
+      game
+        .timeline
+        .iter()
+        .map(|t| {
+          (
+            t.player,
+            t.shots.clone(),
+            t.response.clone(),
+            t.power_up,
+            t.ping,
+          )
+        })
+        .collect::<Vec<_>>()
+    };
+    if found(&game) != found(self) || game.winner.is_some() && game.winner != self.winner {
+      return Err("The fleet doesn't match what the turns found on it".into());
+    }
+    Ok(Self {
+      solo: self.solo,
+      winner: self.winner,
+      ..game
+    })
+  }
+
+  /// Deals both players a fleet of the type instead, from the same seed if there is one
+  pub fn set_fleet_type(&mut self, fleet_type: FleetType) {
+    self.fleet_type = fleet_type;
+    let seed = self.seed.unwrap_or_else(rand::random);
+    self.reseed(seed);
+  }
+
+  /// Rebuilds a game from the ship placements of both players by replaying the recorded turns
+  pub fn restore(
+    rule: Rule,
+    difficulty: Difficulty,
+    fleets: [Vec<Placement>; 2],
+    turns: &[Turn],
+  ) -> Self {
+    Self {
+      seed: None,
+      ..Self::new(rule, difficulty, 0)
+    }
+    .replay(fleets, turns)
+  }
+
+  /// Rebuilds a game dealt from the seed like `restore`, drawing the computer's shots again
+  /// along the way so it plays on as it would have
+  pub fn restore_seeded(
+    rule: Rule,
+    difficulty: Difficulty,
+    seed: u64,
+    fleets: [Vec<Placement>; 2],
+    turns: &[Turn],
+  ) -> Self {
+    let fleet_type = FleetType::of(&fleets[1]);
+    Self::seeded(rule, difficulty, fleet_type, seed).replay(fleets, turns)
+  }
+
+  fn replay(mut self, fleets: [Vec<Placement>; 2], turns: &[Turn]) -> Self {
+    let [player_fleet, computer_fleet] = fleets;
+    self.fleet_type = FleetType::of(&computer_fleet);
+    self.players = [
+      Player::with_fleet(false, &player_fleet),
+      Player::with_fleet(true, &computer_fleet),
+    ];
+    self.rig_boards();
+    self.replay_turns(turns)
+  }
+
+  /// Plays the recorded turns again on a game restored without them, once what the players
+  /// picked before the first shot, like their flagships, is set again
+  pub fn replay_turns(mut self, turns: &[Turn]) -> Self {
+    if let Some(turn) = turns.first() {
+      self.set_first(turn.player);
+    }
+    for turn in turns {
+      if self.is_won() {
+        break;
+      }
+      let bot = !self.is_user_turn();
+      if bot && self.seed.is_some() && turn.power_up.is_none() {
+        self.generate_bot_firing_coordinates();
+      }
+      if self.play(turn, bot).is_err() {
+        break;
+      }
+    }
+    self
+  }
+
+  /// Plays the recorded turn again, firing its shots or using its power-up, or going by
+  /// what it was answered with when the fleet it was played upon is kept elsewhere
+  pub fn play(&mut self, turn: &Turn, bot: bool) -> Result<String, String> {
+    if self.is_kept_elsewhere(1 - self.turn) {
+      let answer = turn
+        .answer
+        .clone()
+        .ok_or("The turn upon a fleet kept elsewhere has no answer")?;
+      return self.settle_turn(&turn.play(), answer);
+    }
+    match turn.power_up {
+      Some((power_up, cell)) => self.use_power_up(power_up, cell, bot),
+      None => Ok(self.fire(&turn.shots, bot)),
+    }
+  }
+
+  fn player_by_turn_mut(&mut self, turn: usize) -> &mut Player {
+    &mut self.players[turn]
+  }
+
+  fn generate_bot_firing_coordinates(&mut self) -> BTreeSet<Coordinate> {
+    let view = self.computer().opponent_board();
+    let mut legal_shots = self.legal_shots(1);
+    // cells the sonar found empty are a waste of a shot while there are others
+    let unswept = legal_shots
+      .iter()
+      .filter(|c| view.status_at(**c) != Status::Clear)
+      .copied()
+      .collect::<BTreeSet<_>>();
+    if !unswept.is_empty() {
+      legal_shots = unswept;
+    }
+    // never wait for more shots than there are cells left to fire upon, which is as many as
+    // a salvo has to fire
+    let number_of_shots = self.shot_budget(1).min(legal_shots.len());
+
+    let mut difficulty_strategy;
+    let Player {
+      boards,
+      memory,
+      strategy,
+      ..
+    } = &mut self.players[1];
+    let strategy = match strategy {
+      Some(strategy) => strategy.as_mut(),
+      None => {
+        difficulty_strategy = self.difficulty.strategy();
+        difficulty_strategy.as_mut()
+      }
+    };
+    // the random numbers are lent to the strategy while it picks, and put back after
+    let view = BoardView::new(
+      &boards[1],
+      memory,
+      &legal_shots,
+      self.rule,
+      self.bot_rng.clone(),
+    );
+    let mut shots = strategy.next_shots(&view, number_of_shots);
+    self.bot_rng = view.into_rng();
+    // whatever a strategy comes up with, only legal shots within the budget are fired
+    shots.retain(|c| legal_shots.contains(c));
+    while shots.len() > number_of_shots {
+      shots.pop_last();
+    }
+    shots
+  }
+
+  /// Has the computer pick its shots by the strategy from now on, in place of the one of
+  /// the difficulty
+  pub fn set_strategy(&mut self, strategy: Box<dyn Strategy>) {
+    self.players[1].strategy = Some(strategy);
+  }
+
+  pub fn fire(&mut self, shots: &BTreeSet<Coordinate>, bot: bool) -> String {
+    let volley = self.fire_upon(shots);
+    self.settle_salvo(shots, volley, bot)
+  }
+
+  /// Resolves the shots of the player whose turn it is on the opponent's fleet
+  fn fire_upon(&mut self, shots: &BTreeSet<Coordinate>) -> Volley {
+    let player_index = self.turn;
+    let mut modifiers = self.shot_modifiers(player_index);
+    let criticals = self.rule == Rule::Critical;
+    let opponent_board = self.players[1 - player_index].player_board_mut();
+    let alive = opponent_board
+      .ships_alive()
+      .iter()
+      .map(|s| s.id.clone())
+      .collect::<Vec<_>>();
+    let before = opponent_board.positions.clone();
+    let mut volley = opponent_board.take_fire(shots, &mut modifiers, criticals);
+    volley.set_off = opponent_board.set_off_hazards(&volley.response);
+    volley.fleet_changes = changes(&before, &opponent_board.positions);
+    volley.sunk = opponent_board
+      .ships
+      .iter()
+      .filter(|s| !s.alive && alive.contains(&s.id))
+      .map(|s| s.ship_type.clone())
+      .collect();
+    volley.flagship = opponent_board
+      .flagship()
+      .filter(|f| volley.lost && volley.sunk.contains(f))
+      .cloned();
+    volley
+  }
+
+  /// Takes what the side keeping the opponent's fleet answered the shots found into the
+  /// board standing in for it
+  fn learn_volley(
+    &mut self,
+    shots: &BTreeSet<Coordinate>,
+    answer: Answer,
+  ) -> Result<Volley, String> {
+    let (response, sunk, criticals, flagship, lost) = match answer {
+      Answer::Salvo {
+        response,
+        sunk,
+        criticals,
+        flagship,
+        lost,
+      } => (response, sunk, criticals, flagship, lost),
+      _ => return Err(MISMATCHED_ANSWER.into()),
+    };
+    if let Some(shot) = shots.iter().find(|c| !response.contains_key(c)) {
+      return Err(format!(
+        "The answer doesn't tell what the shot at {} found",
+        a1_notation(*shot)
+      ));
+    }
+    let board = self.players[1 - self.turn].player_board_mut();
+    let before = board.positions.clone();
+    board.learn(&response);
+    for ship_type in &sunk {
+      if !board.sink(ship_type) {
+        return Err(format!(
+          "The answer sinks a {} the fleet doesn't have afloat",
+          ship_type.name()
+        ));
+      }
+    }
+    Ok(Volley {
+      fleet_changes: changes(&before, &board.positions),
+      response,
+      criticals,
+      lost,
+      sunk,
+      flagship,
+      ..Default::default()
+    })
+  }
+
+  /// Plays out the player's side of the salvo once it was resolved on the opponent's fleet
+  fn settle_salvo(&mut self, shots: &BTreeSet<Coordinate>, volley: Volley, bot: bool) -> String {
+    let answer = volley.answer();
+    let night = self.is_night();
+    let player_index = self.turn;
+    let opponent_index = 1 - player_index;
+    let Volley {
+      response,
+      drifts,
+      criticals,
+      lost,
+      sunk,
+      flagship,
+      set_off,
+      fleet_changes,
+    } = volley;
+    let flagship_sunk = flagship.is_some();
+    let events = sunk.iter().map(|ship_type| {
+      let ship_type = ship_type.clone();
+      if flagship_sunk && flagship.as_ref() == Some(&ship_type) {
+        GameEvent::FlagshipSunk {
+          by: player_index,
+          ship_type,
+        }
+      } else if criticals.contains(&ship_type) {
+        GameEvent::CriticalSink {
+          by: player_index,
+          ship_type,
+        }
+      } else {
+        GameEvent::ShipSunk {
+          by: player_index,
+          ship_type,
+        }
+      }
+    });
+    // the night keeps the sinkings to itself along with the rest
+    if night {
+      self.in_the_dark.push((self.timeline.len(), sunk.clone()));
+    } else {
+      self.events.extend(events);
+    }
+
+    let player = self.player_by_turn_mut(player_index);
+    if player.is_bot && !night {
+      player.memory.learn(&response, &sunk);
+    }
+    let view = player.opponent_board_mut();
+    let before = view.positions.clone();
+    let mut message = if night {
+      view.learn(&shots.iter().map(|c| (*c, Status::Splash)).collect());
+      format!(
+        "Splash heard at {}. The night hides what {} found for {} turns.",
+        shots
+          .iter()
+          .map(|c| a1_notation(*c))
+          .collect::<Vec<_>>()
+          .join(", "),
+        if bot {
+          "the computer's shots"
+        } else {
+          "your shots"
+        },
+        NIGHT_DELAY
+      )
+    } else {
+      view.update_status(response.clone(), bot)
+    };
+    let mines = response.values().filter(|s| **s == Status::Mine).count();
+    for ship_type in &criticals {
+      message.push_str(&format!(
+        " A critical hit on the center sank the {} at once.",
+        ship_type.name()
+      ));
+    }
+    for (cell, _) in response.iter().filter(|(_, s)| **s == Status::Dent) {
+      message.push_str(&format!(
+        " The armor at {} took the hit, the next one gets through.",
+        a1_notation(*cell)
+      ));
+    }
+    for (aimed, landed) in &drifts {
+      message.push_str(&format!(
+        " {} shot at {} drifted to {}.",
+        if bot { "Computer's" } else { "Your" },
+        a1_notation(*aimed),
+        a1_notation(*landed)
+      ));
+    }
+    for cell in &set_off {
+      message.push_str(&format!(
+        " {} shot at {} set off a drifting mine.",
+        if bot { "Computer's" } else { "Your" },
+        a1_notation(*cell)
+      ));
+    }
+    let view_changes = changes(&before, &view.positions);
+
+    let mut blasts = FiringResponse::new();
+    for _ in 0..mines {
+      blasts.extend(self.detonate(player_index));
+    }
+    for cell in blasts.keys() {
+      message.push_str(&format!(
+        " {} hit a mine, which blew up {} ship at {}.",
+        if bot { "Computer" } else { "You" },
+        if bot { "its" } else { "your" },
+        a1_notation(*cell)
+      ));
+    }
+    let blown_up = !blasts.is_empty() && self.players[player_index].player_board().is_lost();
+
+    // under the chain rule a hit earns the shooter another shot
+    let fires_again = self.rule == Rule::Chain
+      && response
+        .values()
+        .any(|status| matches!(status, Status::Hit | Status::Kill));
+    self.timeline.push(Turn {
+      player: player_index,
+      shots: shots.clone(),
+      response,
+      fleet_changes,
+      view_changes,
+      drifts,
+      blasts,
+      criticals,
+      power_up: None,
+      ping: None,
+      answer: Some(answer),
+    });
+    if !self.solo && !fires_again {
+      self.turn = opponent_index;
+    }
+    let message = if flagship_sunk {
+      self.winner = Some(player_index);
+      let name = flagship.as_ref().map_or("", |f| f.name());
+      if bot {
+        format!("The computer sank your flagship, the {}. You lost 🙁", name)
+      } else {
+        format!("You sank the flagship, the {}. You won 🙌", name)
+      }
+    } else if lost {
+      self.winner = Some(player_index);
+      if bot {
+        "You lost 🙁".into()
+      } else {
+        "You won 🙌".into()
+      }
+    } else if blown_up {
+      // the mines sank the last ship of the one who fired
+      self.winner = Some(opponent_index);
+      if bot {
+        "You won 🙌".into()
+      } else {
+        "You lost 🙁".into()
+      }
+    } else {
+      message
+    };
+    self.end_turn(message)
+  }
+
+  /// Plays the computer's turn, firing or using one of its power-ups
+  pub fn bot_fire(&mut self) -> String {
+    if let Some(cell) = self.bot_sonar() {
+      if let Ok(message) = self.use_power_up(PowerUp::Sonar, cell, true) {
+        return message;
+      }
+    }
+    if let Some(cell) = self.bot_ping() {
+      if let Ok(message) = self.use_power_up(PowerUp::Ping, cell, true) {
+        return message;
+      }
+    }
+    let shots = self.generate_bot_firing_coordinates();
+    self.fire(&shots, true)
+  }
+
+  /// Where the computer uses its sonar, once it has fired a few turns and has neither a
+  /// hit to finish off nor a ship picked up to look for. It sweeps the area most likely
+  /// to hide a ship that it knows the least about.
+  fn bot_sonar(&self) -> Option<Coordinate> {
+    let computer = self.computer();
+    let view = computer.opponent_board();
+    let turns = self.timeline.iter().filter(|t| t.player == 1).count();
+    if self.difficulty == Difficulty::Easy
+      || self.power_ups_left(1, PowerUp::Sonar) == 0
+      || turns < BOT_SONAR_AFTER
+      || !computer.memory.open_hits.is_empty()
+      || view.positions().iter().any(|p| p.status == Status::Echo)
+    {
+      return None;
+    }
+    let heat = computer.memory.heatmap();
+    (SONAR_RANGE..ROWS - SONAR_RANGE)
+      .flat_map(|r| (SONAR_RANGE..COLS - SONAR_RANGE).map(move |c| (r, c)))
+      .map(|center| {
+        let heat = sonar_area(center)
+          .iter()
+          .filter(|c| view.status_at(**c) == Status::Space)
+          .map(|c| heat.get(c).copied().unwrap_or_default())
+          .sum::<usize>();
+        (center, heat)
+      })
+      .filter(|(_, heat)| *heat > 0)
+      // the first of the hottest areas in reading order
+      .fold(
+        None,
+        |best: Option<(Coordinate, usize)>, (center, heat)| match best {
+          Some((_, most)) if most >= heat => best,
+          _ => Some((center, heat)),
+        },
+      )
+      .map(|(center, _)| center)
+  }
+
+  /// Where the computer pings with its submarine, under the same conditions as it uses
+  /// its sonar: on the cell it deems the likeliest to hold a ship
+  fn bot_ping(&self) -> Option<Coordinate> {
+    let computer = self.computer();
+    let view = computer.opponent_board();
+    let turns = self.timeline.iter().filter(|t| t.player == 1).count();
+    if self.difficulty == Difficulty::Easy
+      || self.power_ups_left(1, PowerUp::Ping) == 0
+      || turns < BOT_SONAR_AFTER
+      || !computer.memory.open_hits.is_empty()
+      || view.positions().iter().any(|p| p.status == Status::Echo)
+    {
+      return None;
+    }
+    let heat = computer.memory.heatmap();
+    view
+      .positions()
+      .iter()
+      .filter(|p| p.status == Status::Space)
+      .map(|p| {
+        (
+          p.coordinate,
+          heat.get(&p.coordinate).copied().unwrap_or_default(),
+        )
+      })
+      .filter(|(_, heat)| *heat > 0)
+      // the first of the hottest cells in reading order
+      .fold(
+        None,
+        |best: Option<(Coordinate, usize)>, (cell, heat)| match best {
+          Some((_, most)) if most >= heat => best,
+          _ => Some((cell, heat)),
+        },
+      )
+      .map(|(cell, _)| cell)
+  }
+
+  /// Power-ups of the kind the player may still use, a sonar for the match, an
+  /// airstrike for every two ships they sank, and a strike or a ping when the ship with
+  /// the ability is ready
+  pub fn power_ups_left(&self, player: usize, power_up: PowerUp) -> usize {
+    // abilities aren't used up, the ship is ready again after a few turns
+    if let Some(ability) = power_up.ability() {
+      return usize::from(self.cooldown(player, ability) == Some(0));
+    }
+    let earned = match power_up {
+      PowerUp::Sonar => SONARS,
+      PowerUp::Airstrike(_) => self.fleet(1 - player).sunk.len() / AIRSTRIKE_SINKS,
+      PowerUp::Strike(_) | PowerUp::Ping => 0,
+    };
+    earned.saturating_sub(self.power_ups_used(player, power_up))
+  }
+
+  /// Turns before a ship of the player with the ability can use it again, none while the
+  /// player has no such ship afloat
+  pub fn cooldown(&self, player: usize, ability: Ability) -> Option<usize> {
+    let board = self.players[player].player_board();
+    if !board
+      .ships_alive()
+      .iter()
+      .any(|s| s.ship_type.ability() == Some(ability))
+    {
+      return None;
+    }
+    let since = self
+      .timeline
+      .iter()
+      .filter(|t| t.player == player)
+      .rev()
+      .position(|t| t.power_up.and_then(|(p, _)| p.ability()) == Some(ability));
+    Some(since.map_or(0, |since| (ability.cooldown() - 1).saturating_sub(since)))
+  }
+
+  fn power_ups_used(&self, player: usize, power_up: PowerUp) -> usize {
+    self
+      .timeline
+      .iter()
+      .filter_map(|t| t.power_up.filter(|_| t.player == player))
+      .filter(|(p, _)| p.is_like(&power_up))
+      .count()
+  }
+
+  /// Checks that the player may use a power-up of the kind now
+  pub fn check_power_up(&self, player: usize, power_up: PowerUp) -> Result<(), String> {
+    if self.is_won() {
+      return Err("The match is over".into());
+    }
+    if self.power_ups_left(player, power_up) > 0 {
+      return Ok(());
+    }
+    if let Some(ability) = power_up.ability() {
+      let had_ship = self.players[player]
+        .player_board()
+        .ships
+        .iter()
+        .any(|s| s.ship_type.ability() == Some(ability));
+      return Err(match self.cooldown(player, ability) {
+        Some(turns) => format!(
+          "The next {} is ready in {} {}",
+          ability,
+          turns,
+          if turns == 1 { "turn" } else { "turns" }
+        ),
+        None if had_ship => format!("The ships that can {} were sunk", ability),
+        None => format!("No ship of the fleet can {}", ability),
+      });
+    }
+    Err(match power_up {
+      PowerUp::Airstrike(_) => {
+        let needed = (self.power_ups_used(player, power_up) + 1) * AIRSTRIKE_SINKS
+          - self.fleet(1 - player).sunk.len();
+        format!(
+          "Sink {} more {} to earn an airstrike",
+          needed,
+          if needed == 1 { "ship" } else { "ships" }
+        )
+      }
+      _ => format!("No {} left this match", power_up.name()),
+    })
+  }
+
+  /// Uses one of the power-ups of the player whose turn it is on the cell, which takes
+  /// the turn like firing does
+  pub fn use_power_up(
+    &mut self,
+    power_up: PowerUp,
+    cell: Coordinate,
+    bot: bool,
+  ) -> Result<String, String> {
+    self
+      .resolve(&Play::PowerUp(power_up, cell), None, bot)
+      .map(|(message, _)| message)
+  }
+
+  /// Checks that the player whose turn it is may take the turn
+  pub fn check_play(&self, play: &Play) -> Result<(), String> {
+    match play {
+      Play::Salvo(shots) => self.validate_salvo(shots).map_err(|err| err.to_string()),
+      Play::PowerUp(power_up, cell) => {
+        self.check_power_up(self.turn, *power_up)?;
+        if cell.0 >= ROWS || cell.1 >= COLS {
+          return Err(format!(
+            "The {} can't be used off the board",
+            power_up.name()
+          ));
+        }
+        self.strike_cells(*power_up, *cell).map(|_| ())
+      }
+    }
+  }
+
+  /// Takes the turn of the player whose turn it is upon the opponent's fleet kept in this
+  /// game, telling what it found so the side it came from can settle it
+  pub fn take_turn(&mut self, play: &Play) -> Result<(String, Answer), String> {
+    self.check_play(play)?;
+    let bot = !self.is_user_turn();
+    self.resolve(play, None, bot)
+  }
+
+  /// Takes the turn of the player whose turn it is upon the opponent's fleet kept
+  /// elsewhere, by what the side keeping it answered the turn found
+  pub fn settle_turn(&mut self, play: &Play, answer: Answer) -> Result<String, String> {
+    self.check_play(play)?;
+    let bot = !self.is_user_turn();
+    self
+      .resolve(play, Some(answer), bot)
+      .map(|(message, _)| message)
+  }
+
+  /// Plays the turn of the player whose turn it is, resolving it on the opponent's fleet or
+  /// going by the answer of the side keeping it. Tells what the turn found along with the
+  /// message of the turn.
+  fn resolve(
+    &mut self,
+    play: &Play,
+    answer: Option<Answer>,
+    bot: bool,
+  ) -> Result<(String, Answer), String> {
+    let player_index = self.turn;
+    let (power_up, cell) = match play {
+      Play::Salvo(shots) => {
+        let volley = match answer {
+          Some(answer) => self.learn_volley(shots, answer)?,
+          None => self.fire_upon(shots),
+        };
+        let answer = volley.answer();
+        return Ok((self.settle_salvo(shots, volley, bot), answer));
+      }
+      Play::PowerUp(power_up, cell) => (*power_up, *cell),
+    };
+    self.check_power_up(player_index, power_up)?;
+    if cell.0 >= ROWS || cell.1 >= COLS {
+      return Err(format!(
+        "The {} can't be used off the board",
+        power_up.name()
+      ));
+    }
+    let (answer, ping, (response, message)) = match (power_up, answer) {
+      (PowerUp::Sonar, None) => {
+        let found = self.sonar_finds(player_index, cell);
+        (
+          Answer::Sonar(found),
+          None,
+          self.sonar(player_index, cell, found, bot),
+        )
+      }
+      (PowerUp::Sonar, Some(Answer::Sonar(found))) => (
+        Answer::Sonar(found),
+        None,
+        self.sonar(player_index, cell, found, bot),
+      ),
+      (PowerUp::Ping, None) => {
+        let nearest = self.nearest_ship(player_index, cell);
+        (
+          Answer::Ping(nearest),
+          nearest,
+          self.ping(player_index, cell, nearest, bot),
+        )
+      }
+      (PowerUp::Ping, Some(Answer::Ping(nearest))) => (
+        Answer::Ping(nearest),
+        nearest,
+        self.ping(player_index, cell, nearest, bot),
+      ),
+      (PowerUp::Airstrike(line), answer) | (PowerUp::Strike(line), answer) => {
+        return self.strike(power_up, line, cell, answer, bot)
+      }
+      _ => return Err(MISMATCHED_ANSWER.into()),
+    };
+    let player = self.player_by_turn_mut(player_index);
+    if player.is_bot {
+      player.memory.learn(&response, &[]);
+    }
+    let view = player.opponent_board_mut();
+    let before = view.positions.clone();
+    view.learn(&response);
+    let view_changes = changes(&before, &view.positions);
+    self.timeline.push(Turn {
+      player: player_index,
+      response,
+      view_changes,
+      power_up: Some((power_up, cell)),
+      ping,
+      answer: Some(answer.clone()),
+      ..Default::default()
+    });
+    if !self.solo {
+      self.turn = 1 - player_index;
+    }
+    Ok((self.end_turn(message), answer))
+  }
+
+  /// The cells a strike on the cell covers the player whose turn it is can still fire
+  /// upon, none for the other power-ups
+  fn strike_cells(
+    &self,
+    power_up: PowerUp,
+    cell: Coordinate,
+  ) -> Result<BTreeSet<Coordinate>, String> {
+    let line = match power_up.line() {
+      Some(line) => line,
+      None => return Ok(BTreeSet::new()),
+    };
+    let shots = power_up
+      .cells(cell)
+      .into_iter()
+      .filter(|c| self.check_shot(self.turn, *c).is_ok())
+      .collect::<BTreeSet<_>>();
+    if shots.is_empty() {
+      return Err(format!(
+        "Every cell the {} on the {} through {} would hit was fired upon already",
+        power_up.name(),
+        line.name(),
+        a1_notation(cell)
+      ));
+    }
+    Ok(shots)
+  }
+
+  /// Fires upon every cell the strike covers the player can still fire upon, whatever the
+  /// shot budget of the rule, as the strike comes on top of it
+  fn strike(
+    &mut self,
+    power_up: PowerUp,
+    line: Line,
+    cell: Coordinate,
+    answer: Option<Answer>,
+    bot: bool,
+  ) -> Result<(String, Answer), String> {
+    let shots = self.strike_cells(power_up, cell)?;
+    let volley = match answer {
+      Some(answer) => self.learn_volley(&shots, answer)?,
+      None => self.fire_upon(&shots),
+    };
+    let answer = volley.answer();
+    let message = self.settle_salvo(&shots, volley, bot);
+    if let Some(turn) = self.timeline.last_mut() {
+      turn.power_up = Some((power_up, cell));
+    }
+    if self.is_won() {
+      return Ok((message, answer));
+    }
+    let message = format!(
+      "{} {} on the {} through {}. {}",
+      if bot { "Computer's" } else { "Your" },
+      power_up.name(),
+      line.name(),
+      a1_notation(cell),
+      message
+    );
+    Ok((message, answer))
+  }
+
+  /// Whether the opponent of the player has a ship cell in the area a sonar on the cell
+  /// covers
+  fn sonar_finds(&self, player: usize, center: Coordinate) -> bool {
+    sonar_area(center).iter().any(|&(row, col)| {
+      self.players[1 - player].player_board().positions[row][col]
+        .ship_id
+        .is_some()
+    })
+  }
+
+  /// What the player's sonar tells about the area around the cell: unexplored cells turn
+  /// into echoes when a ship is there and clear otherwise
+  fn sonar(
+    &self,
+    player: usize,
+    center: Coordinate,
+    found: bool,
+    bot: bool,
+  ) -> (FiringResponse, String) {
+    let view = self.players[player].opponent_board();
+    let response = sonar_area(center)
+      .into_iter()
+      .filter_map(|cell| match (view.status_at(cell), found) {
+        (Status::Space, true) => Some((cell, Status::Echo)),
+        (Status::Space, false) | (Status::Echo, false) => Some((cell, Status::Clear)),
+        _ => None,
+      })
+      .collect();
+    let message = format!(
+      "{} sonar {} around {}.",
+      if bot { "Computer's" } else { "Your" },
+      if found {
+        "picks up a ship"
+      } else {
+        "finds no ship"
+      },
+      a1_notation(center)
+    );
+    (response, message)
+  }
+
+  /// How far the nearest ship cell of the player's opponent not hit yet is from the cell,
+  /// if any is left
+  fn nearest_ship(&self, player: usize, cell: Coordinate) -> Option<usize> {
+    self.players[1 - player]
+      .player_board()
+      .positions()
+      .iter()
+      .filter(|p| p.status == Status::Live)
+      .map(|p| p.coordinate.0.abs_diff(cell.0) + p.coordinate.1.abs_diff(cell.1))
+      .min()
+  }
+
+  /// What a ping on the cell tells the player given how far the nearest ship cell is:
+  /// unexplored cells any closer are clear and those just as far turn into echoes, one of
+  /// them holding the ship
+  fn ping(
+    &self,
+    player: usize,
+    cell: Coordinate,
+    nearest: Option<usize>,
+    bot: bool,
+  ) -> (FiringResponse, String) {
+    let distance = |(row, col): Coordinate| row.abs_diff(cell.0) + col.abs_diff(cell.1);
+    let who = if bot { "Computer's" } else { "Your" };
+    let nearest = match nearest {
+      Some(nearest) => nearest,
+      None => {
+        let message = format!("{} ping at {} finds no ship.", who, a1_notation(cell));
+        return (FiringResponse::new(), message);
+      }
+    };
+    let response = self.players[player]
+      .opponent_board()
+      .positions()
+      .iter()
+      .filter_map(|p| match p.status {
+        Status::Space | Status::Echo if distance(p.coordinate) < nearest => {
+          Some((p.coordinate, Status::Clear))
+        }
+        Status::Space if distance(p.coordinate) == nearest => Some((p.coordinate, Status::Echo)),
+        _ => None,
+      })
+      .collect();
+    let message = match nearest {
+      0 => format!("{} ping finds a ship right at {}.", who, a1_notation(cell)),
+      _ => format!(
+        "{} ping at {} finds the nearest ship {} {} away.",
+        who,
+        a1_notation(cell),
+        nearest,
+        if nearest == 1 { "cell" } else { "cells" }
+      ),
+    };
+    (response, message)
+  }
+
+  /// Cells the player hasn't fired upon that can't hold a ship afloat, going by what their
+  /// shots uncovered and which ships were sunk
+  pub fn ruled_out(&self, player: usize) -> BTreeSet<Coordinate> {
+    let view = self.players[player].opponent_board();
+    let mut memory = BotMemory {
+      afloat: self.players[1 - player]
+        .player_board()
+        .ships_alive()
+        .iter()
+        .map(|s| s.ship_type.clone())
+        .collect(),
+      ..BotMemory::default()
+    };
+    for position in view.positions() {
+      match position.status {
+        Status::Hit | Status::Dent => {
+          memory.open_hits.insert(position.coordinate);
+        }
+        Status::Miss | Status::Kill | Status::Clear => {
+          memory.excluded.insert(position.coordinate);
+        }
+        _ => {}
+      }
+    }
+    memory.ruled_out()
+  }
+
+  /// Lets the player of the index fire first, as long as no shot was fired yet
+  pub fn set_first(&mut self, player: usize) {
+    if self.timeline.is_empty() && !self.solo {
+      self.first = player;
+      self.turn = player;
+    }
+  }
+
+  /// The player who fired first, or fires first once the match starts
+  pub fn first(&self) -> usize {
+    self.first
+  }
+
+  /// Makes the ship of the index in the player's fleet its flagship under the flagship
+  /// rule, as long as no shot was fired yet
+  pub fn set_flagship(&mut self, player: usize, index: usize) -> Result<(), String> {
+    if self.rule != Rule::Flagship {
+      return Err("Only the flagship rule has flagships".into());
+    }
+    if !self.timeline.is_empty() {
+      return Err("The flagship can't change once the shooting started".into());
+    }
+    let board = self.players[player].player_board_mut();
+    if index >= board.ships.len() {
+      return Err(format!("There is no ship {} in the fleet", index + 1));
+    }
+    board.flagship = Some(index);
+    Ok(())
+  }
+
+  /// Index of the flagship in the player's fleet, under the flagship rule
+  pub fn flagship(&self, player: usize) -> Option<usize> {
+    self.players[player].player_board().flagship
+  }
+
+  /// Hides the player's mines in the cells under the mines rule, as long as no shot was
+  /// fired yet
+  pub fn set_mines(&mut self, player: usize, mines: &BTreeSet<Coordinate>) -> Result<(), String> {
+    if self.rule != Rule::Mines {
+      return Err("Only the mines rule has mines".into());
+    }
+    if !self.timeline.is_empty() {
+      return Err("The mines can't move once the shooting started".into());
+    }
+    if mines.len() != MINES {
+      return Err(format!("Every player hides {} mines", MINES));
+    }
+    let board = self.players[player].player_board_mut();
+    if let Some(cell) = mines
+      .iter()
+      .find(|&&(r, c)| r >= ROWS || c >= COLS || board.status_at((r, c)) != Status::Space)
+    {
+      return Err(format!("{} isn't an empty cell", a1_notation(*cell)));
+    }
+    board.mines = mines.clone();
+    Ok(())
+  }
+
+  /// Cells the player hid their mines in, under the mines rule
+  pub fn mines(&self, player: usize) -> &BTreeSet<Coordinate> {
+    &self.players[player].player_board().mines
+  }
+
+  /// Gives the player firing second a shot more in their first turn, to make up for the
+  /// other one firing first
+  pub fn set_balanced(&mut self, balanced: bool) {
+    self.balanced = balanced;
+  }
+
+  /// Whether the player firing second gets a shot more in their first turn
+  pub fn is_balanced(&self) -> bool {
+    self.balanced
+  }
+
+  pub fn is_user_turn(&self) -> bool {
+    self.turn == 0
+  }
+
+  pub fn is_won(&self) -> bool {
+    self.winner.is_some()
+  }
+
+  pub fn winner(&self) -> Option<usize> {
+    self.winner
+  }
+
+  pub fn is_solo(&self) -> bool {
+    self.solo
+  }
+
+  /// Replaces the computer's sunk fleet with a fresh one, the user's fleet stays as it is
+  pub fn next_fleet(&mut self, difficulty: Difficulty) {
+    *self.players[1].player_board_mut() =
+      Board::new(true, &self.fleet_type, &mut self.placement_rng);
+    *self.players[0].opponent_board_mut() = Board::with_fleet(&[]);
+    self.rig_board(1);
+    // only the first fleet is dealt from the seed alone
+    self.seed = None;
+    self.difficulty = difficulty;
+    self.winner = None;
+    self.turn = 0;
+    self.timeline.clear();
+  }
+
+  /// Puts the user's fleet where they placed it, as long as no shot was fired yet
+  pub fn place_fleet(&mut self, fleet: &[Placement]) -> Result<(), String> {
+    if !self.timeline.is_empty() {
+      return Err("The fleet can't be moved once the shooting started".into());
+    }
+    check_fleet(fleet)?;
+    self.players[0] = Player::with_fleet(false, fleet);
+    self.rig_board(0);
+    Ok(())
+  }
+
+  /// Ends the game with the given player giving up
+  pub fn forfeit(&mut self, player: usize) {
+    if self.winner.is_none() {
+      self.winner = Some(1 - player);
+    }
+  }
+
+  /// Number of shots the player may fire in a turn under the rule
+  pub fn shot_budget(&self, player: usize) -> usize {
+    let budget = match self.rule {
+      Rule::Default
+      | Rule::Chain
+      | Rule::Drift
+      | Rule::Mines
+      | Rule::Critical
+      | Rule::Armored
+      | Rule::Hazards
+      | Rule::Night
+      | Rule::Flagship => 1,
+      Rule::Fury | Rule::Salvo => self.fleet(player).alive,
+      Rule::Charge => self.fleet(1 - player).sunk.len() + 1,
+    };
+    // the first turn of the player firing second makes up for going second
+    let second = player != self.first && !self.timeline.iter().any(|t| t.player == player);
+    if self.balanced && second {
+      budget + 1
+    } else {
+      budget
+    }
+  }
+
+  /// Number of shots the player has to fire in a turn under the rule, never more than the
+  /// cells left to fire upon
+  pub fn min_shots(&self, player: usize) -> usize {
+    match self.rule {
+      Rule::Salvo => self.shot_budget(player).min(self.legal_shots(player).len()),
+      _ => 1,
+    }
+  }
+
+  /// Chance in percent a shot of the player at the cell drifts to a cell next door, which
+  /// grows with every turn in a row the player fired in its region
+  pub fn drift_chance(&self, player: usize, cell: Coordinate) -> usize {
+    if self.rule != Rule::Drift {
+      return 0;
+    }
+    let streak = self
+      .timeline
+      .iter()
+      .rev()
+      .filter(|t| t.player == player)
+      .take_while(|t| t.shots.iter().any(|shot| is_near(*shot, cell)))
+      .count();
+    (streak * DRIFT_STEP).min(DRIFT_MAX)
+  }
+
+  /// Randomness drawn from the match so far, so both players of a network match and a
+  /// replay of it agree on what the rules leave to chance
+  fn turn_rng(&self) -> StdRng {
+    let seed = self
+      .players
+      .iter()
+      .fold(self.timeline.len() as u64, |seed, p| {
+        seed ^ p.player_board().status_hash()
+      });
+    StdRng::seed_from_u64(seed)
+  }
+
+  /// Adds what the rule hides in the fleets of both players
+  fn rig_boards(&mut self) {
+    for player in 0..self.players.len() {
+      self.rig_board(player);
+    }
+  }
+
+  /// Adds what the rule hides in the player's fleet, the mines the player sets up with
+  /// under the mines rule and the armor of the ships under the armored one
+  fn rig_board(&mut self, player: usize) {
+    let board = self.players[player].player_board_mut();
+    match self.rule {
+      // drawn like the fleet, a player who places their own mines sets them after
+      Rule::Mines => board.lay_mines(&mut self.placement_rng),
+      Rule::Armored => board.armor_centers(),
+      Rule::Hazards => board.lay_hazards(),
+      Rule::Flagship => board.pick_flagship(),
+      _ => {}
+    }
+  }
+
+  /// Blows up a ship cell of the player who fired upon a mine, telling the other player
+  /// which one it was
+  fn detonate(&mut self, player: usize) -> FiringResponse {
+    let mut rng = self.turn_rng();
+    let board = self.players[player].player_board_mut();
+    let live = board
+      .positions()
+      .iter()
+      .filter(|p| p.status == Status::Live)
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    match live.choose(&mut rng) {
+      Some(cell) => self.hit_cell(player, *cell),
+      None => FiringResponse::new(),
+    }
+  }
+
+  /// Hits the cell of the player's fleet from outside a volley, telling the other player
+  /// what it found
+  fn hit_cell(&mut self, player: usize, cell: Coordinate) -> FiringResponse {
+    let board = self.players[player].player_board_mut();
+    let alive = board
+      .ships_alive()
+      .iter()
+      .map(|s| s.id.clone())
+      .collect::<Vec<_>>();
+    let blast = board
+      .take_fire(&[cell].iter().cloned().collect(), &mut [], false)
+      .response;
+    let sunk = board
+      .ships
+      .iter()
+      .filter(|s| !s.alive && alive.contains(&s.id))
+      .map(|s| s.ship_type.clone())
+      .collect::<Vec<_>>();
+    self
+      .events
+      .extend(sunk.iter().map(|ship_type| GameEvent::ShipSunk {
+        by: 1 - player,
+        ship_type: ship_type.clone(),
+      }));
+    let opponent = &mut self.players[1 - player];
+    let is_bot = opponent.is_bot;
+    if is_bot {
+      opponent.memory.learn(&blast, &sunk);
+    }
+    opponent
+      .opponent_board_mut()
+      .update_status(blast.clone(), !is_bot);
+    blast
+  }
+
+  /// What happens once a turn is played whatever it was, added to the message of the turn
+  fn end_turn(&mut self, mut message: String) -> String {
+    self.reveal_night(&mut message);
+    self.drift_hazards(message)
+  }
+
+  /// Whether night has fallen under the night rule, hiding what the shots of the round
+  /// found from the players who fired them
+  pub fn is_night(&self) -> bool {
+    self.rule == Rule::Night && self.round().is_multiple_of(NIGHT_EVERY)
+  }
+
+  /// Whether the results of the turn at the index are still hidden by the night
+  pub fn in_the_dark(&self, index: usize) -> bool {
+    self.in_the_dark.iter().any(|(turn, _)| *turn == index)
+  }
+
+  /// The player's ships sunk by night turns whose results the other player hasn't seen yet
+  pub fn sunk_in_the_dark(&self, player: usize) -> Vec<&ShipType> {
+    self
+      .in_the_dark
+      .iter()
+      .filter(|(turn, _)| self.timeline[*turn].player != player)
+      .flat_map(|(_, sunk)| sunk)
+      .collect()
+  }
+
+  /// Shows the players what their night turns found once two more turns were played, or
+  /// right away when the match is over. The computer only learns from them then too.
+  fn reveal_night(&mut self, message: &mut String) {
+    let played = self.timeline.len();
+    let over = self.is_won();
+    let (due, left) = std::mem::take(&mut self.in_the_dark)
+      .into_iter()
+      .partition::<Vec<_>, _>(|(turn, _)| over || turn + NIGHT_DELAY < played);
+    self.in_the_dark = left;
+    for (index, sunk) in due {
+      let turn = &self.timeline[index];
+      let (shooter, response) = (turn.player, turn.response.clone());
+      let shots = turn
+        .landed()
+        .iter()
+        .map(|c| a1_notation(*c))
+        .collect::<Vec<_>>()
+        .join(", ");
+      self
+        .events
+        .extend(sunk.iter().map(|ship_type| GameEvent::ShipSunk {
+          by: shooter,
+          ship_type: ship_type.clone(),
+        }));
+      let player = &mut self.players[shooter];
+      if player.is_bot {
+        player.memory.learn(&response, &sunk);
+      }
+      let bot = shooter == 1;
+      let found = player.opponent_board_mut().update_status(response, bot);
+      if !over {
+        message.push_str(&format!(
+          " Daylight shows what {} night shots at {} found. {}",
+          if bot { "the computer's" } else { "your" },
+          shots,
+          found
+        ));
+      }
+    }
+  }
+
+  /// Moves every mine adrift under the hazards rule a cell once the turn is played, the
+  /// ones drifting onto a ship hitting it, and adds what they hit to the message of the turn
+  fn drift_hazards(&mut self, mut message: String) -> String {
+    if self.rule != Rule::Hazards || self.is_won() {
+      return message;
+    }
+    let mut rng = self.turn_rng();
+    for player in 0..self.players.len() {
+      let hits = self.players[player]
+        .player_board_mut()
+        .drift_hazards(&mut rng);
+      for cell in hits {
+        self.hit_cell(player, cell);
+        message.push_str(&format!(
+          " A drifting mine hit {} ship at {}.",
+          if player == 0 {
+            "your"
+          } else {
+            "the computer's"
+          },
+          a1_notation(cell)
+        ));
+      }
+    }
+    let sunk = (0..self.players.len()).find(|p| self.players[*p].player_board().is_lost());
+    match sunk {
+      Some(player) => {
+        self.winner = Some(1 - player);
+        if player == 0 {
+          "You lost 🙁".into()
+        } else {
+          "You won 🙌".into()
+        }
+      }
+      None => message,
+    }
+  }
+
+  /// What changes where the player's shots land before they're resolved, by the rule
+  fn shot_modifiers(&self, player: usize) -> Vec<Box<dyn ShotModifier>> {
+    match self.rule {
+      Rule::Drift => {
+        let chances = (0..ROWS)
+          .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+          .map(|cell| (cell, self.drift_chance(player, cell)))
+          .filter(|(_, chance)| *chance > 0)
+          .collect();
+        vec![Box::new(Drift {
+          chances,
+          rng: self.turn_rng(),
+        })]
+      }
+      _ => vec![],
+    }
+  }
+
+  /// Checks that the player whose turn it is may fire the salvo
+  pub fn validate_salvo(&self, shots: &BTreeSet<Coordinate>) -> Result<(), SalvoError> {
+    let budget = self.shot_budget(self.turn);
+    if shots.is_empty() {
+      return Err(SalvoError::Empty);
+    }
+    if shots.len() > budget {
+      return Err(SalvoError::OverBudget(budget));
+    }
+    for shot in shots {
+      self
+        .check_shot(self.turn, *shot)
+        .map_err(|reason| SalvoError::Illegal(*shot, reason))?;
+    }
+    // checked last, as a salvo is short of shots while the user picks them
+    let required = self.min_shots(self.turn);
+    if shots.len() < required {
+      return Err(SalvoError::UnderBudget(required));
+    }
+    Ok(())
+  }
+
+  /// The round being played, each player fires once per round
+  pub fn round(&self) -> usize {
+    if self.solo {
+      self.timeline.len() + 1
+    } else {
+      // a round ends when the player firing second hands the turn back, however many
+      // shots it took
+      let players = self
+        .timeline
+        .iter()
+        .map(|t| t.player)
+        .chain(std::iter::once(self.turn))
+        .collect::<Vec<_>>();
+      let handed_back = [1 - self.first, self.first];
+      players.windows(2).filter(|w| *w == handed_back).count() + 1
+    }
+  }
+
+  /// What is left of the player's fleet
+  pub fn fleet(&self, player: usize) -> FleetView {
+    self.players[player].player_board().fleet()
+  }
+
+  /// Ships the player sank all at once with a shot on their center
+  pub fn criticals(&self, player: usize) -> usize {
+    self
+      .timeline
+      .iter()
+      .filter(|t| t.player == player)
+      .map(|t| t.criticals.len())
+      .sum()
+  }
+
+  /// Shots fired so far by the player and how many of them hit a ship
+  pub fn shots_and_hits(&self, player: usize) -> (usize, usize) {
+    self
+      .timeline
+      .iter()
+      .enumerate()
+      .filter(|(_, t)| t.player == player)
+      .flat_map(|(index, t)| {
+        // what the night hides doesn't count as a hit yet
+        let dark = self.in_the_dark(index);
+        t.landed()
+          .into_iter()
+          .map(move |s| t.response.get(&s).filter(|_| !dark))
+      })
+      .fold((0, 0), |(shots, hits), status| match status {
+        Some(Status::Hit) | Some(Status::Kill) | Some(Status::Dent) => (shots + 1, hits + 1),
+        _ => (shots + 1, hits),
+      })
+  }
+
+  pub fn player(&self) -> &Player {
+    &self.players[0]
+  }
+
+  pub fn computer(&self) -> &Player {
+    &self.players[1]
+  }
+
+  pub fn seed(&self) -> Option<u64> {
+    self.seed
+  }
+
+  pub fn note(&self) -> Option<&str> {
+    self.note.as_deref()
+  }
+
+  pub fn set_note(&mut self, note: Option<String>) {
+    self.note = note;
+  }
+
+  /// Randomness for what the match looks like rather than how it goes
+  pub fn cosmetic_rng(&mut self) -> &mut StdRng {
+    &mut self.cosmetic_rng
+  }
+
+  pub fn fleet_type(&self) -> &FleetType {
+    &self.fleet_type
+  }
+
+  pub fn difficulty(&self) -> &Difficulty {
+    &self.difficulty
+  }
+
+  pub fn timeline(&self) -> &[Turn] {
+    &self.timeline
+  }
+
+  /// Checks whether the player can still learn something by firing upon the coordinate
+  pub fn check_shot(&self, player: usize, (row, col): Coordinate) -> Result<(), IllegalShot> {
+    if row >= ROWS || col >= COLS {
+      return Err(IllegalShot::OutOfBounds);
+    }
+    match self.players[player].opponent_board().positions[row][col].status {
+      Status::Kill => Err(IllegalShot::Sunk),
+      // a view gone out of step with the fleet still can't fire upon a wreck
+      _ if self.players[1 - player]
+        .player_board()
+        .status_at((row, col))
+        == Status::Kill =>
+      {
+        Err(IllegalShot::Sunk)
+      }
+      // what a sonar found leaves the cell to be fired upon
+      Status::Space | Status::Echo | Status::Clear => Ok(()),
+      // the armor has to be hit again
+      Status::Dent => Ok(()),
+      status => Err(IllegalShot::Resolved(status)),
+    }
+  }
+
+  /// Whether a shot of the player to move at the cell would resolve to anything
+  pub fn is_resolvable(&self, coordinate: Coordinate) -> bool {
+    self.check_shot(self.turn, coordinate).is_ok()
+  }
+
+  /// All the cells of the opponent board the player can still fire upon
+  pub fn legal_shots(&self, player: usize) -> BTreeSet<Coordinate> {
+    let view = self.players[player].opponent_board();
+    // dented armor was fired upon already, but has to be hit again
+    view
+      .unexplored()
+      .chain(view.cells_with(Status::Dent))
+      .filter(|c| self.check_shot(player, *c).is_ok())
+      .collect()
+  }
+
+  /// Hash of both fleets, which two copies of the same match agree on after every turn
+  pub fn state_hash(&self) -> u64 {
+    self.players.iter().fold(0, |hash, p| {
+      hash.rotate_left(1) ^ p.player_board().status_hash()
+    })
+  }
+
+  /// Whether the fleet of the player is kept elsewhere, the turns played upon it settled by
+  /// what its side answers
+  pub fn is_kept_elsewhere(&self, player: usize) -> bool {
+    self.players[player].player_board().is_unseen()
+  }
+
+  /// Hash of what each player found of the other's fleet, the one firing first's first,
+  /// which both sides of a match against a fleet kept elsewhere agree on after every turn
+  pub fn found_hash(&self) -> u64 {
+    let [first, second] = [self.first, 1 - self.first];
+    self.players[first]
+      .opponent_board()
+      .status_hash()
+      .rotate_left(1)
+      ^ self.players[second].opponent_board().status_hash()
+  }
+
+  /// Events that happened since the last call
+  pub fn take_events(&mut self) -> Vec<GameEvent> {
+    std::mem::take(&mut self.events)
+  }
+
+  /// The turn in which the given player first fired upon the coordinate, if it did
+  pub fn fired_upon_in(&self, shooter: usize, coordinate: Coordinate) -> Option<usize> {
+    self
+      .timeline
+      .iter()
+      .position(|t| t.player == shooter && t.landed().contains(&coordinate))
+      .map(|index| index + 1)
+  }
+}
+
+/// What is known of a cell, which is also what a shot on it found
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Status {
+  Live,
+  Miss,
+  Hit,
+  Kill,
+  Space,
+  /// a mine set off
+  Mine,
+  /// unexplored, in an area where a sonar picked up a ship
+  Echo,
+  /// unexplored, in an area a sonar found empty
+  Clear,
+  /// a ship cell whose armor took a hit, the next one gets through
+  Dent,
+  /// fired upon at night, what the shot found isn't known yet
+  Splash,
+}
+
+impl Status {
+  pub fn describe(&self) -> &'static str {
+    match *self {
+      Status::Live => "ship",
+      Status::Miss => "miss",
+      Status::Hit => "hit",
+      Status::Kill => "sunk",
+      Status::Space => "unexplored",
+      Status::Mine => "mine",
+      Status::Echo => "echo",
+      Status::Clear => "clear",
+      Status::Dent => "dented armor",
+      Status::Splash => "splash heard",
+    }
+  }
+
+  /// Reads a cell back from either its emoji or its plain glyph
+  pub fn from_char(c: char) -> Result<Self, String> {
+    match c {
+      '🚀' | '#' => Ok(Status::Live),
+      '❌' | '.' => Ok(Status::Miss),
+      '💥' | 'x' => Ok(Status::Hit),
+      '💀' | 'X' => Ok(Status::Kill),
+      ' ' => Ok(Status::Space),
+      '💣' | '*' => Ok(Status::Mine),
+      '📡' | '~' => Ok(Status::Echo),
+      '🌊' | '-' => Ok(Status::Clear),
+      '🔩' | 'o' => Ok(Status::Dent),
+      '💦' | '?' => Ok(Status::Splash),
+      _ => Err(format!("unknown cell {:?}", c)),
+    }
+  }
+
+  /// Plain glyph for terminals that can't show emoji
+  pub fn as_char(&self) -> char {
+    match *self {
+      Status::Live => '#',
+      Status::Miss => '.',
+      Status::Hit => 'x',
+      Status::Kill => 'X',
+      Status::Space => ' ',
+      Status::Mine => '*',
+      Status::Echo => '~',
+      Status::Clear => '-',
+      Status::Dent => 'o',
+      Status::Splash => '?',
+    }
+  }
+}
+
+impl Display for Status {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = match *self {
+      Status::Live => "🚀",
+      Status::Miss => "❌",
+      Status::Hit => "💥",
+      Status::Kill => "💀",
+      Status::Space => " ",
+      Status::Mine => "💣",
+      Status::Echo => "📡",
+      Status::Clear => "🌊",
+      Status::Dent => "🔩",
+      Status::Splash => "💦",
+    };
+    write!(f, "{}", s)
+  }
+}
+
+/// One side of the match, with its own board and its view of the opponent's
+#[derive(Clone)]
+pub struct Player {
+  is_bot: bool,
+  boards: [Board; 2],
+  memory: BotMemory,
+  /// how the computer picks its shots when not by its difficulty
+  strategy: Option<Box<dyn Strategy>>,
+}
+
+/// What the computer learned about the user's fleet, kept across turns so a salvo keeps
+/// working on the ships hit by earlier ones. A restored match replays its turns, which
+/// rebuilds the memory as it was.
+#[derive(PartialEq, Debug, Clone)]
+pub struct BotMemory {
+  /// hits on ships that are still afloat
+  pub open_hits: BTreeSet<Coordinate>,
+  /// cells that hold no ship afloat, the misses and the sunk ships
+  pub excluded: BTreeSet<Coordinate>,
+  /// types of the ships still afloat
+  pub afloat: Vec<ShipType>,
+}
+
+impl Default for BotMemory {
+  fn default() -> Self {
+    Self::new(&FleetType::Standard)
+  }
+}
+
+impl BotMemory {
+  fn new(fleet_type: &FleetType) -> Self {
+    Self {
+      open_hits: BTreeSet::new(),
+      excluded: BTreeSet::new(),
+      afloat: fleet_type.ships(),
+    }
+  }
+
+  fn learn(&mut self, response: &FiringResponse, sunk: &[ShipType]) {
+    for (coordinate, status) in response {
+      match status {
+        // a dent is a ship cell as sure as a hit, and needs another shot
+        Status::Hit | Status::Dent => {
+          self.open_hits.insert(*coordinate);
+        }
+        Status::Miss | Status::Kill | Status::Mine | Status::Clear => {
+          self.open_hits.remove(coordinate);
+          self.excluded.insert(*coordinate);
+        }
+        _ => {}
+      }
+    }
+    for ship_type in sunk {
+      if let Some(index) = self.afloat.iter().position(|t| t == ship_type) {
+        self.afloat.remove(index);
+      }
+    }
+  }
+
+  /// Placements of the ships afloat that cover none of the excluded cells
+  fn placements(&self) -> Vec<(Placement, BTreeSet<Coordinate>)> {
+    let mut ship_types = self.afloat.clone();
+    ship_types.dedup();
+    let mut placements = vec![];
+    for ship_type in ship_types {
+      for rotation in ROTATIONS.iter() {
+        let (rows, cols) = ship_type.size(*rotation);
+        for row in 0..=ROWS - rows {
+          for col in 0..=COLS - cols {
+            let placement = Placement {
+              ship_type: ship_type.clone(),
+              rotation: *rotation,
+              origin: (row, col),
+            };
+            let cells = placement.cells();
+            if cells.is_disjoint(&self.excluded) {
+              placements.push((placement, cells));
+            }
+          }
+        }
+      }
+    }
+    placements
+  }
+
+  /// Placements of the ships afloat that cover an open hit and none of the excluded cells
+  pub fn candidates(&self) -> Vec<Placement> {
+    self
+      .placements()
+      .into_iter()
+      .filter(|(_, cells)| !cells.is_disjoint(&self.open_hits))
+      .map(|(placement, _)| placement)
+      .collect()
+  }
+
+  /// How likely each cell not fired upon yet holds a ship, counting the placements of the
+  /// ships afloat that cover it. Placements through open hits count for many more, as one
+  /// of them is certain to be right.
+  pub fn heatmap(&self) -> BTreeMap<Coordinate, usize> {
+    self.weighed_heatmap(|_| 1)
+  }
+
+  /// The heatmap under the flagship rule, where the placements of the smallest ships afloat
+  /// count for more. The smallest ships are the hardest to find, which makes them the
+  /// likeliest flagships.
+  pub fn flagship_heatmap(&self) -> BTreeMap<Coordinate, usize> {
+    const FLAGSHIP_WEIGHT: usize = 3;
+    let smallest = self.afloat.iter().map(|s| s.cells()).min();
+    self.weighed_heatmap(|cells| {
+      if Some(cells.len()) == smallest {
+        FLAGSHIP_WEIGHT
+      } else {
+        1
+      }
+    })
+  }
+
+  fn weighed_heatmap<F: Fn(&BTreeSet<Coordinate>) -> usize>(
+    &self,
+    weigh: F,
+  ) -> BTreeMap<Coordinate, usize> {
+    const HIT_WEIGHT: usize = 50;
+    let mut heat = BTreeMap::new();
+    for (_, cells) in self.placements() {
+      let weight = weigh(&cells) + HIT_WEIGHT * cells.intersection(&self.open_hits).count();
+      for cell in cells.difference(&self.open_hits) {
+        *heat.entry(*cell).or_insert(0) += weight;
+      }
+    }
+    heat
+  }
+
+  /// Cells of the heatmap, hottest first and ties in random order
+  pub fn hottest<R: Rng>(&self, rng: &mut R) -> Vec<Coordinate> {
+    hottest_of(self.heatmap(), rng)
+  }
+
+  /// Cells of the flagship heatmap, hottest first and ties in random order
+  pub fn hottest_flagship<R: Rng>(&self, rng: &mut R) -> Vec<Coordinate> {
+    hottest_of(self.flagship_heatmap(), rng)
+  }
+
+  /// Cells not fired upon that no placement of the ships afloat covers, so they can't
+  /// hold a ship
+  pub fn ruled_out(&self) -> BTreeSet<Coordinate> {
+    let covered = self
+      .placements()
+      .into_iter()
+      .flat_map(|(_, cells)| cells)
+      .collect::<BTreeSet<_>>();
+    (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .filter(|c| !covered.contains(c) && !self.excluded.contains(c) && !self.open_hits.contains(c))
+      .collect()
+  }
+
+  /// Cells not fired upon yet that belong to the most candidate placements, best first
+  pub fn targets(&self) -> Vec<Coordinate> {
+    let mut counts = BTreeMap::new();
+    for placement in self.candidates() {
+      for cell in placement.cells() {
+        if !self.open_hits.contains(&cell) {
+          *counts.entry(cell).or_insert(0) += 1;
+        }
+      }
+    }
+    let mut targets = counts.into_iter().collect::<Vec<_>>();
+    targets.sort_by(|(_, a), (_, b)| b.cmp(a));
+    targets.into_iter().map(|(cell, _)| cell).collect()
+  }
+}
+
+impl Player {
+  fn new<R: Rng>(is_bot: bool, fleet_type: &FleetType, rng: &mut R) -> Self {
+    Self {
+      is_bot,
+      boards: [Board::new(true, fleet_type, rng), Board::with_fleet(&[])],
+      memory: BotMemory::new(fleet_type),
+      strategy: None,
+    }
+  }
+
+  /// A player with the given fleet, the other one has a fleet of the same type
+  fn with_fleet(is_bot: bool, placements: &[Placement]) -> Self {
+    Self {
+      is_bot,
+      boards: [Board::with_fleet(placements), Board::with_fleet(&[])],
+      memory: BotMemory::new(&FleetType::of(placements)),
+      strategy: None,
+    }
+  }
+
+  pub fn player_board_mut(&mut self) -> &mut Board {
+    &mut self.boards[0]
+  }
+  pub fn player_board(&self) -> &Board {
+    &self.boards[0]
+  }
+  pub fn opponent_board_mut(&mut self) -> &mut Board {
+    &mut self.boards[1]
+  }
+  pub fn opponent_board(&self) -> &Board {
+    &self.boards[1]
+  }
+  pub fn memory(&self) -> &BotMemory {
+    &self.memory
+  }
+}
+
+/// What became of the shots of a turn on the board fired upon
+#[derive(Default)]
+struct Volley {
+  response: FiringResponse,
+  /// shots that landed on another cell than the one they were aimed at, aimed and landed
+  drifts: Vec<(Coordinate, Coordinate)>,
+  /// ships sunk all at once by a shot on their center
+  criticals: Vec<ShipType>,
+  /// the last ship of the board went down
+  lost: bool,
+  /// every ship the shots sank
+  sunk: Vec<ShipType>,
+  /// the flagship, when the shots sank it and the fleet went down with it
+  flagship: Option<ShipType>,
+  /// where the mines adrift the shots set off were
+  set_off: Vec<Coordinate>,
+  /// the cells of the board the shots changed
+  fleet_changes: Vec<CellChange>,
+}
+
+impl Volley {
+  /// What the shots found, as the side keeping the fleet answers it
+  fn answer(&self) -> Answer {
+    Answer::Salvo {
+      response: self.response.clone(),
+      sunk: self.sunk.clone(),
+      criticals: self.criticals.clone(),
+      flagship: self.flagship.clone(),
+      lost: self.lost,
+    }
+  }
+}
+
+/// What a shot did to the cell it landed on, worked out on the defender's board before
+/// anything changes. The defender's board takes it with `apply_shot`, and what the attacker
+/// knows of it takes the response of it with `learn`, both going by `settle`.
+#[derive(PartialEq, Debug, Clone)]
+struct ShotResolution {
+  cell: Coordinate,
+  /// what the shot found in the cell
+  found: Status,
+  /// the ship the shot sank, with every cell of it
+  sunk: Option<(String, Vec<Coordinate>)>,
+  /// the ship sank all at once by a shot on its center
+  critical: bool,
+}
+
+impl ShotResolution {
+  /// What the attacker is told, the cell and every cell of a ship it sank
+  fn response(&self) -> FiringResponse {
+    let mut response = self
+      .sunk
+      .iter()
+      .flat_map(|(_, cells)| cells.iter().map(|c| (*c, Status::Kill)))
+      .collect::<FiringResponse>();
+    response.insert(self.cell, self.found);
+    response
+  }
+}
+
+/// What a cell known to be one status becomes when a shot or a power-up finds another in
+/// it, the same on the defender's board as on what the attacker knows of it. A cell
+/// settled by an earlier shot, a miss, a mine set off or a hit, stays as it is unless its
+/// ship sinks, and a ship cell whose armor takes the hit stays afloat.
+fn settle(known: Status, found: Status) -> Status {
+  match (known, found) {
+    (_, Status::Kill) => Status::Kill,
+    (Status::Live, Status::Dent) => Status::Live,
+    (
+      Status::Space | Status::Live | Status::Echo | Status::Clear | Status::Dent | Status::Splash,
+      found,
+    ) => found,
+    (known, _) => known,
+  }
+}
+
+/// Moves a shot away from the cell it was aimed at before it's resolved
+trait ShotModifier {
+  /// Where the shot at the cell lands on the board, never on one of the cells taken by
+  /// the other shots of the turn
+  fn land(&mut self, cell: Coordinate, board: &Board, taken: &BTreeSet<Coordinate>) -> Coordinate;
+}
+
+/// Shots fired in the region of the shooter's last turns drift to a cell next door not
+/// fired upon yet, by the chance of their cell
+struct Drift {
+  chances: BTreeMap<Coordinate, usize>,
+  rng: StdRng,
+}
+
+impl ShotModifier for Drift {
+  fn land(&mut self, cell: Coordinate, board: &Board, taken: &BTreeSet<Coordinate>) -> Coordinate {
+    let chance = self.chances.get(&cell).copied().unwrap_or_default();
+    if !self.rng.gen_ratio(chance as u32, 100) {
+      return cell;
+    }
+    let (row, col) = cell;
+    // a cell past the top or the left edge wraps around to one far off the board
+    let next_door = [
+      (row.wrapping_sub(1), col),
+      (row + 1, col),
+      (row, col.wrapping_sub(1)),
+      (row, col + 1),
+    ]
+    .iter()
+    .copied()
+    .filter(|&(r, c)| r < ROWS && c < COLS && !taken.contains(&(r, c)))
+    .filter(|c| matches!(board.status_at(*c), Status::Space | Status::Live))
+    .collect::<Vec<_>>();
+    next_door.choose(&mut self.rng).copied().unwrap_or(cell)
+  }
+}
+
+/// The cells a sonar used on the cell sweeps, as far as they are on the board
+fn sonar_area((row, col): Coordinate) -> Vec<Coordinate> {
+  let rows = row.saturating_sub(SONAR_RANGE)..=(row + SONAR_RANGE).min(ROWS - 1);
+  rows
+    .flat_map(|r| {
+      (col.saturating_sub(SONAR_RANGE)..=(col + SONAR_RANGE).min(COLS - 1)).map(move |c| (r, c))
+    })
+    .collect()
+}
+
+/// Whether the cells are in the same region for the drift rule
+fn is_near(a: Coordinate, b: Coordinate) -> bool {
+  a.0.abs_diff(b.0) <= DRIFT_REGION && a.1.abs_diff(b.1) <= DRIFT_REGION
+}
+
+/// A grid of cells, either a player's own fleet or what the player knows of the other's
+#[derive(PartialEq, Clone)]
+pub struct Board {
+  pub positions: Vec<Vec<Position>>,
+  ships: Vec<Ship>,
+  firing_status: BTreeMap<String, String>,
+  /// cells the player hid a mine in, under the mines rule
+  mines: BTreeSet<Coordinate>,
+  /// neutral mines adrift in the player's waters, under the hazards rule
+  hazards: BTreeSet<Coordinate>,
+  /// index of the ship whose sinking loses the match, under the flagship rule
+  flagship: Option<usize>,
+}
+
+impl Board {
+  fn new<R: Rng>(is_self: bool, fleet_type: &FleetType, rng: &mut R) -> Self {
+    // create empty positions
+    let mut positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+
+    let ships = if is_self {
+      place_ships(&fleet_type.ships(), &mut positions, rng)
+    } else {
+      vec![]
+    };
+
+    Self {
+      ships,
+      firing_status: BTreeMap::new(),
+      mines: BTreeSet::new(),
+      hazards: BTreeSet::new(),
+      flagship: None,
+      positions,
+    }
+  }
+
+  pub fn with_fleet(placements: &[Placement]) -> Self {
+    let mut positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+
+    let ships = placements
+      .iter()
+      .map(|p| {
+        let ship = Ship {
+          rotation: p.rotation,
+          origin: p.origin,
+          ..Ship::new(p.ship_type.clone())
+        };
+        ship.draw(&mut positions, p.origin);
+        ship
+      })
+      .collect::<Vec<_>>();
+
+    Self {
+      ships,
+      firing_status: BTreeMap::new(),
+      mines: BTreeSet::new(),
+      hazards: BTreeSet::new(),
+      flagship: None,
+      positions,
+    }
+  }
+
+  /// A board standing in for a fleet kept elsewhere: the ships are known, where they are
+  /// is only learnt from what the shots find
+  fn unseen(fleet_type: &FleetType) -> Self {
+    Self {
+      ships: fleet_type.ships().into_iter().map(Ship::new).collect(),
+      ..Self::with_fleet(&[])
+    }
+  }
+
+  /// Whether the board stands in for a fleet kept elsewhere
+  fn is_unseen(&self) -> bool {
+    !self.ships.is_empty() && self.positions.iter().flatten().all(|p| p.ship_id.is_none())
+  }
+
+  /// Marks a ship of the type still afloat sunk, whether there was one to sink
+  fn sink(&mut self, ship_type: &ShipType) -> bool {
+    match self
+      .ships
+      .iter_mut()
+      .find(|s| s.alive && &s.ship_type == ship_type)
+    {
+      Some(ship) => {
+        ship.alive = false;
+        true
+      }
+      None => false,
+    }
+  }
+
+  pub fn placements(&self) -> Vec<Placement> {
+    self.ships.iter().map(|s| s.placement()).collect()
+  }
+
+  /// Hash of the cell statuses, stable across platforms and releases as it's meant to be
+  /// compared with hashes computed elsewhere
+  pub fn status_hash(&self) -> u64 {
+    // FNV-1a over the plain glyphs, row by row
+    self
+      .positions()
+      .iter()
+      // armor that still holds tells two boards apart like a hit does
+      .map(|p| {
+        if p.hit_points > 1 {
+          b'@'
+        } else {
+          p.status.as_char() as u8
+        }
+      })
+      .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+      })
+  }
+
+  fn as_grid(&self) -> Vec<String> {
+    self
+      .positions
+      .iter()
+      .map(|row| {
+        row
+          .iter()
+          .map(|c| c.to_string())
+          .collect::<Vec<_>>()
+          .join("")
+      })
+      .collect::<Vec<_>>()
+  }
+
+  fn fleet(&self) -> FleetView {
+    FleetView {
+      alive: self.ships_alive().len(),
+      sunk: self
+        .ships
+        .iter()
+        .filter(|s| !s.alive)
+        .map(|s| s.ship_type.clone())
+        .collect(),
+      cells_remaining: self
+        .positions()
+        .iter()
+        .filter(|p| p.status == Status::Live)
+        .count(),
+    }
+  }
+
+  /// Hides the mines in empty cells drawn at random, as a player setting up does
+  fn lay_mines(&mut self, rng: &mut impl Rng) {
+    let empty = self
+      .positions()
+      .iter()
+      .filter(|p| p.status == Status::Space)
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    self.mines = empty.choose_multiple(rng, MINES).copied().collect();
+  }
+
+  /// The board with mines hidden in the cells, as its player placed them
+  pub fn with_mines(mut self, mines: &[Coordinate]) -> Self {
+    self.mines = mines.iter().copied().collect();
+    self
+  }
+
+  pub fn is_mined(&self, coordinate: Coordinate) -> bool {
+    self.mines.contains(&coordinate)
+  }
+
+  /// Sets mines adrift in empty cells drawn from the fleet
+  fn lay_hazards(&mut self) {
+    let mut rng = StdRng::seed_from_u64(!self.status_hash());
+    let empty = self
+      .positions()
+      .iter()
+      .filter(|p| p.status == Status::Space)
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    self.hazards = empty.choose_multiple(&mut rng, HAZARDS).copied().collect();
+  }
+
+  pub fn is_hazard(&self, coordinate: Coordinate) -> bool {
+    self.hazards.contains(&coordinate)
+  }
+
+  /// Takes the mines adrift in the cells fired upon off the board, returning where they were
+  fn set_off_hazards(&mut self, response: &FiringResponse) -> Vec<Coordinate> {
+    let cells = response
+      .keys()
+      .filter(|c| self.hazards.contains(c))
+      .copied()
+      .collect::<Vec<_>>();
+    for cell in &cells {
+      self.hazards.remove(cell);
+    }
+    cells
+  }
+
+  /// Moves every mine adrift to a cell next door, over open water or onto a ship cell not
+  /// hit yet, and returns the ship cells mines drifted onto, which they're gone from
+  fn drift_hazards<R: Rng>(&mut self, rng: &mut R) -> Vec<Coordinate> {
+    let mut hits = vec![];
+    for cell in self.hazards.clone() {
+      self.hazards.remove(&cell);
+      let (row, col) = cell;
+      // a cell past the top or the left edge wraps around to one far off the board
+      let next = [
+        (row.wrapping_sub(1), col),
+        (row + 1, col),
+        (row, col.wrapping_sub(1)),
+        (row, col + 1),
+      ]
+      .iter()
+      .copied()
+      .filter(|&(r, c)| r < ROWS && c < COLS)
+      .filter(|c| !self.hazards.contains(c) && !hits.contains(c))
+      .filter(|c| {
+        matches!(
+          self.status_at(*c),
+          Status::Space | Status::Miss | Status::Live
+        )
+      })
+      .collect::<Vec<_>>();
+      match next.choose(rng).copied() {
+        Some(next) if self.status_at(next) == Status::Live => hits.push(next),
+        Some(next) => {
+          self.hazards.insert(next);
+        }
+        None => {
+          self.hazards.insert(cell);
+        }
+      }
+    }
+    hits
+  }
+
+  /// Armors the center cell of every ship, so it takes more than one hit
+  fn armor_centers(&mut self) {
+    let centers = self
+      .ships
+      .iter()
+      .map(|s| s.placement().center())
+      .collect::<Vec<_>>();
+    for (row, col) in centers {
+      self.positions[row][col].hit_points = ARMOR_HIT_POINTS;
+    }
+  }
+
+  /// Whether the cell is a ship cell whose armor still holds
+  pub fn is_armored(&self, (row, col): Coordinate) -> bool {
+    let pos = &self.positions[row][col];
+    pos.status == Status::Live && pos.hit_points > 1
+  }
+
+  fn ships_alive(&self) -> Vec<&Ship> {
+    self.ships.iter().filter(|s| s.alive).collect::<Vec<_>>()
+  }
+
+  /// Whether the fleet is beaten, with every ship or the flagship sunk
+  fn is_lost(&self) -> bool {
+    self.ships_alive().is_empty()
+      || self
+        .flagship
+        .and_then(|i| self.ships.get(i))
+        .is_some_and(|s| !s.alive)
+  }
+
+  /// The type of the flagship, under the flagship rule
+  pub fn flagship(&self) -> Option<&ShipType> {
+    self
+      .flagship
+      .and_then(|i| self.ships.get(i))
+      .map(|s| &s.ship_type)
+  }
+
+  /// Makes the ship the least likely to be found the flagship, the one whose cells the
+  /// fewest placements of the fleet cover. Ties go to the first, so both ends of a match
+  /// and a restore pick the same.
+  fn pick_flagship(&mut self) {
+    let heat = BotMemory {
+      afloat: self.ships.iter().map(|s| s.ship_type.clone()).collect(),
+      ..Default::default()
+    }
+    .heatmap();
+    self.flagship = self
+      .ships
+      .iter()
+      .enumerate()
+      .min_by_key(|(_, ship)| {
+        self
+          .pos_by_ship(ship.id.clone())
+          .iter()
+          .map(|p| heat.get(&p.coordinate).copied().unwrap_or(0))
+          .sum::<usize>()
+      })
+      .map(|(i, _)| i);
+  }
+
+  /// Every ship of the fleet in order and whether it's still afloat
+  pub fn ship_status(&self) -> Vec<(&ShipType, bool)> {
+    let alive = self.ships_alive();
+    self
+      .ships
+      .iter()
+      .map(|s| (&s.ship_type, alive.iter().any(|a| a.id == s.id)))
+      .collect()
+  }
+
+  fn find_ship_mut(&mut self, id: String) -> Option<&mut Ship> {
+    self.ships.iter_mut().find(|s| s.id == id)
+  }
+
+  fn find_ship(&self, id: String) -> Option<&Ship> {
+    self.ships.iter().find(|s| s.id == id)
+  }
+
+  /// Cells of the board with the status, row by row
+  pub fn cells_with(&self, status: Status) -> Vec<Coordinate> {
+    self
+      .positions()
+      .into_iter()
+      .filter(|p| p.status == status)
+      .map(|p| p.coordinate)
+      .collect()
+  }
+
+  /// Cells no shot was fired upon yet, row by row, the ones a sonar or a ping swept
+  /// included. Walks the board without collecting it, for the callers that only need a few.
+  pub fn unexplored(&self) -> impl Iterator<Item = Coordinate> + '_ {
+    self
+      .positions
+      .iter()
+      .flatten()
+      .filter(|p| matches!(p.status, Status::Space | Status::Echo | Status::Clear))
+      .map(|p| p.coordinate)
+  }
+
+  fn positions(&self) -> Vec<&Position> {
+    self
+      .positions
+      .iter()
+      .flat_map(|pr| pr.iter())
+      .collect::<Vec<_>>()
+  }
+
+  fn pos_by_ship(&self, id: String) -> Vec<&Position> {
+    self
+      .positions()
+      .into_iter()
+      .filter(|pc| pc.ship_id.is_some() && pc.ship_id.clone().unwrap() == id)
+      .collect::<Vec<_>>()
+  }
+
+  fn alive_pos_by_ship(&self, id: String) -> Vec<&Position> {
+    self
+      .pos_by_ship(id)
+      .into_iter()
+      .filter(|pc| pc.status == Status::Live)
+      .collect::<Vec<_>>()
+  }
+
+  /// Resolves the shots where the modifiers, in order, let them land. With criticals a
+  /// shot on the center cell of a ship sinks the whole ship.
+  fn take_fire(
+    &mut self,
+    shots: &BTreeSet<Coordinate>,
+    modifiers: &mut [Box<dyn ShotModifier>],
+    criticals: bool,
+  ) -> Volley {
+    let mut response = BTreeMap::new();
+    let mut drifts = vec![];
+    let mut critical_sinks = vec![];
+    let mut taken = shots.clone();
+    for aimed in shots {
+      let shot = modifiers
+        .iter_mut()
+        .fold(*aimed, |cell, modifier| modifier.land(cell, self, &taken));
+      if shot != *aimed {
+        taken.insert(shot);
+        drifts.push((*aimed, shot));
+      }
+      // the wreck was reported when it sank, a shot on it changes nothing
+      if let Some(resolution) = self.resolve_shot(shot, criticals) {
+        self.apply_shot(&resolution);
+        if let (true, Some((id, _))) = (resolution.critical, &resolution.sunk) {
+          if let Some(ship) = self.find_ship(id.clone()) {
+            critical_sinks.push(ship.ship_type.clone());
+          }
+        }
+        response.extend(resolution.response());
+      }
+    }
+    Volley {
+      response,
+      drifts,
+      criticals: critical_sinks,
+      lost: self.is_lost(),
+      ..Default::default()
+    }
+  }
+
+  /// What a shot landing on the cell does, worked out before anything changes. A shot on
+  /// a wreck does nothing, the sinking was reported when the ship went down.
+  fn resolve_shot(&self, shot: Coordinate, criticals: bool) -> Option<ShotResolution> {
+    if self.status_at(shot) == Status::Kill {
+      return None;
+    }
+    let pos = &self.positions[shot.0][shot.1];
+    let mut resolution = ShotResolution {
+      cell: shot,
+      found: match pos.status {
+        // the armor takes the hit, the cell stays afloat
+        Status::Live if pos.hit_points > 1 => Status::Dent,
+        Status::Live => Status::Hit,
+        // a cell settled by an earlier shot finds what that one did
+        Status::Hit | Status::Miss | Status::Mine => pos.status,
+        _ if self.mines.contains(&shot) => Status::Mine,
+        _ => Status::Miss,
+      },
+      sunk: None,
+      critical: false,
+    };
+    if let (Status::Live, Status::Hit, Some(id)) = (pos.status, resolution.found, &pos.ship_id) {
+      let critical = criticals
+        && self
+          .ships
+          .iter()
+          .any(|s| &s.id == id && s.placement().center() == shot);
+      if (critical || self.alive_pos_by_ship(id.clone()).len() <= 1)
+        && self.find_ship(id.clone()).is_some()
+      {
+        let cells = self
+          .pos_by_ship(id.clone())
+          .iter()
+          .map(|p| p.coordinate)
+          .collect();
+        resolution.found = Status::Kill;
+        resolution.sunk = Some((id.clone(), cells));
+        resolution.critical = critical;
+      }
+    }
+    Some(resolution)
+  }
+
+  /// Takes the shot as it was resolved: the armor gives way, or the cell and any ship it
+  /// sank go down
+  fn apply_shot(&mut self, resolution: &ShotResolution) {
+    let (row, col) = resolution.cell;
+    if resolution.found == Status::Dent {
+      self.positions[row][col].hit_points -= 1;
+    }
+    if let Some((id, cells)) = &resolution.sunk {
+      if let Some(ship) = self.find_ship_mut(id.clone()) {
+        ship.alive = false;
+      }
+      // the rest of the ship keeps its hits, drawn as a wreck as the ship is sunk, along
+      // with the cells a critical hit took down with the center
+      for &(r, c) in cells {
+        let pos = &mut self.positions[r][c];
+        pos.status = settle(pos.status, Status::Hit);
+      }
+    }
+    let pos = &mut self.positions[row][col];
+    pos.status = settle(pos.status, resolution.found);
+  }
+
+  /// Takes what the cells were found to be, as the response to a shot or a power-up, into
+  /// what the player knows of the other's board
+  fn learn(&mut self, response: &FiringResponse) {
+    for (&(row, col), &found) in response {
+      let pos = &mut self.positions[row][col];
+      pos.status = settle(pos.status, found);
+    }
+  }
+
+  fn update_status(&mut self, response: FiringResponse, bot: bool) -> String {
+    let mut kill_count = 0;
+    let mut hit_count = 0;
+    let mut miss_count = 0;
+    self.learn(&response);
+    for status in response.values() {
+      match status {
+        Status::Miss => miss_count += 1,
+        Status::Hit => hit_count += 1,
+        Status::Kill => kill_count += 1,
+        _ => {}
+      }
+    }
+    let mut msg: Vec<String> = if bot {
+      vec!["Computer have ".into()]
+    } else {
+      vec!["You have ".into()]
+    };
+    if kill_count > 0 {
+      msg.push("sunk a ship.".to_string());
+    } else {
+      msg.push(format!("{} hit.", hit_count));
+    }
+    if miss_count > 0 {
+      msg.push(format!(
+        " {} missed {}.",
+        if bot { "Computer" } else { "You" },
+        miss_count
+      ));
+    }
+    msg.join("")
+  }
+
+  pub fn find_position_and_ship(&self, coordinate: Coordinate) -> (&Position, Option<&Ship>) {
+    let pos = &self.positions[coordinate.0][coordinate.1];
+    if pos.ship_id.is_some() {
+      (pos, self.find_ship(pos.ship_id.clone().unwrap()))
+    } else {
+      (pos, None)
+    }
+  }
+
+  /// Status of the cell as drawn, cells of a sunk ship showing as killed
+  pub fn status_at(&self, coordinate: Coordinate) -> Status {
+    let (pos, ship) = self.find_position_and_ship(coordinate);
+    pos.get_status(ship)
+  }
+}
+
+impl Display for Board {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = self.as_grid().join("\n");
+    write!(f, "{}", s)
+  }
+}
+
+impl FromStr for Board {
+  type Err = String;
+
+  /// Reads a board in the grid format written by `Display`, one line per row, where cells
+  /// can also be given as plain glyphs. The ships are worked out from the shapes their
+  /// cells make, a board without any live ship cells may also be a knowledge board
+  /// where the hits don't make up whole ships.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let lines = s.split('\n').collect::<Vec<_>>();
+    if lines.len() != ROWS {
+      return Err(format!("expected {} rows, found {}", ROWS, lines.len()));
+    }
+
+    let mut positions = vec![];
+    for (r, line) in lines.iter().enumerate() {
+      let row = line
+        .chars()
+        .enumerate()
+        .map(|(c, ch)| {
+          Status::from_char(ch).map(|status| Position {
+            status,
+            ..Position::new((r, c))
+          })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      if row.len() != COLS {
+        return Err(format!(
+          "expected {} cells in row {}, found {}",
+          COLS,
+          r + 1,
+          row.len()
+        ));
+      }
+      positions.push(row);
+    }
+
+    let ship_cells = positions
+      .iter()
+      .flat_map(|row| row.iter())
+      .filter(|p| matches!(p.status, Status::Live | Status::Hit | Status::Kill))
+      .map(|p| p.coordinate)
+      .collect::<BTreeSet<_>>();
+    let is_live = |c: &Coordinate| positions[c.0][c.1].status == Status::Live;
+
+    // the cells are the same either way when the shapes of both fleets fit them
+    let placements = match fit_ships(&ship_cells, &FleetType::Standard.ships())
+      .or_else(|| fit_ships(&ship_cells, &FleetType::Classic.ships()))
+    {
+      Some(placements) => placements,
+      None if !ship_cells.iter().any(is_live) => vec![],
+      None => return Err("ship cells don't make up whole ships".into()),
+    };
+
+    let ships = placements
+      .iter()
+      .map(|p| {
+        let cells = p.cells();
+        let ship = Ship {
+          rotation: p.rotation,
+          origin: p.origin,
+          alive: cells.iter().any(is_live),
+          ..Ship::new(p.ship_type.clone())
+        };
+        (ship, cells)
+      })
+      .collect::<Vec<_>>();
+    for (ship, cells) in &ships {
+      for c in cells {
+        positions[c.0][c.1].ship_id = Some(ship.id.clone());
+      }
+    }
+
+    Ok(Self {
+      ships: ships.into_iter().map(|(ship, _)| ship).collect(),
+      firing_status: BTreeMap::new(),
+      mines: BTreeSet::new(),
+      hazards: BTreeSet::new(),
+      flagship: None,
+      positions,
+    })
+  }
+}
+
+/// Places the ships at random without overlap, drawing them on the positions. The ships
+/// placed first can leave a crowded fleet no room for the rest, so once a ship misses
+/// every attempt the whole fleet is searched for room instead.
+fn place_ships<R: Rng>(
+  ship_types: &[ShipType],
+  positions: &mut Vec<Vec<Position>>,
+  rng: &mut R,
+) -> Vec<Ship> {
+  let empty = positions.clone();
+  let mut ships = vec![];
+  'fleet: for s_type in ship_types {
+    let mut ship = Ship::random(s_type.clone(), rng);
+    for _ in 0..PLACEMENT_ATTEMPTS {
+      let start_cords = get_random_coordinate(rng, ship.ship_type.size(ship.rotation));
+      if !ship.is_overlapping(positions, start_cords) {
+        // draw ship on to board
+        if ship.draw(positions, start_cords) {
+          ship.origin = start_cords;
+          ships.push(ship);
+          continue 'fleet;
+        }
+      } else {
+        ship = Ship::random(s_type.clone(), rng);
+      }
+    }
+    let mut steps = SEARCH_STEPS;
+    if let Some((searched, fleet)) = search_fleet(ship_types, &empty, rng, &mut steps) {
+      *positions = searched;
+      return fleet;
+    }
+    // a fleet that doesn't fit at all goes without the ship, FleetType::custom keeps
+    // those out of the game
+  }
+  ships
+}
+
+/// Places the ships one after the other where they fit, trying their places in random
+/// order and backtracking when the ships left have no room. Gives up on the fleet once
+/// it has tried as many places as there are steps.
+fn search_fleet<R: Rng>(
+  ship_types: &[ShipType],
+  positions: &[Vec<Position>],
+  rng: &mut R,
+  steps: &mut usize,
+) -> Option<(Vec<Vec<Position>>, Vec<Ship>)> {
+  let (s_type, rest) = match ship_types.split_first() {
+    Some(split) => split,
+    None => return Some((positions.to_vec(), vec![])),
+  };
+  let mut places = ROTATIONS
+    .iter()
+    .flat_map(|rotation| {
+      let (rows, cols) = s_type.size(*rotation);
+      (0..=ROWS - rows)
+        .flat_map(move |row| (0..=COLS - cols).map(move |col| (*rotation, (row, col))))
+    })
+    .collect::<Vec<_>>();
+  places.shuffle(rng);
+  let mut ship = Ship::new(s_type.clone());
+  for (rotation, origin) in places {
+    if *steps == 0 {
+      return None;
+    }
+    *steps -= 1;
+    ship.rotation = rotation;
+    if ship.is_overlapping(positions, origin) {
+      continue;
+    }
+    let mut placed = positions.to_vec();
+    if !ship.draw(&mut placed, origin) {
+      continue;
+    }
+    ship.origin = origin;
+    if let Some((placed, mut fleet)) = search_fleet(rest, &placed, rng, steps) {
+      fleet.insert(0, ship);
+      return Some((placed, fleet));
+    }
+  }
+  None
+}
+
+/// Finds placements of the ship types covering exactly the given cells, trying shapes on
+/// the first uncovered cell and backtracking when the rest can't be covered
+fn fit_ships(cells: &BTreeSet<Coordinate>, ship_types: &[ShipType]) -> Option<Vec<Placement>> {
+  let first = match cells.iter().next() {
+    Some(first) => *first,
+    None => return Some(vec![]),
+  };
+  for ship_type in ship_types {
+    for rotation in ROTATIONS.iter() {
+      let (rows, cols) = ship_type.size(*rotation);
+      for row in first.0.saturating_sub(rows - 1)..=first.0.min(ROWS - rows) {
+        for col in first.1.saturating_sub(cols - 1)..=first.1.min(COLS - cols) {
+          let placement = Placement {
+            ship_type: ship_type.clone(),
+            rotation: *rotation,
+            origin: (row, col),
+          };
+          let placed = placement.cells();
+          if !placed.contains(&first) || !placed.iter().all(|c| cells.contains(c)) {
+            continue;
+          }
+          let rest = cells.difference(&placed).cloned().collect();
+          if let Some(mut placements) = fit_ships(&rest, ship_types) {
+            placements.insert(0, placement);
+            return Some(placements);
+          }
+        }
+      }
+    }
+  }
+  None
+}
+
+/// A cell of a board
+#[derive(PartialEq, Clone)]
+pub struct Position {
+  status: Status,
+  coordinate: Coordinate,
+  ship_id: Option<String>,
+  /// hits a ship cell takes before it counts as hit, more than one where it's armored
+  hit_points: u8,
+}
+
+impl Position {
+  fn new(coordinate: Coordinate) -> Self {
+    Self {
+      coordinate,
+      status: Status::Space,
+      ship_id: None,
+      hit_points: 1,
+    }
+  }
+
+  pub fn get_status(&self, ship: Option<&Ship>) -> Status {
+    if ship.is_some() && !ship.unwrap().alive {
+      Status::Kill
+    } else {
+      self.status
+    }
+  }
+}
+
+impl Display for Position {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.status)
+  }
+}
+
+/// Where and how a ship sits on its board, enough to draw it again
+#[derive(PartialEq, Debug, Clone)]
+pub struct Placement {
+  pub ship_type: ShipType,
+  pub rotation: u16,
+  pub origin: Coordinate,
+}
+
+impl Placement {
+  /// The board cells the ship covers
+  pub fn cells(&self) -> BTreeSet<Coordinate> {
+    let shape = self.ship_type.get_shape(self.rotation);
+    let mut cells = BTreeSet::new();
+    for (x, row) in shape.iter().enumerate() {
+      for (y, status) in row.iter().enumerate() {
+        if *status == Status::Live {
+          cells.insert((self.origin.0 + x, self.origin.1 + y));
+        }
+      }
+    }
+    cells
+  }
+
+  /// Rows and columns the ship spans
+  pub fn size(&self) -> (usize, usize) {
+    self.ship_type.size(self.rotation)
+  }
+
+  /// The cell of the ship nearest the middle of its cells, the first in reading order
+  /// when several are as near
+  pub fn center(&self) -> Coordinate {
+    let cells = self.cells();
+    let count = cells.len();
+    let (rows, cols) = cells
+      .iter()
+      .fold((0, 0), |(rows, cols), (r, c)| (rows + r, cols + c));
+    // distances scaled by the number of cells to stay in whole numbers
+    let distance =
+      |&(r, c): &Coordinate| (r * count).abs_diff(rows).pow(2) + (c * count).abs_diff(cols).pow(2);
+    cells
+      .iter()
+      .copied()
+      .min_by_key(distance)
+      .unwrap_or(self.origin)
+  }
+
+  /// Turns the ship to the next of its rotations, in place
+  pub fn rotate(&mut self) {
+    let index = ROTATIONS
+      .iter()
+      .position(|r| *r == self.rotation)
+      .unwrap_or(0);
+    self.rotation = ROTATIONS[(index + 1) % ROTATIONS.len()];
+  }
+
+  /// The ship flipped to the other side of the board, left to right with `across` or top to
+  /// bottom otherwise, none when no rotation of the ship has the flipped shape
+  pub fn mirrored(&self, across: bool) -> Option<Placement> {
+    let shape = self.ship_type.get_shape(self.rotation);
+    let (rows, cols) = (shape.len(), shape[0].len());
+    let flipped = if across {
+      reverse_cols_of_rows(shape)
+    } else {
+      reverse_rows_of_cols(shape)
+    };
+    let rotation = *ROTATIONS
+      .iter()
+      .find(|r| self.ship_type.get_shape(**r) == flipped)?;
+    let (row, col) = self.origin;
+    Some(Placement {
+      ship_type: self.ship_type.clone(),
+      rotation,
+      origin: if across {
+        (row, COLS - col - cols)
+      } else {
+        (ROWS - row - rows, col)
+      },
+    })
+  }
+
+  /// Every way the ship fits on the board, turned each way it can be
+  pub fn all(ship_type: &ShipType) -> Vec<Placement> {
+    let mut placements = vec![];
+    for rotation in ROTATIONS {
+      let (rows, cols) = ship_type.size(rotation);
+      for row in 0..=ROWS - rows {
+        for col in 0..=COLS - cols {
+          placements.push(Placement {
+            ship_type: ship_type.clone(),
+            rotation,
+            origin: (row, col),
+          });
+        }
+      }
+    }
+    placements
+  }
+}
+
+/// A ship of a fleet, where it sits and whether it's still afloat
+#[derive(PartialEq, Clone)]
+pub struct Ship {
+  id: String,
+  rotation: u16,
+  origin: Coordinate,
+  alive: bool,
+  ship_type: ShipType,
+}
+
+impl Ship {
+  fn new(ship_type: ShipType) -> Self {
+    Self {
+      id: Uuid::new_v4().to_string(),
+      rotation: ROTATIONS[0],
+      origin: (0, 0),
+      alive: true,
+      ship_type,
+    }
+  }
+
+  /// A ship turned any which way
+  fn random<R: Rng>(ship_type: ShipType, rng: &mut R) -> Self {
+    Self {
+      rotation: ROTATIONS.choose(rng).map_or(0, |r| *r),
+      ..Self::new(ship_type)
+    }
+  }
+
+  pub fn ship_type(&self) -> &ShipType {
+    &self.ship_type
+  }
+
+  fn placement(&self) -> Placement {
+    Placement {
+      ship_type: self.ship_type.clone(),
+      rotation: self.rotation,
+      origin: self.origin,
+    }
+  }
+
+  fn shape(&self) -> ShipShape {
+    self.ship_type.get_shape(self.rotation)
+  }
+
+  fn is_overlapping(&self, positions: &[Vec<Position>], start_cord: Coordinate) -> bool {
+    let mut ship_found = false;
+    if !positions.is_empty() && !positions[0].is_empty() {
+      for (x, row) in self.shape().iter().enumerate() {
+        for (y, _) in row.iter().enumerate() {
+          // a cell off the board is as good as taken
+          let position = positions
+            .get(start_cord.0 + x)
+            .and_then(|r| r.get(start_cord.1 + y));
+          if position.is_none_or(|p| p.status == Status::Live) {
+            ship_found = true;
+          }
+        }
+      }
+    }
+    ship_found
+  }
+
+  fn draw(&self, positions: &mut [Vec<Position>], start_cord: Coordinate) -> bool {
+    let mut ship_drawn = false;
+    if !positions.is_empty() && !positions[0].is_empty() {
+      let shape = self.shape();
+
+      for (x, row) in shape.iter().enumerate() {
+        for (y, col) in row.iter().enumerate() {
+          let pos = positions
+            .get_mut(start_cord.0 + x)
+            .and_then(|r| r.get_mut(start_cord.1 + y));
+          if let (Status::Live, Some(pos)) = (*col, pos) {
+            pos.status = Status::Live;
+            pos.ship_id = Some(self.id.to_owned());
+            ship_drawn = true
+          }
+        }
+      }
+    }
+    ship_drawn
+  }
+}
+
+/// The kind of a ship, which gives its name and shape
+#[derive(Clone, PartialEq, Debug)]
+pub enum ShipType {
+  X,
+  V,
+  H,
+  I,
+  // the straight ships of the classic fleet
+  A,
+  B,
+  C,
+  S,
+  P,
+  Custom(CustomShip),
+}
+
+/// A ship of a shape the user drew
+#[derive(Clone, PartialEq, Debug)]
+pub struct CustomShip {
+  name: String,
+  shape: ShipShape,
+  ability: Option<Ability>,
+}
+
+/// Something a ship lets its side do while it is afloat
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Ability {
+  /// launches a strike on three cells in a line every few turns
+  Strike,
+  /// pings a cell every few turns for how far the nearest ship is
+  Ping,
+}
+
+impl Ability {
+  /// Turns between two uses of the ability, the one it is used on included
+  fn cooldown(&self) -> usize {
+    match self {
+      Ability::Strike => STRIKE_COOLDOWN,
+      Ability::Ping => PING_COOLDOWN,
+    }
+  }
+}
+
+impl Display for Ability {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Ability::Strike => write!(f, "strike"),
+      Ability::Ping => write!(f, "ping"),
+    }
+  }
+}
+
+impl FromStr for Ability {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "strike" => Ok(Ability::Strike),
+      "ping" => Ok(Ability::Ping),
+      _ => Err(format!("unknown ability {}", s)),
+    }
+  }
+}
+
+impl Display for ShipType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = match self {
+      ShipType::X => "X",
+      ShipType::V => "V",
+      ShipType::H => "H",
+      ShipType::I => "I",
+      ShipType::A => "A",
+      ShipType::B => "B",
+      ShipType::C => "C",
+      ShipType::S => "S",
+      ShipType::P => "P",
+      // the shape goes along so a match can be read back without the fleet file
+      ShipType::Custom(ship) => {
+        let rows = ship
+          .shape
+          .iter()
+          .map(|row| {
+            row
+              .iter()
+              .map(|s| if *s == Status::Live { '*' } else { '.' })
+              .collect::<String>()
+          })
+          .collect::<Vec<_>>();
+        write!(f, "{}={}", ship.name, rows.join("|"))?;
+        return match ship.ability {
+          Some(ability) => write!(f, "+{}", ability),
+          None => Ok(()),
+        };
+      }
+    };
+    write!(f, "{}", s)
+  }
+}
+
+impl FromStr for ShipType {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "X" => Ok(ShipType::X),
+      "V" => Ok(ShipType::V),
+      "H" => Ok(ShipType::H),
+      "I" => Ok(ShipType::I),
+      "A" => Ok(ShipType::A),
+      "B" => Ok(ShipType::B),
+      "C" => Ok(ShipType::C),
+      "S" => Ok(ShipType::S),
+      "P" => Ok(ShipType::P),
+      _ => match s.split_once('=') {
+        Some((name, rest)) => {
+          let (shape, ability) = match rest.split_once('+') {
+            Some((shape, ability)) => (shape, Some(ability.parse()?)),
+            None => (rest, None),
+          };
+          let ship = ShipType::custom(name, &shape.split('|').collect::<Vec<_>>())?;
+          Ok(match ability {
+            Some(ability) => ship.with_ability(ability),
+            None => ship,
+          })
+        }
+        None => Err(format!("unknown ship type {}", s)),
+      },
+    }
+  }
+}
+
+impl ShipType {
+  /// A ship drawn as rows of `*` for its cells and `.` for the gaps between them
+  pub fn custom(name: &str, rows: &[&str]) -> Result<Self, String> {
+    // the name is written down with the fleet, among spaces, `@` and `=`
+    if name.is_empty()
+      || !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+      return Err(format!("invalid ship name {:?}", name));
+    }
+    let shape = rows
+      .iter()
+      .map(|row| {
+        row
+          .chars()
+          .map(|c| match c {
+            '*' => Ok(Status::Live),
+            '.' => Ok(Status::Space),
+            _ => Err(format!("the {} has an unknown cell {:?}", name, c)),
+          })
+          .collect::<Result<Vec<_>, _>>()
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    let cols = shape.first().map_or(0, |row| row.len());
+    if cols == 0 || shape.iter().any(|row| row.len() != cols) {
+      return Err(format!("the {} isn't drawn as rows of equal length", name));
+    }
+    if !shape.iter().flatten().any(|s| *s == Status::Live) {
+      return Err(format!("the {} has no cells", name));
+    }
+    // turned either way, the ship has to fit the board
+    if shape.len().max(cols) > ROWS.min(COLS) {
+      return Err(format!(
+        "the {} spans {}x{} cells, more than fits the board",
+        name,
+        shape.len(),
+        cols
+      ));
+    }
+    Ok(ShipType::Custom(CustomShip {
+      name: name.into(),
+      shape,
+      ability: None,
+    }))
+  }
+
+  /// The ship given an ability, which only the ships the user drew can be given, the others
+  /// come with theirs
+  pub fn with_ability(mut self, ability: Ability) -> Self {
+    if let ShipType::Custom(ref mut ship) = self {
+      ship.ability = Some(ability);
+    }
+    self
+  }
+
+  pub fn ability(&self) -> Option<Ability> {
+    match self {
+      // the carriers strike, the scout and the submarine ping
+      ShipType::H | ShipType::A => Some(Ability::Strike),
+      ShipType::I | ShipType::S => Some(Ability::Ping),
+      ShipType::Custom(ship) => ship.ability,
+      _ => None,
+    }
+  }
+
+  fn get_shape(&self, rotation: u16) -> ShipShape {
+    let shape = match *self {
+      ShipType::X => vec![
+        vec![Status::Live, Status::Space, Status::Live],
+        vec![Status::Space, Status::Live, Status::Space],
+        vec![Status::Live, Status::Space, Status::Live],
+      ],
+      ShipType::V => vec![
+        vec![Status::Live, Status::Space, Status::Live],
+        vec![Status::Live, Status::Space, Status::Live],
+        vec![Status::Space, Status::Live, Status::Space],
+      ],
+      ShipType::H => vec![
+        vec![Status::Live, Status::Space, Status::Live],
+        vec![Status::Live, Status::Live, Status::Live],
+        vec![Status::Live, Status::Space, Status::Live],
+      ],
+      ShipType::I => vec![
+        vec![Status::Space, Status::Live, Status::Space],
+        vec![Status::Space, Status::Live, Status::Space],
+        vec![Status::Space, Status::Live, Status::Space],
+      ],
+      // lying across the board, standing up when turned
+      ShipType::A => vec![vec![Status::Live; 5]],
+      ShipType::B => vec![vec![Status::Live; 4]],
+      ShipType::C | ShipType::S => vec![vec![Status::Live; 3]],
+      ShipType::P => vec![vec![Status::Live; 2]],
+      ShipType::Custom(ref ship) => ship.shape.clone(),
+    };
+
+    match rotation {
+      180 => reverse_cols_of_rows(transpose(shape)),
+      270 => reverse_rows_of_cols(reverse_cols_of_rows(shape)),
+      360 => reverse_rows_of_cols(transpose(shape)),
+      _ => shape,
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    match *self {
+      ShipType::X => "Fighter",
+      ShipType::V => "Destroyer",
+      ShipType::H => "Carrier",
+      ShipType::I => "Scout",
+      ShipType::A => "Aircraft carrier",
+      ShipType::B => "Battleship",
+      ShipType::C => "Cruiser",
+      ShipType::S => "Submarine",
+      ShipType::P => "Patrol boat",
+      ShipType::Custom(ref ship) => &ship.name,
+    }
+  }
+
+  /// Number of cells the ship takes up
+  fn cells(&self) -> usize {
+    self
+      .get_shape(0)
+      .iter()
+      .flatten()
+      .filter(|s| **s == Status::Live)
+      .count()
+  }
+
+  /// Rows and columns of the shape turned by the rotation
+  fn size(&self, rotation: u16) -> (usize, usize) {
+    let shape = self.get_shape(rotation);
+    (shape.len(), shape[0].len())
+  }
+}
+
+/// The cells of the heatmap, hottest first and ties in random order
+fn hottest_of<R: Rng>(heat: BTreeMap<Coordinate, usize>, rng: &mut R) -> Vec<Coordinate> {
+  let mut cells = heat.into_iter().collect::<Vec<_>>();
+  cells.shuffle(rng);
+  cells.sort_by(|(_, a), (_, b)| b.cmp(a));
+  cells.into_iter().map(|(cell, _)| cell).collect()
+}
+
+/// The cells whose status differs between two snapshots of a board
+fn changes(before: &[Vec<Position>], after: &[Vec<Position>]) -> Vec<CellChange> {
+  before
+    .iter()
+    .flatten()
+    .zip(after.iter().flatten())
+    .filter(|(b, a)| b.status != a.status)
+    .map(|(b, a)| CellChange {
+      coordinate: a.coordinate,
+      from: b.status,
+      to: a.status,
+    })
+    .collect()
+}
+
+/// Turns the requested shots into a salvo, refusing cells requested twice instead of
+/// quietly firing fewer shots than asked for
+pub fn collect_salvo(shots: &[Coordinate]) -> Result<BTreeSet<Coordinate>, SalvoError> {
+  let mut salvo = BTreeSet::new();
+  for shot in shots {
+    if !salvo.insert(*shot) {
+      return Err(SalvoError::Duplicate(*shot));
+    }
+  }
+  Ok(salvo)
+}
+
+/// Formats a coordinate like the physical game does, column as a letter and row as a number
+pub fn a1_notation((row, col): Coordinate) -> String {
+  format!("{}{}", (b'A' + col as u8) as char, row + 1)
+}
+
+/// Reads a cell back from its A1 notation, either case
+pub fn parse_a1(text: &str) -> Option<Coordinate> {
+  let mut chars = text.chars();
+  let col = chars.next()?.to_ascii_uppercase();
+  let row = chars.as_str().parse::<usize>().ok()?;
+  let col = (col as usize).checked_sub('A' as usize)?;
+  if (1..=ROWS).contains(&row) && col < COLS {
+    Some((row - 1, col))
+  } else {
+    None
+  }
+}
+
+/// Checks that the ships of a fleet are all on the board and clear of each other
+pub fn check_fleet(fleet: &[Placement]) -> Result<(), String> {
+  let mut covered = BTreeSet::new();
+  for placement in fleet {
+    let cells = placement.cells();
+    let name = placement.ship_type.name();
+    if cells.iter().any(|(r, c)| *r >= ROWS || *c >= COLS) {
+      return Err(format!("The {} is off the board", name));
+    }
+    if !cells.is_disjoint(&covered) {
+      return Err(format!("The {} overlaps another ship", name));
+    }
+    covered.extend(cells);
+  }
+  Ok(())
+}
+
+/// A fleet of the type placed at random, as the computer deals them
+pub fn random_fleet<R: Rng>(fleet_type: &FleetType, rng: &mut R) -> Vec<Placement> {
+  Board::new(true, fleet_type, rng).placements()
+}
+
+/// A random top left corner for something of the given rows and columns to fit the board
+pub(crate) fn get_random_coordinate<R: Rng>(
+  rng: &mut R,
+  (rows, cols): (usize, usize),
+) -> Coordinate {
+  (
+    rng.gen_range(0..=ROWS - rows),
+    rng.gen_range(0..=COLS - cols),
+  )
+}
+/**
+ * transpose a 2D char array.
+ */
+fn transpose(inp: ShipShape) -> ShipShape {
+  if inp.is_empty() {
+    //empty or unset array, nothing do to here
+    return inp;
+  }
+
+  // the rows become the columns, which a shape that isn't square needs a new grid for
+  let mut out = vec![vec![Status::Space; inp.len()]; inp[0].len()];
+
+  for (x, cols) in inp.iter().enumerate() {
+    for (y, _) in cols.iter().enumerate() {
+      out[y][x] = inp[x][y];
+    }
+  }
+  out
+}
+
+/**
+ * reverse columns of each rows in a 2d array.
+ */
+fn reverse_cols_of_rows(inp: ShipShape) -> ShipShape {
+  if inp.is_empty() {
+    //empty or unset array, nothing do to here
+    return inp;
+  }
+  let mut out = inp.clone();
+
+  for (x, cols) in inp.iter().enumerate() {
+    for (y, _) in cols.iter().enumerate() {
+      out[x][cols.len() - y - 1] = inp[x][y];
+    }
+  }
+  out
+}
+
+/**
+ * reverse rows of each column in a 2d array.
+ */
+fn reverse_rows_of_cols(inp: ShipShape) -> ShipShape {
+  if inp.is_empty() {
+    //empty or unset array, nothing do to here
+    return inp;
+  }
+
+  let mut out = inp.clone();
+
+  for (x, cols) in inp.iter().enumerate() {
+    for (y, _) in cols.iter().enumerate() {
+      out[inp.len() - x - 1][y] = inp[x][y];
+    }
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Rows and columns of the shapes of the standard fleet
+  const SHIP_SIZE: usize = 3;
+
+  #[test]
+  fn test_game_shot_budget() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    assert_eq!(game.shot_budget(0), 1);
+
+    game.rule = Rule::Fury;
+
+    assert_eq!(game.shot_budget(0), 4);
+    assert_eq!(game.shot_budget(1), 4);
+
+    game.rule = Rule::Charge;
+
+    assert_eq!(game.shot_budget(0), 1);
+    assert_eq!(game.shot_budget(1), 1);
+    assert_eq!(game.min_shots(0), 1);
+
+    game.rule = Rule::Salvo;
+
+    assert_eq!(game.shot_budget(0), 4);
+    assert_eq!(game.min_shots(0), 4);
+    assert_eq!(game.min_shots(1), 4);
+  }
+
+  #[test]
+  fn test_game_first() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 1);
+    assert!(!game.is_user_turn());
+    assert_eq!(game.first(), 1);
+    game.set_balanced(true);
+    assert_eq!(game.shot_budget(0), 2);
+    assert_eq!(game.shot_budget(1), 1);
+
+    game.bot_fire();
+    // the round goes on until the player firing second hands the turn back
+    assert_eq!(game.round(), 1);
+    game.fire(&[(0, 0), (0, 1)].into(), false);
+    assert_eq!(game.round(), 2);
+    assert_eq!(game.shot_budget(0), 1);
+    // the first shot was fired already
+    game.set_first(0);
+    assert_eq!(game.first(), 1);
+
+    // the starting player comes back with the turns, and with the seed
+    let fleets = [
+      game.player().player_board().placements(),
+      game.computer().player_board().placements(),
+    ];
+    let restored = Game::restore(Rule::Default, Difficulty::Easy, fleets, game.timeline());
+    assert_eq!(restored.first(), 1);
+    assert_eq!(restored.timeline().len(), 2);
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 4);
+    game.set_first(1);
+    game.reseed(5);
+    assert!(!game.is_user_turn());
+    assert_eq!(game.run_back().first(), 1);
+    assert_eq!(First::Bot.player(game.cosmetic_rng()), 1);
+  }
+
+  #[test]
+  fn test_game_salvo() {
+    let mut game = Game::new(Rule::Salvo, Difficulty::Hard, 0);
+    let salvo = |shots: &[Coordinate]| shots.iter().cloned().collect::<BTreeSet<_>>();
+
+    assert_eq!(
+      game.validate_salvo(&salvo(&[(0, 0), (1, 1)])),
+      Err(SalvoError::UnderBudget(4))
+    );
+    // an illegal cell is told apart while the salvo is still short
+    assert_eq!(
+      game.validate_salvo(&salvo(&[(0, ROWS)])),
+      Err(SalvoError::Illegal((0, ROWS), IllegalShot::OutOfBounds))
+    );
+    game.fire(&salvo(&[(0, 0), (1, 1), (2, 2), (3, 3)]), false);
+    // the computer keeps to the same rule
+    game.bot_fire();
+    assert_eq!(game.timeline()[1].shots.len(), game.shot_budget(1));
+  }
+
+  #[test]
+  fn test_game_chain() {
+    let mut game = Game::new(Rule::Chain, Difficulty::Easy, 0);
+    let ships = game
+      .computer()
+      .player_board()
+      .placements()
+      .iter()
+      .flat_map(|p| p.cells())
+      .collect::<BTreeSet<_>>();
+    let hit = *ships.iter().next().unwrap();
+    let miss = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .find(|cell| !ships.contains(cell))
+      .unwrap();
+
+    game.fire(&[hit].iter().cloned().collect(), false);
+    assert!(game.is_user_turn());
+    assert_eq!(game.round(), 1);
+    game.fire(&[miss].iter().cloned().collect(), false);
+    assert!(!game.is_user_turn());
+    // the computer keeps firing for as long as it hits
+    while !game.is_user_turn() {
+      game.bot_fire();
+    }
+    assert_eq!(game.round(), 2);
+    assert_eq!(game.shot_budget(0), 1);
+  }
+
+  #[test]
+  fn test_game_drift() {
+    let mut game = Game::seeded(Rule::Drift, Difficulty::Hard, FleetType::Standard, 7);
+    let block = (4..7)
+      .flat_map(|r| (4..7).map(move |c| (r, c)))
+      .collect::<BTreeSet<_>>();
+    let mut chances = vec![];
+    for _ in 0..6 {
+      let shot = *game.legal_shots(0).intersection(&block).next().unwrap();
+      chances.push(game.drift_chance(0, shot));
+      game.fire(&[shot].iter().cloned().collect(), false);
+      game.bot_fire();
+    }
+    assert_eq!(chances, vec![0, 20, 40, 60, 60, 60]);
+
+    let drifts = game
+      .timeline()
+      .iter()
+      .flat_map(|t| t.drifts.clone())
+      .collect::<Vec<_>>();
+    assert!(!drifts.is_empty());
+    for (aimed, landed) in &drifts {
+      assert_eq!(aimed.0.abs_diff(landed.0) + aimed.1.abs_diff(landed.1), 1);
+    }
+    // a replay of the turns drifts the same shots the same way
+    let replayed = Game::restore(
+      Rule::Drift,
+      Difficulty::Hard,
+      [
+        game.player().player_board().placements(),
+        game.computer().player_board().placements(),
+      ],
+      game.timeline(),
+    );
+    assert_eq!(replayed.timeline(), game.timeline());
+    assert_eq!(
+      Game::new(Rule::Default, Difficulty::Easy, 0).drift_chance(0, (0, 0)),
+      0
+    );
+  }
+
+  #[test]
+  fn test_game_mines() {
+    let mut game = Game::seeded(Rule::Mines, Difficulty::Hard, FleetType::Standard, 3);
+    let mines = game.computer().player_board().mines.clone();
+    assert_eq!(mines.len(), MINES);
+    assert!(mines
+      .iter()
+      .all(|c| game.computer().player_board().status_at(*c) == Status::Space));
+    assert_eq!(game.player().player_board().mines.len(), MINES);
+
+    let mine = *mines.iter().next().unwrap();
+    let msg = game.fire(&[mine].iter().cloned().collect(), false);
+    let turn = &game.timeline()[0];
+    assert_eq!(turn.response[&mine], Status::Mine);
+    let (&blasted, _) = turn.blasts.iter().next().unwrap();
+    assert!(msg.contains(&format!("blew up your ship at {}", a1_notation(blasted))));
+    assert_eq!(game.player().player_board().status_at(blasted), Status::Hit);
+    // the computer saw it go off
+    assert_eq!(
+      game.computer().opponent_board().status_at(blasted),
+      Status::Hit
+    );
+    assert!(game.check_shot(0, mine).is_err());
+    assert!(game.set_mines(0, &mines).is_err());
+
+    let replayed = Game::restore(
+      Rule::Mines,
+      Difficulty::Hard,
+      [
+        game.player().player_board().placements(),
+        game.computer().player_board().placements(),
+      ],
+      &[],
+    )
+    .with_setup_of(&game)
+    .replay_turns(game.timeline());
+    assert_eq!(replayed.timeline(), game.timeline());
+    assert_eq!(replayed.mines(1), &mines);
+    assert!(Game::new(Rule::Default, Difficulty::Easy, 0)
+      .computer()
+      .player_board()
+      .mines
+      .is_empty());
+  }
+
+  #[test]
+  fn test_game_set_mines() {
+    let mut game = Game::seeded(Rule::Mines, Difficulty::Hard, FleetType::Standard, 3);
+    let computer = game.mines(1).clone();
+    let board = game.player().player_board();
+    let empty = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .filter(|c| board.status_at(*c) == Status::Space)
+      .take(MINES)
+      .collect::<BTreeSet<_>>();
+    let ship = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .find(|c| board.status_at(*c) == Status::Live)
+      .unwrap();
+    game.set_mines(0, &empty).unwrap();
+    assert_eq!(game.mines(0), &empty);
+    // placing the user's fleet leaves the computer's mines where it hid them
+    game
+      .place_fleet(&game.player().player_board().placements())
+      .unwrap();
+    assert_eq!(game.mines(1), &computer);
+
+    let taken = empty
+      .iter()
+      .skip(1)
+      .copied()
+      .chain(std::iter::once(ship))
+      .collect();
+    assert!(game.set_mines(0, &taken).is_err());
+    assert!(game
+      .set_mines(0, &[(ROWS, 0), (0, COLS)].iter().copied().collect())
+      .is_err());
+    assert!(game
+      .set_mines(0, &empty.iter().take(1).copied().collect())
+      .is_err());
+    assert!(Game::new(Rule::Default, Difficulty::Easy, 0)
+      .set_mines(0, &empty)
+      .is_err());
+  }
+
+  #[test]
+  fn test_game_hazards() {
+    let mut game = Game::seeded(Rule::Hazards, Difficulty::Hard, FleetType::Standard, 3);
+    let hazards = game.computer().player_board().hazards.clone();
+    assert_eq!(hazards.len(), HAZARDS);
+    assert!(hazards
+      .iter()
+      .all(|c| game.computer().player_board().status_at(*c) == Status::Space));
+    assert_eq!(game.player().player_board().hazards.len(), HAZARDS);
+
+    let hazard = *hazards.iter().next().unwrap();
+    let msg = game.fire(&[hazard].iter().cloned().collect(), false);
+    assert!(msg.contains(&format!(
+      "Your shot at {} set off a drifting mine.",
+      a1_notation(hazard)
+    )));
+    assert_eq!(game.timeline()[0].response[&hazard], Status::Miss);
+    // the others drifted a cell, unless they ran into a ship
+    let drifted = game.computer().player_board().hazards.clone();
+    assert!(drifted.len() < HAZARDS);
+    assert!(drifted.iter().all(|&(row, col)| hazards
+      .iter()
+      .any(|&(r, c)| (r, c) != hazard && row.abs_diff(r) + col.abs_diff(c) <= 1)));
+
+    game.bot_fire();
+    let replayed = Game::restore(
+      Rule::Hazards,
+      Difficulty::Hard,
+      [
+        game.player().player_board().placements(),
+        game.computer().player_board().placements(),
+      ],
+      game.timeline(),
+    );
+    assert_eq!(replayed.timeline(), game.timeline());
+    for player in 0..2 {
+      assert_eq!(
+        replayed.players[player].player_board().hazards,
+        game.players[player].player_board().hazards
+      );
+    }
+
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap();
+    let mut game = Game::restore_seeded(
+      Rule::Hazards,
+      Difficulty::Hard,
+      7,
+      [fleet.clone(), fleet],
+      &[],
+    );
+    // B1 is boxed in by the X, the mine there can only drift onto it
+    game.players[0].player_board_mut().hazards = [(0, 1)].iter().cloned().collect();
+    let msg = game.fire(&[(9, 9)].iter().cloned().collect(), false);
+    let hit = [(0, 0), (0, 2), (1, 1)]
+      .iter()
+      .copied()
+      .find(|c| game.player().player_board().status_at(*c) == Status::Hit)
+      .unwrap();
+    assert!(msg.contains(&format!(
+      "A drifting mine hit your ship at {}.",
+      a1_notation(hit)
+    )));
+    assert_eq!(game.computer().opponent_board().status_at(hit), Status::Hit);
+    assert!(game.player().player_board().hazards.is_empty());
+  }
+
+  #[test]
+  fn test_game_night() {
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap();
+    let mut game = Game::restore_seeded(
+      Rule::Night,
+      Difficulty::Hard,
+      7,
+      [fleet.clone(), fleet],
+      &[],
+    );
+    let shot = |cell: Coordinate| [cell].iter().cloned().collect::<BTreeSet<_>>();
+    for cell in &[(9, 9), (9, 8)] {
+      assert!(!game.is_night());
+      game.fire(&shot(*cell), false);
+      game.fire(&shot(*cell), true);
+    }
+
+    // night falls on the third round
+    assert!(game.is_night());
+    let msg = game.fire(&shot((0, 0)), false);
+    assert!(msg.starts_with("Splash heard at A1."));
+    assert_eq!(
+      game.player().opponent_board().status_at((0, 0)),
+      Status::Splash
+    );
+    assert_eq!(game.shots_and_hits(0), (3, 0));
+    assert!(game.in_the_dark(4));
+    assert!(game.check_shot(0, (0, 0)).is_err());
+    game.fire(&shot((0, 2)), true);
+    assert_eq!(
+      game.computer().opponent_board().status_at((0, 2)),
+      Status::Splash
+    );
+    // the computer doesn't know it hit either
+    assert!(game.computer().memory().targets().is_empty());
+
+    // and the shots come to light two turns on
+    assert!(!game.is_night());
+    let msg = game.fire(&shot((9, 7)), false);
+    assert!(msg.contains("Daylight shows what your night shots at A1 found. You have 1 hit."));
+    assert_eq!(
+      game.player().opponent_board().status_at((0, 0)),
+      Status::Hit
+    );
+    assert_eq!(game.shots_and_hits(0), (4, 1));
+    assert!(!game.in_the_dark(4));
+    let msg = game.fire(&shot((9, 7)), true);
+    assert!(msg.contains("the computer's night shots at C1 found"));
+    assert_eq!(
+      game.computer().opponent_board().status_at((0, 2)),
+      Status::Hit
+    );
+    assert!(!game.computer().memory().targets().is_empty());
+
+    let replayed = Game::restore(
+      Rule::Night,
+      Difficulty::Hard,
+      [
+        game.player().player_board().placements(),
+        game.computer().player_board().placements(),
+      ],
+      game.timeline(),
+    );
+    assert_eq!(replayed.timeline(), game.timeline());
+    assert_eq!(
+      replayed.player().opponent_board().to_string(),
+      game.player().opponent_board().to_string()
+    );
+  }
+
+  #[test]
+  fn test_game_critical() {
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap();
+    assert_eq!(fleet[0].center(), (1, 1));
+    assert_eq!(fleet[1].center(), (1, 3));
+    assert_eq!(fleet[3].center(), (6, 6));
+
+    let mut game = Game::restore(
+      Rule::Critical,
+      Difficulty::Easy,
+      [fleet.clone(), fleet.clone()],
+      &[],
+    );
+    let msg = game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    assert!(msg.contains("critical hit on the center sank the Fighter"));
+    let turn = &game.timeline()[0];
+    assert_eq!(turn.criticals, vec![ShipType::X]);
+    assert_eq!(turn.response.len(), 5);
+    assert!(turn.response.values().all(|s| *s == Status::Kill));
+    assert_eq!(
+      game.take_events(),
+      vec![GameEvent::CriticalSink {
+        by: 0,
+        ship_type: ShipType::X
+      }]
+    );
+    game.bot_fire();
+    // off the center a hit is a hit
+    game.fire(&[(0, 3)].iter().cloned().collect(), false);
+    assert_eq!(game.timeline()[2].response[&(0, 3)], Status::Hit);
+    assert_eq!(game.criticals(0), 1);
+
+    let mut game = Game::restore(Rule::Default, Difficulty::Easy, [fleet.clone(), fleet], &[]);
+    game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    assert_eq!(game.timeline()[0].response[&(1, 1)], Status::Hit);
+  }
+
+  #[test]
+  fn test_game_flagship() {
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap();
+    let mut game = Game::restore(
+      Rule::Flagship,
+      Difficulty::Easy,
+      [fleet.clone(), fleet.clone()],
+      &[],
+    );
+    // both sides value the same fleet the same
+    assert!(game.flagship(1).is_some());
+    assert_eq!(game.flagship(0), game.flagship(1));
+    assert!(game.set_flagship(1, 4).is_err());
+    game.set_flagship(1, 3).unwrap();
+    assert_eq!(
+      game.computer().player_board().flagship(),
+      Some(&ShipType::I)
+    );
+
+    let cells = fleet[3].cells().into_iter().collect::<Vec<_>>();
+    let mut msg = String::new();
+    for (i, cell) in cells.iter().enumerate() {
+      msg = game.fire(&[*cell].iter().cloned().collect(), false);
+      if i + 1 < cells.len() {
+        assert!(!game.is_won());
+        game.fire(&[(9, i)].iter().cloned().collect(), true);
+      }
+    }
+    assert_eq!(msg, "You sank the flagship, the Scout. You won 🙌");
+    assert_eq!(game.winner(), Some(0));
+    assert_eq!(game.fleet(1).alive, 3);
+    assert_eq!(
+      game.take_events().last(),
+      Some(&GameEvent::FlagshipSunk {
+        by: 0,
+        ship_type: ShipType::I
+      })
+    );
+    assert!(game.set_flagship(0, 0).is_err());
+
+    let text = crate::notation::write(&game);
+    assert!(text.contains(&format!(
+      "[Flagships \"{} 4\"]",
+      game.flagship(0).unwrap() + 1
+    )));
+    let restored = crate::notation::parse(&text).unwrap();
+    assert_eq!(restored.flagship(1), Some(3));
+    assert_eq!(restored.winner(), Some(0));
+    assert_eq!(game.run_back().flagship(1), Some(3));
+
+    // other rules have no flagship to lose
+    let game = Game::restore(Rule::Default, Difficulty::Easy, [fleet.clone(), fleet], &[]);
+    assert_eq!(game.flagship(0), None);
+  }
+
+  #[test]
+  fn test_memory_flagship_heatmap() {
+    let memory = BotMemory::default();
+    let heat = memory.heatmap();
+    let flagship = memory.flagship_heatmap();
+    // only the cells the smallest ships can take up get hotter
+    assert!(flagship.iter().all(|(cell, h)| *h >= heat[cell]));
+    assert!(flagship.values().sum::<usize>() > heat.values().sum::<usize>());
+  }
+
+  #[test]
+  fn test_game_armored() {
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap();
+    let mut game = Game::restore(
+      Rule::Armored,
+      Difficulty::Hard,
+      [fleet.clone(), fleet.clone()],
+      &[],
+    );
+    assert!(game.computer().player_board().is_armored((1, 1)));
+    assert!(!game.computer().player_board().is_armored((0, 1)));
+    let hash = game.state_hash();
+
+    let msg = game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    assert!(msg.contains("The armor at B2 took the hit"));
+    assert_eq!(game.timeline()[0].response[&(1, 1)], Status::Dent);
+    assert_ne!(game.state_hash(), hash);
+    assert!(!game.computer().player_board().is_armored((1, 1)));
+    assert_eq!(game.fleet(1).cells_remaining, game.fleet(0).cells_remaining);
+    assert_eq!(game.shots_and_hits(0), (1, 1));
+    assert!(game.check_shot(0, (1, 1)).is_ok());
+
+    // the computer goes back to a dent first
+    game.fire(&[(1, 1)].iter().cloned().collect(), true);
+    game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    assert_eq!(game.timeline()[2].response[&(1, 1)], Status::Hit);
+    game.bot_fire();
+    assert_eq!(game.timeline()[3].shots, [(1, 1)].iter().cloned().collect());
+    assert_eq!(game.timeline()[3].response[&(1, 1)], Status::Hit);
+
+    let game = Game::restore(Rule::Default, Difficulty::Easy, [fleet.clone(), fleet], &[]);
+    assert!(!game.computer().player_board().is_armored((1, 1)));
+  }
+
+  #[test]
+  fn test_game_sonar() {
+    assert_eq!(write_power_up((PowerUp::Sonar, (3, 2))), "C4?");
+    assert_eq!(parse_power_up("c4?"), Some((PowerUp::Sonar, (3, 2))));
+    assert_eq!(parse_power_up("C4"), None);
+
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap();
+    let mut game = Game::restore(Rule::Default, Difficulty::Easy, [fleet.clone(), fleet], &[]);
+    let msg = game.use_power_up(PowerUp::Sonar, (1, 1), false).unwrap();
+    assert_eq!(msg, "Your sonar picks up a ship around B2.");
+    let turn = &game.timeline()[0];
+    assert!(turn.shots.is_empty());
+    assert_eq!(turn.response.len(), 9);
+    assert!(turn.response.values().all(|s| *s == Status::Echo));
+    assert!(!game.is_user_turn());
+    // nothing was damaged, and the area can still be fired upon
+    assert_eq!(game.fleet(1).cells_remaining, game.fleet(0).cells_remaining);
+    assert!(game.check_shot(0, (1, 1)).is_ok());
+
+    // in a corner only the cells on the board are swept
+    let msg = game.use_power_up(PowerUp::Sonar, (9, 9), true).unwrap();
+    assert_eq!(msg, "Computer's sonar finds no ship around J10.");
+    assert_eq!(game.timeline()[1].response.len(), 4);
+    assert!(game.computer().memory().excluded.contains(&(8, 8)));
+    assert_eq!(
+      game.use_power_up(PowerUp::Sonar, (5, 5), false),
+      Err("No sonar left this match".into())
+    );
+    game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    assert_eq!(game.timeline()[2].response[&(1, 1)], Status::Hit);
+
+    // the computer falls back on its own sonar once it runs out of leads
+    let mut game = Game::seeded(Rule::Default, Difficulty::Hard, FleetType::Standard, 7);
+    while !game.is_won() {
+      if game.is_user_turn() {
+        let shots = game.legal_shots(0).into_iter().take(1).collect();
+        game.fire(&shots, false);
+      } else {
+        game.bot_fire();
+      }
+    }
+    let sonars = game
+      .timeline()
+      .iter()
+      .filter(|t| matches!(t.power_up, Some((PowerUp::Sonar, _))))
+      .collect::<Vec<_>>();
+    assert_eq!(sonars.len(), 1);
+    assert_eq!(sonars[0].player, 1);
+    let fleets = [
+      game.player().player_board().placements(),
+      game.computer().player_board().placements(),
+    ];
+    let restored =
+      Game::restore_seeded(Rule::Default, Difficulty::Hard, 7, fleets, game.timeline());
+    assert_eq!(restored.timeline(), game.timeline());
+  }
+
+  #[test]
+  fn test_game_airstrike() {
+    assert_eq!(
+      write_power_up((PowerUp::Airstrike(Line::Column), (0, 9))),
+      "J1|"
+    );
+    assert_eq!(
+      parse_power_up("A3-"),
+      Some((PowerUp::Airstrike(Line::Row), (2, 0)))
+    );
+    assert_eq!(
+      "airstrike-row".parse::<PowerUp>(),
+      Ok(PowerUp::Airstrike(Line::Row))
+    );
+
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap();
+    let mut game = Game::restore(
+      Rule::Default,
+      Difficulty::Easy,
+      [fleet.clone(), fleet.clone()],
+      &[],
+    );
+    let strike = PowerUp::Airstrike(Line::Column);
+    assert_eq!(
+      game.check_power_up(0, strike),
+      Err("Sink 2 more ships to earn an airstrike".into())
+    );
+    for cell in fleet[..2].iter().flat_map(|p| p.cells()) {
+      game.fire(&[cell].iter().cloned().collect(), false);
+      game.bot_fire();
+    }
+    assert_eq!(game.power_ups_left(0, strike), 1);
+
+    // the whole column at once, well over the single shot of the rule
+    let legal = game.legal_shots(0);
+    let msg = game.use_power_up(strike, (0, 9), false).unwrap();
+    assert!(msg.starts_with("Your airstrike on the column through J1. "));
+    let turn = game.timeline().last().unwrap();
+    assert_eq!(turn.power_up, Some((strike, (0, 9))));
+    assert_eq!(
+      turn.shots,
+      (0..ROWS)
+        .map(|r| (r, 9))
+        .filter(|c| legal.contains(c))
+        .collect()
+    );
+    assert!(!game.is_user_turn());
+    game.bot_fire();
+    assert_eq!(
+      game.check_power_up(0, PowerUp::Airstrike(Line::Row)),
+      Err("Sink 2 more ships to earn an airstrike".into())
+    );
+
+    let restored = Game::restore(
+      Rule::Default,
+      Difficulty::Easy,
+      [fleet.clone(), fleet],
+      game.timeline(),
+    );
+    assert_eq!(restored.timeline(), game.timeline());
+  }
+
+  #[test]
+  fn test_game_strike() {
+    assert_eq!(write_power_up((PowerUp::Strike(Line::Row), (3, 2))), "C4_");
+    assert_eq!(
+      parse_power_up("C4:"),
+      Some((PowerUp::Strike(Line::Column), (3, 2)))
+    );
+    assert_eq!(
+      PowerUp::Strike(Line::Column).cells((0, 4)),
+      vec![(0, 4), (1, 4)]
+    );
+
+    let fleet =
+      crate::notation::parse_fleet("Carrier=***+strike@A10/90 X@A1/90 V@D1/90 I@F6/90").unwrap();
+    let fleets = [fleet.clone(), fleet];
+    let mut game = Game::restore_seeded(Rule::Default, Difficulty::Easy, 7, fleets.clone(), &[]);
+    let strike = PowerUp::Strike(Line::Row);
+    assert_eq!(game.cooldown(0, Ability::Strike), Some(0));
+    let msg = game.use_power_up(strike, (4, 9), false).unwrap();
+    assert!(msg.starts_with("Your strike on the row through J5. "));
+    let turn = game.timeline().last().unwrap();
+    assert_eq!(turn.shots, [(4, 8), (4, 9)].iter().copied().collect());
+    game.bot_fire();
+    assert_eq!(
+      game.check_power_up(0, PowerUp::Strike(Line::Column)),
+      Err("The next strike is ready in 4 turns".into())
+    );
+
+    // sinking the computer's carrier takes its strikes away
+    for cell in [(9, 0), (9, 1), (9, 2), (0, 9)].iter() {
+      game.fire(&[*cell].iter().cloned().collect(), false);
+      game.bot_fire();
+    }
+    assert_eq!(game.power_ups_left(0, strike), 1);
+    assert_eq!(
+      game.check_power_up(1, strike),
+      Err("The ships that can strike were sunk".into())
+    );
+    // the carrier of the standard fleet strikes too
+    assert_eq!(ShipType::H.ability(), Some(Ability::Strike));
+    assert_eq!(
+      Game::new(Rule::Default, Difficulty::Easy, 0).check_power_up(0, strike),
+      Ok(())
+    );
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 I@F6/90").unwrap();
+    assert_eq!(
+      Game::restore(Rule::Default, Difficulty::Easy, [fleet.clone(), fleet], &[])
+        .check_power_up(0, strike),
+      Err("No ship of the fleet can strike".into())
+    );
+
+    let restored =
+      Game::restore_seeded(Rule::Default, Difficulty::Easy, 7, fleets, game.timeline());
+    assert_eq!(restored.timeline(), game.timeline());
+  }
+
+  #[test]
+  fn test_game_ping() {
+    assert_eq!(write_power_up((PowerUp::Ping, (3, 2))), "C4#");
+    assert_eq!("ping".parse::<PowerUp>(), Ok(PowerUp::Ping));
+    assert_eq!(ShipType::S.ability(), Some(Ability::Ping));
+
+    let fleet = crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap();
+    let fleets = [fleet.clone(), fleet];
+    let mut game = Game::restore_seeded(Rule::Default, Difficulty::Easy, 7, fleets.clone(), &[]);
+    // the scout at F6 is the nearest, its bottom cell at G8
+    let msg = game.use_power_up(PowerUp::Ping, (9, 9), false).unwrap();
+    assert_eq!(msg, "Your ping at J10 finds the nearest ship 5 cells away.");
+    let turn = game.timeline().last().unwrap();
+    assert_eq!(turn.ping, Some(5));
+    assert_eq!(turn.response[&(9, 9)], Status::Clear);
+    assert_eq!(turn.response[&(7, 6)], Status::Echo);
+    assert!(!turn.response.contains_key(&(7, 5)));
+
+    let msg = game.use_power_up(PowerUp::Ping, (1, 1), true).unwrap();
+    assert_eq!(msg, "Computer's ping finds a ship right at B2.");
+    assert_eq!(game.timeline()[1].response[&(1, 1)], Status::Echo);
+    assert_eq!(
+      game.check_power_up(0, PowerUp::Ping),
+      Err("The next ping is ready in 3 turns".into())
+    );
+
+    // sinking the scout takes the pings away
+    for cell in [(5, 6), (6, 6), (7, 6)].iter() {
+      game.fire(&[*cell].iter().cloned().collect(), false);
+      game.bot_fire();
+    }
+    assert_eq!(
+      game.check_power_up(1, PowerUp::Ping),
+      Err("The ships that can ping were sunk".into())
+    );
+    let restored =
+      Game::restore_seeded(Rule::Default, Difficulty::Easy, 7, fleets, game.timeline());
+    assert_eq!(restored.timeline(), game.timeline());
+
+    // the computer pings once it runs out of leads, and hunts among the echoes
+    let mut game = Game::seeded(Rule::Default, Difficulty::Hard, FleetType::Standard, 7);
+    while !game.is_won() {
+      if game.is_user_turn() {
+        let shots = game.legal_shots(0).into_iter().take(1).collect();
+        game.fire(&shots, false);
+      } else {
+        game.bot_fire();
+      }
+    }
+    assert!(game
+      .timeline()
+      .iter()
+      .any(|t| t.player == 1 && t.ping.is_some()));
+  }
+
+  #[test]
+  fn test_game_validate_salvo() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy, 0);
+    let salvo = |shots: &[Coordinate]| shots.iter().cloned().collect::<BTreeSet<_>>();
+
+    assert_eq!(game.validate_salvo(&salvo(&[])), Err(SalvoError::Empty));
+    assert_eq!(
+      game.validate_salvo(&salvo(&[(0, 0), (1, 1), (2, 2), (3, 3)])),
+      Ok(())
+    );
+    assert_eq!(
+      game.validate_salvo(&salvo(&[(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)])),
+      Err(SalvoError::OverBudget(4))
+    );
+    assert_eq!(
+      game.validate_salvo(&salvo(&[(0, ROWS)])),
+      Err(SalvoError::Illegal((0, ROWS), IllegalShot::OutOfBounds))
+    );
+
+    assert_eq!(
+      collect_salvo(&[(0, 0), (1, 1), (0, 0)]),
+      Err(SalvoError::Duplicate((0, 0)))
+    );
+    assert_eq!(
+      collect_salvo(&[(1, 1), (0, 0)]),
+      Ok(salvo(&[(0, 0), (1, 1)]))
+    );
+
+    // the salvo is checked against the board of the player whose turn it is
+    game.fire(&salvo(&[(0, 0)]), false);
+    assert_eq!(game.validate_salvo(&salvo(&[(0, 0)])), Ok(()));
+    game.bot_fire();
+    assert!(matches!(
+      game.validate_salvo(&salvo(&[(0, 0)])),
+      Err(SalvoError::Illegal((0, 0), IllegalShot::Resolved(_)))
+    ));
+  }
+
+  #[test]
+  fn test_game_fire() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+
+    let mut shots = BTreeSet::new();
+    shots.insert((1, 1));
+    shots.insert((3, 3));
+
+    let msg = game.fire(&shots, false);
+
+    assert!(!msg.is_empty());
+    assert!(!game.is_user_turn());
+    assert!(game.winner.is_none());
+  }
+
+  #[test]
+  fn test_game_solo() {
+    let mut game = Game::solo(Rule::Default, Difficulty::Easy);
+
+    for (r, c) in [(1, 1), (3, 3)].iter() {
+      game.fire(&[(*r, *c)].iter().cloned().collect(), false);
+      assert!(game.is_user_turn());
+    }
+    assert_eq!(game.round(), 3);
+    assert_eq!(game.fleet(1).size(), 4);
+    assert_eq!(game.fleet(1).alive, 4);
+
+    game.forfeit(0);
+    assert_eq!(game.winner(), Some(1));
+    // the first result stands
+    game.forfeit(1);
+    assert_eq!(game.winner(), Some(1));
+  }
+
+  #[test]
+  fn test_game_next_fleet() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    game.bot_fire();
+    let fleet = game.computer().player_board().placements();
+    let damage = game.player().player_board().to_string();
+
+    game.forfeit(1);
+    game.next_fleet(Difficulty::Hard);
+    assert!(!game.is_won());
+    assert!(game.is_user_turn());
+    assert_eq!(game.difficulty(), &Difficulty::Hard);
+    assert_eq!(game.round(), 1);
+    assert_ne!(game.computer().player_board().placements(), fleet);
+    assert_eq!(game.player().player_board().to_string(), damage);
+    assert_eq!(game.legal_shots(0).len(), ROWS * COLS);
+  }
+
+  #[test]
+  fn test_game_seeded() {
+    let play = |taunts: usize| {
+      let mut game = Game::seeded(Rule::Fury, Difficulty::Hard, FleetType::Standard, 42);
+      for _ in 0..taunts {
+        game.cosmetic_rng().gen::<u64>();
+      }
+      for _ in 0..5 {
+        let shots = game.legal_shots(0).into_iter().take(1).collect();
+        game.fire(&shots, false);
+        game.bot_fire();
+      }
+      game
+    };
+    let game = play(0);
+    // the cosmetic stream has no say in the fleets or the bot's shots
+    let other = play(3);
+    assert_eq!(
+      game.computer().player_board().placements(),
+      other.computer().player_board().placements()
+    );
+    assert_eq!(game.timeline(), other.timeline());
+    assert_ne!(
+      Game::seeded(Rule::Fury, Difficulty::Hard, FleetType::Standard, 43)
+        .player()
+        .player_board()
+        .placements(),
+      game.player().player_board().placements()
+    );
+  }
+
+  #[test]
+  fn test_game_run_back() {
+    let mut game = Game::seeded(Rule::Fury, Difficulty::Hard, FleetType::Standard, 42);
+    let play = |game: &mut Game| {
+      for _ in 0..5 {
+        let shots = game.legal_shots(0).into_iter().take(1).collect();
+        game.fire(&shots, false);
+        game.bot_fire();
+      }
+    };
+    play(&mut game);
+    let mut again = game.run_back();
+    assert!(again.timeline().is_empty());
+    assert_eq!(again.seed(), Some(42));
+    for player in 0..2 {
+      assert_eq!(
+        again.players[player].player_board().placements(),
+        game.players[player].player_board().placements()
+      );
+    }
+    // the same shots draw the same answers
+    play(&mut again);
+    assert_eq!(again.timeline(), game.timeline());
+    assert!(Game::solo(Rule::Default, Difficulty::Easy)
+      .run_back()
+      .is_solo());
+  }
+
+  #[test]
+  fn test_bot_memory() {
+    let mut memory = BotMemory::default();
+    let response =
+      |cells: &[(Coordinate, Status)]| cells.iter().cloned().collect::<FiringResponse>();
+
+    memory.learn(
+      &response(&[((0, 1), Status::Hit), ((5, 5), Status::Miss)]),
+      &[],
+    );
+    assert!(memory.open_hits.contains(&(0, 1)));
+    assert!(memory.excluded.contains(&(5, 5)));
+    // a ship on the top edge can only reach down to the third row
+    let targets = memory.targets();
+    assert!(!targets.is_empty());
+    assert!(targets
+      .iter()
+      .all(|(r, c)| *r < SHIP_SIZE && *c <= SHIP_SIZE));
+    assert!(!targets.contains(&(0, 1)));
+
+    let scout = Placement {
+      ship_type: ShipType::I,
+      rotation: 90,
+      origin: (0, 0),
+    };
+    let kill = scout
+      .cells()
+      .into_iter()
+      .map(|c| (c, Status::Kill))
+      .collect::<Vec<_>>();
+    memory.learn(&response(&kill), &[ShipType::I]);
+    assert!(memory.open_hits.is_empty());
+    assert!(memory.targets().is_empty());
+    assert_eq!(memory.afloat, vec![ShipType::X, ShipType::V, ShipType::H]);
+  }
+
+  #[test]
+  fn test_bot_heatmap() {
+    let mut memory = BotMemory::default();
+    let heat = memory.heatmap();
+    assert_eq!(heat.len(), ROWS * COLS);
+    // ships fit through the middle of the board in more ways than through its corners
+    assert!(heat[&(4, 4)] > heat[&(0, 0)]);
+
+    let mut response = FiringResponse::new();
+    response.insert((0, 0), Status::Miss);
+    response.insert((5, 5), Status::Hit);
+    memory.learn(&response, &[]);
+    let heat = memory.heatmap();
+    assert!(!heat.contains_key(&(0, 0)));
+    assert!(!heat.contains_key(&(5, 5)));
+
+    let hottest = memory.hottest(&mut rand::thread_rng());
+    assert_eq!(hottest.len(), heat.len());
+    let (r, c) = hottest[0];
+    assert!(r.abs_diff(5) < SHIP_SIZE && c.abs_diff(5) < SHIP_SIZE);
+  }
+
+  #[test]
+  fn test_bot_ruled_out() {
+    let game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    assert!(game.ruled_out(0).is_empty());
+
+    // a cell walled in by misses is too small for any ship
+    let mut memory = BotMemory {
+      excluded: (0..ROWS)
+        .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+        .filter(|c| *c != (5, 5))
+        .collect(),
+      ..BotMemory::default()
+    };
+    assert_eq!(memory.ruled_out(), [(5, 5)].iter().cloned().collect());
+    memory.excluded.clear();
+    memory.afloat.clear();
+    assert_eq!(memory.ruled_out().len(), ROWS * COLS);
+  }
+
+  #[test]
+  fn test_game_bot_works_on_open_hits() {
+    for difficulty in [Difficulty::Hard, Difficulty::Expert] {
+      let mut game = Game::new(Rule::Default, difficulty, 0);
+      let fleet = game.player().player_board().placements();
+      let hit = *fleet[0].cells().iter().next().unwrap();
+      game.fire(&[(9, 9)].iter().cloned().collect(), false);
+      game.fire(&[hit].iter().cloned().collect(), true);
+      assert!(game.computer().memory.open_hits.contains(&hit));
+
+      // the next shot lands close to the hit
+      game.fire(&[(9, 8)].iter().cloned().collect(), false);
+      let shots = game.generate_bot_firing_coordinates();
+      let shot = shots.iter().next().unwrap();
+      assert!(shot.0.abs_diff(hit.0) < SHIP_SIZE && shot.1.abs_diff(hit.1) < SHIP_SIZE);
+    }
+  }
+
+  #[test]
+  fn test_game_place_fleet() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    let placement = |ship_type, origin| Placement {
+      ship_type,
+      rotation: 90,
+      origin,
+    };
+    let mut fleet = vec![
+      placement(ShipType::X, (0, 0)),
+      placement(ShipType::V, (0, 3)),
+      placement(ShipType::H, (5, 0)),
+      placement(ShipType::I, (5, 5)),
+    ];
+    game.place_fleet(&fleet).unwrap();
+    assert_eq!(game.player().player_board().placements(), fleet);
+
+    fleet[3].origin = (0, 1);
+    assert_eq!(
+      game.place_fleet(&fleet).unwrap_err(),
+      "The Scout overlaps another ship"
+    );
+    fleet[3].origin = (9, 9);
+    assert_eq!(
+      game.place_fleet(&fleet).unwrap_err(),
+      "The Scout is off the board"
+    );
+
+    fleet[3].origin = (5, 5);
+    game.fire(&[(0, 0)].iter().cloned().collect(), false);
+    assert!(game.place_fleet(&fleet).is_err());
+  }
+
+  #[test]
+  fn test_game_turn_changes() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    let fleet = game.computer().player_board().placements();
+    let hit = *fleet[0].cells().iter().next().unwrap();
+    game.fire(&[hit].iter().cloned().collect(), false);
+
+    let turn = &game.timeline()[0];
+    assert_eq!(
+      turn.fleet_changes,
+      vec![CellChange {
+        coordinate: hit,
+        from: Status::Live,
+        to: Status::Hit,
+      }]
+    );
+    assert_eq!(
+      turn.view_changes,
+      vec![CellChange {
+        coordinate: hit,
+        from: Status::Space,
+        to: Status::Hit,
+      }]
+    );
+    assert!(turn.view_changes[0]
+      .to_string()
+      .ends_with("unexplored → hit"));
+
+    // firing on the same cell again changes nothing
+    game.bot_fire();
+    game.fire(&[hit].iter().cloned().collect(), false);
+    let turn = &game.timeline()[2];
+    assert!(turn.fleet_changes.is_empty());
+    assert!(turn.view_changes.is_empty());
+  }
+
+  #[test]
+  fn test_game_generate_firing_coordinates() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+
+    let shots = game.generate_bot_firing_coordinates();
+    assert_eq!(shots.len(), 1);
+
+    let mut game = Game::new(Rule::Charge, Difficulty::Easy, 0);
+
+    let shots = game.generate_bot_firing_coordinates();
+    assert_eq!(shots.len(), 1);
+
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy, 0);
+
+    let shots = game.generate_bot_firing_coordinates();
+    assert_eq!(shots.len(), 4);
+  }
+
+  #[test]
+  fn test_game_restore() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Hard, 0);
+
+    let mut shots = BTreeSet::new();
+    shots.insert((1, 1));
+    shots.insert((3, 3));
+    game.fire(&shots, false);
+    game.bot_fire();
+    game.fire(&[(5, 5)].iter().cloned().collect(), false);
+
+    let restored = Game::restore(
+      Rule::Fury,
+      Difficulty::Hard,
+      [
+        game.player().player_board().placements(),
+        game.computer().player_board().placements(),
+      ],
+      game.timeline(),
+    );
+
+    assert_eq!(restored.timeline(), game.timeline());
+    assert_eq!(restored.turn, game.turn);
+    for (restored, player) in restored.players.iter().zip(game.players.iter()) {
+      assert_eq!(
+        restored.player_board().to_string(),
+        player.player_board().to_string()
+      );
+      assert_eq!(
+        restored.opponent_board().to_string(),
+        player.opponent_board().to_string()
+      );
+    }
+  }
+
+  #[test]
+  fn test_game_versus() {
+    let fleets = [
+      crate::notation::parse_fleet("X@A1/90 V@D1/90 H@A6/90 I@F6/90").unwrap(),
+      crate::notation::parse_fleet("X@H8/180 V@A1/90 H@E4/270 I@B6/360").unwrap(),
+    ];
+    // each side keeps its own fleet, the spectator neither
+    let versus = |fleet: Option<&[Placement]>| {
+      Game::versus(Rule::Fury, Difficulty::Easy, fleet, &FleetType::Standard)
+    };
+    let (mut host, mut guest, mut spectator) = (
+      versus(Some(&fleets[0])),
+      versus(Some(&fleets[1])),
+      versus(None),
+    );
+    guest.set_first(1);
+    assert_eq!(host.fleet(1).cells_remaining, 0);
+
+    let play = Play::PowerUp(PowerUp::Sonar, (1, 1));
+    let (msg, answer) = guest.take_turn(&play).unwrap();
+    assert_eq!(msg, "Computer's sonar picks up a ship around B2.");
+    assert_eq!(answer, Answer::Sonar(true));
+    assert_eq!(
+      host.settle_turn(&play, answer.clone()),
+      Ok("Your sonar picks up a ship around B2.".into())
+    );
+    spectator.settle_turn(&play, answer).unwrap();
+    while !host.is_won() {
+      let (shooter, defender) = if host.is_user_turn() {
+        (&mut host, &mut guest)
+      } else {
+        (&mut guest, &mut host)
+      };
+      let shots = shooter.legal_shots(0).into_iter().take(1).collect();
+      let play = Play::Salvo(shots);
+      let (_, answer) = defender.take_turn(&play).unwrap();
+      assert_eq!(
+        shooter.settle_turn(&play, Answer::Sonar(false)),
+        Err(MISMATCHED_ANSWER.into())
+      );
+      shooter.settle_turn(&play, answer.clone()).unwrap();
+      spectator.settle_turn(&play, answer).unwrap();
+    }
+    assert_eq!(host.winner().map(|w| 1 - w), guest.winner());
+    assert_eq!(spectator.winner(), host.winner());
+    let responses = |game: &Game| {
+      game
+        .timeline()
+        .iter()
+        .map(|t| t.response.clone())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(responses(&spectator), responses(&host));
+    // a shot out of turn is refused before it reaches the fleet
+    let play = Play::Salvo([(9, 9)].iter().cloned().collect());
+    assert!(host.take_turn(&play).is_err());
+
+    let revealed = host.reveal(fleets.clone()).unwrap();
+    assert_eq!(revealed.winner(), host.winner());
+    assert_eq!(revealed.computer().player_board().placements(), fleets[1]);
+    assert_eq!(
+      host.reveal([fleets[0].clone(), fleets[0].clone()]).err(),
+      Some("The fleet doesn't match what the turns found on it".into())
+    );
+    let classic = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Classic, 0)
+      .player()
+      .player_board()
+      .placements();
+    assert_eq!(
+      host.reveal([fleets[0].clone(), classic]).err(),
+      Some("The fleet isn't made of the ships of the match".into())
+    );
+  }
+
+  #[test]
+  fn test_game_fired_upon_in() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    game.fire(&[(2, 2)].iter().cloned().collect(), true);
+    game.fire(&[(2, 2)].iter().cloned().collect(), false);
+
+    assert_eq!(game.fired_upon_in(0, (1, 1)), Some(1));
+    assert_eq!(game.fired_upon_in(1, (2, 2)), Some(2));
+    assert_eq!(game.fired_upon_in(0, (2, 2)), Some(3));
+    assert_eq!(game.fired_upon_in(1, (1, 1)), None);
+  }
+
+  #[test]
+  fn test_game_shots_and_hits() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy, 0);
+    assert_eq!(game.round(), 1);
+    assert_eq!(game.shots_and_hits(0), (0, 0));
+
+    let board = game.computer().player_board();
+    let ship_cell = board
+      .positions()
+      .iter()
+      .find(|p| p.ship_id.is_some())
+      .unwrap()
+      .coordinate;
+    let water_cell = board
+      .positions()
+      .iter()
+      .find(|p| p.ship_id.is_none())
+      .unwrap()
+      .coordinate;
+
+    game.fire(&[ship_cell, water_cell].iter().cloned().collect(), false);
+    assert_eq!(game.round(), 1);
+    assert_eq!(game.shots_and_hits(0), (2, 1));
+    assert_eq!(game.shots_and_hits(1), (0, 0));
+
+    game.bot_fire();
+    assert_eq!(game.round(), 2);
+    assert_eq!(game.shots_and_hits(1).0, 4);
+  }
+
+  #[test]
+  fn test_game_legal_shots() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    assert_eq!(game.legal_shots(0).len(), ROWS * COLS);
+    assert_eq!(game.check_shot(0, (ROWS, 0)), Err(IllegalShot::OutOfBounds));
+
+    game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    assert!(!game.legal_shots(0).contains(&(1, 1)));
+    assert_eq!(game.legal_shots(0).len(), ROWS * COLS - 1);
+    assert!(matches!(
+      game.check_shot(0, (1, 1)),
+      Err(IllegalShot::Resolved(_))
+    ));
+    assert_eq!(game.check_shot(0, (2, 2)), Ok(()));
+  }
+
+  #[test]
+  fn test_game_sunk_cells_unresolvable() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy, 0);
+    let ship = game.computer().player_board().ships[3].clone();
+    let cells = game
+      .computer()
+      .player_board()
+      .pos_by_ship(ship.id.clone())
+      .iter()
+      .map(|p| p.coordinate)
+      .collect::<BTreeSet<_>>();
+    let cell = *cells.iter().next().unwrap();
+    assert!(game.is_resolvable(cell));
+
+    game.turn = 0;
+    game.fire(&cells, false);
+    game.turn = 0;
+    assert!(!game.is_resolvable(cell));
+    assert_eq!(game.check_shot(0, cell), Err(IllegalShot::Sunk));
+    assert!(!game.legal_shots(0).contains(&cell));
+    assert_eq!(
+      game.validate_salvo(&[cell].iter().cloned().collect()),
+      Err(SalvoError::Illegal(cell, IllegalShot::Sunk))
+    );
+
+    // a view out of step with the fleet doesn't open the wreck up again
+    game.players[0].opponent_board_mut().positions[cell.0][cell.1].status = Status::Space;
+    assert_eq!(game.check_shot(0, cell), Err(IllegalShot::Sunk));
+    game.fire(&[cell].iter().cloned().collect(), false);
+    let turn = game.timeline.last().unwrap();
+    assert!(!turn.response.contains_key(&cell));
+    assert_eq!(game.computer().player_board().status_at(cell), Status::Kill);
+  }
+
+  #[test]
+  fn test_game_ship_sunk_event() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy, 0);
+    let ship = game.computer().player_board().ships[3].clone();
+    let shots = game
+      .computer()
+      .player_board()
+      .pos_by_ship(ship.id.clone())
+      .iter()
+      .map(|p| p.coordinate)
+      .collect::<BTreeSet<_>>();
+
+    game.fire(&[(0, 0)].iter().cloned().collect(), true);
+    assert!(game.take_events().is_empty());
+    game.turn = 0;
+    game.fire(&shots, false);
+    assert_eq!(
+      game.take_events(),
+      vec![GameEvent::ShipSunk {
+        by: 0,
+        ship_type: ship.ship_type
+      }]
+    );
+    assert!(game.take_events().is_empty());
+  }
+
+  #[test]
+  fn test_a1_notation() {
+    assert_eq!(a1_notation((0, 0)), "A1");
+    assert_eq!(a1_notation((6, 3)), "D7");
+    assert_eq!(a1_notation((9, 9)), "J10");
+
+    assert_eq!(parse_a1("b7"), Some((6, 1)));
+    assert_eq!(parse_a1("J10"), Some((9, 9)));
+    assert_eq!(parse_a1("K1"), None);
+    assert_eq!(parse_a1("A11"), None);
+    assert_eq!(parse_a1("A0"), None);
+  }
+
+  #[test]
+  fn test_get_random_coordinate() {
+    let mut rng = rand::thread_rng();
+    assert!(get_random_coordinate(&mut rng, (SHIP_SIZE, SHIP_SIZE)) < (ROWS, COLS));
+  }
+
+  #[test]
+  fn test_reverse_rows_of_cols() {
+    let ship = vec![
+      vec![Status::Live, Status::Live, Status::Space],
+      vec![Status::Space, Status::Live, Status::Space],
+      vec![Status::Space, Status::Space, Status::Live],
+    ];
+    let expected = [
+      [Status::Space, Status::Space, Status::Live],
+      [Status::Space, Status::Live, Status::Space],
+      [Status::Live, Status::Live, Status::Space],
+    ];
+    assert_eq!(reverse_rows_of_cols(ship), expected);
+  }
+
+  #[test]
+  fn test_reverse_cols_of_rows() {
+    let ship = vec![
+      vec![Status::Live, Status::Live, Status::Space],
+      vec![Status::Space, Status::Live, Status::Space],
+      vec![Status::Space, Status::Space, Status::Space],
+    ];
+    let expected = [
+      [Status::Space, Status::Live, Status::Live],
+      [Status::Space, Status::Live, Status::Space],
+      [Status::Space, Status::Space, Status::Space],
+    ];
+    assert_eq!(reverse_cols_of_rows(ship), expected);
+  }
+
+  #[test]
+  fn test_transpose() {
+    let ship = vec![
+      vec![Status::Live, Status::Live, Status::Space],
+      vec![Status::Space, Status::Live, Status::Space],
+      vec![Status::Space, Status::Space, Status::Space],
+    ];
+    let expected = [
+      [Status::Live, Status::Space, Status::Space],
+      [Status::Live, Status::Live, Status::Space],
+      [Status::Space, Status::Space, Status::Space],
+    ];
+    assert_eq!(transpose(ship), expected);
+  }
+
+  #[test]
+  fn test_ship_type_get_shape() {
+    let ship = ShipType::H;
+    assert_eq!(
+      ship.get_shape(90),
+      [
+        [Status::Live, Status::Space, Status::Live],
+        [Status::Live, Status::Live, Status::Live],
+        [Status::Live, Status::Space, Status::Live],
+      ]
+    );
+    assert_eq!(
+      ship.get_shape(180),
+      [
+        [Status::Live, Status::Live, Status::Live],
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Live, Status::Live, Status::Live],
+      ]
+    );
+    let ship = ShipType::V;
+    assert_eq!(
+      ship.get_shape(270),
+      [
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Live, Status::Space, Status::Live],
+        [Status::Live, Status::Space, Status::Live],
+      ]
+    );
+    assert_eq!(
+      ship.get_shape(360),
+      [
+        [Status::Live, Status::Live, Status::Space],
+        [Status::Space, Status::Space, Status::Live],
+        [Status::Live, Status::Live, Status::Space],
+      ]
+    );
+  }
+
+  #[test]
+  fn test_ship_is_overlapping() {
+    let ship = Ship::new(ShipType::H);
+
+    assert!(!ship.is_overlapping(&[], (0, 0)));
+    assert!(!ship.is_overlapping(&[vec![]], (0, 0)));
+
+    let mut positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    // should pass as there is no overlap in default
+    assert!(!ship.is_overlapping(&positions, (0, 0)));
+
+    positions[1][5] = Position {
+      coordinate: (1, 5),
+      ship_id: Some("123".into()),
+      status: Status::Live,
+      hit_points: 1,
+    };
+    // should fail when there is overlap
+    assert!(ship.is_overlapping(&positions, (1, 5)));
+  }
+
+  #[test]
+  fn test_ship_draw() {
+    let ship = Ship {
+      id: "123".into(),
+      rotation: 90,
+      origin: (5, 5),
+      alive: true,
+      ship_type: ShipType::H,
+    };
+    let mut positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    assert!(ship.draw(&mut positions, (5, 5)));
+    let p = positions
+      .iter()
+      .map(|row| {
+        row
+          .iter()
+          .map(|c| c.to_string())
+          .collect::<Vec<_>>()
+          .join("")
+      })
+      .collect::<Vec<_>>()
+      .join("\n");
+    assert_eq!(p, "          \n          \n          \n          \n          \n     🚀 🚀  \n     🚀🚀🚀  \n     🚀 🚀  \n          \n          ");
+    assert!(ship.is_overlapping(&positions, (5, 5)));
+  }
+
+  #[test]
+  fn test_board_new() {
+    let opponent_board = Board::new(false, &FleetType::Standard, &mut rand::thread_rng());
+
+    // should be empty board initially
+    assert_eq!(opponent_board.to_string(), "          \n          \n          \n          \n          \n          \n          \n          \n          \n          ");
+
+    let my_board = Board::new(true, &FleetType::Standard, &mut rand::thread_rng());
+
+    // should be empty board initially
+    assert_eq!(my_board.ships.len(), 4);
+    assert_eq!(my_board.positions.len(), ROWS);
+    // check if all ships are placed on the board
+    my_board.ships.iter().for_each(|it| {
+      let found = my_board
+        .positions
+        .iter()
+        .flat_map(|pr| pr.iter())
+        .filter(|pc| pc.ship_id.is_some() && pc.ship_id.clone().unwrap() == it.id)
+        .collect::<Vec<_>>();
+      match it.ship_type {
+        ShipType::X => assert!(found.len() == 5, "ship X not placed!"),
+        ShipType::V => assert!(found.len() == 5, "ship V not placed!"),
+        ShipType::H => assert!(found.len() == 7, "ship H not placed!"),
+        ShipType::I => assert!(found.len() == 3, "ship I not placed!"),
+        _ => panic!("ship {} isn't in the standard fleet", it.ship_type),
+      }
+    })
+  }
+
+  #[test]
+  fn test_board_new_classic() {
+    let board = Board::new(true, &FleetType::Classic, &mut rand::thread_rng());
+    let placements = board.placements();
+    assert_eq!(
+      placements
+        .iter()
+        .map(|p| p.cells().len())
+        .collect::<Vec<_>>(),
+      vec![5, 4, 3, 3, 2]
+    );
+    assert_eq!(check_fleet(&placements), Ok(()));
+    assert_eq!(FleetType::of(&placements), FleetType::Classic);
+
+    // the cells of a classic board read back as straight ships
+    let read = board.to_string().parse::<Board>().unwrap();
+    let cells = |b: &Board| {
+      b.placements()
+        .iter()
+        .flat_map(|p| p.cells())
+        .collect::<BTreeSet<_>>()
+    };
+    assert_eq!(cells(&read), cells(&board));
+  }
+
+  #[test]
+  fn test_ship_type_classic_shapes() {
+    assert_eq!(ShipType::A.size(90), (1, 5));
+    assert_eq!(ShipType::A.size(180), (5, 1));
+    assert_eq!(ShipType::P.size(270), (1, 2));
+    assert_eq!(ShipType::B.get_shape(360), vec![vec![Status::Live]; 4]);
+
+    let mut game = Game::new(Rule::Fury, Difficulty::Expert, 0);
+    game.set_fleet_type(FleetType::Classic);
+    assert_eq!(game.fleet(0).alive, 5);
+    assert_eq!(game.computer().memory.afloat, FleetType::Classic.ships());
+    // a carrier standing on the left edge fits in six ways down the column
+    let heat = game.computer().memory.heatmap();
+    assert!(heat[&(4, 0)] > heat[&(0, 0)]);
+    assert_eq!(game.shot_budget(0), 5);
+  }
+
+  #[test]
+  fn test_ship_type_custom() {
+    let tug = ShipType::custom("Tug", &["**.", ".**"]).unwrap();
+    assert_eq!(tug.size(90), (2, 3));
+    assert_eq!(tug.size(180), (3, 2));
+    assert_eq!(tug.name(), "Tug");
+    assert_eq!(tug.to_string(), "Tug=**.|.**");
+    assert_eq!("Tug=**.|.**".parse::<ShipType>(), Ok(tug.clone()));
+    assert!("Tug=**.|*".parse::<ShipType>().is_err());
+
+    let fleet = FleetType::custom(vec![tug.clone(), tug.clone(), ShipType::P]).unwrap();
+    let game = Game::seeded(Rule::Default, Difficulty::Hard, fleet.clone(), 3);
+    assert_eq!(game.fleet(0).alive, 3);
+    assert_eq!(
+      FleetType::of(&game.computer().player_board().placements()),
+      fleet
+    );
+    assert_eq!(game.computer().memory.afloat, fleet.ships());
+    assert!(FleetType::custom(vec![]).is_err());
+
+    // two blocks fit side by side, but not once the first one is placed in the middle
+    let block = ShipType::custom("Block", &["*****"; 5]).unwrap();
+    let fleet = FleetType::custom(vec![block.clone(), block.clone()]).unwrap();
+    for seed in 0..10 {
+      let game = Game::seeded(Rule::Default, Difficulty::Easy, fleet.clone(), seed);
+      assert_eq!(game.fleet(0).alive, 2);
+      assert_eq!(game.fleet(1).alive, 2);
+      assert_eq!(
+        game.player().player_board().cells_with(Status::Live).len(),
+        50
+      );
+    }
+
+    // three blocks a side longer than half the board have no room at all
+    let block = ShipType::custom("Block", &["******"; 6]).unwrap();
+    let positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    let (mut rng, mut steps) = (StdRng::seed_from_u64(0), SEARCH_STEPS);
+    let blocks = vec![block; 3];
+    assert!(search_fleet(&blocks, &positions, &mut rng, &mut steps).is_none());
+  }
+
+  #[test]
+  fn test_placement_mirrored() {
+    let destroyer = Placement {
+      ship_type: ShipType::V,
+      rotation: 180,
+      origin: (1, 2),
+    };
+    let across = destroyer.mirrored(true).unwrap();
+    assert_eq!(
+      across.cells(),
+      destroyer
+        .cells()
+        .iter()
+        .map(|(r, c)| (*r, COLS - 1 - c))
+        .collect()
+    );
+    let down = destroyer.mirrored(false).unwrap();
+    assert_eq!(
+      down.cells(),
+      destroyer
+        .cells()
+        .iter()
+        .map(|(r, c)| (ROWS - 1 - r, *c))
+        .collect()
+    );
+    assert_eq!(down.mirrored(false), Some(destroyer));
+
+    // a tug flipped over is no turn of a tug
+    let tug = Placement {
+      ship_type: ShipType::custom("Tug", &["**.", ".**"]).unwrap(),
+      rotation: 90,
+      origin: (0, 0),
+    };
+    assert_eq!(tug.mirrored(true), None);
+
+    let patrol = Placement::all(&ShipType::P);
+    assert_eq!(patrol.len(), 4 * ROWS * (COLS - 1));
+    assert!(check_fleet(&patrol[..1]).is_ok());
+  }
+
+  const FIXTURE: &str = "# #  # #  
+ #   x #  
+# #   #   
+   .      
+          
+ # #      
+ #x#    X 
+ # #    X 
+        X 
+.         ";
+
+  #[test]
+  fn test_board_from_str() {
+    let board = FIXTURE.parse::<Board>().unwrap();
+
+    let emoji = FIXTURE
+      .replace('#', "🚀")
+      .replace('x', "💥")
+      .replace('X', "💀")
+      .replace('.', "❌");
+    assert_eq!(board.to_string(), emoji);
+    assert_eq!(
+      board.fleet(),
+      FleetView {
+        alive: 3,
+        sunk: vec![ShipType::I],
+        cells_remaining: 15,
+      }
+    );
+
+    let (position, ship) = board.find_position_and_ship((1, 5));
+    assert_eq!(position.status, Status::Hit);
+    assert_eq!(ship.unwrap().ship_type, ShipType::V);
+    let (_, ship) = board.find_position_and_ship((6, 2));
+    assert_eq!(ship.unwrap().ship_type, ShipType::H);
+    let (_, ship) = board.find_position_and_ship((7, 8));
+    assert!(!ship.unwrap().alive);
+    let (_, ship) = board.find_position_and_ship((3, 3));
+    assert!(ship.is_none());
+
+    // the emoji format reads back the same
+    let reparsed = emoji.parse::<Board>().unwrap();
+    assert_eq!(reparsed.to_string(), emoji);
+    assert_eq!(reparsed.placements(), board.placements());
+  }
+
+  #[test]
+  fn test_board_from_str_round_trip() {
+    for _ in 0..20 {
+      let board = Board::new(true, &FleetType::Standard, &mut rand::thread_rng());
+      let parsed = board.to_string().parse::<Board>().unwrap();
+      assert_eq!(parsed.to_string(), board.to_string());
+    }
+    let knowledge = FIXTURE.replace('#', " ").parse::<Board>().unwrap();
+    assert_eq!(
+      knowledge.to_string().parse::<Board>().unwrap().to_string(),
+      knowledge.to_string()
+    );
+  }
+
+  #[test]
+  fn test_board_from_str_errors() {
+    assert!("".parse::<Board>().is_err());
+    assert!(FIXTURE
+      .replacen("# #  # #  \n", "", 1)
+      .parse::<Board>()
+      .is_err());
+    assert!(FIXTURE.replacen('.', "$", 1).parse::<Board>().is_err());
+    assert!(FIXTURE
+      .replacen(".         ", ".          ", 1)
+      .parse::<Board>()
+      .is_err());
+    // a stray live cell is not a ship
+    assert!(FIXTURE
+      .replacen(".         ", ".   #     ", 1)
+      .parse::<Board>()
+      .is_err());
+  }
+
+  #[test]
+  fn test_board_from_str_take_fire() {
+    let mut board = FIXTURE.parse::<Board>().unwrap();
+    let shots = [(5, 1), (5, 3), (6, 1), (6, 3), (7, 1), (7, 3)];
+    let won = board
+      .take_fire(&shots.iter().cloned().collect(), &mut [], false)
+      .lost;
+    assert!(!won);
+    assert_eq!(board.ships_alive().len(), 2);
+  }
+
+  #[test]
+  fn test_board_status_hash() {
+    let board = FIXTURE.parse::<Board>().unwrap();
+    assert_eq!(
+      board.status_hash(),
+      FIXTURE.parse::<Board>().unwrap().status_hash()
+    );
+    assert_eq!(board.status_hash(), 0xd704_d485_7b5f_1962);
+
+    let mut fired = board.clone();
+    fired.take_fire(&[(0, 0)].iter().cloned().collect(), &mut [], false);
+    assert_ne!(fired.status_hash(), board.status_hash());
+  }
+
+  #[test]
+  fn test_board_take_fire() {
+    let mut board = Board::new(true, &FleetType::Standard, &mut rand::thread_rng());
+
+    board.positions[1][1].status = Status::Space;
+    board.positions[3][3].status = Status::Live;
+
+    let mut shots = BTreeSet::new();
+    shots.insert((1, 1));
+    shots.insert((3, 3));
+
+    let Volley {
+      response: res,
+      lost,
+      ..
+    } = board.take_fire(&shots, &mut [], false);
+    assert_eq!(res.get(&(1, 1)).unwrap(), &Status::Miss);
+    assert_eq!(res.get(&(3, 3)).unwrap(), &Status::Hit);
+    assert!(!lost);
+
+    let mut board = Board::new(true, &FleetType::Standard, &mut rand::thread_rng());
+
+    // set a ship as hit except for one position
+    let ship_id = board.ships[0].id.clone();
+    let mut pos = board
+      .positions
+      .iter_mut()
+      .flat_map(|pr| pr.iter_mut())
+      .filter(|pc| pc.ship_id.is_some() && pc.ship_id.clone().unwrap() == ship_id)
+      .collect::<Vec<_>>();
+
+    pos.iter_mut().skip(1).for_each(|p| p.status = Status::Hit);
+    let c = pos.iter().take(1).map(|p| p.coordinate).collect::<Vec<_>>();
+
+    let mut shots = BTreeSet::new();
+    shots.insert(c[0]);
+
+    let Volley {
+      response: res,
+      lost,
+      ..
+    } = board.take_fire(&shots, &mut [], false);
+    assert_eq!(res.get(&c[0]).unwrap(), &Status::Kill);
+    assert!(!lost);
+  }
+
+  #[test]
+  fn test_settle() {
+    use Status::*;
+    // what a shot finds takes the cells nothing settled yet
+    for known in [Space, Echo, Clear, Splash, Dent] {
+      for found in [Miss, Mine, Hit, Dent, Kill, Echo, Clear, Splash] {
+        assert_eq!(
+          settle(known, found),
+          found,
+          "{:?} finding {:?}",
+          known,
+          found
+        );
+      }
+    }
+    assert_eq!(settle(Live, Hit), Hit);
+    assert_eq!(settle(Live, Kill), Kill);
+    assert_eq!(settle(Live, Dent), Live);
+    // a settled cell stays as it is, unless its ship sinks
+    for known in [Miss, Mine, Hit, Kill] {
+      for found in [Miss, Mine, Hit, Dent, Echo, Clear, Splash] {
+        assert_eq!(
+          settle(known, found),
+          known,
+          "{:?} finding {:?}",
+          known,
+          found
+        );
+      }
+    }
+    assert_eq!(settle(Hit, Kill), Kill);
+  }
+
+  #[test]
+  fn test_board_resolve_shot() {
+    let mut board = FIXTURE.parse::<Board>().unwrap();
+    let found = |board: &Board, cell| board.resolve_shot(cell, false).map(|r| r.found);
+    assert_eq!(found(&board, (4, 0)), Some(Status::Miss));
+    assert_eq!(found(&board, (3, 3)), Some(Status::Miss));
+    assert_eq!(found(&board, (0, 0)), Some(Status::Hit));
+    // a hit found again changes nothing, and a wreck isn't resolved at all
+    assert_eq!(found(&board, (1, 5)), Some(Status::Hit));
+    assert_eq!(found(&board, (7, 8)), None);
+    board.mines.insert((4, 0));
+    assert_eq!(found(&board, (4, 0)), Some(Status::Mine));
+
+    // the armor gives way to the second hit
+    board.positions[0][0].hit_points = 2;
+    let dent = board.resolve_shot((0, 0), false).unwrap();
+    assert_eq!(
+      dent.response(),
+      [((0, 0), Status::Dent)].iter().copied().collect()
+    );
+    board.apply_shot(&dent);
+    assert_eq!(board.positions[0][0].status, Status::Live);
+    assert_eq!(found(&board, (0, 0)), Some(Status::Hit));
+
+    // a shot on the center sinks the whole ship under criticals
+    let critical = board.resolve_shot((1, 1), true).unwrap();
+    assert!(critical.critical);
+    assert_eq!(critical.response().len(), 5);
+    assert!(critical.response().values().all(|s| *s == Status::Kill));
+    assert_eq!(found(&board, (1, 1)), Some(Status::Hit));
+
+    // the last cell of a ship sinks it, the rest of its cells keeping their hits
+    for cell in [(5, 1), (5, 3), (6, 1), (6, 3), (7, 1)] {
+      let hit = board.resolve_shot(cell, false).unwrap();
+      assert_eq!((hit.found, hit.sunk.is_some()), (Status::Hit, false));
+      board.apply_shot(&hit);
+    }
+    let kill = board.resolve_shot((7, 3), false).unwrap();
+    assert_eq!(kill.found, Status::Kill);
+    assert_eq!(kill.sunk.as_ref().unwrap().1.len(), 7);
+    board.apply_shot(&kill);
+    assert_eq!(board.positions[7][3].status, Status::Kill);
+    assert_eq!(board.positions[6][2].status, Status::Hit);
+    assert_eq!(board.status_at((6, 2)), Status::Kill);
+    assert_eq!(found(&board, (6, 2)), None);
+
+    // what the attacker knows takes the response the same way
+    let mut view = Board::new(false, &FleetType::Standard, &mut rand::thread_rng());
+    view.learn(&dent.response());
+    assert_eq!(view.positions[0][0].status, Status::Dent);
+    view.learn(&kill.response());
+    assert_eq!(view.status_at((6, 2)), Status::Kill);
+    view.learn(&[((6, 2), Status::Miss)].iter().copied().collect());
+    assert_eq!(view.status_at((6, 2)), Status::Kill);
+  }
+
+  #[test]
+  fn test_board_unexplored() {
+    let mut view = Board::new(false, &FleetType::Standard, &mut rand::thread_rng());
+    assert_eq!(view.unexplored().count(), ROWS * COLS);
+    assert_eq!(view.unexplored().next(), Some((0, 0)));
+    view.learn(
+      &[
+        ((0, 0), Status::Miss),
+        ((0, 1), Status::Echo),
+        ((0, 2), Status::Clear),
+        ((0, 3), Status::Dent),
+        ((0, 4), Status::Splash),
+      ]
+      .iter()
+      .copied()
+      .collect(),
+    );
+    // what a sonar swept is still there to be fired upon
+    assert_eq!(
+      view.unexplored().take(3).collect::<Vec<_>>(),
+      vec![(0, 1), (0, 2), (0, 5)]
+    );
+    assert_eq!(view.unexplored().count(), ROWS * COLS - 3);
+
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    game.fire(&[(0, 0)].iter().copied().collect(), false);
+    let legal = game.legal_shots(0);
+    assert_eq!(legal, game.player().opponent_board().unexplored().collect());
+    assert!(!legal.contains(&(0, 0)));
+  }
+
+  #[test]
+  fn test_board_update_status() {
+    let mut board = Board::new(false, &FleetType::Standard, &mut rand::thread_rng());
+
+    let mut res = BTreeMap::new();
+    res.insert((1, 1), Status::Miss);
+    res.insert((3, 3), Status::Hit);
+    res.insert((0, 2), Status::Kill);
+
+    let message = board.update_status(res, false);
+    assert_eq!(message, "You have sunk a ship. You missed 1.");
+
+    let mut res = BTreeMap::new();
+    res.insert((3, 3), Status::Hit);
+    res.insert((0, 2), Status::Hit);
+
+    let message = board.update_status(res.clone(), false);
+    assert_eq!(message, "You have 2 hit.");
+    let message = board.update_status(res, true);
+    assert_eq!(message, "Computer have 2 hit.");
+  }
+}
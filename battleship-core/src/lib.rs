@@ -0,0 +1,52 @@
+//! The engine of battleship-rs: the rules, the fleets, the computer player and the ways a
+//! match is written down, without any user interface. The terminal game is one frontend
+//! of it, others like a GUI, a web page or a bot can drive a [`game::Game`] the same way.
+//!
+//! A match is a [`game::Game`] between the user, player 0, and the computer, player 1.
+//! The user's salvos are checked with `validate_salvo` and fired with `fire`, the
+//! computer plays its turn with `bot_fire`, by the [`strategy`] of the difficulty or one
+//! of the frontend's own, and what every turn did is kept in the `timeline`:
+//!
+//! ```
+//! use battleship_core::game::{Difficulty, FleetType, Game, Rule};
+//!
+//! let mut game = Game::seeded(Rule::Default, Difficulty::Hard, FleetType::Standard, 7);
+//! while !game.is_won() {
+//!   if game.is_user_turn() {
+//!     // fire upon the first cell left, as a frontend would fire what the user picked
+//!     let shots = game.legal_shots(0).into_iter().take(1).collect();
+//!     game.validate_salvo(&shots).unwrap();
+//!     game.fire(&shots, false);
+//!   } else {
+//!     game.bot_fire();
+//!   }
+//! }
+//! assert!(game.winner().is_some());
+//! assert!(!game.timeline().is_empty());
+//! ```
+//!
+//! A match is saved by writing it down in the [`notation`], and restored by reading it
+//! back, to the same turn with the same fleets:
+//!
+//! ```
+//! use battleship_core::{
+//!   game::{Difficulty, FleetType, Game, Rule},
+//!   notation,
+//! };
+//!
+//! let mut game = Game::seeded(Rule::Salvo, Difficulty::Easy, FleetType::Classic, 42);
+//! let shots = game.legal_shots(0).into_iter().take(2).collect();
+//! game.fire(&shots, false);
+//! game.bot_fire();
+//!
+//! let text = notation::write(&game);
+//! let restored = notation::parse(&text).unwrap();
+//! assert_eq!(restored.timeline().len(), game.timeline().len());
+//! assert_eq!(restored.state_hash(), game.state_hash());
+//! ```
+
+pub mod features;
+pub mod game;
+pub mod notation;
+pub mod strategy;
+pub mod turn_log;
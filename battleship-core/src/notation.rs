@@ -0,0 +1,457 @@
+//! A plain text notation for a whole match, to share it in an issue or a forum post. Tag
+//! pairs give the rule, the difficulty and both fleets, followed by the numbered turns and
+//! the result, much like PGN for chess:
+//!
+//! ```text
+//! [Rule "Default"]
+//! [Difficulty "Hard"]
+//! [You "X@A1/90 V@D1/90 H@A6/90 I@F6/90"]
+//! [Computer "X@H8/180 V@A1/90 H@E4/270 I@B6/360"]
+//! [Seed "4127"]
+//!
+//! 1. B7 2. C3 3. A1,A2 4. J10 *
+//! ```
+//!
+//! A ship is its type, the cell of the top left corner of its shape and its rotation. Shots
+//! fired in the same turn are joined with commas, and a power-up used in place of firing
+//! is the cell it was used on followed by its mark, `C4?` for a sonar. The result is `1-0`
+//! when the user won, `0-1` when the computer did and `*` while the match is still on. The
+//! seed is there when the match was dealt from one, for the computer to play on as it did.
+//! A `Features` tag lists the optional features a match depends on, separated by spaces,
+//! and a `Note` tag has what the user wrote down about the match. Under the flagship rule a
+//! `Flagships` tag has the number of each player's flagship in its fleet, the user's first.
+//! Under the mines rule a `Mines` tag has the cells each player hid their mines in, joined
+//! with commas like a salvo, the user's first.
+//! A `First` tag names the computer when it fired the first turn, and a `Balanced` tag of
+//! `Yes` is there when the player firing second got a shot more in their first turn.
+
+use std::{
+  collections::BTreeSet,
+  io::{self, ErrorKind},
+};
+
+use super::{
+  features,
+  game::{
+    a1_notation, check_fleet, collect_salvo, parse_a1, parse_power_up, write_power_up, Difficulty,
+    Game, Placement, Turn,
+  },
+};
+
+/// Width the turns are wrapped at
+const LINE_WIDTH: usize = 80;
+
+pub fn write(game: &Game) -> String {
+  let mut text = format!(
+    "[Rule \"{}\"]\n[Difficulty \"{}\"]\n[You \"{}\"]\n[Computer \"{}\"]\n\n",
+    game.rule,
+    game.difficulty(),
+    write_fleet(&game.player().player_board().placements()),
+    write_fleet(&game.computer().player_board().placements()),
+  );
+  if let Some(seed) = game.seed() {
+    text.insert_str(text.len() - 1, &format!("[Seed \"{}\"]\n", seed));
+  }
+  if game.first() == 1 {
+    text.insert_str(text.len() - 1, "[First \"Computer\"]\n");
+  }
+  if game.is_balanced() {
+    text.insert_str(text.len() - 1, "[Balanced \"Yes\"]\n");
+  }
+  if let [Some(you), Some(computer)] = [game.flagship(0), game.flagship(1)] {
+    text.insert_str(
+      text.len() - 1,
+      &format!("[Flagships \"{} {}\"]\n", you + 1, computer + 1),
+    );
+  }
+  if !game.mines(0).is_empty() && !game.mines(1).is_empty() {
+    let mines = (0..2)
+      .map(|player| {
+        game
+          .mines(player)
+          .iter()
+          .map(|c| a1_notation(*c))
+          .collect::<Vec<_>>()
+          .join(",")
+      })
+      .collect::<Vec<_>>();
+    text.insert_str(
+      text.len() - 1,
+      &format!("[Mines \"{}\"]\n", mines.join(" ")),
+    );
+  }
+  if let Some(note) = game.note() {
+    text.insert_str(text.len() - 1, &format!("[Note \"{}\"]\n", note));
+  }
+
+  let result = match game.winner() {
+    Some(0) => "1-0",
+    Some(_) => "0-1",
+    None => "*",
+  };
+  let tokens = game
+    .timeline()
+    .iter()
+    .enumerate()
+    .map(|(index, turn)| {
+      let shots = match turn.power_up {
+        Some(power_up) => write_power_up(power_up),
+        None => turn
+          .shots
+          .iter()
+          .map(|c| a1_notation(*c))
+          .collect::<Vec<_>>()
+          .join(","),
+      };
+      format!("{}. {}", index + 1, shots)
+    })
+    .chain(std::iter::once(result.to_string()));
+  let mut line = String::new();
+  for token in tokens {
+    if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+      text.push_str(&line);
+      text.push('\n');
+      line.clear();
+    }
+    if !line.is_empty() {
+      line.push(' ');
+    }
+    line.push_str(&token);
+  }
+  text.push_str(&line);
+  text.push('\n');
+  text
+}
+
+pub fn parse(text: &str) -> io::Result<Game> {
+  let invalid = |msg: String| io::Error::new(ErrorKind::InvalidData, format!("notation: {}", msg));
+
+  let mut rule = None;
+  let mut used = vec![];
+  let mut difficulty = None;
+  let mut fleets = [None, None];
+  let mut seed = None;
+  let mut first = 0;
+  let mut balanced = false;
+  let mut note = None;
+  let mut flagships = vec![];
+  let mut mines = vec![];
+  let mut moves = vec![];
+  for line in text.lines().map(str::trim) {
+    if let Some(tag) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+      let (name, value) = tag
+        .split_once(' ')
+        .map(|(n, v)| (n, v.trim().trim_matches('"')))
+        .ok_or_else(|| invalid(format!("invalid tag {}", line)))?;
+      match name {
+        "Rule" => rule = Some(value),
+        "Features" => used.extend(value.split_whitespace().map(String::from)),
+        "Difficulty" => difficulty = Some(value.parse::<Difficulty>().map_err(invalid)?),
+        "You" => fleets[0] = Some(parse_fleet(value).map_err(invalid)?),
+        "Computer" => fleets[1] = Some(parse_fleet(value).map_err(invalid)?),
+        "Seed" => {
+          seed = Some(
+            value
+              .parse::<u64>()
+              .map_err(|_| invalid(format!("invalid seed {}", value)))?,
+          )
+        }
+        "First" => {
+          first = match value {
+            "You" => 0,
+            "Computer" => 1,
+            _ => return Err(invalid(format!("invalid first player {}", value))),
+          }
+        }
+        "Balanced" => {
+          balanced = match value {
+            "Yes" => true,
+            "No" => false,
+            _ => return Err(invalid(format!("invalid balance {}", value))),
+          }
+        }
+        "Note" => note = Some(value.to_string()),
+        "Flagships" => {
+          flagships = value
+            .split_whitespace()
+            .map(|n| match n.parse::<usize>() {
+              Ok(n) if n > 0 => Ok(n - 1),
+              _ => Err(invalid(format!("invalid flagship {}", n))),
+            })
+            .collect::<io::Result<Vec<_>>>()?
+        }
+        "Mines" => {
+          mines = value
+            .split_whitespace()
+            .map(|cells| {
+              cells
+                .split(',')
+                .map(|s| parse_a1(s).ok_or_else(|| invalid(format!("invalid mine {}", s))))
+                .collect::<io::Result<BTreeSet<_>>>()
+            })
+            .collect::<io::Result<Vec<_>>>()?
+        }
+        // other tags, like who played or when, are for people to read
+        _ => {}
+      }
+    } else {
+      moves.extend(line.split_whitespace());
+    }
+  }
+
+  // what the build lacks says more than the rule it can't read
+  features::check(&used).map_err(invalid)?;
+  let (rule, difficulty) = match (rule, difficulty) {
+    (Some(rule), Some(difficulty)) => (features::parse_rule(rule).map_err(invalid)?, difficulty),
+    _ => return Err(invalid("missing the rule or difficulty".into())),
+  };
+  let fleets = match fleets {
+    [Some(you), Some(computer)] => [you, computer],
+    _ => return Err(invalid("missing a fleet".into())),
+  };
+
+  let mut turns = vec![];
+  let mut result = None;
+  let mut tokens = moves.into_iter();
+  while let Some(token) = tokens.next() {
+    if let Some(number) = token.strip_suffix('.') {
+      if number.parse::<usize>() != Ok(turns.len() + 1) {
+        return Err(invalid(format!("expected turn {}", turns.len() + 1)));
+      }
+      let token = tokens
+        .next()
+        .ok_or_else(|| invalid(format!("turn {} has no shots", number)))?;
+      if let Some(power_up) = parse_power_up(token) {
+        turns.push(Turn {
+          player: (first + turns.len()) % 2,
+          power_up: Some(power_up),
+          ..Default::default()
+        });
+        continue;
+      }
+      let shots = token
+        .split(',')
+        .map(|s| parse_a1(s).ok_or_else(|| invalid(format!("invalid cell {}", s))))
+        .collect::<io::Result<Vec<_>>>()?;
+      turns.push(Turn {
+        // players alternate, starting with the one who fired first
+        player: (first + turns.len()) % 2,
+        shots: collect_salvo(&shots).map_err(|err| invalid(err.to_string()))?,
+        ..Default::default()
+      });
+    } else if result.is_none() && ["1-0", "0-1", "*"].contains(&token) {
+      result = Some(token);
+    } else {
+      return Err(invalid(format!("unexpected {}", token)));
+    }
+  }
+
+  let mut game = match seed {
+    Some(seed) => Game::restore_seeded(rule, difficulty, seed, fleets, &[]),
+    None => Game::restore(rule, difficulty, fleets, &[]),
+  };
+  // who fires first, the flagships and the mines are picked before the first shot, so
+  // they're set before the turns
+  game.set_first(first);
+  game.set_balanced(balanced);
+  for (player, index) in flagships.into_iter().enumerate().take(2) {
+    game.set_flagship(player, index).map_err(invalid)?;
+  }
+  for (player, cells) in mines.iter().enumerate().take(2) {
+    game.set_mines(player, cells).map_err(invalid)?;
+  }
+  let mut game = game.replay_turns(&turns);
+  if game.timeline().len() < turns.len() {
+    return Err(invalid(format!(
+      "the match is over after turn {}",
+      game.timeline().len()
+    )));
+  }
+  let winner = match result {
+    Some("1-0") => Some(0),
+    Some("0-1") => Some(1),
+    _ => None,
+  };
+  match (game.winner(), winner) {
+    // a match lost on time or out of misses ends before its turns decide it
+    (None, Some(winner)) => game.forfeit(1 - winner),
+    (decided, _) if result.is_some() && decided != winner => {
+      return Err(invalid(format!(
+        "the turns don't lead to the result {}",
+        result.unwrap_or_default()
+      )))
+    }
+    _ => {}
+  }
+  game.set_note(note);
+  Ok(game)
+}
+
+/// A fleet as its ships separated by spaces, `X@A1/90 V@D1/90 H@A6/90 I@F6/90`
+pub fn write_fleet(placements: &[Placement]) -> String {
+  placements
+    .iter()
+    .map(|p| format!("{}@{}/{}", p.ship_type, a1_notation(p.origin), p.rotation))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+pub fn parse_fleet(value: &str) -> Result<Vec<Placement>, String> {
+  let fleet = value
+    .split_whitespace()
+    .map(|ship| {
+      let invalid = || format!("invalid ship {}", ship);
+      let (ship_type, rest) = ship.split_once('@').ok_or_else(invalid)?;
+      let (origin, rotation) = rest.split_once('/').ok_or_else(invalid)?;
+      Ok(Placement {
+        ship_type: ship_type.parse()?,
+        origin: parse_a1(origin).ok_or_else(invalid)?,
+        rotation: rotation
+          .parse()
+          .ok()
+          .filter(|r| [90, 180, 270, 360].contains(r))
+          .ok_or_else(invalid)?,
+      })
+    })
+    .collect::<Result<Vec<_>, String>>()?;
+  check_fleet(&fleet)?;
+  Ok(fleet)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::{FleetType, PowerUp, Rule, ShipType};
+
+  #[test]
+  fn test_notation_round_trip() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy, 0);
+    while !game.is_won() {
+      let shots = game.legal_shots(0).into_iter().take(2).collect();
+      game.fire(&shots, false);
+      if !game.is_won() {
+        game.bot_fire();
+      }
+    }
+    game.set_note(Some("bot missed an obvious kill on turn 12".into()));
+    let text = write(&game);
+    assert!(text.starts_with("[Rule \"Fury\"]\n[Difficulty \"Easy\"]\n[You \""));
+    assert!(text.contains("\n[Note \"bot missed an obvious kill on turn 12\"]\n\n"));
+    assert!(text.contains("\n\n1. A1,B1 2. "));
+    assert!(text.lines().all(|l| l.len() <= LINE_WIDTH));
+
+    let restored = parse(&text).unwrap();
+    assert_eq!(restored.timeline(), game.timeline());
+    assert_eq!(restored.winner(), game.winner());
+    assert_eq!(restored.note(), game.note());
+    assert_eq!(write(&restored), text);
+  }
+
+  #[test]
+  fn test_notation_computer_first() {
+    let mut game = Game::seeded(Rule::Default, Difficulty::Hard, FleetType::Standard, 3);
+    game.set_first(1);
+    game.set_balanced(true);
+    for _ in 0..3 {
+      game.bot_fire();
+      let shots = game.legal_shots(0).into_iter().take(1).collect();
+      game.fire(&shots, false);
+    }
+    let text = write(&game);
+    assert!(text.contains("\n[First \"Computer\"]\n[Balanced \"Yes\"]\n"));
+
+    let restored = parse(&text).unwrap();
+    assert_eq!(restored.first(), 1);
+    assert!(restored.is_balanced());
+    assert_eq!(restored.timeline(), game.timeline());
+    assert_eq!(restored.state_hash(), game.state_hash());
+    assert_eq!(write(&restored), text);
+    assert_eq!(
+      parse(&text.replace("\"Computer\"]", "\"Bot\"]"))
+        .err()
+        .map(|err| err.to_string()),
+      Some("notation: invalid first player Bot".into())
+    );
+  }
+
+  #[test]
+  fn test_notation_mines() {
+    let mut game = Game::new(Rule::Mines, Difficulty::Hard, 0);
+    for _ in 0..4 {
+      let shots = game.legal_shots(0).into_iter().take(1).collect();
+      game.fire(&shots, false);
+      game.bot_fire();
+    }
+    let text = write(&game);
+    let mines = game
+      .mines(1)
+      .iter()
+      .map(|c| a1_notation(*c))
+      .collect::<Vec<_>>()
+      .join(",");
+    assert!(text.contains(&format!(" {}\"]\n", mines)));
+
+    let restored = parse(&text).unwrap();
+    assert_eq!(restored.mines(0), game.mines(0));
+    assert_eq!(restored.mines(1), game.mines(1));
+    assert_eq!(restored.state_hash(), game.state_hash());
+    assert_eq!(write(&restored), text);
+  }
+
+  #[test]
+  fn test_notation_custom_fleet() {
+    let tug = ShipType::custom("Tug", &["**.", ".**"]).unwrap();
+    let fleet = FleetType::custom(vec![tug, ShipType::P]).unwrap();
+    let mut game = Game::seeded(Rule::Default, Difficulty::Hard, fleet.clone(), 5);
+    for _ in 0..3 {
+      let shots = game.legal_shots(0).into_iter().take(1).collect();
+      game.fire(&shots, false);
+      game.bot_fire();
+    }
+    game.use_power_up(PowerUp::Sonar, (4, 4), false).unwrap();
+    let text = write(&game);
+    assert!(text.contains("Tug=**.|.**@"));
+    assert!(text.contains(" 7. E5? *"));
+
+    let restored = parse(&text).unwrap();
+    assert_eq!(restored.fleet_type(), &fleet);
+    assert_eq!(restored.timeline(), game.timeline());
+    assert_eq!(write(&restored), text);
+  }
+
+  #[test]
+  fn test_notation_errors() {
+    let game = Game::new(Rule::Default, Difficulty::Hard, 0);
+    let text = write(&game);
+    assert!(text.ends_with("\n\n*\n"));
+
+    let error = |text: String| parse(&text).err().unwrap().to_string();
+    assert_eq!(
+      error(text.replace("*", "1. A1 3. B2")),
+      "notation: expected turn 2"
+    );
+    assert_eq!(
+      parse(&text.replace("*", "1. A1 0-1")).unwrap().winner(),
+      Some(1)
+    );
+    assert_eq!(
+      error(text.replace("*", "1. A1,A1")),
+      "notation: A1 is fired upon more than once"
+    );
+    assert_eq!(
+      error(text.replace("*", "1. K1")),
+      "notation: invalid cell K1"
+    );
+    assert_eq!(
+      error(text.replace("[Rule \"Default\"]\n", "")),
+      "notation: missing the rule or difficulty"
+    );
+    assert_eq!(
+      error(text.replace(
+        "[Rule \"Default\"]",
+        "[Rule \"Torpedoes\"]\n[Features \"torpedoes\"]"
+      )),
+      "notation: the match needs features this build lacks: torpedoes"
+    );
+  }
+}
@@ -0,0 +1,257 @@
+//! How the computer picks the cells it fires upon. Every difficulty has a strategy of its
+//! own, and a frontend embedding the engine can have the computer play by one of its own
+//! making with [`Game::set_strategy`](crate::game::Game::set_strategy):
+//!
+//! ```
+//! use std::collections::BTreeSet;
+//!
+//! use battleship_core::{
+//!   game::{Coordinate, Difficulty, FleetType, Game, Rule},
+//!   strategy::{BoardView, Strategy},
+//! };
+//!
+//! /// Fires upon the cells in reading order, the first ones left every turn
+//! #[derive(Clone)]
+//! struct Sweep;
+//!
+//! impl Strategy for Sweep {
+//!   fn next_shots(&mut self, view: &BoardView, budget: usize) -> BTreeSet<Coordinate> {
+//!     view.legal.iter().take(budget).copied().collect()
+//!   }
+//! }
+//!
+//! let mut game = Game::seeded(Rule::Default, Difficulty::Hard, FleetType::Standard, 3);
+//! game.set_strategy(Box::new(Sweep));
+//! let shots = game.legal_shots(0).into_iter().take(1).collect();
+//! game.fire(&shots, false);
+//! game.bot_fire();
+//! assert!(game.timeline()[1].shots.contains(&(0, 0)));
+//! ```
+
+use std::{
+  cell::{RefCell, RefMut},
+  collections::BTreeSet,
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use super::game::{
+  get_random_coordinate, Board, BotMemory, Coordinate, Rule, Status, COLS, POS_ADDITION, ROWS,
+};
+
+/// What the computer knows when it picks its shots
+pub struct BoardView<'a> {
+  /// what it found out about the user's fleet so far
+  pub board: &'a Board,
+  /// what it worked out from that about the ships left
+  pub memory: &'a BotMemory,
+  /// the cells worth a shot this turn, any other shot is dropped
+  pub legal: &'a BTreeSet<Coordinate>,
+  pub rule: Rule,
+  rng: RefCell<StdRng>,
+}
+
+impl<'a> BoardView<'a> {
+  pub fn new(
+    board: &'a Board,
+    memory: &'a BotMemory,
+    legal: &'a BTreeSet<Coordinate>,
+    rule: Rule,
+    rng: StdRng,
+  ) -> Self {
+    Self {
+      board,
+      memory,
+      legal,
+      rule,
+      rng: RefCell::new(rng),
+    }
+  }
+
+  /// The computer's random numbers, which follow from the seed of the match so a seeded
+  /// match plays out the same again
+  pub fn rng(&self) -> RefMut<'_, StdRng> {
+    self.rng.borrow_mut()
+  }
+
+  /// The random numbers as the strategy left them, for the next turn
+  pub fn into_rng(self) -> StdRng {
+    self.rng.into_inner()
+  }
+}
+
+/// Picks the cells the computer fires upon in its turn
+pub trait Strategy: CloneStrategy {
+  /// As many cells as the budget from the legal ones of the view. The budget is never
+  /// more than there are legal cells, and fewer shots than it end the turn short.
+  fn next_shots(&mut self, view: &BoardView, budget: usize) -> BTreeSet<Coordinate>;
+}
+
+/// Lets a match be copied along with the strategy its computer plays by, for every
+/// strategy that can be cloned
+pub trait CloneStrategy {
+  fn clone_box(&self) -> Box<dyn Strategy>;
+}
+
+impl<T: Strategy + Clone + 'static> CloneStrategy for T {
+  fn clone_box(&self) -> Box<dyn Strategy> {
+    Box::new(self.clone())
+  }
+}
+
+impl Clone for Box<dyn Strategy> {
+  fn clone(&self) -> Self {
+    self.clone_box()
+  }
+}
+
+/// Fires at random, without a thought for earlier shots
+#[derive(Clone)]
+pub struct Random;
+
+impl Strategy for Random {
+  fn next_shots(&mut self, view: &BoardView, budget: usize) -> BTreeSet<Coordinate> {
+    let mut rng = view.rng();
+    let mut shots = BTreeSet::new();
+    while shots.len() < budget {
+      let shot = get_random_coordinate(&mut *rng, (1, 1));
+      if view.legal.contains(&shot) {
+        shots.insert(shot);
+      }
+    }
+    shots
+  }
+}
+
+/// Finishes off the ships it hit where the ships left can still be, and hunts around its
+/// earlier hits otherwise
+#[derive(Clone)]
+pub struct HuntTarget;
+
+impl Strategy for HuntTarget {
+  fn next_shots(&mut self, view: &BoardView, budget: usize) -> BTreeSet<Coordinate> {
+    hunt(view, budget, view.memory.targets())
+  }
+}
+
+/// Fires where the ships left are most likely to be, going by every way they could still
+/// be placed, and under the flagship rule where the flagship likely is
+#[derive(Clone)]
+pub struct Probability;
+
+impl Strategy for Probability {
+  fn next_shots(&mut self, view: &BoardView, budget: usize) -> BTreeSet<Coordinate> {
+    let targets = if view.rule == Rule::Flagship {
+      // hunt the likeliest flagship before the rest of the fleet
+      view.memory.hottest_flagship(&mut *view.rng())
+    } else {
+      view.memory.hottest(&mut *view.rng())
+    };
+    hunt(view, budget, targets)
+  }
+}
+
+/// Fires upon dented armor first, then the targets, then around earlier hits
+fn hunt(view: &BoardView, budget: usize, mut targets: Vec<Coordinate>) -> BTreeSet<Coordinate> {
+  let previous_hits = view.board.cells_with(Status::Hit);
+  let echoes = view.board.cells_with(Status::Echo);
+  if view.memory.open_hits.is_empty() && !echoes.is_empty() {
+    // with no hit to finish off, look for the ship the sonar picked up
+    targets.retain(|c| echoes.contains(c));
+    if targets.is_empty() {
+      targets = echoes;
+    }
+  }
+  // a dent is a ship cell sure to give way to the next hit
+  let dents = view.board.cells_with(Status::Dent);
+  let mut targets = dents.into_iter().chain(targets);
+
+  let mut rng = view.rng();
+  let mut shots = BTreeSet::new();
+  while shots.len() < budget {
+    let shot = if let Some(target) = targets.next() {
+      // finish off the ships already hit where they can still be
+      target
+    } else if let Some(&(row, col)) = previous_hits.choose(&mut *rng) {
+      // try the cells near previous hits, staying on the board
+      let row_addition = POS_ADDITION.choose(&mut *rng).unwrap_or(&0);
+      let col_addition = POS_ADDITION.choose(&mut *rng).unwrap_or(&0);
+      let r = row as i32 + row_addition;
+      let c = col as i32 + col_addition;
+      (
+        if r >= ROWS as i32 || r < 0 {
+          row
+        } else {
+          r as usize
+        },
+        if c >= COLS as i32 || c < 0 {
+          col
+        } else {
+          c as usize
+        },
+      )
+    } else {
+      get_random_coordinate(&mut *rng, (1, 1))
+    };
+    if view.legal.contains(&shot) {
+      shots.insert(shot);
+    }
+  }
+  shots
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::{Difficulty, FleetType, Game};
+
+  /// Fires upon every cell at once, fired upon before or not
+  #[derive(Clone)]
+  struct Everywhere;
+
+  impl Strategy for Everywhere {
+    fn next_shots(&mut self, _: &BoardView, _: usize) -> BTreeSet<Coordinate> {
+      (0..ROWS)
+        .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+        .collect()
+    }
+  }
+
+  fn bot_turn(game: &mut Game) -> BTreeSet<Coordinate> {
+    let shots = game.legal_shots(0).into_iter().take(1).collect();
+    game.fire(&shots, false);
+    game.bot_fire();
+    game.timeline().last().unwrap().shots.clone()
+  }
+
+  #[test]
+  fn test_strategy_custom() {
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 5);
+    game.set_strategy(Box::new(Everywhere));
+    assert_eq!(bot_turn(&mut game), [(0, 0)].iter().copied().collect());
+    // what was fired upon already is dropped, and the budget holds
+    assert_eq!(bot_turn(&mut game), [(0, 1)].iter().copied().collect());
+
+    // a copy of the match keeps the strategy
+    let mut copy = game.clone();
+    assert_eq!(bot_turn(&mut copy), [(0, 2)].iter().copied().collect());
+  }
+
+  #[test]
+  fn test_strategy_hunt_target() {
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 9);
+    game.set_strategy(Box::new(HuntTarget));
+    while game.computer().memory().open_hits.is_empty() {
+      assert_eq!(bot_turn(&mut game).len(), 1);
+    }
+    // once it hits a ship it doesn't take long to sink it
+    let hit = *game.computer().memory().open_hits.iter().next().unwrap();
+    let turns = (0..20)
+      .take_while(|_| {
+        bot_turn(&mut game);
+        game.computer().memory().open_hits.contains(&hit)
+      })
+      .count();
+    assert!(turns < 19);
+  }
+}
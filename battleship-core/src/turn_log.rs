@@ -0,0 +1,654 @@
+//! Persists the timeline of a match to disk as it progresses, so an interrupted
+//! match can be restored from the log instead of being lost.
+//!
+//! The log is a plain text file with one record per line:
+//!
+//! ```text
+//! rule Fury
+//! difficulty Hard
+//! first 0
+//! balanced
+//! ship 0 X 90 2,3
+//! flagship 0 2
+//! mines 0 4,4 7,1
+//! turn 0 1,1 3,3
+//! turn 1 4,5
+//! power 0 sonar 6,2
+//! hash 5e1c0a2b9f3d4e71
+//! ```
+//!
+//! A `first` line has the player who fired first, and a `balanced` line is there when the
+//! player firing second got a shot more in their first turn. A `flagship` line has the
+//! index of the player's flagship in its fleet, under the flagship rule, and a `mines` line
+//! the cells the player hid their mines in, under the mines rule. A `power` line is
+//! a turn in which the player used a power-up on a cell instead of firing. A `hash` line
+//! holds the hash of both fleets after the turns before it. Replaying the turns must
+//! arrive at the same hashes, otherwise the log is out of sync with the engine and
+//! restoring it would resume a different match.
+//!
+//! A match over the network only has the fleet of this side, the opponent's is kept on
+//! the other side:
+//!
+//! ```text
+//! versus X,V,H,I
+//! salt 8f14e45fceea167a5a36dedd4bea2543
+//! commitment 5d41402abc4b2a76b9719d911017c592...
+//! ship 0 X 90 2,3
+//! turn 0 1,1 3,3
+//! answer RESULT B2=. D4=X
+//! turn 1 4,5
+//! ```
+//!
+//! The `versus` line has the ships of the opponent's fleet, the `salt` line the salt this
+//! side committed to its fleet with and the `commitment` line the hash the other side
+//! committed to theirs with, to connect to the same match again. Every turn upon the fleet
+//! kept elsewhere is followed by an `answer` line with what the other side answered it
+//! found, which settles it again.
+
+use std::{
+  collections::BTreeSet,
+  fs::{self, File, OpenOptions},
+  io::{self, BufRead, BufReader, Write},
+  path::PathBuf,
+};
+
+use super::game::{
+  collect_salvo, parse_ships, write_ships, Coordinate, Difficulty, FleetType, Game, Placement,
+  PowerUp, Rule, Turn, COLS, ROWS,
+};
+
+/// What both sides of a match over the network committed to their fleets with, for this
+/// side to connect to the same match again
+#[derive(PartialEq, Debug, Clone)]
+pub struct Commitments {
+  /// the salt of the hash this side committed to its fleet with
+  pub salt: String,
+  /// the hash the other side committed to its fleet with
+  pub theirs: String,
+}
+
+pub struct TurnLog {
+  path: PathBuf,
+  written: usize,
+  /// the commitments of the match over the network kept in the log
+  commitments: Option<Commitments>,
+}
+
+impl TurnLog {
+  pub fn new(path: PathBuf) -> Self {
+    Self {
+      path,
+      written: 0,
+      commitments: None,
+    }
+  }
+
+  /// The commitments of the match over the network kept in the log, once restored or set
+  pub fn commitments(&self) -> Option<&Commitments> {
+    self.commitments.as_ref()
+  }
+
+  /// Keeps the commitments of the match over the network the log is started with next
+  pub fn set_commitments(&mut self, commitments: Commitments) {
+    self.commitments = Some(commitments);
+  }
+
+  /// Reads the log of an interrupted match, if there is one, along with the commitments of
+  /// a match over the network
+  pub fn restore(&mut self) -> io::Result<Option<Game>> {
+    if !self.path.exists() {
+      return Ok(None);
+    }
+    let file = File::open(&self.path)?;
+    let lines = BufReader::new(file)
+      .lines()
+      .collect::<io::Result<Vec<_>>>()?;
+    if lines.iter().all(|l| l.trim().is_empty()) {
+      return Ok(None);
+    }
+    let (game, commitments) = read(&lines)?;
+    self.commitments = commitments;
+    Ok(Some(game))
+  }
+
+  /// Writes the full state of the game, replacing any previous log
+  pub fn start(&mut self, game: &Game) -> io::Result<()> {
+    let mut file = File::create(&self.path)?;
+    writeln!(file, "rule {}", game.rule)?;
+    writeln!(file, "difficulty {}", game.difficulty())?;
+    writeln!(file, "first {}", game.first())?;
+    if game.is_balanced() {
+      writeln!(file, "balanced")?;
+    }
+    if game.is_kept_elsewhere(1) {
+      writeln!(file, "versus {}", write_ships(&game.fleet_type().ships()))?;
+    }
+    if let Some(commitments) = &self.commitments {
+      writeln!(file, "salt {}", commitments.salt)?;
+      writeln!(file, "commitment {}", commitments.theirs)?;
+    }
+    let fleets = [
+      game.player().player_board().placements(),
+      game.computer().player_board().placements(),
+    ];
+    for (player, fleet) in fleets.iter().enumerate() {
+      if game.is_kept_elsewhere(player) {
+        continue;
+      }
+      for p in fleet {
+        writeln!(
+          file,
+          "ship {} {} {} {}",
+          player,
+          p.ship_type,
+          p.rotation,
+          format_coordinate(p.origin)
+        )?;
+      }
+    }
+    for player in 0..fleets.len() {
+      if let Some(index) = game.flagship(player) {
+        writeln!(file, "flagship {} {}", player, index)?;
+      }
+      let mines = game.mines(player);
+      if !mines.is_empty() {
+        let cells = mines
+          .iter()
+          .map(|c| format_coordinate(*c))
+          .collect::<Vec<_>>();
+        writeln!(file, "mines {} {}", player, cells.join(" "))?;
+      }
+    }
+    self.written = 0;
+    self.write_turns(&mut file, game)
+  }
+
+  /// Appends the turns played since the last call
+  pub fn record(&mut self, game: &Game) -> io::Result<()> {
+    if game.timeline().len() > self.written {
+      let mut file = OpenOptions::new().append(true).open(&self.path)?;
+      self.write_turns(&mut file, game)?;
+    }
+    Ok(())
+  }
+
+  /// Removes the log once the match is over as there is nothing left to restore
+  pub fn finish(&self) -> io::Result<()> {
+    if self.path.exists() {
+      fs::remove_file(&self.path)?;
+    }
+    Ok(())
+  }
+
+  fn write_turns(&mut self, file: &mut File, game: &Game) -> io::Result<()> {
+    for turn in &game.timeline()[self.written..] {
+      match turn.power_up {
+        Some((power_up, cell)) => writeln!(
+          file,
+          "power {} {} {}",
+          turn.player,
+          power_up,
+          format_coordinate(cell)
+        )?,
+        None => {
+          let shots = turn
+            .shots
+            .iter()
+            .map(|c| format_coordinate(*c))
+            .collect::<Vec<_>>()
+            .join(" ");
+          writeln!(file, "turn {} {}", turn.player, shots)?;
+        }
+      }
+      match &turn.answer {
+        Some(answer) if game.is_kept_elsewhere(1 - turn.player) => {
+          writeln!(file, "answer {}", answer)?
+        }
+        _ => {}
+      }
+    }
+    writeln!(file, "hash {:016x}", game.state_hash())?;
+    file.flush()?;
+    self.written = game.timeline().len();
+    Ok(())
+  }
+}
+
+fn format_coordinate(c: Coordinate) -> String {
+  format!("{},{}", c.0, c.1)
+}
+
+fn parse_coordinate(s: &str) -> Result<Coordinate, String> {
+  let mut parts = s.split(',').map(|p| p.parse::<usize>());
+  match (parts.next(), parts.next(), parts.next()) {
+    (Some(Ok(r)), Some(Ok(c)), None) if r < ROWS && c < COLS => Ok((r, c)),
+    _ => Err(format!("invalid coordinate {}", s)),
+  }
+}
+
+pub fn parse(lines: &[String]) -> io::Result<Game> {
+  read(lines).map(|(game, _)| game)
+}
+
+/// The match in the log and the commitments of a match over the network
+fn read(lines: &[String]) -> io::Result<(Game, Option<Commitments>)> {
+  let mut rule = None;
+  let mut difficulty = None;
+  let mut first = 0;
+  let mut balanced = false;
+  let mut fleets = [vec![], vec![]];
+  // the ships of the fleet kept elsewhere, for a match over the network
+  let mut versus = None;
+  let (mut salt, mut theirs) = (None, None);
+  // flagships by player with the index of their line
+  let mut flagships = vec![];
+  // mines by player with the index of their line
+  let mut mines = vec![];
+  // turns with the index of their line
+  let mut turns = vec![];
+  // expected state hashes by the number of turns played
+  let mut hashes = vec![];
+
+  for (index, line) in lines.iter().enumerate() {
+    let invalid = |msg: String| {
+      io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("turn log line {}: {}", index + 1, msg),
+      )
+    };
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    match words.as_slice() {
+      [] => {}
+      ["rule", r] => rule = Some(r.parse::<Rule>().map_err(invalid)?),
+      ["difficulty", d] => difficulty = Some(d.parse::<Difficulty>().map_err(invalid)?),
+      ["first", player] => {
+        first = player
+          .parse::<usize>()
+          .ok()
+          .filter(|p| *p < 2)
+          .ok_or_else(|| invalid(format!("invalid player {}", player)))?
+      }
+      ["balanced"] => balanced = true,
+      ["versus", ships] => versus = Some(FleetType::of_ships(parse_ships(ships).map_err(invalid)?)),
+      ["salt", value] => salt = Some(value.to_string()),
+      ["commitment", value] => theirs = Some(value.to_string()),
+      ["ship", player, ship_type, rotation, origin] => {
+        let player = player
+          .parse::<usize>()
+          .ok()
+          .filter(|p| *p < 2)
+          .ok_or_else(|| invalid(format!("invalid player {}", player)))?;
+        let placement = Placement {
+          ship_type: ship_type.parse().map_err(invalid)?,
+          rotation: rotation
+            .parse()
+            .map_err(|_| invalid(format!("invalid rotation {}", rotation)))?,
+          origin: parse_coordinate(origin)
+            .map_err(|_| invalid(format!("invalid origin {}", origin)))?,
+        };
+        let (rows, cols) = placement.size();
+        let (r, c) = placement.origin;
+        if r + rows > ROWS || c + cols > COLS {
+          return Err(invalid(format!("invalid origin {}", origin)));
+        }
+        fleets[player].push(placement);
+      }
+      ["flagship", player, ship] => {
+        let player = player
+          .parse::<usize>()
+          .ok()
+          .filter(|p| *p < 2)
+          .ok_or_else(|| invalid(format!("invalid player {}", player)))?;
+        let ship = ship
+          .parse::<usize>()
+          .map_err(|_| invalid(format!("invalid flagship {}", ship)))?;
+        flagships.push((index, player, ship));
+      }
+      ["mines", player, cells @ ..] => {
+        let player = player
+          .parse::<usize>()
+          .ok()
+          .filter(|p| *p < 2)
+          .ok_or_else(|| invalid(format!("invalid player {}", player)))?;
+        let cells = cells
+          .iter()
+          .map(|s| parse_coordinate(s))
+          .collect::<Result<BTreeSet<_>, _>>()
+          .map_err(invalid)?;
+        mines.push((index, player, cells));
+      }
+      ["turn", player, shots @ ..] => {
+        // whose turn it is comes out of replaying the turns before
+        let player = player
+          .parse::<usize>()
+          .ok()
+          .filter(|p| *p < 2)
+          .ok_or_else(|| invalid(format!("invalid player {}", player)))?;
+        let shots = shots
+          .iter()
+          .map(|s| parse_coordinate(s))
+          .collect::<Result<Vec<_>, _>>()
+          .map_err(invalid)?;
+        turns.push((
+          index,
+          Turn {
+            player,
+            shots: collect_salvo(&shots).map_err(|err| invalid(err.to_string()))?,
+            ..Default::default()
+          },
+        ));
+      }
+      ["power", player, power_up, cell] => {
+        let player = player
+          .parse::<usize>()
+          .ok()
+          .filter(|p| *p < 2)
+          .ok_or_else(|| invalid(format!("invalid player {}", player)))?;
+        let power_up = power_up.parse::<PowerUp>().map_err(invalid)?;
+        turns.push((
+          index,
+          Turn {
+            player,
+            power_up: Some((power_up, parse_coordinate(cell).map_err(invalid)?)),
+            ..Default::default()
+          },
+        ));
+      }
+      ["answer", answer @ ..] => match turns.last_mut() {
+        Some((_, turn)) if turn.answer.is_none() => {
+          turn.answer = Some(answer.join(" ").parse().map_err(invalid)?)
+        }
+        _ => return Err(invalid("answer without a turn".into())),
+      },
+      ["hash", hash] => hashes.push((
+        turns.len(),
+        u64::from_str_radix(hash, 16).map_err(|_| invalid(format!("invalid hash {}", hash)))?,
+      )),
+      _ => return Err(invalid(format!("unknown record {}", line))),
+    }
+  }
+
+  let (rule, difficulty) = match (rule, difficulty) {
+    (Some(rule), Some(difficulty)) => (rule, difficulty),
+    _ => {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "turn log is missing the rule or difficulty",
+      ))
+    }
+  };
+
+  let mut game = match &versus {
+    Some(fleet_type) => Game::versus(rule, difficulty, Some(&fleets[0]), fleet_type),
+    None => Game::restore(rule, difficulty, fleets, &[]),
+  };
+  game.set_first(first);
+  game.set_balanced(balanced);
+  for (index, player, ship) in flagships {
+    game.set_flagship(player, ship).map_err(|err| {
+      io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("turn log line {}: {}", index + 1, err),
+      )
+    })?;
+  }
+  for (index, player, cells) in mines {
+    game.set_mines(player, &cells).map_err(|err| {
+      io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("turn log line {}: {}", index + 1, err),
+      )
+    })?;
+  }
+  let mut hashes = hashes.into_iter().peekable();
+  for played in 0..=turns.len() {
+    if played > 0 && !game.is_won() {
+      let (index, turn) = &turns[played - 1];
+      let expected = if game.is_user_turn() { 0 } else { 1 };
+      if turn.player != expected {
+        return Err(io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!(
+            "turn log line {}: expected a turn of player {}",
+            index + 1,
+            expected
+          ),
+        ));
+      }
+      let bot = !game.is_user_turn();
+      game.play(turn, bot).map_err(|err| {
+        io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("turn log line {}: {}", index + 1, err),
+        )
+      })?;
+    }
+    while let Some((_, expected)) = hashes.next_if(|(at, _)| *at == played) {
+      if expected != game.state_hash() {
+        return Err(io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!(
+            "turn log out of sync after {} turns: expected hash {:016x}, replay gave {:016x}",
+            played,
+            expected,
+            game.state_hash()
+          ),
+        ));
+      }
+    }
+  }
+  let commitments = match (salt, theirs) {
+    (Some(salt), Some(theirs)) => Some(Commitments { salt, theirs }),
+    _ => None,
+  };
+  Ok((game, commitments))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::game::Play;
+
+  fn temp_log() -> TurnLog {
+    TurnLog::new(env::temp_dir().join(format!("battleship-{}.log", Uuid::new_v4())))
+  }
+
+  #[test]
+  fn test_turn_log_restore() {
+    let mut log = temp_log();
+    assert!(log.restore().unwrap().is_none());
+
+    let mut game = Game::new(Rule::Charge, Difficulty::Easy, 0);
+    log.start(&game).unwrap();
+
+    game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    log.record(&game).unwrap();
+    game.bot_fire();
+    log.record(&game).unwrap();
+    game.fire(&[(4, 2)].iter().cloned().collect(), false);
+    log.record(&game).unwrap();
+    game.bot_fire();
+    game.use_power_up(PowerUp::Sonar, (6, 6), false).unwrap();
+    log.record(&game).unwrap();
+
+    let restored = log.restore().unwrap().unwrap();
+    assert_eq!(restored.rule.to_string(), "Charge");
+    assert_eq!(restored.difficulty(), &Difficulty::Easy);
+    assert_eq!(restored.timeline(), game.timeline());
+    assert_eq!(restored.is_user_turn(), game.is_user_turn());
+    assert_eq!(
+      restored.player().player_board().to_string(),
+      game.player().player_board().to_string()
+    );
+    assert_eq!(
+      restored.computer().opponent_board().to_string(),
+      game.computer().opponent_board().to_string()
+    );
+
+    log.finish().unwrap();
+    assert!(log.restore().unwrap().is_none());
+  }
+
+  #[test]
+  fn test_turn_log_computer_first() {
+    let mut log = temp_log();
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 1);
+    game.set_balanced(true);
+    log.start(&game).unwrap();
+    game.bot_fire();
+    log.record(&game).unwrap();
+    game.fire(&[(1, 1), (4, 2)].iter().cloned().collect(), false);
+    log.record(&game).unwrap();
+
+    let restored = log.restore().unwrap().unwrap();
+    assert_eq!(restored.first(), 1);
+    assert!(restored.is_balanced());
+    assert_eq!(restored.timeline(), game.timeline());
+    assert_eq!(restored.is_user_turn(), game.is_user_turn());
+    assert_eq!(restored.round(), 2);
+
+    log.finish().unwrap();
+  }
+
+  #[test]
+  fn test_turn_log_mines() {
+    let mut log = temp_log();
+    let mut game = Game::new(Rule::Mines, Difficulty::Easy, 0);
+    log.start(&game).unwrap();
+    for _ in 0..3 {
+      let shots = game.legal_shots(0).into_iter().take(1).collect();
+      game.fire(&shots, false);
+      game.bot_fire();
+      log.record(&game).unwrap();
+    }
+
+    // the mines are the ones the players hid, not drawn again
+    let restored = log.restore().unwrap().unwrap();
+    assert_eq!(restored.mines(0), game.mines(0));
+    assert_eq!(restored.mines(1), game.mines(1));
+    assert_eq!(restored.timeline(), game.timeline());
+
+    log.finish().unwrap();
+  }
+
+  #[test]
+  fn test_turn_log_versus() {
+    let fleet = |seed| {
+      Game::new(Rule::Default, Difficulty::Easy, seed)
+        .player()
+        .player_board()
+        .placements()
+    };
+    let (ours, theirs) = (fleet(0), fleet(1));
+    let mut game = Game::versus(
+      Rule::Default,
+      Difficulty::Easy,
+      Some(&ours),
+      &FleetType::Standard,
+    );
+    let mut other = Game::versus(
+      Rule::Default,
+      Difficulty::Easy,
+      Some(&theirs),
+      &FleetType::Standard,
+    );
+    other.set_first(1);
+    let commitments = Commitments {
+      salt: "8f14".into(),
+      theirs: "5d41".into(),
+    };
+    let mut log = temp_log();
+    log.set_commitments(commitments.clone());
+    log.start(&game).unwrap();
+
+    // each turn is answered by the side keeping the fleet it was played upon
+    let play = Play::Salvo([(1, 1)].iter().cloned().collect());
+    let (_, answer) = other.take_turn(&play).unwrap();
+    game.settle_turn(&play, answer).unwrap();
+    log.record(&game).unwrap();
+    let play = Play::Salvo([(4, 5)].iter().cloned().collect());
+    let (_, answer) = game.take_turn(&play).unwrap();
+    other.settle_turn(&play, answer).unwrap();
+    log.record(&game).unwrap();
+
+    let content = fs::read_to_string(&log.path).unwrap();
+    assert!(content.contains("versus X,V,H,I\nsalt 8f14\ncommitment 5d41\n"));
+    assert!(content.contains("turn 0 1,1\nanswer RESULT B2="));
+    assert!(!content.contains("ship 1 "));
+    let mut restored = TurnLog::new(log.path.clone());
+    let resumed = restored.restore().unwrap().unwrap();
+    assert_eq!(restored.commitments(), Some(&commitments));
+    assert!(resumed.is_kept_elsewhere(1));
+    assert_eq!(resumed.timeline(), game.timeline());
+    assert_eq!(resumed.found_hash(), other.found_hash());
+
+    // a turn upon the fleet kept elsewhere can't be settled without its answer
+    let lines = content
+      .lines()
+      .filter(|l| !l.starts_with("answer "))
+      .map(String::from)
+      .collect::<Vec<_>>();
+    let err = parse(&lines).err().unwrap();
+    assert!(err.to_string().contains("has no answer"));
+
+    log.finish().unwrap();
+  }
+
+  #[test]
+  fn test_turn_log_desync() {
+    let mut log = temp_log();
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    log.start(&game).unwrap();
+    game.fire(&[(1, 1)].iter().cloned().collect(), false);
+    log.record(&game).unwrap();
+
+    let content = fs::read_to_string(&log.path).unwrap();
+    let mut lines = content.lines().map(String::from).collect::<Vec<_>>();
+    assert!(parse(&lines).is_ok());
+    assert_eq!(lines.iter().filter(|l| l.starts_with("hash ")).count(), 2);
+
+    // the fired upon board no longer matches what was recorded
+    let last = lines.len() - 1;
+    lines[last] = format!("hash {:016x}", game.state_hash() ^ 1);
+    let err = parse(&lines).err().unwrap();
+    assert!(err.to_string().contains("out of sync after 1 turns"));
+
+    log.finish().unwrap();
+  }
+
+  #[test]
+  fn test_turn_log_parse_errors() {
+    let lines = |l: &[&str]| l.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert!(parse(&lines(&["rule Fury"])).is_err());
+    assert!(parse(&lines(&["rule Fury", "difficulty Hard", "turn 1 1,1"])).is_err());
+    assert!(parse(&lines(&["rule Fury", "difficulty Hard", "turn 0 1;1"])).is_err());
+    assert!(parse(&lines(&["rule Fury", "difficulty Hard", "ship 2 X 90 1,1"])).is_err());
+    assert!(parse(&lines(&["rule Fury", "difficulty Hard", "ship 0 Q 90 1,1"])).is_err());
+    assert!(parse(&lines(&["rule Fury", "difficulty Hard", "ship 0 X 90 8,1"])).is_err());
+    assert!(parse(&lines(&["rule Fury", "difficulty Hard", "turn 0 10,1"])).is_err());
+    assert!(parse(&lines(&[
+      "rule Fury",
+      "difficulty Hard",
+      "power 0 radar 1,1"
+    ]))
+    .is_err());
+    assert!(parse(&lines(&[
+      "rule Fury",
+      "difficulty Hard",
+      "power 1 sonar 1,1"
+    ]))
+    .is_err());
+    assert!(parse(&lines(&[
+      "rule Fury",
+      "difficulty Hard",
+      "turn 0 1,1 2,2 1,1"
+    ]))
+    .is_err());
+    assert!(parse(&lines(&["rule Fury", "difficulty Hard", "turn 0 1,1"])).is_ok());
+  }
+}
@@ -0,0 +1,181 @@
+//! WebAssembly bindings of the engine, for a game in the browser. Build them with
+//! `wasm-pack build battleship-web --target web` and drive a [`WebGame`] from JavaScript
+//! the way the terminal game drives a `Game`:
+//!
+//! ```js
+//! import init, { WebGame } from "./pkg/battleship_web.js";
+//!
+//! await init();
+//! const game = new WebGame("salvo", "hard");
+//! game.fire("B3 C4 D5 E6 F7");
+//! game.botFire();
+//! console.log(game.view(0));
+//! ```
+//!
+//! Boards are handed over as snapshots, a row of plain glyphs per line, `#` a ship, `x` a
+//! hit, `X` a sunk ship, `.` a miss and a space a cell not fired upon yet. Errors come back
+//! as exceptions with the reason.
+
+use std::{collections::BTreeSet, str::FromStr};
+
+use battleship_core::game::{
+  a1_notation, collect_salvo, parse_a1, Board, Coordinate, Difficulty, Game, Player, Rule, COLS,
+  ROWS,
+};
+use wasm_bindgen::prelude::*;
+
+/// A match between the user in the browser, player 0, and the computer, player 1
+#[wasm_bindgen]
+pub struct WebGame {
+  game: Game,
+}
+
+#[wasm_bindgen]
+impl WebGame {
+  /// A match under the rule at the difficulty, named as on the command line, with the
+  /// user firing first
+  #[wasm_bindgen(constructor)]
+  pub fn new(rule: &str, difficulty: &str) -> Result<WebGame, String> {
+    let rule = Rule::from_str(rule)?;
+    let difficulty = Difficulty::from_str(difficulty)?;
+    Ok(Self {
+      game: Game::new(rule, difficulty, 0),
+    })
+  }
+
+  /// Fires the user's salvo, the cells in A1 notation apart by spaces or commas, and tells
+  /// what it found
+  pub fn fire(&mut self, cells: &str) -> Result<String, String> {
+    if !self.game.is_user_turn() || self.game.is_won() {
+      return Err("It's not your turn".into());
+    }
+    let shots = cells
+      .split(|c: char| c == ',' || c.is_whitespace())
+      .filter(|cell| !cell.is_empty())
+      .map(|cell| parse_a1(cell).ok_or_else(|| format!("{} isn't a cell", cell)))
+      .collect::<Result<Vec<Coordinate>, String>>()?;
+    let salvo = collect_salvo(&shots).map_err(|err| err.to_string())?;
+    self
+      .game
+      .validate_salvo(&salvo)
+      .map_err(|err| err.to_string())?;
+    Ok(self.game.fire(&salvo, false))
+  }
+
+  /// Plays the computer's turn, and tells what it did
+  #[wasm_bindgen(js_name = botFire)]
+  pub fn bot_fire(&mut self) -> Result<String, String> {
+    if self.game.is_user_turn() || self.game.is_won() {
+      return Err("It's not the computer's turn".into());
+    }
+    Ok(self.game.bot_fire())
+  }
+
+  #[wasm_bindgen(js_name = isUserTurn)]
+  pub fn is_user_turn(&self) -> bool {
+    self.game.is_user_turn()
+  }
+
+  #[wasm_bindgen(js_name = isWon)]
+  pub fn is_won(&self) -> bool {
+    self.game.is_won()
+  }
+
+  /// The player who won, once the match is over
+  pub fn winner(&self) -> Option<u32> {
+    self.game.winner().map(|player| player as u32)
+  }
+
+  /// Shots the user may fire this turn
+  #[wasm_bindgen(js_name = shotBudget)]
+  pub fn shot_budget(&self) -> u32 {
+    self.game.shot_budget(0) as u32
+  }
+
+  /// The player's own fleet as a snapshot. The computer's gives its ships away, show the
+  /// user `view(0)` instead.
+  pub fn fleet(&self, player: usize) -> Result<String, String> {
+    Ok(snapshot(self.player(player)?.player_board()))
+  }
+
+  /// What the player knows of the other player's fleet as a snapshot
+  pub fn view(&self, player: usize) -> Result<String, String> {
+    Ok(snapshot(self.player(player)?.opponent_board()))
+  }
+
+  /// The cells the user may fire upon this turn, in A1 notation
+  #[wasm_bindgen(js_name = legalShots)]
+  pub fn legal_shots(&self) -> String {
+    let legal: BTreeSet<Coordinate> = self.game.legal_shots(0);
+    legal
+      .iter()
+      .map(|c| a1_notation(*c))
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+}
+
+impl WebGame {
+  fn player(&self, player: usize) -> Result<&Player, String> {
+    match player {
+      0 => Ok(self.game.player()),
+      1 => Ok(self.game.computer()),
+      _ => Err(format!("There's no player {}", player)),
+    }
+  }
+}
+
+/// The board as it's drawn, a row of plain glyphs per line
+fn snapshot(board: &Board) -> String {
+  (0..ROWS)
+    .map(|row| {
+      (0..COLS)
+        .map(|col| board.status_at((row, col)).as_char())
+        .collect::<String>()
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_web_game() {
+    assert!(WebGame::new("default", "nightmare").is_err());
+    let mut game = WebGame::new("Salvo", "easy").unwrap();
+    assert!(game.is_user_turn());
+    assert_eq!(
+      game.view(0).unwrap(),
+      vec![" ".repeat(COLS); ROWS].join("\n")
+    );
+    assert_eq!(game.fleet(0).unwrap().matches('#').count(), 20);
+    assert!(game.fleet(2).is_err());
+    assert!(game.bot_fire().is_err());
+
+    // a salvo has to fire every shot
+    assert!(game.fire("A1").is_err());
+    assert!(game.fire("A1 A1 B1 C1 D1").is_err());
+    assert!(game.fire("A1 B1 C1 D1 K1").is_err());
+    assert_eq!(game.legal_shots().split(' ').count(), ROWS * COLS);
+    let budget = game.shot_budget() as usize;
+    let cells = game
+      .legal_shots()
+      .split(' ')
+      .take(budget)
+      .collect::<Vec<_>>()
+      .join(",");
+    game.fire(&cells).unwrap();
+    assert!(!game.is_user_turn());
+    assert_eq!(
+      game.view(0).unwrap().lines().next().unwrap().trim().len(),
+      budget
+    );
+
+    assert!(game.fire(&cells).is_err());
+    game.bot_fire().unwrap();
+    assert!(game.is_user_turn());
+    assert_eq!(game.winner(), None);
+  }
+}
@@ -0,0 +1,16 @@
+//! Feeds arbitrary text to the board grid parser, which must reject bad grids
+//! with an error rather than panic, and read back every board it accepts the
+//! same once written down again.
+#![no_main]
+use battleship_core::game::Board;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(text) = std::str::from_utf8(data) {
+    if let Ok(board) = text.parse::<Board>() {
+      let grid = board.to_string();
+      let again = grid.parse::<Board>().expect("a written board reads back");
+      assert_eq!(again.to_string(), grid);
+    }
+  }
+});
@@ -0,0 +1,68 @@
+//! Fires arbitrary salvos from both sides and checks that what each player
+//! knows about the opponent board never contradicts the opponent's fleet.
+//!
+//! The input starts with the seed of the match, eight bytes, then a byte for
+//! the rule and one for the fleet, so a crash plays out the same again.
+#![no_main]
+use std::{collections::BTreeSet, convert::TryInto, str::FromStr};
+
+use battleship_core::game::{Difficulty, FleetType, Game, Player, Rule, Status, COLS, ROWS};
+use libfuzzer_sys::fuzz_target;
+
+// a salvo ends at this byte, every other byte is a shot
+const SALVO_END: u8 = 0xff;
+
+fn check_knowledge(attacker: &Player, defender: &Player) {
+  for r in 0..ROWS {
+    for c in 0..COLS {
+      let (known, _) = attacker.opponent_board().find_position_and_ship((r, c));
+      let (_, ship) = defender.player_board().find_position_and_ship((r, c));
+      match known.get_status(None) {
+        Status::Hit | Status::Kill | Status::Dent => {
+          assert!(ship.is_some(), "hit on water at {:?}", (r, c))
+        }
+        Status::Miss | Status::Mine => assert!(ship.is_none(), "miss on a ship at {:?}", (r, c)),
+        Status::Live => panic!("opponent ship revealed at {:?}", (r, c)),
+        Status::Clear => assert!(ship.is_none(), "ship cleared by sonar at {:?}", (r, c)),
+        Status::Space | Status::Echo | Status::Splash => {}
+      }
+    }
+  }
+}
+
+/// The variant of the byte among the names
+fn pick<T: FromStr>(names: &[&str], byte: u8) -> T {
+  match names[usize::from(byte) % names.len()].parse() {
+    Ok(variant) => variant,
+    Err(_) => unreachable!("the names of the variants parse"),
+  }
+}
+
+fuzz_target!(|data: &[u8]| {
+  if data.len() < 10 {
+    return;
+  }
+  let (header, salvos) = data.split_at(10);
+  let seed = u64::from_le_bytes(header[..8].try_into().unwrap());
+  let rule: Rule = pick(&Rule::variants(), header[8]);
+  let fleet_type: FleetType = pick(&FleetType::variants(), header[9]);
+  let mut game = Game::seeded(rule, Difficulty::Easy, fleet_type, seed);
+  for salvo in salvos.split(|b| *b == SALVO_END) {
+    if game.is_won() {
+      break;
+    }
+    let shots = salvo
+      .iter()
+      .map(|b| (usize::from(b >> 4) % ROWS, usize::from(b & 0x0f) % COLS))
+      .collect::<BTreeSet<_>>();
+    if shots.is_empty() {
+      continue;
+    }
+    game.fire(&shots, !game.is_user_turn());
+
+    check_knowledge(game.player(), game.computer());
+    check_knowledge(game.computer(), game.player());
+    let (shots, hits) = game.shots_and_hits(0);
+    assert!(hits <= shots);
+  }
+});
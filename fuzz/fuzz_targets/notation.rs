@@ -0,0 +1,15 @@
+//! Feeds arbitrary text to the notation parser, which must reject bad matches
+//! with an error rather than panic while replaying them, and write down every
+//! match it accepts so that it reads back to the same state.
+#![no_main]
+use battleship_core::notation;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(text) = std::str::from_utf8(data) {
+    if let Ok(game) = notation::parse(text) {
+      let again = notation::parse(&notation::write(&game)).expect("a written match reads back");
+      assert_eq!(again.state_hash(), game.state_hash());
+    }
+  }
+});
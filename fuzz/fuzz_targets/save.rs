@@ -0,0 +1,24 @@
+//! Feeds arbitrary JSON to the save file of the game, which must refuse a bad
+//! save with an error rather than panic while resuming it, and save every
+//! match it resumes so that it resumes to the same state again.
+#![no_main]
+use battleship_core::{features, game, notation};
+use libfuzzer_sys::fuzz_target;
+
+// the save file lives with the terminal game, not the engine
+#[allow(dead_code)]
+#[path = "../../src/save.rs"]
+mod save;
+
+use save::Save;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(save) = serde_json::from_slice::<Save>(data) {
+    if let Ok((game, elapsed)) = save.restore() {
+      let (again, _) = Save::of(&game, elapsed)
+        .restore()
+        .expect("a saved match resumes");
+      assert_eq!(again.state_hash(), game.state_hash());
+    }
+  }
+});
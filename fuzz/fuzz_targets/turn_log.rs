@@ -0,0 +1,12 @@
+//! Feeds arbitrary text to the turn log parser, which must reject bad logs
+//! with an error rather than panic while restoring the game.
+#![no_main]
+use battleship_core::turn_log;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(text) = std::str::from_utf8(data) {
+    let lines = text.lines().map(String::from).collect::<Vec<_>>();
+    let _ = turn_log::parse(&lines);
+  }
+});
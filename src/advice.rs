@@ -0,0 +1,208 @@
+//! Tips on the user's placement habits drawn from the fleets kept in the stats, and fleets
+//! placed at random away from the cells where the computer found their ships before.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::Rng;
+
+use super::{
+  game::{random_fleet, Coordinate, FleetType, Placement, COLS, ROWS},
+  stats::FleetRecord,
+};
+
+/// Fleets needed before a habit says anything
+const MIN_FLEETS: usize = 3;
+/// Share of the fleets, in percent, that makes a habit worth a tip
+const HABIT_SHARE: usize = 70;
+/// Random fleets tried for the one furthest from the habits
+const CANDIDATES: usize = 200;
+
+/// Habits of the user's recent placements worth breaking, most telling first
+pub fn tips(records: &[FleetRecord]) -> Vec<String> {
+  if records.len() < MIN_FLEETS {
+    return vec![format!(
+      "Tips on your placement after {} more matches",
+      MIN_FLEETS - records.len()
+    )];
+  }
+  let share = |count: usize| count * 100 / records.len();
+  let mut tips = vec![];
+
+  if let Some((name, average)) = found_first(records) {
+    tips.push(format!(
+      "The computer finds your {} first, after {} shots on average",
+      name, average
+    ));
+  }
+
+  let mut on_edge = BTreeMap::<&str, usize>::new();
+  for placement in records.iter().flat_map(|r| &r.fleet) {
+    if placement.cells().iter().any(is_on_edge) {
+      *on_edge.entry(placement.ship_type.name()).or_default() += 1;
+    }
+  }
+  for (name, count) in on_edge {
+    if share(count) >= HABIT_SHARE {
+      tips.push(format!(
+        "You place the {} on an edge {}% of the time",
+        name,
+        share(count).min(100)
+      ));
+    }
+  }
+
+  let touching = records.iter().filter(|r| ships_touch(&r.fleet)).count();
+  if share(touching) >= HABIT_SHARE {
+    tips.push(format!(
+      "Your ships touch in {}% of matches, one hit gives away two",
+      share(touching)
+    ));
+  }
+
+  if tips.is_empty() {
+    tips.push(format!(
+      "No habits stand out in your last {} fleets",
+      records.len()
+    ));
+  }
+  tips
+}
+
+/// A random fleet of the type that keeps clear of the cells the user's ships sat on most,
+/// those found early counting the most
+pub fn suggest<R: Rng>(
+  records: &[FleetRecord],
+  fleet_type: &FleetType,
+  rng: &mut R,
+) -> Vec<Placement> {
+  let mut heat = BTreeMap::<Coordinate, usize>::new();
+  for record in records {
+    for (placement, found) in record.fleet.iter().zip(&record.found) {
+      // a ship found after a handful of shots weighs up to ten times one never found
+      let weight = found.map_or(1, |shots| 1 + 9 * ROWS * COLS / (ROWS * COLS + shots * 10));
+      for cell in placement.cells() {
+        *heat.entry(cell).or_default() += weight;
+      }
+    }
+  }
+  let score = |fleet: &[Placement]| {
+    fleet
+      .iter()
+      .flat_map(|p| p.cells())
+      .map(|c| heat.get(&c).copied().unwrap_or_default())
+      .sum::<usize>()
+  };
+  (0..CANDIDATES)
+    .map(|_| random_fleet(fleet_type, rng))
+    .min_by_key(|fleet| score(fleet))
+    .unwrap_or_default()
+}
+
+/// The ship the computer found earliest on average, with the shots it took
+fn found_first(records: &[FleetRecord]) -> Option<(&str, usize)> {
+  let mut found = BTreeMap::<&str, Vec<usize>>::new();
+  for record in records {
+    for (placement, shots) in record.fleet.iter().zip(&record.found) {
+      if let Some(shots) = shots {
+        found
+          .entry(placement.ship_type.name())
+          .or_default()
+          .push(*shots);
+      }
+    }
+  }
+  found
+    .into_iter()
+    .map(|(name, shots)| (name, shots.iter().sum::<usize>() / shots.len()))
+    .min_by_key(|(_, average)| *average)
+}
+
+fn is_on_edge(&(row, col): &Coordinate) -> bool {
+  row == 0 || col == 0 || row == ROWS - 1 || col == COLS - 1
+}
+
+/// Whether any two ships of the fleet sit side by side
+fn ships_touch(fleet: &[Placement]) -> bool {
+  let mut seen = BTreeSet::new();
+  for placement in fleet {
+    let cells = placement.cells();
+    let touches = cells.iter().any(|&(row, col)| {
+      // a cell past the top or the left edge wraps around to one far off the board
+      [
+        (row.wrapping_sub(1), col),
+        (row + 1, col),
+        (row, col.wrapping_sub(1)),
+        (row, col + 1),
+      ]
+      .iter()
+      .any(|cell| seen.contains(cell))
+    });
+    if touches {
+      return true;
+    }
+    seen.extend(cells);
+  }
+  false
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::notation::parse_fleet;
+
+  fn record(fleet: &str, found: &[Option<usize>]) -> FleetRecord {
+    FleetRecord {
+      fleet: parse_fleet(fleet).unwrap(),
+      found: found.to_vec(),
+    }
+  }
+
+  #[test]
+  fn test_advice_tips() {
+    let edge = record(
+      "I@A1/90 X@D4/90 V@H4/90 H@D8/90",
+      &[Some(4), Some(30), Some(40), None],
+    );
+    let mut records = vec![edge.clone()];
+    assert_eq!(
+      tips(&records),
+      vec!["Tips on your placement after 2 more matches"]
+    );
+
+    records.extend(vec![edge.clone(), edge]);
+    let tips = tips(&records);
+    assert_eq!(
+      tips[0],
+      "The computer finds your Scout first, after 4 shots on average"
+    );
+    assert!(tips.contains(&"You place the Scout on an edge 100% of the time".into()));
+    assert!(!tips.iter().any(|t| t.contains("touch")));
+  }
+
+  #[test]
+  fn test_advice_ships_touch() {
+    assert!(!ships_touch(&parse_fleet("I@A1/90 X@E1/90").unwrap()));
+    assert!(ships_touch(&parse_fleet("I@A1/90 X@C1/90").unwrap()));
+  }
+
+  #[test]
+  fn test_advice_suggest() {
+    let records = vec![record(
+      "X@A1/90 V@D1/90 H@A4/90 I@D4/90",
+      &[Some(2), Some(3), Some(5), Some(8)],
+    )];
+    let habit = records[0]
+      .fleet
+      .iter()
+      .flat_map(|p| p.cells())
+      .collect::<BTreeSet<_>>();
+    let fleet = suggest(&records, &FleetType::Standard, &mut rand::thread_rng());
+    assert_eq!(fleet.len(), 4);
+    let cells = fleet
+      .iter()
+      .flat_map(|p| p.cells())
+      .collect::<BTreeSet<_>>();
+    // the top left is left to the computer's empty shots
+    assert!(cells.intersection(&habit).count() < habit.len() / 2);
+  }
+}
@@ -0,0 +1,183 @@
+//! Alerts shown on top of the boards, stacked and each expiring on its own.
+
+use std::{collections::VecDeque, fmt};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Severity {
+  Info,
+  Success,
+  Warn,
+  Error,
+}
+
+impl Severity {
+  /// Ticks an alert of this severity stays up, the more important the longer
+  fn ticks(self) -> u16 {
+    match self {
+      Severity::Info => 8,
+      Severity::Success | Severity::Warn => 12,
+      Severity::Error => 20,
+    }
+  }
+}
+
+/// Who an alert is about, told apart by colour and name on the screen
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Actor {
+  /// the game itself
+  System,
+  /// the player of the index, the user being the first
+  Player(usize),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Alert {
+  pub text: String,
+  pub severity: Severity,
+  pub actor: Actor,
+  /// ticks until the alert goes away, none when it stays up until cleared
+  pub ticks_left: Option<u16>,
+}
+
+impl fmt::Display for Alert {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.text)
+  }
+}
+
+#[derive(Default)]
+pub struct Alerts {
+  queue: VecDeque<Alert>,
+}
+
+impl Alerts {
+  /// Most alerts stacked at once, older ones make way for new ones
+  const MAX: usize = 3;
+
+  /// Shows the alert for as long as its severity calls for
+  pub fn push(&mut self, severity: Severity, text: impl Into<String>) {
+    self.push_for(Actor::System, severity, text, Some(severity.ticks()));
+  }
+
+  /// Shows the alert as something the player did
+  pub fn push_by(&mut self, player: usize, severity: Severity, text: impl Into<String>) {
+    self.push_for(
+      Actor::Player(player),
+      severity,
+      text,
+      Some(severity.ticks()),
+    );
+  }
+
+  /// Shows the alert until it's cleared
+  pub fn push_sticky(&mut self, severity: Severity, text: impl Into<String>) {
+    self.push_for(Actor::System, severity, text, None);
+  }
+
+  fn push_for(
+    &mut self,
+    actor: Actor,
+    severity: Severity,
+    text: impl Into<String>,
+    ticks_left: Option<u16>,
+  ) {
+    let alert = Alert {
+      text: text.into(),
+      severity,
+      actor,
+      ticks_left,
+    };
+    // repeating the last alert only keeps it up longer
+    if let Some(last) = self.queue.back_mut() {
+      if last.text == alert.text && last.severity == alert.severity && last.actor == alert.actor {
+        *last = alert;
+        return;
+      }
+    }
+    self.queue.push_back(alert);
+    if self.queue.len() > Self::MAX {
+      let oldest = self
+        .queue
+        .iter()
+        .position(|a| a.ticks_left.is_some())
+        .unwrap_or(0);
+      self.queue.remove(oldest);
+    }
+  }
+
+  /// Counts down the alerts, dropping the expired ones
+  pub fn tick(&mut self) {
+    for alert in self.queue.iter_mut() {
+      if let Some(ticks) = &mut alert.ticks_left {
+        *ticks = ticks.saturating_sub(1);
+      }
+    }
+    self.queue.retain(|a| a.ticks_left != Some(0));
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Alert> {
+    self.queue.iter()
+  }
+
+  #[cfg(test)]
+  pub fn is_empty(&self) -> bool {
+    self.queue.is_empty()
+  }
+
+  pub fn clear(&mut self) {
+    self.queue.clear();
+  }
+}
+
+impl fmt::Display for Alerts {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let texts = self
+      .queue
+      .iter()
+      .map(|a| a.text.as_str())
+      .collect::<Vec<_>>();
+    write!(f, "{}", texts.join("\n"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_alerts_expire() {
+    let mut alerts = Alerts::default();
+    alerts.push(Severity::Info, "You missed 1.");
+    alerts.push(Severity::Error, "Failed to write stats");
+    alerts.push_sticky(Severity::Success, "You won 🙌");
+
+    (0..8).for_each(|_| alerts.tick());
+    assert_eq!(alerts.to_string(), "Failed to write stats\nYou won 🙌");
+    (0..12).for_each(|_| alerts.tick());
+    assert_eq!(alerts.to_string(), "You won 🙌");
+    alerts.clear();
+    assert!(alerts.is_empty());
+  }
+
+  #[test]
+  fn test_alerts_stack() {
+    let mut alerts = Alerts::default();
+    alerts.push_sticky(Severity::Error, "You lost 🙁");
+    alerts.push(Severity::Warn, "Not your turn");
+    alerts.tick();
+    // a repeat keeps the alert up longer rather than stacking it
+    alerts.push(Severity::Warn, "Not your turn");
+    assert_eq!(alerts.iter().count(), 2);
+    assert_eq!(alerts.iter().last().unwrap().ticks_left, Some(12));
+
+    alerts.push(Severity::Info, "one");
+    alerts.push(Severity::Info, "two");
+    // the oldest alert that expires anyway makes way
+    assert_eq!(alerts.to_string(), "You lost 🙁\none\ntwo");
+
+    // the same words from someone else are news
+    alerts.push_by(1, Severity::Info, "two");
+    assert_eq!(alerts.iter().count(), 3);
+    assert_eq!(alerts.iter().last().unwrap().actor, Actor::Player(1));
+  }
+}
@@ -1,29 +1,275 @@
 use std::{
-  collections::BTreeSet,
-  fmt,
+  collections::{BTreeMap, BTreeSet, VecDeque},
+  fs, io, mem,
+  path::PathBuf,
+  process::Stdio,
   time::{Duration, Instant},
 };
 
 use termion::event::Key;
-use tui::{
-  style::{Color, Style},
-  widgets::{Block, BorderType, Borders},
+
+#[cfg(feature = "chat")]
+use super::chat::ChatVote;
+use super::{
+  advice,
+  alert::{Alerts, Severity},
+  bot_cmd::BotCmd,
+  clock::{format_duration, Clock},
+  game::{
+    a1_notation, Answer, Board, Coordinate, Difficulty, First, FleetType, Game, Line, Placement,
+    Play, PowerUp, Rule, SalvoError, Status, Turn, COLS, MINES, ROWS,
+  },
+  gauntlet::Gauntlet,
+  hunt::BotView,
+  keymap::{Action, Keymap},
+  net::{Message, Peer, Versus},
+  notation,
+  persona::Persona,
+  placement::Placer,
+  preset::{Mode, Preset, PresetFile},
+  replay::{Replay, SCRUB_STEP},
+  sandbox::Sandbox,
+  save::{Save, SaveFile},
+  session::{Session, SessionFile, SessionTab},
+  skin::Skin,
+  stats::{FleetRecord, Leaderboard, Outcome, Played, Stats, StatsFile},
+  status::{StatusServer, Summary},
+  theme::Theme,
+  turn_log::{Commitments, TurnLog},
+  ui::LayoutMode,
+  widget::{AbilitySlot, BoardState, Impact, IMPACT_FRAMES},
 };
 
-use super::game::{Coordinate, Difficulty, Game, Rule, Status, COLS, ROWS};
+/// The board the cursor is on
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Focus {
+  Player,
+  Opponent,
+}
+
+/// What the keys on the board do, the mode entered last on top of the stack
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum InputMode {
+  /// moving the cursor, selecting shots and firing them
+  Normal,
+  /// placing the fleet before the first shot
+  Placing,
+  /// aiming a power-up, <enter> uses it on the cell under the cursor
+  Targeting(PowerUp),
+}
+
+/// The power-ups on the ability bar, in the order of their <alt> hotkeys
+const ABILITY_BAR: [PowerUp; 4] = [
+  PowerUp::Sonar,
+  PowerUp::Ping,
+  PowerUp::Airstrike(Line::Row),
+  PowerUp::Strike(Line::Row),
+];
+
+/// Ticks between two cells of a salvo revealed one at a time
+const REVEAL_TICKS: u16 = 2;
+/// Longest note the user can write down about a match
+const NOTE_LENGTH: usize = 80;
+/// Lines of the log one press of page up or down scrolls by
+const LOG_PAGE: usize = 5;
+
+/// Drops repeats of the same action that arrive faster than a human could mean them,
+/// as fast key repeat or a bouncing key would otherwise fire twice or toggle a selection
+/// back off between two frames. Rapid identical movement keys are coalesced on a shorter
+/// interval so the cursor still moves smoothly, one cell at a time.
+pub struct Debouncer {
+  interval: Duration,
+  last: Option<(Action, Instant)>,
+}
+
+impl Debouncer {
+  pub fn new(interval: Duration) -> Self {
+    Self {
+      interval,
+      last: None,
+    }
+  }
+
+  fn allow(&mut self, action: Action, now: Instant) -> bool {
+    if matches!(action, Action::Quit | Action::Count(_)) {
+      return true;
+    }
+    let interval = if action.is_movement() {
+      self.interval / 4
+    } else {
+      self.interval
+    };
+    match self.last {
+      Some((last, at)) if last == action && now.duration_since(at) < interval => false,
+      _ => {
+        self.last = Some((action, now));
+        true
+      }
+    }
+  }
+}
 
 pub struct App {
   pub title: String,
   pub should_quit: bool,
-  pub enhanced_graphics: bool,
-  pub message: String,
   pub frame_count: u16,
-  pub start_time: Instant,
+  pub debouncer: Debouncer,
+  /// render cells with emoji rather than plain glyphs
+  pub emoji: bool,
+  pub theme: Theme,
+  /// the keys the game is played with
+  pub keymap: Keymap,
+  pub layout: LayoutMode,
+  /// refuse to select cells that can't be fired upon
+  pub strict: bool,
+  /// mark the cells of the opponent's board that can't hold a ship
+  pub auto_mark: bool,
+  /// reveal the results of the user's shots one cell at a time
+  pub suspense: bool,
+  /// who fires first in a match against the computer
+  pub first: First,
+  /// whether the side firing second gets a shot more in their first turn
+  pub balanced: bool,
+  pub persona: Persona,
+  /// what the ships are called and drawn as
+  pub skin: Skin,
+  /// the program of the user's picking the computer's shots in place of its own strategy,
+  /// started again for every tab
+  bot: Option<String>,
+  /// count matches quit before they were decided as losses in the stats
+  pub count_abandoned: bool,
+  pub sandbox: Option<Sandbox>,
+  /// chat votes on the user's shots
+  #[cfg(feature = "chat")]
+  pub chat: Option<ChatVote>,
+  /// the terminal bell is due, for a cell just revealed
+  bell: bool,
+  stats: Option<StatsFile>,
+  presets: Option<PresetFile>,
+  saves: Option<SaveFile>,
+  /// where the open matches are kept at quit for the next start
+  sessions: Option<SessionFile>,
+  status: Option<StatusServer>,
+  /// the matches played at once, each with everything the app keeps of it
+  tabs: Vec<Tab>,
+  /// index of the tab in play
+  active_tab: usize,
+  /// whether the terminal has the focus, as far as it reports it
+  focused: bool,
+}
+
+/// The user's latest shots while their results are shown one cell at a time
+struct Reveal {
+  /// cells still drawn as splashes, in the order they're shown
+  hidden: VecDeque<Coordinate>,
+  /// what the shots did, announced once every cell is shown
+  message: String,
+}
+
+/// A match in a tab, with everything the app keeps of it
+pub struct Tab {
   game: Game,
+  pub clock: Clock,
+  pub alerts: Alerts,
+  /// count typed before a movement key, vim style
+  pub pending_count: Option<usize>,
+  pub focus: Focus,
+  pub inspecting: bool,
+  modes: Vec<InputMode>,
   active_column: usize,
   active_row: usize,
   selected_coordinates: BTreeSet<Coordinate>,
-  duration: Option<Duration>,
+  /// time allowed to sink the fleet in a time attack, which counts up when there's none
+  pub time_limit: Option<Duration>,
+  pub gauntlet: Option<Gauntlet>,
+  /// scroll offset of the devtools screen when it's open
+  pub devtools: Option<usize>,
+  /// lines the log is scrolled back from the latest turn, none while it follows the match
+  pub log_scroll: Option<usize>,
+  /// a recorded match stepped through turn by turn, with --replay
+  pub replay: Option<Replay>,
+  /// how the computer saw the user's fleet, opened once a match against it is over
+  pub bot_view: Option<BotView>,
+  /// the ships placed so far while the user places their fleet
+  pub placing: Option<Placer>,
+  /// what the computer says with its next announcement
+  reaction: Option<String>,
+  revealing: Option<Reveal>,
+  /// cells the latest shots landed on, whether on the user's own board and the frame their
+  /// animation started at
+  impacts: Vec<(bool, Coordinate, Impact, u16)>,
+  /// note typed so far about the match just over
+  noting: Option<String>,
+  turn_log: Option<TurnLog>,
+  /// the stats as they were after the match was recorded
+  lifetime: Option<Stats>,
+  /// where the match made it on its leaderboard once recorded
+  best_place: Option<usize>,
+  /// the bot's program picking the computer's shots, with the request it's thinking about
+  bot: Option<BotCmd>,
+  /// the other player, who fires instead of the computer, or the host when spectating
+  peer: Option<Peer>,
+  /// following a match between two other players without taking part
+  spectating: bool,
+  /// file the match is written to in the text notation as it's played
+  notation: Option<PathBuf>,
+  /// a game started from the sandbox, which doesn't count in the stats
+  throwaway: bool,
+  /// port of the match hosted in the tab, until the other player joins
+  waiting: Option<u16>,
+  /// something happened in the match since the user last looked at it
+  news: bool,
+}
+
+impl Tab {
+  fn new(game: Game) -> Self {
+    Self {
+      game,
+      clock: Clock::start(Instant::now()),
+      alerts: Alerts::default(),
+      pending_count: None,
+      focus: Focus::Opponent,
+      inspecting: false,
+      modes: vec![],
+      active_column: 0,
+      active_row: 0,
+      selected_coordinates: BTreeSet::new(),
+      time_limit: None,
+      gauntlet: None,
+      devtools: None,
+      log_scroll: None,
+      replay: None,
+      bot_view: None,
+      placing: None,
+      reaction: None,
+      revealing: None,
+      impacts: vec![],
+      noting: None,
+      turn_log: None,
+      lifetime: None,
+      best_place: None,
+      bot: None,
+      peer: None,
+      spectating: false,
+      notation: None,
+      throwaway: false,
+      waiting: None,
+      news: false,
+    }
+  }
+}
+
+/// What the match in a tab is against, for the tab bar
+fn tab_label(waiting: bool, spectating: bool, peer: bool) -> &'static str {
+  if waiting {
+    "Waiting"
+  } else if spectating {
+    "Watching"
+  } else if peer {
+    "Opponent"
+  } else {
+    "Computer"
+  }
 }
 
 impl App {
@@ -31,206 +277,4399 @@ impl App {
     App {
       title,
       should_quit: false,
-      enhanced_graphics: true,
-      active_column: 0,
-      active_row: 0,
-      selected_coordinates: BTreeSet::new(),
-      game: Game::new(rule, difficulty),
-      message: String::default(),
       frame_count: 0,
-      start_time: Instant::now(),
-      duration: None,
+      debouncer: Debouncer::new(Duration::ZERO),
+      emoji: true,
+      theme: Theme::default(),
+      keymap: Keymap::default(),
+      layout: LayoutMode::Auto,
+      strict: false,
+      auto_mark: false,
+      suspense: false,
+      first: First::Me,
+      balanced: false,
+      persona: Persona::Captain,
+      skin: Skin::Naval,
+      bot: None,
+      count_abandoned: true,
+      sandbox: None,
+      #[cfg(feature = "chat")]
+      chat: None,
+      bell: false,
+      stats: None,
+      presets: None,
+      saves: None,
+      sessions: None,
+      status: None,
+      tabs: vec![Tab::new(Game::new(rule, difficulty, 0))],
+      active_tab: 0,
+      focused: true,
     }
   }
 
-  fn on_up(&mut self) {
-    if let Some(active_row) = self.active_row.checked_sub(1) {
-      self.active_row = active_row;
-    }
+  /// The match in play
+  pub fn tab(&self) -> &Tab {
+    &self.tabs[self.active_tab]
   }
 
-  fn on_down(&mut self) {
-    if self.active_row < ROWS - 1 {
-      self.active_row += 1;
+  fn tab_mut(&mut self) -> &mut Tab {
+    &mut self.tabs[self.active_tab]
+  }
+
+  /// Has the bot's program pick the computer's shots, in this tab and every one opened after
+  pub fn set_bot(&mut self, command: String) -> io::Result<()> {
+    // the bot's errors would draw over the screen
+    self.tab_mut().bot = Some(BotCmd::spawn(&command, Stdio::null())?);
+    self.bot = Some(command);
+    Ok(())
+  }
+
+  /// A tab for the match, with a bot of its own when the computer's shots are the bot's
+  fn open_tab(&self, game: Game) -> Tab {
+    let mut tab = Tab::new(game);
+    if let Some(command) = &self.bot {
+      match BotCmd::spawn(command, Stdio::null()) {
+        Ok(bot) => tab.bot = Some(bot),
+        Err(err) => tab.alerts.push(
+          Severity::Warn,
+          format!(
+            "Failed to start the bot, the computer fires for it: {}",
+            err
+          ),
+        ),
+      }
     }
+    tab
   }
 
-  fn on_right(&mut self) {
-    if self.active_column < COLS - 1 {
-      self.active_column += 1;
+  /// Moves to the match in the tab, the one after the last opens a new match against the
+  /// computer. A match against the computer is paused while in another tab.
+  fn switch_tab(&mut self, index: usize) {
+    if index == self.active_tab || index > self.tabs.len() {
+      return;
+    }
+    if self.tab().peer.is_none() && !self.is_paused() {
+      self.on_pause();
+    }
+    let opened = index == self.tabs.len();
+    if opened {
+      let game = Game::new(self.tab().game.rule, *self.tab().game.difficulty(), 0);
+      let tab = self.open_tab(game);
+      self.tabs.push(tab);
+    }
+    self.active_tab = index;
+    self.tab_mut().news = false;
+    if opened {
+      self.deal_first();
     }
   }
 
-  fn on_left(&mut self) {
-    if let Some(active_column) = self.active_column.checked_sub(1) {
-      self.active_column = active_column;
+  /// Runs the function on the match in the tab, as if it were in play
+  fn in_tab(&mut self, index: usize, f: impl FnOnce(&mut Self)) {
+    if index >= self.tabs.len() {
+      return;
+    }
+    let active = mem::replace(&mut self.active_tab, index);
+    f(self);
+    self.active_tab = active;
+    if index != active {
+      self.tabs[index].news = true;
     }
   }
 
-  fn on_select(&mut self) {
-    if !self.game.is_won() {
-      if self.is_selected((self.active_row, self.active_column)) {
-        self
-          .selected_coordinates
-          .remove(&(self.active_row, self.active_column));
-      } else if self.is_valid_rule() {
-        self
-          .selected_coordinates
-          .insert((self.active_row, self.active_column));
-      } else {
-        self.message = "Maximum shots for rule selected".into()
+  /// The tabs as their function key and what they're playing against, the one in play
+  /// in brackets and ones with news marked
+  pub fn tabs(&self) -> Vec<String> {
+    self
+      .tabs
+      .iter()
+      .enumerate()
+      .map(|(index, tab)| {
+        let label = tab_label(tab.waiting.is_some(), tab.spectating, tab.peer.is_some());
+        if index == self.active_tab {
+          format!("[F{} {}]", index + 1, label)
+        } else {
+          format!(
+            "F{} {}{}",
+            index + 1,
+            label,
+            if tab.news { "*" } else { "" }
+          )
+        }
+      })
+      .collect()
+  }
+
+  /// Hosts the match in the tab, which waits for the other player to join while the user
+  /// plays on in other tabs
+  pub fn wait_for_opponent(&mut self, port: u16) {
+    let tab = self.tab_mut();
+    tab.waiting = Some(port);
+    tab.clock.stop(Instant::now());
+    tab.alerts.push_sticky(
+      Severity::Info,
+      format!(
+        "Waiting for an opponent on port {}, <F2> plays the computer meanwhile",
+        port
+      ),
+    );
+  }
+
+  /// Plays the match hosted in the tab now that the other player joined
+  pub fn on_hosted(&mut self, tab: usize, hosted: Result<(Peer, Versus), String>) {
+    self.in_tab(tab, |app| {
+      app.tab_mut().waiting = None;
+      app.tab_mut().alerts.clear();
+      match hosted {
+        Ok((peer, versus)) => app.set_peer(peer, versus),
+        Err(err) => {
+          app.tab_mut().clock = Clock::start(Instant::now());
+          app.tab_mut().alerts.push_sticky(
+            Severity::Error,
+            format!("Failed to host the match, playing the computer: {}", err),
+          );
+        }
       }
+    });
+  }
+
+  /// Persists the match to the given log, resuming the match recorded in it if there is one.
+  /// A match over the network resumed this way goes on once connected to the other player
+  /// again.
+  pub fn set_turn_log(&mut self, mut turn_log: TurnLog) -> io::Result<()> {
+    if self.tab().spectating {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "A watched match can't be kept in a turn log",
+      ));
     }
+    if let Some(commitments) = self.tab().peer.as_ref().and_then(Peer::commitments) {
+      // the match going on over the network is kept from now on
+      turn_log.set_commitments(commitments);
+    } else if let Some(game) = turn_log.restore()? {
+      let tab = self.tab_mut();
+      tab.clock.skip_laps(game.timeline().len());
+      tab.game = game;
+      tab
+        .alerts
+        .push(Severity::Info, "Restored interrupted match from turn log");
+      // the restored fleet is already in position
+      tab.placing = None;
+      self.leave_mode(InputMode::Placing);
+    }
+    turn_log.start(&self.tab().game)?;
+    self.tab_mut().turn_log = Some(turn_log);
+    Ok(())
   }
 
-  fn on_fire(&mut self) {
-    let msg = if self.selected_coordinates.is_empty() {
-      "Select opponent coordinates to hit".into()
-    } else if !self.game.is_won() && self.game.is_user_turn() {
-      let msg = self.game.fire(&self.selected_coordinates, false);
-      self.selected_coordinates = BTreeSet::new();
-      msg
-    } else {
-      "Not your turn".into()
+  /// Saves the match to the file on <ctrl-s>
+  pub fn set_save_file(&mut self, saves: SaveFile) {
+    self.saves = Some(saves);
+  }
+
+  /// Picks up a saved match where it was left
+  pub fn resume(&mut self, game: Game, elapsed: Duration) {
+    let tab = self.tab_mut();
+    tab.clock = Clock::start_at(Instant::now(), elapsed);
+    tab.clock.skip_laps(game.timeline().len());
+    if game.is_won() {
+      tab.clock.stop(Instant::now());
+    }
+    tab.game = game;
+    tab.alerts.push(Severity::Info, "Resumed the saved match");
+  }
+
+  fn on_save(&mut self) {
+    // only what a replay of the turns rebuilds can be saved
+    if self.is_time_attack() || self.tab().gauntlet.is_some() || self.tab().peer.is_some() {
+      self.tab_mut().alerts.push(
+        Severity::Warn,
+        "Only a match against the computer can be saved",
+      );
+      return;
+    }
+    let saved = match &self.saves {
+      Some(saves) => saves.save(&Save::of(&self.tab().game, self.elapsed())),
+      None => return,
     };
-    // append to previous msg
-    self.message = format!(
-      "{}{}{}",
-      self.message,
-      if self.message.is_empty() { "" } else { "\n" },
-      msg
-    );
+    match saved {
+      Ok(()) => self.tab_mut().alerts.push(Severity::Success, "Match saved"),
+      Err(err) => self.tab_mut().alerts.push(
+        Severity::Error,
+        format!("Failed to save the match: {}", err),
+      ),
+    }
   }
 
-  fn is_valid_rule(&mut self) -> bool {
-    self.game.is_valid_rule(self.selected_coordinates.len())
+  /// Keeps the open matches in the file at quit
+  pub fn set_session_file(&mut self, sessions: SessionFile) {
+    self.sessions = Some(sessions);
   }
 
-  fn is_selected(&self, coordinate: Coordinate) -> bool {
-    self.selected_coordinates.iter().any(|c| *c == coordinate)
+  /// The match in play as it's kept for the next start, if it can be picked up again
+  fn session_tab(&self) -> Option<SessionTab> {
+    let tab = self.tab();
+    if let Some(port) = tab.waiting {
+      return Some(SessionTab {
+        save: Save::of(&tab.game, Duration::ZERO),
+        hosting: Some(port),
+      });
+    }
+    // like on <ctrl-s>, only what a replay of the turns rebuilds, and a match kept in a
+    // turn log goes on from there
+    let keep = tab.peer.is_none()
+      && !tab.spectating
+      && !self.is_won()
+      && !self.is_time_attack()
+      && tab.gauntlet.is_none()
+      && tab.replay.is_none()
+      && tab.turn_log.is_none()
+      && !tab.throwaway
+      && !tab.game.timeline().is_empty();
+    keep.then(|| SessionTab {
+      save: Save::of(&tab.game, self.elapsed()),
+      hosting: None,
+    })
   }
 
-  fn active(&self) -> Coordinate {
-    (self.active_row, self.active_column)
+  /// Writes the matches still open to the session file for the next start
+  pub fn save_session(&mut self) -> io::Result<()> {
+    let sessions = match self.sessions.take() {
+      Some(sessions) => sessions,
+      None => return Ok(()),
+    };
+    let mut session = Session::default();
+    for index in 0..self.tabs.len() {
+      self.in_tab(index, |app| session.tabs.extend(app.session_tab()));
+    }
+    sessions.save(&session)
   }
 
-  pub fn rule(&self) -> &Rule {
-    &self.game.rule
+  /// Opens the matches of the last session in tabs after the ones open, paused, and hosts
+  /// the ones that were waiting for the other player again
+  pub fn restore_session(
+    &mut self,
+    session: Session,
+    mut host: impl FnMut(usize, u16, Rule, Vec<Placement>),
+  ) -> io::Result<()> {
+    let first = self.tabs.len();
+    for kept in session.tabs {
+      let (game, elapsed) = kept.save.restore()?;
+      let now = Instant::now();
+      let mut tab = self.open_tab(game);
+      tab.clock = Clock::start_at(now, elapsed);
+      tab.clock.skip_laps(tab.game.timeline().len());
+      match kept.hosting {
+        Some(port) => {
+          tab.clock.stop(now);
+          tab.waiting = Some(port);
+          tab.alerts.push_sticky(
+            Severity::Info,
+            format!("Waiting for an opponent on port {}", port),
+          );
+          let fleet = tab.game.player().player_board().placements();
+          host(self.tabs.len(), port, tab.game.rule, fleet);
+        }
+        None => tab.clock.pause(now),
+      }
+      tab.news = true;
+      self.tabs.push(tab);
+    }
+    let reopened = self.tabs.len() - first;
+    if reopened > 0 {
+      self.tab_mut().alerts.push(
+        Severity::Info,
+        format!(
+          "Reopened {} matches of the last session from <F{}> on",
+          reopened,
+          first + 1
+        ),
+      );
+    }
+    Ok(())
+  }
+
+  /// Switches to a time attack, where the computer doesn't fire back and the clock restarts
+  pub fn set_time_attack(&mut self, time_limit: Option<Duration>) {
+    self.tab_mut().game = Game::solo(self.tab().game.rule, *self.tab().game.difficulty());
+    let tab = self.tab_mut();
+    tab.time_limit = time_limit;
+    tab.clock = Clock::start(Instant::now());
+  }
+
+  /// Plays the match dealt from the seed instead of a random one
+  pub fn set_seed(&mut self, seed: u64) {
+    self.tab_mut().game.reseed(seed);
+  }
+
+  /// Lets the side the start option picks fire first in the match about to start, giving
+  /// the other one a shot more in their first turn when the start is balanced
+  pub fn deal_first(&mut self) {
+    if self.tab().peer.is_some() {
+      return;
+    }
+    let game = &mut self.tabs[self.active_tab].game;
+    let first = self.first.player(game.cosmetic_rng());
+    game.set_first(first);
+    game.set_balanced(self.balanced);
+    if !self.tab().game.is_user_turn() {
+      let msg = format!("{} fires first", self.opponent_name());
+      self.tab_mut().alerts.push(Severity::Info, msg);
+    }
+  }
+
+  /// Deals both fleets with ships of the type
+  pub fn set_fleet_type(&mut self, fleet_type: FleetType) {
+    self.tab_mut().game.set_fleet_type(fleet_type);
+  }
+
+  pub fn is_time_attack(&self) -> bool {
+    self.tab().game.is_solo()
+  }
+
+  /// Switches to a gauntlet, where every sunk fleet is followed by a harder one
+  pub fn set_gauntlet(&mut self) {
+    let gauntlet = Gauntlet::default();
+    self.tab_mut().game = Game::new(self.tab().game.rule, gauntlet.difficulty(), 0);
+    self.tab_mut().gauntlet = Some(gauntlet);
+  }
+
+  /// Misses the user can still make against the current fleet of a gauntlet
+  pub fn misses_left(&self) -> Option<usize> {
+    let (shots, hits) = self.tab().game.shots_and_hits(0);
+    self
+      .tab()
+      .gauntlet
+      .as_ref()
+      .map(|g| g.miss_allowance().saturating_sub(shots - hits))
   }
 
-  pub fn elapsed_duration(&self) -> u64 {
-    if let Some(duration) = self.duration {
-      duration.as_secs()
+  /// Sends in the next fleet once the user sank one, or ends a gauntlet out of misses
+  fn run_gauntlet(&mut self) -> Option<String> {
+    let tab = self.tab_mut();
+    let gauntlet = tab.gauntlet.as_mut()?;
+    if tab.game.winner() == Some(0) {
+      gauntlet.cleared += 1;
+      tab.game.next_fleet(gauntlet.difficulty());
+      Some(format!(
+        "Fleet {} sunk 🙌 Here comes the next one",
+        gauntlet.cleared
+      ))
+    } else if !self.tab().game.is_won() && self.misses_left() == Some(0) {
+      self.tab_mut().game.forfeit(0);
+      Some("Out of misses 🙁".into())
     } else {
-      self.start_time.elapsed().as_secs()
+      None
     }
   }
 
-  pub fn is_won(&self) -> bool {
-    self.game.is_won()
+  /// Time left of the time limit, if there is one
+  pub fn remaining(&self) -> Option<Duration> {
+    self
+      .tab()
+      .time_limit
+      .map(|limit| limit.saturating_sub(self.elapsed()))
+  }
+
+  /// Summary of a finished time attack
+  pub fn results(&self) -> Option<Vec<String>> {
+    let tab = self.tab();
+    if !self.is_time_attack() || !self.is_won() {
+      return None;
+    }
+    let headline = if tab.game.winner() == Some(0) {
+      format!("Fleet sunk in {}", format_duration(self.elapsed()))
+    } else {
+      "Out of time".into()
+    };
+    let (shots, _) = tab.game.shots_and_hits(0);
+    let fleet = tab.game.fleet(1);
+    Some(vec![
+      headline,
+      format!("Ships sunk {}/{}", fleet.sunk.len(), fleet.size()),
+      format!("Shots fired {}", shots),
+      format!("Accuracy {}%", self.accuracy().unwrap_or_default()),
+    ])
   }
 
-  pub fn cell(&self, c: Coordinate, read_only: bool) -> Cell {
-    Cell::new(self, c, read_only)
+  /// Whether the end screen is up, once a match the user played is decided and recorded
+  pub fn is_game_over(&self) -> bool {
+    let tab = self.tab();
+    self.is_won() && tab.clock.is_stopped() && tab.replay.is_none() && !tab.spectating
   }
 
-  pub fn on_key(&mut self, key: Key) {
+  /// The fastest wins, or fleets sunk in time attacks, and the place the match just won
+  /// took among them, for the end screen of a win
+  pub fn leaderboard(&self) -> Option<(Leaderboard<'_>, Option<usize>)> {
+    let tab = self.tab();
+    match &tab.lifetime {
+      Some(stats) if self.is_game_over() && tab.game.winner() == Some(0) => {
+        let board = if self.is_time_attack() {
+          Leaderboard::Attacks(&stats.attacks)
+        } else {
+          Leaderboard::Wins(&stats.leaderboard)
+        };
+        Some((board, tab.best_place))
+      }
+      _ => None,
+    }
+  }
+
+  /// How the match went and the keys left, for the end screen
+  pub fn game_over(&self) -> Option<Vec<String>> {
+    let tab = self.tab();
+    if !self.is_game_over() {
+      return None;
+    }
+    let mut lines = match self.results() {
+      Some(results) => results,
+      None => {
+        let winner = match tab.game.winner() {
+          Some(0) => "You won".into(),
+          _ => format!("{} won", self.opponent_name()),
+        };
+        let mut lines = vec![format!("{} in {}", winner, format_duration(self.elapsed()))];
+        if let Some(gauntlet) = &tab.gauntlet {
+          lines.push(format!("Fleets sunk {}", gauntlet.cleared));
+        }
+        let (sunk, lost) = (tab.game.fleet(1), tab.game.fleet(0));
+        let (shots, _) = tab.game.shots_and_hits(0);
+        lines.extend(vec![
+          format!("Ships sunk {}/{}", sunk.sunk.len(), sunk.size()),
+          format!("Ships lost {}/{}", lost.sunk.len(), lost.size()),
+          format!("Shots fired {}", shots),
+          format!("Accuracy {}%", self.accuracy().unwrap_or_default()),
+        ]);
+        lines
+      }
+    };
+    if let Some(stats) = &tab.lifetime {
+      lines.push(format!(
+        "All time: wins {} | losses {}",
+        stats.won,
+        stats.losses()
+      ));
+    }
+    match (&tab.noting, tab.game.note()) {
+      (Some(draft), _) => lines.push(format!("Note: {}_", draft)),
+      (None, Some(note)) => lines.push(format!("Note: {}", note)),
+      (None, None) => {}
+    }
+    if tab.noting.is_some() {
+      lines.push(String::new());
+      lines.push("<enter> keep | <esc> discard".into());
+      return Some(lines);
+    }
+    let mut keys = vec![];
+    if tab.peer.is_none() {
+      keys.push("<r> rematch");
+    }
+    if self.can_run_back() {
+      keys.push("<R> run it back");
+    }
+    keys.push("<n> note");
+    if self.can_view_bot() {
+      keys.push("<b> how you were hunted");
+    }
+    keys.push("<q> quit");
+    lines.push(String::new());
+    lines.push(keys.join(" | "));
+    Some(lines)
+  }
+
+  /// The end screen only takes a rematch, a look back at the match or quitting
+  fn on_game_over_key(&mut self, key: Key) {
+    match self.keymap.game_over_action(key) {
+      Some(Action::Rematch) if self.tab().peer.is_none() => self.rematch(),
+      Some(Action::RunBack) if self.can_run_back() => self.run_back(),
+      Some(Action::BotView) => self.on_open_bot_view(),
+      Some(Action::Devtools) => self.tab_mut().devtools = Some(0),
+      Some(Action::Note) => {
+        self.tab_mut().noting = Some(self.tab().game.note().unwrap_or_default().into())
+      }
+      Some(Action::Save) => self.on_save(),
+      Some(Action::Quit) => self.on_quit(),
+      _ => {}
+    }
+  }
+
+  /// The note written down about the match in play or the one replayed
+  pub fn note(&self) -> Option<&str> {
+    match &self.tab().replay {
+      Some(replay) => replay.note(),
+      None => self.tab().game.note(),
+    }
+  }
+
+  /// Types the note on the match, kept with its save and notation
+  fn on_note_key(&mut self, key: Key) {
+    let draft = match &mut self.tab_mut().noting {
+      Some(draft) => draft,
+      None => return,
+    };
     match key {
-      Key::Up | Key::Char('k') => self.on_up(),
-      Key::Down | Key::Char('j') => self.on_down(),
-      Key::Left | Key::Char('h') => self.on_left(),
-      Key::Right | Key::Char('l') => self.on_right(),
-      Key::Char(' ') => self.on_select(),
-      Key::Char('\n') => self.on_fire(),
-      _ => { /* do nothing */ }
+      Key::Char('\n') => {
+        let note = Some(draft.trim().to_string()).filter(|note| !note.is_empty());
+        let tab = self.tab_mut();
+        tab.noting = None;
+        tab.game.set_note(note);
+        self.write_notation();
+      }
+      // a quote would end the tag of the notation
+      Key::Char(c) if !c.is_control() && c != '"' && draft.chars().count() < NOTE_LENGTH => {
+        draft.push(c)
+      }
+      Key::Backspace => {
+        draft.pop();
+      }
+      Key::Esc => self.tab_mut().noting = None,
+      _ => {}
     }
   }
 
-  pub fn on_tick(&mut self) {
-    if self.is_won() && self.duration.is_none() {
-      let duration = self.start_time.elapsed();
-      self.duration = Some(duration);
-      self.message = format!("{} (In {} seconds)", self.message, duration.as_secs());
+  /// A fresh match against the computer with the rule, difficulty, mode and fleets of the
+  /// one just played
+  fn rematch(&mut self) {
+    let fleet_type = self.tab().game.fleet_type().clone();
+    self.apply_preset(&self.preset());
+    self.set_fleet_type(fleet_type);
+    self.start_again("Rematch! Your turn");
+  }
+
+  /// The match just played again on the same fleets, from the same seed
+  fn run_back(&mut self) {
+    self.tab_mut().game = self.tab().game.run_back();
+    let tab = self.tab_mut();
+    tab.clock = Clock::start(Instant::now());
+    tab.selected_coordinates.clear();
+    tab.reaction = None;
+    self.start_again("Run it back! Same fleets, your turn");
+  }
+
+  /// Clears what's left of the match over for the one starting
+  fn start_again(&mut self, msg: &str) {
+    let tab = self.tab_mut();
+    tab.alerts.clear();
+    tab.active_row = 0;
+    tab.active_column = 0;
+    tab.focus = Focus::Opponent;
+    tab.inspecting = false;
+    tab.modes.clear();
+    tab.bot_view = None;
+    if let Some(turn_log) = &mut tab.turn_log {
+      if let Err(err) = turn_log.start(&tab.game) {
+        tab.alerts.push(
+          Severity::Error,
+          format!("Failed to write turn log: {}", err),
+        );
+      }
     }
-    // computer delays firing by 2 seconds to make the game feel more natural
-    if !self.game.is_user_turn() && !self.is_won() && self.frame_count % 8 == 0 {
-      self.message = self.game.bot_fire();
+    tab.alerts.push(Severity::Info, msg);
+  }
+
+  /// Whether the match over can be played again on the same fleets, which a gauntlet
+  /// leaves behind and the other player would have to agree to
+  fn can_run_back(&self) -> bool {
+    self.tab().peer.is_none() && self.tab().gauntlet.is_none()
+  }
+
+  /// Writes the match to the given file in the text notation after every turn, to be
+  /// watched again with --replay
+  pub fn set_notation(&mut self, path: PathBuf) {
+    self.tab_mut().notation = Some(path);
+  }
+
+  /// Opens a match read from elsewhere, to look at or play on, which doesn't count in the
+  /// stats
+  pub fn import(&mut self, game: Game) {
+    let msg = match game.winner() {
+      Some(0) => "Imported a match you won",
+      Some(_) => "Imported a match the computer won",
+      None => "Imported a match still on",
+    };
+    let msg = match game.note() {
+      Some(note) => format!("{}\nNote: {}", msg, note),
+      None => msg.into(),
+    };
+    let tab = self.tab_mut();
+    tab.throwaway = true;
+    tab.selected_coordinates.clear();
+    tab.clock = Clock::start(Instant::now());
+    tab.clock.skip_laps(game.timeline().len());
+    if game.is_won() {
+      tab.clock.stop(Instant::now());
     }
-    self.frame_count += 1;
+    tab.game = game;
+    tab.devtools = Some(0);
+    tab.alerts.push_sticky(Severity::Info, msg);
   }
-}
 
-pub struct Cell<'app> {
-  app: &'app App,
-  coordinate: Coordinate,
-  read_only: bool,
-}
+  /// Opens a recorded match at its first turn, to step through it without playing
+  pub fn open_replay(&mut self, game: Game) {
+    let replay = Replay::new(game, 0);
+    let tab = self.tab_mut();
+    tab.game = replay.at();
+    tab.replay = Some(replay);
+    tab.throwaway = true;
+    tab.selected_coordinates.clear();
+    tab.clock.stop(Instant::now());
+  }
 
-impl<'app> Cell<'app> {
-  fn new(app: &'app App, coordinate: Coordinate, read_only: bool) -> Self {
-    Self {
-      app,
-      coordinate,
-      read_only,
+  fn on_replay_key(&mut self, key: Key) {
+    let tab = &mut self.tabs[self.active_tab];
+    let replay = match &mut tab.replay {
+      Some(replay) => replay,
+      None => return,
+    };
+    match self.keymap.action(key) {
+      Some(Action::Left) => replay.go(-1),
+      Some(Action::Right) => replay.go(1),
+      Some(Action::Up) => replay.go(-SCRUB_STEP),
+      Some(Action::Down) => replay.go(SCRUB_STEP),
+      Some(Action::Count(0)) | Some(Action::RowStart) | Some(Action::Top) => replay.go_to(0),
+      Some(Action::RowEnd) | Some(Action::Bottom) => replay.go_to(replay.len()),
+      Some(Action::Quit) | Some(Action::Cancel) => {
+        self.should_quit = true;
+        return;
+      }
+      _ => return,
     }
+    tab.game = replay.at();
   }
 
-  fn get_position_status(&self) -> Status {
-    let (pos, ship) = if self.read_only {
-      self
-        .app
-        .game
-        .player()
-        .player_board()
-        .find_position_and_ship(self.coordinate)
+  /// Whether the match was played out against the computer on one fleet, so its memory
+  /// can be rebuilt turn by turn
+  fn can_view_bot(&self) -> bool {
+    self.is_won()
+      && self.tab().peer.is_none()
+      && self.tab().gauntlet.is_none()
+      && !self.is_time_attack()
+  }
+
+  fn on_open_bot_view(&mut self) {
+    if self.can_view_bot() {
+      self.tab_mut().bot_view = Some(BotView::new(self.tab().game.clone(), self.placement_tips()));
+    }
+  }
+
+  fn on_bot_view_key(&mut self, key: Key) {
+    let view = match &mut self.tabs[self.active_tab].bot_view {
+      Some(view) => view,
+      None => return,
+    };
+    match self.keymap.action(key) {
+      Some(Action::Left) => view.go(-1),
+      Some(Action::Right) => view.go(1),
+      Some(Action::Count(0)) | Some(Action::RowStart) | Some(Action::Top) => view.go_to_start(),
+      Some(Action::RowEnd) | Some(Action::Bottom) => view.go_to_end(),
+      Some(Action::Cancel) | Some(Action::BotView) => self.tab_mut().bot_view = None,
+      Some(Action::Quit) => self.on_quit(),
+      _ => {}
+    }
+  }
+
+  /// The fleet of the user, to send to the other player of a network match
+  pub fn fleet(&self) -> Vec<Placement> {
+    self.tab().game.player().player_board().placements()
+  }
+
+  /// The commitments of the match over the network restored from the turn log, to connect
+  /// to the other player again with
+  pub fn commitments(&self) -> Option<Commitments> {
+    self
+      .tab()
+      .turn_log
+      .as_ref()
+      .and_then(TurnLog::commitments)
+      .filter(|_| self.tab().game.is_kept_elsewhere(1))
+      .cloned()
+  }
+
+  /// Plays the match agreed on with the other player, who takes the place of the computer
+  /// and keeps their fleet to themselves until the end. A match resumed from the turn log
+  /// goes on once both sides agree on the turns played.
+  pub fn set_peer(&mut self, peer: Peer, versus: Versus) {
+    let resumed = self.commitments().is_some();
+    if !resumed {
+      self.tab_mut().game = Game::versus(
+        versus.rule,
+        *self.tab().game.difficulty(),
+        versus.fleet.as_deref(),
+        &versus.fleet_type,
+      );
+      if !versus.host {
+        self.tab_mut().game.set_first(1);
+      }
+    }
+    // the stats keep score against the computer only
+    let tab = self.tab_mut();
+    tab.throwaway = true;
+    if let (Some(turn_log), Some(commitments)) = (&mut tab.turn_log, peer.commitments()) {
+      turn_log.set_commitments(commitments);
+      if let Err(err) = turn_log.start(&tab.game) {
+        tab.alerts.push(
+          Severity::Error,
+          format!("Failed to write turn log: {}", err),
+        );
+      }
+    }
+    tab.peer = Some(peer);
+    tab.clock = Clock::start(Instant::now());
+    if resumed {
+      tab.clock.skip_laps(tab.game.timeline().len());
+      // spectators coming in are caught up on the turns played before
+      for turn in tab.game.timeline().to_vec() {
+        self.broadcast(Message::from(&turn.play()));
+        if let Some(answer) = turn.answer {
+          self.broadcast(Message::Answer(answer, None));
+        }
+      }
+      self.send(Message::Sync(
+        self.tab().game.timeline().len(),
+        self.tab().game.found_hash(),
+      ));
+      return self
+        .tab_mut()
+        .alerts
+        .push(Severity::Info, "Connected again, checking the match");
+    }
+    let msg = if versus.host {
+      "Your opponent joined, fire away"
     } else {
-      self
-        .app
-        .game
-        .player()
-        .opponent_board()
-        .find_position_and_ship(self.coordinate)
+      "Joined, your opponent fires first"
     };
+    self.tab_mut().alerts.push(Severity::Info, msg);
+  }
 
-    pos.get_status(ship)
+  /// Follows the match of the host, whose side takes the place of the user's
+  pub fn watch(&mut self, peer: Peer, versus: Versus) {
+    self.tab_mut().game = Game::versus(
+      versus.rule,
+      *self.tab().game.difficulty(),
+      None,
+      &versus.fleet_type,
+    );
+    let tab = self.tab_mut();
+    tab.throwaway = true;
+    tab.turn_log = None;
+    tab.peer = Some(peer);
+    tab.spectating = true;
+    tab.clock = Clock::start(Instant::now());
+    tab.alerts.push(Severity::Info, "Watching the match");
   }
 
-  fn is_active(&self) -> bool {
-    !self.read_only && self.app.active() == self.coordinate
+  pub fn is_spectating(&self) -> bool {
+    self.tab().spectating
   }
 
-  fn is_selected(&self) -> bool {
-    !self.read_only && self.app.is_selected(self.coordinate)
+  pub fn is_waiting(&self) -> bool {
+    self.tab().waiting.is_some()
   }
 
-  pub fn block(&self) -> Block {
-    Block::default()
-      .borders(Borders::ALL)
-      .style(Style::default().bg(Color::Black).fg(
-        // cell  border color
-        if self.is_selected() {
-          Color::Yellow
-        } else if self.is_active() {
-          Color::Cyan
-        } else {
-          match self.get_position_status() {
-            Status::Live => Color::Yellow,
-            Status::Hit | Status::Kill => Color::Red,
-            Status::Miss | Status::Space => Color::White,
+  /// The name of the user's side in titles and the log
+  pub fn player_name(&self) -> &'static str {
+    if self.tab().spectating {
+      "Host"
+    } else {
+      "You"
+    }
+  }
+
+  /// The name of the opponent in titles and the log
+  pub fn opponent_name(&self) -> &'static str {
+    if self.tab().spectating {
+      "Guest"
+    } else if self.tab().peer.is_some() {
+      "Opponent"
+    } else {
+      "Computer"
+    }
+  }
+
+  /// The names of both sides, by player index
+  pub fn names(&self) -> [&'static str; 2] {
+    [self.player_name(), self.opponent_name()]
+  }
+
+  fn broadcast(&self, message: Message) {
+    if let Some(peer) = &self.tab().peer {
+      peer.broadcast(message);
+    }
+  }
+
+  fn send(&mut self, message: Message) {
+    if let Some(peer) = &mut self.tab_mut().peer {
+      if let Err(err) = peer.send(&message) {
+        self.tab_mut().alerts.push(
+          Severity::Error,
+          format!("Lost the connection to your opponent: {}", err),
+        );
+      }
+    }
+  }
+
+  /// Plays out a message from the other player of the match in the tab
+  pub fn on_net(&mut self, tab: usize, message: Message) {
+    self.in_tab(tab, |app| app.play_net(message));
+  }
+
+  /// Pauses the match in the tab when the connection to the other player drops, while the
+  /// turn log keeps it to go on with once both start again, and counts it as them leaving
+  /// otherwise
+  pub fn on_dropped(&mut self, tab: usize) {
+    self.in_tab(tab, |app| {
+      if app.tab().turn_log.is_none() || app.tab().spectating || app.is_won() {
+        return app.play_net(Message::Bye);
+      }
+      app.tab_mut().clock.stop(Instant::now());
+      app.tab_mut().alerts.push_sticky(
+        Severity::Warn,
+        "Lost the connection to your opponent, start again with the turn log to go on",
+      );
+    });
+  }
+
+  fn play_net(&mut self, message: Message) {
+    if self.tab().spectating {
+      return self.on_watched(message);
+    }
+    let behind = self.tab().peer.as_ref().is_some_and(Peer::is_behind);
+    match message {
+      Message::Fire(..) | Message::PowerUp(..) => {
+        match (message.play(), &mut self.tab_mut().peer) {
+          // the turn the other side is ahead by comes along with its answer
+          (Some(play), Some(peer)) if behind => peer.expect_answer(play),
+          (Some(play), _) => self.answer_play(play),
+          _ => {}
+        }
+      }
+      Message::Answer(answer, hash) if behind => self.catch_up(answer, hash),
+      Message::Answer(answer, hash) => self.settle_play(answer, hash),
+      Message::Sync(turns, hash) => self.resync(turns, hash),
+      Message::Fleet(salt, fleet) if self.is_won() => {
+        self.broadcast(Message::Fleet(salt.clone(), fleet.clone()));
+        self.open_fleet(&salt, fleet)
+      }
+      Message::Bye => {
+        if !self.is_won() {
+          self.tab_mut().game.forfeit(1);
+          self
+            .tab_mut()
+            .alerts
+            .push(Severity::Info, "Your opponent left");
+          self.broadcast(Message::Bye);
+        }
+      }
+      Message::Fleet(..) | Message::Hello(..) | Message::Watch => {}
+    }
+  }
+
+  /// Goes on with a match both sides resumed: the side a turn ahead sends the turn again,
+  /// otherwise both must have found the same
+  fn resync(&mut self, turns: usize, hash: u64) {
+    let played = self.tab().game.timeline().len();
+    if turns == played {
+      self.tab_mut().alerts.push(
+        Severity::Info,
+        format!("Back in the match at turn {}", turns),
+      );
+      self.check_found(Some(hash));
+    } else if turns == played + 1 {
+      if let Some(peer) = &mut self.tab_mut().peer {
+        peer.fall_behind();
+      }
+    } else if let (true, Some(turn)) = (played == turns + 1, self.tab().game.timeline().last()) {
+      let (play, answer) = (Message::from(&turn.play()), turn.answer.clone());
+      self.send(play);
+      if let Some(answer) = answer {
+        self.send(Message::Answer(answer, Some(self.tab().game.found_hash())));
+      }
+    } else {
+      self.tab_mut().alerts.push_sticky(
+        Severity::Error,
+        format!(
+          "Your opponent played {} turns and you {}, the match can't go on",
+          turns, played
+        ),
+      );
+    }
+  }
+
+  /// Plays the turn the other side was ahead by when the match resumed, as it was played and
+  /// answered there
+  fn catch_up(&mut self, answer: Answer, hash: Option<u64>) {
+    let play = match self.tab_mut().peer.as_mut() {
+      Some(peer) => {
+        peer.catch_up();
+        peer.take_awaiting()
+      }
+      None => None,
+    };
+    let (shots, power_up) = match play {
+      Some(Play::Salvo(shots)) => (shots, None),
+      Some(Play::PowerUp(power_up, cell)) => (BTreeSet::new(), Some((power_up, cell))),
+      None => return,
+    };
+    let turn = Turn {
+      shots,
+      power_up,
+      answer: Some(answer),
+      ..Default::default()
+    };
+    let player = usize::from(!self.tab().game.is_user_turn());
+    match self.tab_mut().game.play(&turn, player == 1) {
+      Ok(msg) => {
+        self.tab_mut().alerts.push_by(player, Severity::Info, msg);
+        self.check_found(hash);
+        self.record_turns();
+      }
+      Err(err) => self.tab_mut().alerts.push(
+        Severity::Error,
+        format!("The turn your opponent sent again doesn't add up: {}", err),
+      ),
+    }
+  }
+
+  /// Sends the user's turn to the other player, whose answer settles it
+  fn send_play(&mut self, play: Play) {
+    if self.tab().peer.as_ref().and_then(Peer::awaiting).is_some() {
+      return self
+        .tab_mut()
+        .alerts
+        .push(Severity::Warn, "Waiting on your opponent's answer");
+    }
+    if let Err(err) = self.tab().game.check_play(&play) {
+      return self.tab_mut().alerts.push(Severity::Warn, err);
+    }
+    self.stop_targeting();
+    self.tab_mut().selected_coordinates.clear();
+    self.send(Message::from(&play));
+    self.broadcast(Message::from(&play));
+    if let Some(peer) = &mut self.tab_mut().peer {
+      peer.expect_answer(play);
+    }
+  }
+
+  /// Settles the user's turn by what the other player answered it found
+  fn settle_play(&mut self, answer: Answer, hash: Option<u64>) {
+    let play = match self.tab_mut().peer.as_mut().and_then(Peer::take_awaiting) {
+      Some(play) => play,
+      None => {
+        return self.tab_mut().alerts.push(
+          Severity::Error,
+          "Your opponent answered a turn you didn't play",
+        )
+      }
+    };
+    match self.tab_mut().game.settle_turn(&play, answer.clone()) {
+      Ok(mut msg) => {
+        if self.tab().game.rule == Rule::Chain
+          && self.tab().game.is_user_turn()
+          && !self.tab().game.is_won()
+        {
+          msg.push_str(" Fire again!");
+        }
+        match play {
+          Play::Salvo(shots) if self.suspense => {
+            self.tab_mut().revealing = Some(Reveal {
+              hidden: shots.into_iter().collect(),
+              message: msg,
+            })
           }
-        },
-      ))
-      .border_type(BorderType::Rounded)
+          _ => self.tab_mut().alerts.push_by(0, Severity::Info, msg),
+        }
+        self.broadcast(Message::Answer(answer, hash));
+        self.check_found(hash);
+        self.record_turns();
+        self.collect_reactions();
+      }
+      Err(err) => self.tab_mut().alerts.push(
+        Severity::Error,
+        format!("Your opponent's answer doesn't add up: {}", err),
+      ),
+    }
+  }
+
+  /// Plays the other player's turn upon the user's fleet and answers it with what it found
+  fn answer_play(&mut self, play: Play) {
+    if self.tab().game.is_user_turn() || self.is_won() {
+      return self
+        .tab_mut()
+        .alerts
+        .push(Severity::Error, "Your opponent played out of turn");
+    }
+    match self.tab_mut().game.take_turn(&play) {
+      Ok((msg, answer)) => {
+        self.tab_mut().alerts.push_by(1, Severity::Info, msg);
+        // kept before it's answered, so the turn log is never behind the other side
+        self.record_turns();
+        self.broadcast(Message::from(&play));
+        let hash = Some(self.tab().game.found_hash());
+        self.send(Message::Answer(answer.clone(), hash));
+        self.broadcast(Message::Answer(answer, hash));
+      }
+      Err(err) => self.tab_mut().alerts.push(
+        Severity::Error,
+        format!("Your opponent's turn was refused: {}", err),
+      ),
+    }
   }
 
-  pub fn text_style(&self) -> Style {
-    // cell background color
-    Style::default().bg(Color::Black)
+  /// Reports the match going apart from the one on the other side, the first time the hash
+  /// of what both sides found that came with an answer isn't the one of this side. The
+  /// fleets sent at the end tell which side is right.
+  fn check_found(&mut self, hash: Option<u64>) {
+    let turn = self.tab().game.timeline().len();
+    let tab = self.tab_mut();
+    let apart = match (&mut tab.peer, hash) {
+      (Some(peer), Some(hash)) if hash != tab.game.found_hash() => peer.desync(turn),
+      _ => false,
+    };
+    if apart {
+      let msg = if tab.spectating {
+        format!("The host and the guest disagree on turn {}", turn)
+      } else {
+        format!("Your opponent disagrees on what turn {} found", turn)
+      };
+      tab.alerts.push_sticky(Severity::Error, msg);
+    }
   }
-}
 
-impl fmt::Display for Cell<'_> {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "{}", self.get_position_status())
+  /// Sends the user's fleet to the other player once the match is over, for them to check
+  /// the answers against
+  fn send_fleet(&mut self) {
+    if let Some(peer) = &mut self.tab_mut().peer {
+      if let Err(err) = peer.send_fleet() {
+        self.tab_mut().alerts.push(
+          Severity::Error,
+          format!("Lost the connection to your opponent: {}", err),
+        );
+      }
+    }
+  }
+
+  /// Checks a fleet sent at the end against the commitment to it, and shows the match with
+  /// both fleets in the open once both are in and every answer checks out
+  fn open_fleet(&mut self, salt: &str, fleet: Vec<Placement>) {
+    let opened = match &mut self.tab_mut().peer {
+      Some(peer) => peer.open_fleet(salt, fleet),
+      None => return,
+    };
+    match opened.and_then(|fleets| {
+      fleets
+        .map(|fleets| self.tab().game.reveal(fleets))
+        .transpose()
+    }) {
+      Ok(Some(game)) => {
+        self.tab_mut().game = game;
+        self.write_notation();
+        self
+          .tab_mut()
+          .alerts
+          .push(Severity::Info, "Both fleets are in the open and check out");
+      }
+      Ok(None) => {}
+      Err(err) => self.tab_mut().alerts.push(
+        Severity::Error,
+        format!("The fleets don't check out: {}", err),
+      ),
+    }
+  }
+
+  /// Plays out a turn of the match being watched once its answer comes in, the host has
+  /// checked it already
+  fn on_watched(&mut self, message: Message) {
+    match message {
+      Message::Fire(..) | Message::PowerUp(..) if !self.is_won() => {
+        if let (Some(peer), Some(play)) = (&mut self.tab_mut().peer, message.play()) {
+          peer.expect_answer(play);
+        }
+      }
+      Message::Answer(answer, hash) if !self.is_won() => {
+        let play = match self.tab_mut().peer.as_mut().and_then(Peer::take_awaiting) {
+          Some(play) => play,
+          None => return,
+        };
+        let player = usize::from(!self.tab().game.is_user_turn());
+        let shooter = self.names()[player];
+        if self.tab_mut().game.settle_turn(&play, answer).is_err() {
+          return;
+        }
+        self.check_found(hash);
+        match (self.tab().game.winner(), play) {
+          (Some(winner), _) => self.tab_mut().alerts.push(
+            Severity::Info,
+            format!("The {} won", if winner == 0 { "host" } else { "guest" }),
+          ),
+          (None, Play::Salvo(shots)) => {
+            let cells = shots.iter().map(|c| a1_notation(*c)).collect::<Vec<_>>();
+            self.tab_mut().alerts.push_by(
+              player,
+              Severity::Info,
+              format!("{} fired at {}", shooter, cells.join(" ")),
+            )
+          }
+          (None, Play::PowerUp(power_up, cell)) => self.tab_mut().alerts.push_by(
+            player,
+            Severity::Info,
+            format!(
+              "{} used a {} on {}",
+              shooter,
+              power_up.name(),
+              a1_notation(cell)
+            ),
+          ),
+        }
+      }
+      Message::Fleet(salt, fleet) if self.is_won() => self.open_fleet(&salt, fleet),
+      Message::Bye if !self.is_won() => {
+        self.tab_mut().clock.stop(Instant::now());
+        self
+          .tab_mut()
+          .alerts
+          .push_sticky(Severity::Info, "The match was stopped");
+      }
+      _ => {}
+    }
+  }
+
+  fn write_notation(&mut self) {
+    if let Some(path) = &self.tab().notation {
+      if let Err(err) = fs::write(path, notation::write(&self.tab().game)) {
+        self.tab_mut().alerts.push(
+          Severity::Error,
+          format!("Failed to write the match: {}", err),
+        );
+      }
+    }
+  }
+
+  /// Keeps the lifetime statistics in the given file
+  pub fn set_stats(&mut self, stats: StatsFile) {
+    self.stats = Some(stats);
+  }
+
+  fn record_outcome(&mut self, outcome: Outcome) {
+    if self.tab().throwaway {
+      return;
+    }
+    let stats = match &self.stats {
+      Some(stats) => stats,
+      None => return,
+    };
+    // only a fleet the computer hunted down to the end tells how it was found, and only a
+    // match played out on one fleet says how fast and well the user fired
+    let decided =
+      outcome != Outcome::Abandoned && !self.is_time_attack() && self.tab().gauntlet.is_none();
+    let fleet = Some(FleetRecord::of(&self.tab().game)).filter(|_| decided);
+    let played = Some(Played::of(&self.tab().game, self.elapsed())).filter(|_| decided);
+    // a time attack goes on a leaderboard of its own
+    let attack = Some(Played::of(&self.tab().game, self.elapsed()))
+      .filter(|_| outcome != Outcome::Abandoned && self.is_time_attack());
+    let criticals = self.tab().game.criticals(0);
+    let mut place = None;
+    let recorded = stats
+      .record(outcome)
+      .and_then(|_| match fleet {
+        Some(fleet) => stats.record_fleet(fleet),
+        None => Ok(()),
+      })
+      .and_then(|_| match played {
+        Some(played) => stats.record_played(&played).map(|p| place = p),
+        None => Ok(()),
+      })
+      .and_then(|_| match attack {
+        Some(attack) => stats.record_attack(&attack).map(|p| place = p),
+        None => Ok(()),
+      })
+      .and_then(|_| match criticals {
+        0 => Ok(()),
+        _ => stats.record_criticals(criticals),
+      })
+      .and_then(|_| stats.load());
+    self.tab_mut().best_place = place;
+    match recorded {
+      Ok(stats) => self.tab_mut().lifetime = Some(stats),
+      Err(err) => self
+        .tab_mut()
+        .alerts
+        .push(Severity::Error, format!("Failed to write stats: {}", err)),
+    }
+  }
+
+  /// Habits of the user's placement in the recent matches kept in the stats
+  fn placement_tips(&self) -> Vec<String> {
+    match self.stats.as_ref().map(|stats| stats.load()) {
+      Some(Ok(stats)) => advice::tips(&stats.fleets),
+      _ => vec![],
+    }
+  }
+
+  /// Places the user's fleet at random away from where the computer found their ships in
+  /// the recent matches kept in the stats
+  pub fn avoid_habits(&mut self) -> io::Result<()> {
+    let stats = match &self.stats {
+      Some(stats) => stats.load()?,
+      None => return Ok(()),
+    };
+    let fleet = advice::suggest(
+      &stats.fleets,
+      self.tab().game.fleet_type(),
+      &mut rand::thread_rng(),
+    );
+    self
+      .tab_mut()
+      .game
+      .place_fleet(&fleet)
+      .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+  }
+
+  /// Publishes a summary of the match on the given server every tick
+  pub fn set_status_server(&mut self, status: StatusServer) {
+    self.status = Some(status);
+  }
+
+  /// Saves presets made in the sandbox to the given file
+  pub fn set_presets(&mut self, presets: PresetFile) {
+    self.presets = Some(presets);
+  }
+
+  /// The rule, difficulty and mode of the current game
+  pub fn preset(&self) -> Preset {
+    let tab = self.tab();
+    let mode = if self.is_time_attack() {
+      Mode::TimeAttack
+    } else if tab.gauntlet.is_some() {
+      Mode::Gauntlet
+    } else {
+      Mode::Normal
+    };
+    Preset {
+      rule: tab.game.rule,
+      difficulty: *tab.game.difficulty(),
+      mode,
+      time_limit: tab.time_limit.map(|limit| limit.as_secs()),
+      strict: self.strict,
+    }
+  }
+
+  /// Starts a new game with the rule, difficulty and mode of the preset
+  pub fn apply_preset(&mut self, preset: &Preset) {
+    let tab = self.tab_mut();
+    tab.game = Game::new(preset.rule, preset.difficulty, 0);
+    tab.time_limit = None;
+    tab.gauntlet = None;
+    match preset.mode {
+      Mode::TimeAttack => self.set_time_attack(preset.time_limit.map(Duration::from_secs)),
+      Mode::Gauntlet => self.set_gauntlet(),
+      Mode::Normal => {}
+    }
+    self.deal_first();
+    self.strict = preset.strict;
+    let tab = self.tab_mut();
+    tab.clock = Clock::start(Instant::now());
+    tab.selected_coordinates.clear();
+    tab.reaction = None;
+  }
+
+  fn on_open_sandbox(&mut self) {
+    // a throwaway game would take the place of the match kept in the log
+    if self.tab().turn_log.is_some() {
+      self.tab_mut().alerts.push(
+        Severity::Warn,
+        "The sandbox is not available with a turn log",
+      );
+      return;
+    }
+    self.tab_mut().clock.pause(Instant::now());
+    self.sandbox = Some(Sandbox::new(self.preset()));
+  }
+
+  fn on_sandbox_key(&mut self, key: Key) {
+    let sandbox = match &mut self.sandbox {
+      Some(sandbox) => sandbox,
+      None => return,
+    };
+    if let Some(name) = &mut sandbox.naming {
+      match key {
+        Key::Char('\n') if !name.is_empty() => {
+          let name = name.clone();
+          let preset = sandbox.preset.clone();
+          sandbox.naming = None;
+          match &self.presets {
+            Some(presets) => match presets.save(&name, &preset) {
+              Ok(()) => self
+                .tab_mut()
+                .alerts
+                .push(Severity::Success, format!("Saved preset {}", name)),
+              Err(err) => self
+                .tab_mut()
+                .alerts
+                .push(Severity::Error, format!("Failed to save preset: {}", err)),
+            },
+            None => self
+              .tab_mut()
+              .alerts
+              .push(Severity::Warn, "No config directory to save presets in"),
+          }
+        }
+        Key::Char(c) if c.is_alphanumeric() || c == '-' || c == '_' => name.push(c),
+        Key::Backspace => {
+          name.pop();
+        }
+        Key::Esc => sandbox.naming = None,
+        _ => {}
+      }
+      return;
+    }
+    match self.keymap.action(key) {
+      Some(Action::Up) => sandbox.up(),
+      Some(Action::Down) => sandbox.down(),
+      Some(Action::Left) => sandbox.change(false),
+      Some(Action::Right) => sandbox.change(true),
+      Some(Action::Fire) => {
+        let preset = sandbox.preset.clone();
+        self.sandbox = None;
+        self.apply_preset(&preset);
+        let tab = self.tab_mut();
+        tab.throwaway = true;
+        tab.alerts.clear();
+        tab.alerts.push(
+          Severity::Info,
+          format!("Started a throwaway {} game", preset.mode),
+        );
+      }
+      Some(Action::Cancel) | Some(Action::Sandbox) => {
+        self.sandbox = None;
+        self.tab_mut().clock.resume(Instant::now());
+      }
+      Some(Action::Quit) => self.on_quit(),
+      Some(Action::NamePreset) => sandbox.naming = Some(String::new()),
+      _ => {}
+    }
+  }
+
+  /// The turns played so far with the cells each of them changed, for debugging the engine
+  pub fn turn_changes(&self) -> Vec<String> {
+    let mut lines = vec![];
+    for (index, turn) in self.tab().game.timeline().iter().enumerate() {
+      let shots = turn
+        .shots
+        .iter()
+        .map(|c| a1_notation(*c))
+        .collect::<Vec<_>>()
+        .join(" ");
+      let (player, opponent) = if turn.player == 0 {
+        ("You", "Computer")
+      } else {
+        ("Computer", "You")
+      };
+      lines.push(format!("Turn {} by {}: {}", index + 1, player, shots));
+      for change in &turn.fleet_changes {
+        lines.push(format!("  {} fleet {}", opponent, change));
+      }
+      for change in &turn.view_changes {
+        lines.push(format!("  {} view {}", player, change));
+      }
+      if turn.fleet_changes.is_empty() && turn.view_changes.is_empty() {
+        lines.push("  no changes".into());
+      }
+    }
+    if lines.is_empty() {
+      lines.push("No turns played yet".into());
+    }
+    lines
+  }
+
+  fn on_devtools_key(&mut self, key: Key) {
+    let offset = self.tab().devtools.unwrap_or_default();
+    match self.keymap.action(key) {
+      Some(Action::Up) => self.tab_mut().devtools = Some(offset.saturating_sub(1)),
+      Some(Action::Down) => {
+        let last = self.turn_changes().len().saturating_sub(1);
+        self.tab_mut().devtools = Some((offset + 1).min(last));
+      }
+      Some(Action::Top) => self.tab_mut().devtools = Some(0),
+      Some(Action::Cancel) | Some(Action::Devtools) => self.tab_mut().devtools = None,
+      Some(Action::Quit) => self.on_quit(),
+      _ => {}
+    }
+  }
+
+  fn on_quit(&mut self) {
+    for index in 0..self.tabs.len() {
+      self.in_tab(index, Self::leave_match);
+    }
+    self.should_quit = true;
+  }
+
+  fn leave_match(&mut self) {
+    // a match kept in a turn log or the session isn't abandoned, it goes on at the next start
+    let kept = self.sessions.is_some() && self.session_tab().is_some();
+    if self.count_abandoned
+      && self.tab().turn_log.is_none()
+      && !kept
+      && !self.is_won()
+      && !self.tab().game.timeline().is_empty()
+    {
+      self.record_outcome(Outcome::Abandoned);
+    }
+    if !self.is_won() && !self.tab().spectating {
+      self.send(Message::Bye);
+      // leaving a match over the network ends it on both sides
+      if let (Some(turn_log), true) = (&self.tab().turn_log, self.tab().peer.is_some()) {
+        let _ = turn_log.finish();
+      }
+    }
+  }
+
+  fn collect_reactions(&mut self) {
+    let tab = &mut self.tabs[self.active_tab];
+    for event in tab.game.take_events() {
+      if let Some(reaction) = self.persona.react(&event, tab.game.cosmetic_rng()) {
+        tab.reaction = Some(self.skin.rename(&reaction, event.ship_type()));
+      }
+    }
+  }
+
+  /// Lets the user place their own fleet before the first shot
+  pub fn start_placement(&mut self) {
+    let mines = if self.tab().game.rule == Rule::Mines {
+      MINES
+    } else {
+      0
+    };
+    let placer = Placer::new(self.tab().game.fleet_type()).with_mines(mines);
+    self.tab_mut().placing = Some(placer);
+    self.enter_mode(InputMode::Placing);
+    self.tab_mut().focus = Focus::Player;
+  }
+
+  fn on_placement_key(&mut self, key: Key) {
+    let tab = &mut self.tabs[self.active_tab];
+    let placer = match &mut tab.placing {
+      Some(placer) => placer,
+      None => return,
+    };
+    if self.keymap.action(key) == Some(Action::Flagship) {
+      if tab.game.rule != Rule::Flagship {
+        return tab
+          .alerts
+          .push(Severity::Warn, "Only the flagship rule has flagships");
+      }
+      placer.toggle_flagship();
+      if let Some(current) = &placer.current {
+        let msg = if placer.flagship == Some(placer.placed.len()) {
+          format!(
+            "The {} is your flagship",
+            self.skin.ship_name(&current.ship_type)
+          )
+        } else {
+          format!(
+            "The {} is no longer your flagship",
+            self.skin.ship_name(&current.ship_type)
+          )
+        };
+        tab.alerts.push(Severity::Info, msg);
+      }
+      return;
+    }
+    let laying_mines = placer.is_laying_mines();
+    let tool = match self.keymap.action(key) {
+      Some(Action::MirrorAcross) => Some(placer.mirror(true)),
+      Some(Action::MirrorDown) => Some(placer.mirror(false)),
+      Some(Action::Fill) => Some(placer.fill_symmetric()),
+      _ => None,
+    };
+    if let Some(result) = tool {
+      match result {
+        Err(err) => return tab.alerts.push(Severity::Warn, err),
+        Ok(()) if placer.is_done() => return self.finish_placement(),
+        Ok(()) => {}
+      }
+    }
+    match self.keymap.action(key) {
+      Some(Action::Up) => placer.shift(-1, 0),
+      Some(Action::Down) => placer.shift(1, 0),
+      Some(Action::Left) => placer.shift(0, -1),
+      Some(Action::Right) => placer.shift(0, 1),
+      Some(Action::Rotate) => placer.rotate(),
+      Some(Action::Select) | Some(Action::Fire) => match placer.drop() {
+        Err(err) => tab.alerts.push(Severity::Warn, err),
+        Ok(()) if placer.is_done() => self.finish_placement(),
+        Ok(()) => {}
+      },
+      Some(Action::Cancel) => placer.undo(),
+      Some(Action::Quit) => self.on_quit(),
+      _ => {}
+    }
+    if !laying_mines
+      && self
+        .tab()
+        .placing
+        .as_ref()
+        .is_some_and(Placer::is_laying_mines)
+    {
+      self.tab_mut().alerts.push(
+        Severity::Info,
+        format!("Fleet placed, now hide your {} mines", MINES),
+      );
+    }
+  }
+
+  fn finish_placement(&mut self) {
+    let placer = match self.tab_mut().placing.take() {
+      Some(placer) => placer,
+      None => return,
+    };
+    self.leave_mode(InputMode::Placing);
+    if let Err(err) = self.tab_mut().game.place_fleet(&placer.placed) {
+      return self.tab_mut().alerts.push(Severity::Error, err);
+    }
+    if let Some(index) = placer.flagship {
+      if let Err(err) = self.tab_mut().game.set_flagship(0, index) {
+        return self.tab_mut().alerts.push(Severity::Error, err);
+      }
+    }
+    if !placer.mines.is_empty() {
+      let mines = placer.mines.iter().copied().collect();
+      if let Err(err) = self.tab_mut().game.set_mines(0, &mines) {
+        return self.tab_mut().alerts.push(Severity::Error, err);
+      }
+    }
+    // the log still has the fleet the game started with
+    let tab = self.tab_mut();
+    if let Some(turn_log) = &mut tab.turn_log {
+      if let Err(err) = turn_log.start(&tab.game) {
+        tab.alerts.push(
+          Severity::Error,
+          format!("Failed to write turn log: {}", err),
+        );
+      }
+    }
+    tab.clock = Clock::start(Instant::now());
+    tab.focus = Focus::Opponent;
+    tab
+      .alerts
+      .push(Severity::Success, "Fleet in position, fire away");
+  }
+
+  fn record_turns(&mut self) {
+    let tab = self.tab_mut();
+    tab.clock.lap(Instant::now(), tab.game.timeline().len());
+    self.animate_impacts();
+    self.write_notation();
+    let tab = self.tab_mut();
+    if let Some(turn_log) = &mut tab.turn_log {
+      let result = if tab.game.is_won() {
+        turn_log.finish()
+      } else {
+        turn_log.record(&tab.game)
+      };
+      if let Err(err) = result {
+        tab.alerts.push(
+          Severity::Error,
+          format!("Failed to write turn log: {}", err),
+        );
+      }
+    }
+  }
+
+  fn on_up(&mut self) {
+    if let Some(active_row) = self.tab().active_row.checked_sub(1) {
+      self.tab_mut().active_row = active_row;
+    }
+  }
+
+  fn on_down(&mut self) {
+    if self.tab().active_row < ROWS - 1 {
+      self.tab_mut().active_row += 1;
+    }
+  }
+
+  fn on_right(&mut self) {
+    if self.tab().active_column < COLS - 1 {
+      self.tab_mut().active_column += 1;
+    }
+  }
+
+  fn on_left(&mut self) {
+    if let Some(active_column) = self.tab().active_column.checked_sub(1) {
+      self.tab_mut().active_column = active_column;
+    }
+  }
+
+  fn on_switch_focus(&mut self) {
+    self.tab_mut().focus = match self.tab_mut().focus {
+      Focus::Player => Focus::Opponent,
+      Focus::Opponent => Focus::Player,
+    };
+  }
+
+  fn on_select(&mut self) {
+    if self.tab().focus != Focus::Opponent {
+      self.tab_mut().alerts.push(
+        Severity::Warn,
+        "Switch to the opponent board with <tab> to select",
+      )
+    } else if !self.tab().game.is_won() {
+      let active = self.active();
+      if self.is_selected(active) {
+        self.tab_mut().selected_coordinates.remove(&active);
+      } else {
+        let mut salvo = self.tab().selected_coordinates.clone();
+        salvo.insert(active);
+        match self.tab().game.validate_salvo(&salvo) {
+          Err(SalvoError::OverBudget(_)) => self
+            .tab_mut()
+            .alerts
+            .push(Severity::Warn, "Maximum shots for rule selected"),
+          Err(err @ SalvoError::Illegal(..)) if self.strict => {
+            self.tab_mut().alerts.push(Severity::Warn, err.to_string())
+          }
+          _ => self.tab_mut().selected_coordinates = salvo,
+        }
+      }
+    }
+  }
+
+  fn on_fire(&mut self) {
+    let short = match self
+      .tab()
+      .game
+      .validate_salvo(&self.tab().selected_coordinates)
+    {
+      Err(err @ SalvoError::UnderBudget(_)) if self.tab().game.is_user_turn() => Some(err),
+      _ => None,
+    };
+    if self.tab().selected_coordinates.is_empty() {
+      self
+        .tab_mut()
+        .alerts
+        .push(Severity::Warn, "Select opponent coordinates to hit");
+    } else if let Some(err) = short {
+      self.tab_mut().alerts.push(Severity::Warn, err.to_string());
+    } else if !self.tab().game.is_won()
+      && self.tab().game.is_user_turn()
+      && self.tab().peer.is_some()
+    {
+      self.send_play(Play::Salvo(self.tab().selected_coordinates.clone()));
+    } else if !self.tab().game.is_won() && self.tab().game.is_user_turn() {
+      let tab = self.tab_mut();
+      let mut msg = tab.game.fire(&tab.selected_coordinates, false);
+      if tab.game.rule == Rule::Chain && tab.game.is_user_turn() && !tab.game.is_won() {
+        msg.push_str(" Fire again!");
+      }
+      let shots = std::mem::take(&mut tab.selected_coordinates);
+      if self.suspense {
+        self.tab_mut().revealing = Some(Reveal {
+          hidden: shots.iter().copied().collect(),
+          message: msg,
+        });
+      } else {
+        self.tab_mut().alerts.push_by(0, Severity::Info, msg);
+      }
+      self.record_turns();
+      self.collect_reactions();
+      if let Some(msg) = self.run_gauntlet() {
+        self.tab_mut().alerts.push(Severity::Info, msg);
+      }
+    } else {
+      self.tab_mut().alerts.push(Severity::Warn, "Not your turn");
+    }
+  }
+
+  /// Uses the user's sonar or ping on the cursor in place of firing
+  fn on_scan(&mut self, power_up: PowerUp) {
+    if self.tab().focus != Focus::Opponent {
+      self.tab_mut().alerts.push(
+        Severity::Warn,
+        format!(
+          "Switch to the opponent board with <tab> to use the {}",
+          power_up.name()
+        ),
+      );
+    } else if !self.tab().game.is_won()
+      && self.tab().game.is_user_turn()
+      && self.tab().peer.is_some()
+    {
+      self.send_play(Play::PowerUp(power_up, self.active()));
+    } else if !self.tab().game.is_won() && self.tab().game.is_user_turn() {
+      let cell = self.active();
+      match self.tab_mut().game.use_power_up(power_up, cell, false) {
+        Ok(msg) => {
+          self.stop_targeting();
+          self.tab_mut().alerts.push_by(0, Severity::Info, msg);
+          self.record_turns();
+        }
+        Err(err) => self.tab_mut().alerts.push(Severity::Warn, err),
+      }
+    } else {
+      self.tab_mut().alerts.push(Severity::Warn, "Not your turn");
+    }
+  }
+
+  /// The mode the keys are in, the one entered last
+  pub fn input_mode(&self) -> InputMode {
+    self
+      .tab()
+      .modes
+      .last()
+      .copied()
+      .unwrap_or(InputMode::Normal)
+  }
+
+  /// The name of the mode shown above the keys, for every mode but the normal one
+  pub fn mode_indicator(&self) -> Option<String> {
+    match self.input_mode() {
+      InputMode::Normal => None,
+      InputMode::Placing => Some("PLACING".into()),
+      InputMode::Targeting(power_up) => Some(format!("AIMING {}", power_up.name().to_uppercase())),
+    }
+  }
+
+  /// Enters the mode on top of the current one, aiming another power-up in place of the
+  /// one aimed already
+  fn enter_mode(&mut self, mode: InputMode) {
+    if let (InputMode::Targeting(_), InputMode::Targeting(_)) = (self.input_mode(), mode) {
+      self.tab_mut().modes.pop();
+    }
+    self.tab_mut().modes.push(mode);
+  }
+
+  /// Leaves the mode along with any entered on top of it
+  fn leave_mode(&mut self, mode: InputMode) {
+    if let Some(index) = self.tab().modes.iter().rposition(|m| *m == mode) {
+      self.tab_mut().modes.truncate(index);
+    }
+  }
+
+  /// Puts away the power-up being aimed, if any
+  fn stop_targeting(&mut self) {
+    if let InputMode::Targeting(_) = self.input_mode() {
+      self.tab_mut().modes.pop();
+    }
+  }
+
+  /// Aims the power-up for <enter> once the user may use it, or puts it away again
+  fn on_target(&mut self, power_up: PowerUp) {
+    if let InputMode::Targeting(aimed) = self.input_mode() {
+      if aimed.is_like(&power_up) {
+        return self.stop_targeting();
+      }
+    }
+    match self.tab().game.check_power_up(0, power_up) {
+      Ok(()) => {
+        self.enter_mode(InputMode::Targeting(power_up));
+        self.tab_mut().alerts.push(
+          Severity::Info,
+          format!(
+            "Aiming the {}, {}<enter> uses it on the cursor and <esc> puts it away",
+            power_up.name(),
+            if power_up.line().is_some() {
+              "<r> turns it, "
+            } else {
+              ""
+            }
+          ),
+        );
+      }
+      Err(err) => self.tab_mut().alerts.push(Severity::Warn, err),
+    }
+  }
+
+  /// Strikes every cell the armed power-up covers the user can still fire upon
+  fn on_strike(&mut self, power_up: PowerUp) {
+    if self.tab().focus != Focus::Opponent {
+      self.tab_mut().alerts.push(
+        Severity::Warn,
+        "Switch to the opponent board with <tab> to strike",
+      );
+    } else if !self.tab().game.is_won()
+      && self.tab().game.is_user_turn()
+      && self.tab().peer.is_some()
+    {
+      self.send_play(Play::PowerUp(power_up, self.active()));
+    } else if !self.tab().game.is_won() && self.tab().game.is_user_turn() {
+      let cell = self.active();
+      match self.tab_mut().game.use_power_up(power_up, cell, false) {
+        Ok(msg) => {
+          self.stop_targeting();
+          self.tab_mut().selected_coordinates.clear();
+          self.tab_mut().alerts.push_by(0, Severity::Info, msg);
+          self.record_turns();
+          self.collect_reactions();
+          if let Some(msg) = self.run_gauntlet() {
+            self.tab_mut().alerts.push(Severity::Info, msg);
+          }
+        }
+        Err(err) => self.tab_mut().alerts.push(Severity::Warn, err),
+      }
+    } else {
+      self.tab_mut().alerts.push(Severity::Warn, "Not your turn");
+    }
+  }
+
+  fn is_selected(&self, coordinate: Coordinate) -> bool {
+    self
+      .tab()
+      .selected_coordinates
+      .iter()
+      .any(|c| *c == coordinate)
+  }
+
+  /// The cell under the cursor
+  pub fn active(&self) -> Coordinate {
+    (self.tab().active_row, self.tab().active_column)
+  }
+
+  pub fn rule(&self) -> &Rule {
+    &self.tab().game.rule
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    self.tab().clock.elapsed(Instant::now())
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.tab().clock.is_paused()
+  }
+
+  /// The shots the rule allows and every key, shown while the game is paused
+  pub fn help(&self) -> Vec<String> {
+    let (_, budget) = self.shots_left();
+    let mut lines = vec![
+      "Press <p>, <?> or <esc> to resume".into(),
+      String::new(),
+      format!("{}: {}", self.rule(), self.tab().game.rule.shot_limit()),
+      format!("Shots this turn: {}", budget),
+      String::new(),
+    ];
+    let keys = [
+      ("move", "🠔 🠗 🠕 🠖 hjkl, 3j for 3"),
+      ("jump", "0 $ g G c"),
+      ("board", "<tab>"),
+      ("select", "<space>"),
+      ("fire", "<enter>"),
+      ("sonar", "s"),
+      ("ping", "n"),
+      ("airstrike", "a, r to turn"),
+      ("strike", "f, r to turn"),
+      ("power-ups", "<alt-1>…<alt-4>"),
+      ("cancel", "<esc>"),
+      ("inspect", "i"),
+      ("save", "<ctrl-s>"),
+      ("sandbox", "o"),
+      ("turn log", "D"),
+      ("tabs", "<F1>…"),
+      ("help", "? p"),
+      ("quit", "q"),
+    ];
+    lines.extend(
+      keys
+        .iter()
+        .map(|(name, keys)| format!("{:<10} {:<24}", name, keys)),
+    );
+    lines
+  }
+
+  fn on_pause(&mut self) {
+    if self.tab().clock.is_paused() {
+      self.tab_mut().clock.resume(Instant::now());
+    } else if !self.tab().clock.is_stopped() {
+      self.tab_mut().clock.pause(Instant::now());
+    }
+  }
+
+  pub fn is_won(&self) -> bool {
+    self.tab().game.is_won()
+  }
+
+  pub fn round(&self) -> usize {
+    self.tab().game.round()
+  }
+
+  pub fn is_night(&self) -> bool {
+    self.tab().game.is_night()
+  }
+
+  /// Whether the selection used up the shots of the user's turn, so no other cell can be
+  /// picked until one is let go
+  pub fn is_selection_full(&self) -> bool {
+    let tab = self.tab();
+    self.shots_left().0 == 0
+      && !tab.selected_coordinates.is_empty()
+      && tab.game.is_user_turn()
+      && !tab.game.is_won()
+      && self.input_mode() == InputMode::Normal
+  }
+
+  /// Shots left to select this turn and the budget of the turn
+  pub fn shots_left(&self) -> (usize, usize) {
+    let budget = self.tab().game.shot_budget(0);
+    (
+      budget.saturating_sub(self.tab().selected_coordinates.len()),
+      budget,
+    )
+  }
+
+  /// The power-ups on the ability bar, with what's left of each and which one is aimed
+  pub fn ability_slots(&self) -> Vec<AbilitySlot> {
+    let tab = self.tab();
+    ABILITY_BAR
+      .iter()
+      .enumerate()
+      .map(|(index, power_up)| {
+        let aimed = match self.input_mode() {
+          InputMode::Targeting(aimed) if aimed.is_like(power_up) => Some(aimed),
+          _ => None,
+        };
+        let left = match power_up.ability() {
+          Some(ability) => match tab.game.cooldown(0, ability) {
+            Some(0) => "ready".into(),
+            Some(turns) => format!("in {}", turns),
+            None => "-".into(),
+          },
+          None => tab.game.power_ups_left(0, *power_up).to_string(),
+        };
+        let name = match aimed.and_then(|p| p.line()) {
+          Some(line) => format!("{} {}", power_up.name(), line.name()),
+          None => power_up.name().into(),
+        };
+        AbilitySlot {
+          hotkey: index + 1,
+          label: format!("{} {}", name, left),
+          ready: tab.game.power_ups_left(0, *power_up) > 0,
+          aimed: aimed.is_some(),
+        }
+      })
+      .collect()
+  }
+
+  /// Chance in percent a shot at the cell under the cursor drifts, under the drift rule
+  pub fn drift_chance(&self) -> Option<usize> {
+    let tab = self.tab();
+    match tab.game.rule {
+      Rule::Drift if tab.focus == Focus::Opponent => Some(tab.game.drift_chance(0, self.active())),
+      _ => None,
+    }
+  }
+
+  /// Percentage of the user's shots that hit, once there are any
+  pub fn accuracy(&self) -> Option<usize> {
+    match self.tab().game.shots_and_hits(0) {
+      (0, _) => None,
+      (shots, hits) => Some(hits * 100 / shots),
+    }
+  }
+
+  /// Shots, hits and ships afloat of both sides
+  pub fn scoreboard(&self) -> Vec<String> {
+    let tab = self.tab();
+    self
+      .names()
+      .iter()
+      .enumerate()
+      .map(|(player, name)| {
+        let (shots, hits) = tab.game.shots_and_hits(player);
+        // the computer's ships the user sank at night stay afloat until daylight
+        let hidden = if player == 1 {
+          tab.game.sunk_in_the_dark(1).len()
+        } else {
+          0
+        };
+        format!(
+          "{:<9} shots {:>3} | hits {:>2} | afloat {}",
+          name,
+          shots,
+          hits,
+          tab.game.fleet(player).alive + hidden
+        )
+      })
+      .collect()
+  }
+
+  /// Every ship of both fleets and whether it's afloat or sunk, the user's fleet first
+  pub fn fleet_status(&self) -> Vec<String> {
+    let tab = self.tab();
+    let boards = [
+      tab.game.player().player_board(),
+      tab.game.computer().player_board(),
+    ];
+    let skin = self.skin;
+    self
+      .names()
+      .iter()
+      .zip(boards.iter())
+      .enumerate()
+      .flat_map(|(player, (name, board))| {
+        let mut hidden = if player == 1 {
+          tab.game.sunk_in_the_dark(1)
+        } else {
+          vec![]
+        };
+        let ships = board
+          .ship_status()
+          .into_iter()
+          .map(|(ship_type, alive)| {
+            // a ship the user sank at night stays afloat until daylight
+            match hidden.iter().position(|s| *s == ship_type) {
+              Some(index) if !alive => {
+                hidden.remove(index);
+                (ship_type, true)
+              }
+              _ => (ship_type, alive),
+            }
+          })
+          .collect::<Vec<_>>();
+        let afloat = ships.iter().filter(|(_, alive)| *alive).count();
+        let header = format!("{} {}/{} afloat", name, afloat, ships.len());
+        // the computer's flagship stays hidden until the match is over
+        let flagship = self
+          .tab()
+          .game
+          .flagship(player)
+          .filter(|_| player == 0 || tab.game.is_won());
+        std::iter::once(header).chain(ships.into_iter().enumerate().map(
+          move |(index, (ship_type, alive))| {
+            format!(
+              "  {:<16} {}{}",
+              skin.ship_name(ship_type),
+              if alive { "afloat" } else { "sunk" },
+              if flagship == Some(index) {
+                ", flagship"
+              } else {
+                ""
+              }
+            )
+          },
+        ))
+      })
+      .collect()
+  }
+
+  /// The lines of the history, each with the player whose turn it was
+  pub fn history_by(&self) -> Vec<(usize, String)> {
+    self
+      .tab()
+      .game
+      .timeline()
+      .iter()
+      .map(|turn| turn.player)
+      .zip(self.history())
+      .collect()
+  }
+
+  /// The lines of the log, the history with the time on the clock each turn was played at
+  /// where it's known
+  pub fn log(&self) -> Vec<(usize, String)> {
+    self
+      .history_by()
+      .into_iter()
+      .enumerate()
+      .map(|(index, (player, line))| {
+        // the turns of a replay were played on another clock
+        match self
+          .tab()
+          .clock
+          .lap_at(index)
+          .filter(|_| self.tab().replay.is_none())
+        {
+          Some(at) => (player, format!("{} {}", format_duration(at), line)),
+          None => (player, line),
+        }
+      })
+      .collect()
+  }
+
+  /// Scrolls the log a page back to older turns
+  fn on_log_up(&mut self) {
+    let oldest = self.tab().game.timeline().len().saturating_sub(1);
+    let scroll = self
+      .tab()
+      .log_scroll
+      .map_or(LOG_PAGE, |scroll| scroll + LOG_PAGE);
+    self.tab_mut().log_scroll = Some(scroll.min(oldest));
+  }
+
+  /// Scrolls the log a page on to newer turns, following the match again once it's back
+  /// at the latest
+  fn on_log_down(&mut self) {
+    self.tab_mut().log_scroll = self
+      .tab_mut()
+      .log_scroll
+      .filter(|scroll| *scroll > LOG_PAGE)
+      .map(|scroll| scroll - LOG_PAGE);
+  }
+
+  /// One line per turn with what each shot found, the latest last
+  pub fn history(&self) -> Vec<String> {
+    self
+      .tab()
+      .game
+      .timeline()
+      .iter()
+      .enumerate()
+      .map(|(index, turn)| {
+        let player = if turn.player == 0 {
+          self.player_name()
+        } else {
+          self.opponent_name()
+        };
+        if let Some((power_up @ PowerUp::Sonar, cell)) = turn.power_up {
+          // an area already known all over comes back the same
+          let found = turn
+            .response
+            .values()
+            .next()
+            .map_or("no news", |s| s.describe());
+          return format!(
+            "{}. {}: {} at {} ({})",
+            index + 1,
+            player,
+            power_up.name(),
+            a1_notation(cell),
+            found
+          );
+        }
+        if let (Some((PowerUp::Ping, cell)), Some(distance)) = (turn.power_up, turn.ping) {
+          return format!(
+            "{}. {}: ping at {} ({} away)",
+            index + 1,
+            player,
+            a1_notation(cell),
+            distance
+          );
+        }
+        let shots = turn
+          .landed()
+          .iter()
+          .map(|c| {
+            let status = if self.tab().game.in_the_dark(index) {
+              Status::Splash.describe()
+            } else {
+              turn.response.get(c).map_or("miss", |s| s.describe())
+            };
+            format!("{} {}", a1_notation(*c), status)
+          })
+          .collect::<Vec<_>>();
+        let weapon = match turn.power_up {
+          Some((power_up, _)) => format!("{} ", power_up.name()),
+          None => String::new(),
+        };
+        format!("{}. {}: {}{}", index + 1, player, weapon, shots.join(", "))
+      })
+      .collect()
+  }
+
+  #[cfg(test)]
+  pub fn game(&self) -> &Game {
+    &self.tab().game
+  }
+
+  /// Details of the cell under the cursor on the focused board
+  pub fn inspect(&self) -> Vec<String> {
+    let own = self.tab().focus == Focus::Player;
+    let coordinate = self.active();
+    let status = self.board(own).status_at(coordinate);
+
+    let mut details = vec![format!(
+      "{}: {} {}",
+      a1_notation(coordinate),
+      status,
+      status.describe()
+    )];
+    if own {
+      let (_, ship) = self
+        .tab()
+        .game
+        .player()
+        .player_board()
+        .find_position_and_ship(coordinate);
+      if let Some(ship) = ship {
+        details.push(format!("Your {}", self.skin.ship_name(ship.ship_type())));
+      }
+    }
+    let shooter = if own { 1 } else { 0 };
+    details.push(match self.tab().game.fired_upon_in(shooter, coordinate) {
+      Some(turn) => format!("Fired upon in turn {}", turn),
+      None => "Not fired upon yet".into(),
+    });
+    details
+  }
+
+  /// The cell under the cursor and what is known about it, kept in view above the boards
+  /// as the cursor moves
+  pub fn cursor_status(&self) -> Option<String> {
+    let tab = self.tab();
+    if tab.replay.is_some() || tab.placing.is_some() || tab.spectating || self.is_waiting() {
+      return None;
+    }
+    let own = tab.focus == Focus::Player;
+    let coordinate = self.active();
+    // a cell still drawn as a splash keeps what the shot found to itself
+    let hidden = !own
+      && self
+        .tab()
+        .revealing
+        .as_ref()
+        .is_some_and(|reveal| reveal.hidden.contains(&coordinate));
+    let status = if hidden {
+      Status::Space
+    } else {
+      self.board(own).status_at(coordinate)
+    };
+    Some(format!(
+      "Cursor: {} — {}",
+      a1_notation(coordinate),
+      status.describe()
+    ))
+  }
+
+  /// The user's own board, or their view of the computer's, which gives away the whole
+  /// fleet once the match is over
+  pub fn board(&self, own: bool) -> &Board {
+    let tab = self.tab();
+    if own {
+      tab.game.player().player_board()
+    } else if self.is_game_over() {
+      tab.game.computer().player_board()
+    } else {
+      tab.game.player().opponent_board()
+    }
+  }
+
+  /// Cursor and selection to draw on the board
+  pub fn board_state(&self, own: bool) -> BoardState {
+    let tab = self.tab();
+    let focus = if own { Focus::Player } else { Focus::Opponent };
+    BoardState {
+      cursor: Some(self.active()).filter(|_| tab.focus == focus),
+      selected: match self.input_mode() {
+        _ if own => BTreeSet::new(),
+        // the cells the power-up aimed would reach
+        InputMode::Targeting(power_up) => power_up
+          .cells(self.active())
+          .into_iter()
+          .filter(|c| tab.game.check_shot(0, *c).is_ok())
+          .collect(),
+        _ => tab.selected_coordinates.clone(),
+      },
+      ruled_out: if self.auto_mark && !own {
+        tab.game.ruled_out(0)
+      } else {
+        BTreeSet::new()
+      },
+      // the mines of the latest turn went off on the board of the one who fired
+      blasts: match tab.game.timeline().last() {
+        Some(turn) if (turn.player == 0) == own => turn.blasts.keys().copied().collect(),
+        _ => BTreeSet::new(),
+      },
+      hidden: match &tab.revealing {
+        Some(reveal) if !own => reveal.hidden.iter().copied().collect(),
+        _ => BTreeSet::new(),
+      },
+      impacts: self.impacts_on(own),
+      full: !own && self.is_selection_full(),
+    }
+  }
+
+  /// Starts the animations of the hits and kills of the latest turn, on the board they
+  /// landed on
+  fn animate_impacts(&mut self) {
+    let frame = self.frame_count;
+    let tab = self.tab_mut();
+    tab
+      .impacts
+      .retain(|(.., start)| frame.wrapping_sub(*start) < IMPACT_FRAMES);
+    let turn = match tab.game.timeline().last() {
+      Some(turn) => turn,
+      None => return,
+    };
+    let own = turn.player != 0;
+    for (cell, status) in &turn.response {
+      let impact = match status {
+        Status::Hit => Impact::Hit,
+        Status::Kill => Impact::Kill,
+        _ => continue,
+      };
+      tab.impacts.retain(|(o, c, ..)| (*o, *c) != (own, *cell));
+      tab.impacts.push((own, *cell, impact, frame));
+    }
+  }
+
+  /// The cells of the board still animating a shot landing, with the frame each is at
+  fn impacts_on(&self, own: bool) -> BTreeMap<Coordinate, (Impact, u16)> {
+    self
+      .tab()
+      .impacts
+      .iter()
+      .filter(|(o, ..)| *o == own)
+      .map(|(_, cell, impact, start)| (*cell, (*impact, self.frame_count.wrapping_sub(*start))))
+      .filter(|(_, (_, frame))| *frame < IMPACT_FRAMES)
+      .collect()
+  }
+
+  /// Shows the next cell of the shots being revealed, announcing what they did once the
+  /// last one is shown
+  fn reveal_next(&mut self) {
+    let frame = self.frame_count;
+    let tab = &mut self.tabs[self.active_tab];
+    if let Some(reveal) = &mut tab.revealing {
+      // the shot lands once it's shown
+      if let Some(cell) = reveal.hidden.pop_front() {
+        for (own, c, _, start) in &mut tab.impacts {
+          if !*own && *c == cell {
+            *start = frame;
+          }
+        }
+      }
+      self.bell = true;
+      if reveal.hidden.is_empty() {
+        self.finish_reveal();
+      }
+    }
+  }
+
+  /// Shows every cell left at once
+  fn finish_reveal(&mut self) {
+    if let Some(reveal) = self.tab_mut().revealing.take() {
+      self
+        .tab_mut()
+        .alerts
+        .push_by(0, Severity::Info, reveal.message);
+    }
+  }
+
+  /// Whether the terminal bell should ring, once for every cell revealed
+  pub fn take_bell(&mut self) -> bool {
+    mem::take(&mut self.bell)
+  }
+
+  /// The terminal gained or lost the focus
+  pub fn on_focus(&mut self, focused: bool) {
+    self.focused = focused;
+  }
+
+  /// Whether nobody is looking and nothing comes over the network or from a bot, so the
+  /// screen can be drawn less often
+  pub fn is_idle(&self) -> bool {
+    #[cfg(feature = "chat")]
+    if self.chat.is_some() {
+      return false;
+    }
+    !self.focused
+      && !self.tabs.iter().any(|tab| {
+        tab.peer.is_some()
+          || tab.spectating
+          || tab.waiting.is_some()
+          || tab.bot.as_ref().is_some_and(BotCmd::is_thinking)
+      })
+  }
+
+  pub fn on_key(&mut self, key: Key) {
+    // a key can only come from a terminal that has the focus
+    self.focused = true;
+    // any key skips the rest of a reveal
+    if self.tab().revealing.is_some() {
+      return self.finish_reveal();
+    }
+    if self.tab().noting.is_some() {
+      return self.on_note_key(key);
+    }
+    if self.sandbox.is_some() {
+      return self.on_sandbox_key(key);
+    }
+    if let Some(Action::Tab(index)) = self.keymap.action(key) {
+      return self.switch_tab(index);
+    }
+    if self.input_mode() == InputMode::Placing {
+      return self.on_placement_key(key);
+    }
+    if self.tab().devtools.is_some() {
+      return self.on_devtools_key(key);
+    }
+    if self.tab().replay.is_some() {
+      return self.on_replay_key(key);
+    }
+    if self.tab().bot_view.is_some() {
+      return self.on_bot_view_key(key);
+    }
+    if self.tab().spectating || self.tab().waiting.is_some() {
+      if self.keymap.action(key) == Some(Action::Quit) {
+        self.on_quit();
+      }
+      return;
+    }
+    if self.is_game_over() {
+      return self.on_game_over_key(key);
+    }
+    let action = match self.keymap.action(key) {
+      Some(action) if self.debouncer.allow(action, Instant::now()) => action,
+      _ => return,
+    };
+    // nothing but resuming or quitting while the game is paused
+    if self.is_paused() && !matches!(action, Action::Pause | Action::Cancel | Action::Quit) {
+      return;
+    }
+    let action = match action {
+      // like in vim, 0 jumps to the row start unless it continues a count
+      Action::Count(0) if self.tab().pending_count.is_none() => Action::RowStart,
+      Action::Count(digit) => return self.on_count(digit),
+      _ => action,
+    };
+    // a count only applies to the next movement, any other key discards it
+    let count = self.tab_mut().pending_count.take().unwrap_or(1);
+    match action {
+      Action::Quit => self.on_quit(),
+      Action::Up => (0..count).for_each(|_| self.on_up()),
+      Action::Down => (0..count).for_each(|_| self.on_down()),
+      Action::Left => (0..count).for_each(|_| self.on_left()),
+      Action::Right => (0..count).for_each(|_| self.on_right()),
+      Action::RowStart => self.tab_mut().active_column = 0,
+      Action::RowEnd => self.tab_mut().active_column = COLS - 1,
+      Action::Top => self.tab_mut().active_row = 0,
+      Action::Bottom => self.tab_mut().active_row = ROWS - 1,
+      Action::Center => {
+        let tab = self.tab_mut();
+        tab.active_row = ROWS / 2;
+        tab.active_column = COLS / 2;
+      }
+      Action::SwitchFocus => self.on_switch_focus(),
+      Action::Inspect => self.tab_mut().inspecting = !self.tab_mut().inspecting,
+      Action::Sandbox => self.on_open_sandbox(),
+      Action::Devtools => self.tab_mut().devtools = Some(0),
+      Action::BotView => self.on_open_bot_view(),
+      Action::Tab(_)
+      | Action::RunBack
+      | Action::MirrorAcross
+      | Action::MirrorDown
+      | Action::Fill
+      | Action::Flagship
+      | Action::NamePreset => {}
+      Action::LogUp => self.on_log_up(),
+      Action::LogDown => self.on_log_down(),
+      Action::Rotate => {
+        if let InputMode::Targeting(power_up) = self.input_mode() {
+          self.enter_mode(InputMode::Targeting(power_up.turned()));
+        }
+      }
+      Action::Save => self.on_save(),
+      Action::Pause => self.on_pause(),
+      Action::Cancel if self.is_paused() => self.on_pause(),
+      Action::Cancel => {
+        self.tab_mut().inspecting = false;
+        self.stop_targeting();
+      }
+      Action::Select => self.on_select(),
+      Action::Fire => match self.input_mode() {
+        InputMode::Targeting(power_up @ PowerUp::Sonar)
+        | InputMode::Targeting(power_up @ PowerUp::Ping) => self.on_scan(power_up),
+        InputMode::Targeting(power_up) => self.on_strike(power_up),
+        _ => self.on_fire(),
+      },
+      Action::Sonar => self.on_scan(PowerUp::Sonar),
+      Action::Ping => self.on_scan(PowerUp::Ping),
+      Action::Airstrike => self.on_target(PowerUp::Airstrike(Line::Row)),
+      Action::Strike => self.on_target(PowerUp::Strike(Line::Row)),
+      Action::Ability(slot) => {
+        if let Some(power_up) = ABILITY_BAR.get(slot) {
+          self.on_target(*power_up);
+        }
+      }
+      // counts were taken above, and the end screen has keys of its own
+      Action::Count(_) | Action::Rematch | Action::Note => {}
+    }
+  }
+
+  fn on_count(&mut self, digit: u8) {
+    let count = self.tab().pending_count.unwrap_or_default() * 10 + usize::from(digit);
+    // counts beyond the board size make no difference, so keep them small
+    self.tab_mut().pending_count = if count == 0 {
+      None
+    } else {
+      Some(count.min(ROWS.max(COLS)))
+    };
+  }
+
+  /// Counts a chat message as a vote for the next shot
+  #[cfg(feature = "chat")]
+  pub fn on_chat(&mut self, user: String, text: &str) {
+    if let Some(chat) = &mut self.chat {
+      chat.vote(&self.tab().game, user, text);
+    }
+  }
+
+  /// Fires the cells the chat voted for once the voting window closes
+  #[cfg(feature = "chat")]
+  fn run_chat_vote(&mut self) {
+    if !self.tab().game.is_user_turn()
+      || self.is_won()
+      || self.is_paused()
+      || self.input_mode() == InputMode::Placing
+    {
+      return;
+    }
+    let budget = self.tab().game.shot_budget(0);
+    if let Some(cells) = self.chat.as_mut().and_then(|chat| chat.tick(budget)) {
+      self.tab().selected_coordinates = cells.into_iter().collect();
+      // a salvo the chat left short is made up with the first cells left
+      let required = self.tab().game.min_shots(0);
+      for cell in self.tab().game.legal_shots(0) {
+        if self.tab().selected_coordinates.len() >= required {
+          break;
+        }
+        self.tab().selected_coordinates.insert(cell);
+      }
+      self.on_fire();
+    }
+  }
+
+  pub fn on_tick(&mut self) {
+    // a replay only moves on the keys, nobody fires and nothing is recorded, and a hosted
+    // match waits for the other player
+    if self.tab().replay.is_some() || self.tab().waiting.is_some() {
+      self.tab_mut().alerts.tick();
+      self.frame_count += 1;
+      return;
+    }
+    // nothing goes on until the user has seen what their shots did
+    if self.tab().revealing.is_some() {
+      if self.frame_count.is_multiple_of(REVEAL_TICKS) {
+        self.reveal_next();
+      }
+      self.tab_mut().alerts.tick();
+      self.frame_count += 1;
+      return;
+    }
+    #[cfg(feature = "chat")]
+    self.run_chat_vote();
+    if !self.is_won() && self.remaining() == Some(Duration::ZERO) {
+      // the clock stops as the loss is recorded below
+      let tab = self.tab_mut();
+      tab.game.forfeit(0);
+      tab.alerts.push(Severity::Error, "Time's up ⏰");
+    }
+    // the end screen takes over once the result is recorded
+    if self.is_won() && !self.tab().clock.is_stopped() {
+      self.tab_mut().clock.stop(Instant::now());
+      let won = self.tab().game.winner() == Some(0);
+      self.record_outcome(if won { Outcome::Won } else { Outcome::Lost });
+      self.write_notation();
+      self.send_fleet();
+    }
+    self.tab_mut().alerts.tick();
+    if let Some(status) = &self.status {
+      status.publish(&Summary::of(&self.tab().game, self.names()));
+    }
+    // computer delays firing by 2 seconds to make the game feel more natural, and a bot
+    // fires as soon as it answered after that
+    let thinking = self.tab().bot.as_ref().is_some_and(BotCmd::is_thinking);
+    if !self.tab().game.is_user_turn()
+      && self.tab().peer.is_none()
+      && !self.tab().game.is_kept_elsewhere(1)
+      && self.tab().placing.is_none()
+      && !self.is_won()
+      && !self.is_paused()
+      && (self.frame_count.is_multiple_of(8) || thinking)
+      && self
+        .tab()
+        .bot
+        .as_ref()
+        .is_none_or(|bot| bot.prepare(&self.tab().game))
+    {
+      let tab = self.tab_mut();
+      if let Some(bot) = &tab.bot {
+        tab.game.set_strategy(Box::new(bot.clone()));
+      }
+      let msg = tab.game.bot_fire();
+      let msg = match tab.reaction.take() {
+        Some(reaction) => format!("{} {}", reaction, msg),
+        None => msg,
+      };
+      tab.alerts.push_by(1, Severity::Info, msg);
+      if let Some(failure) = tab.bot.as_ref().and_then(BotCmd::take_failure) {
+        tab.alerts.push(
+          Severity::Warn,
+          format!("The bot {}, the computer fires for it from now on", failure),
+        );
+      }
+      self.record_turns();
+      self.collect_reactions();
+    }
+    self.frame_count += 1;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::mpsc;
+
+  use super::*;
+  use crate::{alert::Actor, event::Event, game::Ability, harness::Harness, net};
+
+  #[test]
+  fn test_debouncer_allow() {
+    let mut debouncer = Debouncer::new(Duration::from_millis(100));
+    let start = Instant::now();
+
+    assert!(debouncer.allow(Action::Select, start));
+    // a bounce of the same key is dropped
+    assert!(!debouncer.allow(Action::Select, start + Duration::from_millis(50)));
+    assert!(debouncer.allow(Action::Select, start + Duration::from_millis(150)));
+    // other actions are never held back by the previous one
+    assert!(debouncer.allow(Action::Fire, start + Duration::from_millis(160)));
+    assert!(!debouncer.allow(Action::Fire, start + Duration::from_millis(170)));
+    assert!(debouncer.allow(Action::Quit, start + Duration::from_millis(170)));
+    assert!(debouncer.allow(Action::Quit, start + Duration::from_millis(170)));
+  }
+
+  #[test]
+  fn test_debouncer_coalesces_movement() {
+    let mut debouncer = Debouncer::new(Duration::from_millis(100));
+    let start = Instant::now();
+
+    assert!(debouncer.allow(Action::Right, start));
+    assert!(!debouncer.allow(Action::Right, start + Duration::from_millis(10)));
+    assert!(debouncer.allow(Action::Right, start + Duration::from_millis(30)));
+    assert!(debouncer.allow(Action::Down, start + Duration::from_millis(31)));
+  }
+
+  #[test]
+  fn test_app_count_prefix() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+
+    app.on_key(Key::Char('5'));
+    assert_eq!(app.tab().pending_count, Some(5));
+    app.on_key(Key::Char('l'));
+    assert_eq!(app.active(), (0, 5));
+    assert_eq!(app.tab().pending_count, None);
+
+    app.on_key(Key::Char('3'));
+    app.on_key(Key::Char('j'));
+    assert_eq!(app.active(), (3, 5));
+
+    // counts are capped by the board and stop at the edge
+    app.on_key(Key::Char('4'));
+    app.on_key(Key::Char('2'));
+    assert_eq!(app.tab().pending_count, Some(ROWS.max(COLS)));
+    app.on_key(Key::Char('j'));
+    assert_eq!(app.active(), (ROWS - 1, 5));
+
+    // other keys discard the count
+    app.on_key(Key::Char('2'));
+    app.on_key(Key::Char(' '));
+    assert_eq!(app.tab().pending_count, None);
+    app.on_key(Key::Char('h'));
+    assert_eq!(app.active(), (ROWS - 1, 4));
+  }
+
+  #[test]
+  fn test_app_jump_keys() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+
+    app.on_key(Key::Char('c'));
+    assert_eq!(app.active(), (ROWS / 2, COLS / 2));
+    app.on_key(Key::Char('$'));
+    assert_eq!(app.active(), (ROWS / 2, COLS - 1));
+    app.on_key(Key::Char('G'));
+    assert_eq!(app.active(), (ROWS - 1, COLS - 1));
+    app.on_key(Key::Char('0'));
+    assert_eq!(app.active(), (ROWS - 1, 0));
+    app.on_key(Key::Char('g'));
+    assert_eq!(app.active(), (0, 0));
+
+    // 0 continues a pending count instead of jumping
+    app.on_key(Key::Char('$'));
+    app.on_key(Key::Char('1'));
+    app.on_key(Key::Char('0'));
+    assert_eq!(app.tab().pending_count, Some(10));
+    app.on_key(Key::Char('h'));
+    assert_eq!(app.active(), (0, 0));
+  }
+
+  #[test]
+  fn test_app_switch_focus() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    assert_eq!(app.board_state(false).cursor, Some((0, 0)));
+    assert_eq!(app.board_state(true).cursor, None);
+
+    app.on_key(Key::Char('\t'));
+    assert_eq!(app.tab().focus, Focus::Player);
+    assert_eq!(app.board_state(false).cursor, None);
+    assert_eq!(app.board_state(true).cursor, Some((0, 0)));
+
+    // own cells can't be targeted
+    app.on_key(Key::Char(' '));
+    assert!(app.tab().selected_coordinates.is_empty());
+    assert!(app.tab().alerts.to_string().contains("<tab>"));
+
+    app.on_key(Key::Char('\t'));
+    assert_eq!(app.tab().focus, Focus::Opponent);
+    app.on_key(Key::Char(' '));
+    assert!(app.is_selected((0, 0)));
+  }
+
+  #[test]
+  fn test_app_inspect() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.on_key(Key::Char('i'));
+    assert!(app.tab().inspecting);
+    assert_eq!(
+      app.inspect(),
+      vec!["A1:   unexplored", "Not fired upon yet"]
+    );
+
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    assert!(app.inspect()[0].starts_with("A1: "));
+    assert_eq!(app.inspect().last().unwrap(), "Fired upon in turn 1");
+
+    // own cells tell which ship sits on them
+    app.on_key(Key::Char('\t'));
+    let ship_cell = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .find(|c| app.board(true).status_at(*c) == Status::Live)
+      .unwrap();
+    app.tab_mut().active_row = ship_cell.0;
+    app.tab_mut().active_column = ship_cell.1;
+    assert!(app.inspect()[1].starts_with("Your "));
+
+    app.on_key(Key::Esc);
+    assert!(!app.tab().inspecting);
+  }
+
+  #[test]
+  fn test_app_cursor_status() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    assert_eq!(app.cursor_status().unwrap(), "Cursor: A1 — unexplored");
+    app.on_key(Key::Char('l'));
+    app.on_key(Key::Char('j'));
+    assert_eq!(app.cursor_status().unwrap(), "Cursor: B2 — unexplored");
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    let status = app.game().player().opponent_board().status_at((1, 1));
+    assert_eq!(
+      app.cursor_status().unwrap(),
+      format!("Cursor: B2 — {}", status.describe())
+    );
+
+    app.start_placement();
+    assert_eq!(app.cursor_status(), None);
+  }
+
+  #[test]
+  fn test_app_pause() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    assert!(!app.tab().game.is_user_turn());
+
+    app.on_key(Key::Char('p'));
+    assert!(app.is_paused());
+    // the bot holds fire and keys are ignored while paused
+    (0..16).for_each(|_| app.on_tick());
+    assert!(!app.tab().game.is_user_turn());
+    app.on_key(Key::Char('l'));
+    assert_eq!(app.active(), (0, 0));
+
+    app.on_key(Key::Char('p'));
+    assert!(!app.is_paused());
+    (0..8).for_each(|_| app.on_tick());
+    assert!(app.tab().game.is_user_turn());
+
+    // the help pauses the game as well, listing what the rule allows
+    app.on_key(Key::Char('?'));
+    assert!(app.is_paused());
+    let help = app.help();
+    assert_eq!(help[2], "Default: one shot a turn");
+    assert_eq!(help[3], "Shots this turn: 1");
+    assert!(help
+      .iter()
+      .any(|line| line.starts_with("fire       <enter>")));
+    app.on_key(Key::Esc);
+    assert!(!app.is_paused());
+  }
+
+  #[test]
+  fn test_app_strict_selection() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.tab_mut().game.bot_fire();
+
+    // lenient by default, firing again at a resolved cell is just a wasted shot
+    app.on_key(Key::Char(' '));
+    assert!(app.is_selected((0, 0)));
+    app.on_key(Key::Char(' '));
+
+    app.strict = true;
+    app.on_key(Key::Char(' '));
+    assert!(!app.is_selected((0, 0)));
+    let latest = app.tab().alerts.iter().last().unwrap();
+    assert!(latest.text.starts_with("A1 is already a "));
+    assert_eq!(latest.severity, Severity::Warn);
+
+    app.on_key(Key::Char('l'));
+    app.on_key(Key::Char(' '));
+    assert!(app.is_selected((0, 1)));
+  }
+
+  #[test]
+  fn test_app_salvo() {
+    let mut app = App::new("test".into(), Rule::Salvo, Difficulty::Easy);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    assert!(app.tab().game.timeline().is_empty());
+    let latest = app.tab().alerts.iter().last().unwrap();
+    assert_eq!(latest.text, "all 4 shots must be fired this turn");
+
+    for _ in 0..3 {
+      app.on_key(Key::Char('l'));
+      app.on_key(Key::Char(' '));
+    }
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.tab().game.timeline()[0].shots.len(), 4);
+  }
+
+  #[test]
+  fn test_app_chain() {
+    let mut app = App::new("test".into(), Rule::Chain, Difficulty::Easy);
+    let ship = &app.tab().game.computer().player_board().placements()[0];
+    let hit = *ship.cells().iter().next().unwrap();
+    app.tab_mut().selected_coordinates.insert(hit);
+    app.on_key(Key::Char('\n'));
+    assert!(app.tab().game.is_user_turn());
+    let latest = app.tab().alerts.iter().last().unwrap();
+    assert!(latest.text.ends_with("Fire again!"));
+  }
+
+  #[test]
+  fn test_app_drift() {
+    let mut app = App::new("test".into(), Rule::Drift, Difficulty::Easy);
+    assert_eq!(app.drift_chance(), Some(0));
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    (0..8).for_each(|_| app.on_tick());
+    assert!(app.tab().game.is_user_turn());
+    // the next cell is in the region of the last shot
+    app.on_key(Key::Char('l'));
+    assert_eq!(app.drift_chance(), Some(20));
+
+    let app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    assert_eq!(app.drift_chance(), None);
+  }
+
+  #[test]
+  fn test_app_sonar() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    assert_eq!(app.tab().game.power_ups_left(0, PowerUp::Sonar), 1);
+    app.on_key(Key::Char('c'));
+    app.on_key(Key::Char('s'));
+    let latest = app.tab().alerts.iter().last().unwrap();
+    assert!(latest.text.starts_with("Your sonar"));
+    assert!(latest.text.ends_with("around F6."));
+    assert_eq!(app.tab().game.power_ups_left(0, PowerUp::Sonar), 0);
+    assert!(!app.tab().game.is_user_turn());
+    assert!(app.history()[0].starts_with("1. You: sonar at F6 ("));
+
+    (0..8).for_each(|_| app.on_tick());
+    app.on_key(Key::Char('s'));
+    let latest = app.tab().alerts.iter().last().unwrap();
+    assert_eq!(latest.text, "No sonar left this match");
+    assert!(app.tab().game.is_user_turn());
+  }
+
+  #[test]
+  fn test_app_fleet_status() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    let fleet = app.tab().game.computer().player_board().placements();
+    for cell in fleet[0].cells() {
+      app
+        .tab_mut()
+        .game
+        .fire(&[cell].iter().cloned().collect(), false);
+      app.tab_mut().game.bot_fire();
+    }
+    let lines = app.fleet_status();
+    assert_eq!(lines.len(), 2 + 2 * fleet.len());
+    assert!(lines[0].starts_with("You ") && lines[0].ends_with("/4 afloat"));
+    assert_eq!(lines[5], "Computer 3/4 afloat");
+    assert_eq!(
+      lines[6],
+      format!("  {:<16} sunk", fleet[0].ship_type.name())
+    );
+    assert!(lines[7].ends_with(" afloat"));
+
+    app.skin = Skin::Pirate;
+    assert_eq!(
+      app.fleet_status()[6],
+      format!("  {:<16} sunk", Skin::Pirate.ship_name(&fleet[0].ship_type))
+    );
+    assert_ne!(app.fleet_status()[6], lines[6]);
+  }
+
+  #[test]
+  fn test_app_night() {
+    let mut app = App::new("test".into(), Rule::Night, Difficulty::Easy);
+    let fleet = app.tab().game.computer().player_board().placements();
+    let ship_cells = fleet
+      .iter()
+      .flat_map(|p| p.cells())
+      .collect::<BTreeSet<_>>();
+    let mut cells = fleet[0].cells().into_iter().collect::<Vec<_>>();
+    let last = cells.pop().unwrap();
+    for cell in cells {
+      app
+        .tab_mut()
+        .game
+        .fire(&[cell].iter().cloned().collect(), false);
+      app.tab_mut().game.bot_fire();
+    }
+    while !app.is_night() {
+      let miss = app
+        .tab()
+        .game
+        .legal_shots(0)
+        .into_iter()
+        .find(|c| !ship_cells.contains(c))
+        .unwrap();
+      app
+        .tab_mut()
+        .game
+        .fire(&[miss].iter().cloned().collect(), false);
+      app.tab_mut().game.bot_fire();
+    }
+    app
+      .tab_mut()
+      .game
+      .fire(&[last].iter().cloned().collect(), false);
+
+    // the ship sunk at night is still afloat as far as the user knows
+    assert_eq!(app.fleet_status()[5], "Computer 4/4 afloat");
+    assert!(app.scoreboard()[1].ends_with("afloat 4"));
+    let history = app.history();
+    assert!(history
+      .last()
+      .unwrap()
+      .ends_with(&format!("{} splash heard", a1_notation(last))));
+  }
+
+  #[test]
+  fn test_app_suspense() {
+    let mut app = App::new("test".into(), Rule::Fury, Difficulty::Easy);
+    app.suspense = true;
+    app.tab_mut().selected_coordinates =
+      app.tab().game.legal_shots(0).into_iter().take(4).collect();
+    app.on_fire();
+    assert!(app.tab().alerts.is_empty());
+    assert_eq!(app.board_state(false).hidden.len(), 4);
+    assert!(app.board_state(true).hidden.is_empty());
+
+    // a cell a tick with the bell, and the computer holds its fire meanwhile
+    app.on_tick();
+    assert!(app.take_bell());
+    assert!(!app.take_bell());
+    assert_eq!(app.board_state(false).hidden.len(), 3);
+    for _ in 0..3 {
+      app.on_tick();
+    }
+    assert_eq!(app.board_state(false).hidden.len(), 2);
+    assert_eq!(app.tab().game.timeline().len(), 1);
+
+    // any key shows the rest and announces the results
+    app.on_key(Key::Char('x'));
+    assert!(app.board_state(false).hidden.is_empty());
+    assert!(app
+      .tab()
+      .alerts
+      .iter()
+      .last()
+      .unwrap()
+      .text
+      .starts_with("You have"));
+    assert_eq!(app.active(), (0, 0));
+  }
+
+  #[test]
+  fn test_app_airstrike() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.on_key(Key::Char('a'));
+    let latest = app.tab().alerts.iter().last().unwrap();
+    assert_eq!(latest.text, "Sink 2 more ships to earn an airstrike");
+    assert_eq!(app.input_mode(), InputMode::Normal);
+
+    let fleet = app.tab().game.computer().player_board().placements();
+    for cell in fleet[..2].iter().flat_map(|p| p.cells()) {
+      app
+        .tab_mut()
+        .game
+        .fire(&[cell].iter().cloned().collect(), false);
+      app.tab_mut().game.bot_fire();
+    }
+    assert_eq!(
+      app
+        .tab()
+        .game
+        .power_ups_left(0, PowerUp::Airstrike(Line::Row)),
+      1
+    );
+    app.on_key(Key::Char('a'));
+    assert_eq!(
+      app.input_mode(),
+      InputMode::Targeting(PowerUp::Airstrike(Line::Row))
+    );
+    app.on_key(Key::Char('r'));
+    assert_eq!(
+      app.input_mode(),
+      InputMode::Targeting(PowerUp::Airstrike(Line::Column))
+    );
+    let targets = app.board_state(false).selected;
+    assert!(targets.iter().all(|(_, col)| *col == 0));
+
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.input_mode(), InputMode::Normal);
+    let turn = app.tab().game.timeline().last().unwrap();
+    assert_eq!(turn.shots, targets);
+    assert_eq!(
+      turn.power_up,
+      Some((PowerUp::Airstrike(Line::Column), (0, 0)))
+    );
+    assert!(app.history().last().unwrap().contains(": airstrike A"));
+  }
+
+  #[test]
+  fn test_app_ping() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    assert_eq!(app.tab().game.cooldown(0, Ability::Ping), Some(0));
+    app.on_key(Key::Char('n'));
+    let turn = app.tab().game.timeline().last().unwrap();
+    assert_eq!(turn.power_up, Some((PowerUp::Ping, (0, 0))));
+    assert!(app
+      .history()
+      .last()
+      .unwrap()
+      .starts_with("1. You: ping at A1 ("));
+    app.tab_mut().game.bot_fire();
+    assert_eq!(app.tab().game.cooldown(0, Ability::Ping), Some(3));
+  }
+
+  #[test]
+  fn test_app_ability_bar() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    let labels = |app: &App| {
+      app
+        .ability_slots()
+        .iter()
+        .map(|s| s.label.clone())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      labels(&app),
+      vec!["sonar 1", "ping ready", "airstrike 0", "strike ready"]
+    );
+
+    app.on_key(Key::Alt('3'));
+    assert_eq!(app.input_mode(), InputMode::Normal);
+    assert_eq!(app.mode_indicator(), None);
+    app.on_key(Key::Alt('1'));
+    assert_eq!(app.input_mode(), InputMode::Targeting(PowerUp::Sonar));
+    assert_eq!(app.mode_indicator(), Some("AIMING SONAR".into()));
+    assert!(app.ability_slots()[0].aimed);
+    // another power-up takes the place of the one aimed
+    app.on_key(Key::Alt('2'));
+    assert_eq!(app.input_mode(), InputMode::Targeting(PowerUp::Ping));
+    app.on_key(Key::Esc);
+    assert_eq!(app.input_mode(), InputMode::Normal);
+
+    app.on_key(Key::Alt('1'));
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.input_mode(), InputMode::Normal);
+    assert_eq!(
+      app.tab().game.timeline().last().unwrap().power_up,
+      Some((PowerUp::Sonar, (0, 0)))
+    );
+    assert!(!app.ability_slots()[0].ready);
+
+    // placing the fleet takes every key until the last ship is down
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.start_placement();
+    assert_eq!(app.input_mode(), InputMode::Placing);
+    assert_eq!(app.mode_indicator(), Some("PLACING".into()));
+    app.on_key(Key::Alt('1'));
+    assert_eq!(app.input_mode(), InputMode::Placing);
+    // until the rest of the fleet is placed symmetrically
+    app.on_key(Key::Char('S'));
+    assert_eq!(app.input_mode(), InputMode::Normal);
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().text,
+      "Fleet in position, fire away"
+    );
+
+    // the flagship is picked while placing under the flagship rule only
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.start_placement();
+    app.on_key(Key::Char('F'));
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().text,
+      "Only the flagship rule has flagships"
+    );
+    let mut app = App::new("test".into(), Rule::Flagship, Difficulty::Easy);
+    app.start_placement();
+    app.on_key(Key::Char('F'));
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().text,
+      "The Fighter is your flagship"
+    );
+    app.on_key(Key::Char('F'));
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().text,
+      "The Fighter is no longer your flagship"
+    );
+    app.on_key(Key::Char('F'));
+    app.on_key(Key::Char('S'));
+    assert_eq!(app.tab().game.flagship(0), Some(0));
+    let status = app.fleet_status();
+    assert!(status[1].ends_with("afloat, flagship"));
+    // the computer's stays hidden
+    assert!(!status[6..].iter().any(|l| l.contains("flagship")));
+  }
+
+  #[test]
+  fn test_app_strike() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    let fleet = crate::notation::parse_fleet("X@A1/90 I@F6/90").unwrap();
+    app.tab_mut().game =
+      Game::restore(Rule::Default, Difficulty::Easy, [fleet.clone(), fleet], &[]);
+    app.on_key(Key::Char('f'));
+    let latest = app.tab().alerts.iter().last().unwrap();
+    assert_eq!(latest.text, "No ship of the fleet can strike");
+    assert_eq!(app.tab().game.cooldown(0, Ability::Strike), None);
+
+    let fleet = crate::notation::parse_fleet("Carrier=***+strike@A10/90 I@F6/90").unwrap();
+    app.tab_mut().game =
+      Game::restore(Rule::Default, Difficulty::Easy, [fleet.clone(), fleet], &[]);
+    assert_eq!(app.tab().game.cooldown(0, Ability::Strike), Some(0));
+    app.on_key(Key::Char('f'));
+    assert_eq!(
+      app.input_mode(),
+      InputMode::Targeting(PowerUp::Strike(Line::Row))
+    );
+    app.on_key(Key::Char('r'));
+    assert_eq!(
+      app.input_mode(),
+      InputMode::Targeting(PowerUp::Strike(Line::Column))
+    );
+    let targets = app.board_state(false).selected;
+    assert_eq!(targets, [(0, 0), (1, 0)].iter().copied().collect());
+
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.input_mode(), InputMode::Normal);
+    let turn = app.tab().game.timeline().last().unwrap();
+    assert_eq!(turn.shots, targets);
+    assert_eq!(turn.power_up, Some((PowerUp::Strike(Line::Column), (0, 0))));
+    assert!(app.history().last().unwrap().contains(": strike A"));
+    app.tab_mut().game.bot_fire();
+    assert_eq!(app.tab().game.cooldown(0, Ability::Strike), Some(4));
+  }
+
+  #[test]
+  fn test_app_mines() {
+    let mut app = App::new("test".into(), Rule::Mines, Difficulty::Easy);
+    let board = app.tab().game.computer().player_board();
+    let mine = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .find(|c| board.is_mined(*c))
+      .unwrap();
+    app.tab_mut().selected_coordinates.insert(mine);
+    app.on_key(Key::Char('\n'));
+    let latest = app.tab().alerts.iter().last().unwrap();
+    assert!(latest.text.contains("You hit a mine"));
+    assert_eq!(app.board_state(true).blasts.len(), 1);
+    assert!(app.board_state(false).blasts.is_empty());
+  }
+
+  #[test]
+  fn test_app_bot_reaction() {
+    let mut app = App::new("test".into(), Rule::Fury, Difficulty::Easy);
+    let board = app.tab().game.computer().player_board();
+    let scout = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .filter(|c| {
+        let (_, ship) = board.find_position_and_ship(*c);
+        ship.is_some_and(|s| s.ship_type().name() == "Scout")
+      })
+      .collect::<BTreeSet<_>>();
+
+    app.tab_mut().selected_coordinates = scout;
+    app.on_key(Key::Char('\n'));
+    assert!(app.tab().reaction.is_some());
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().actor,
+      Actor::Player(0)
+    );
+
+    (0..8).for_each(|_| app.on_tick());
+    assert!(app.tab().alerts.to_string().contains("Scout"));
+    assert!(app.tab().alerts.to_string().contains("Computer have"));
+    assert!(app.tab().reaction.is_none());
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().actor,
+      Actor::Player(1)
+    );
+    let history = app.history_by();
+    assert_eq!(history[0].0, 0);
+    assert!(history[1].1.starts_with("2. Computer:"));
+    assert_eq!(history[1].0, 1);
+  }
+
+  #[test]
+  fn test_app_selection_full() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    assert!(!app.is_selection_full());
+    app.on_key(Key::Char(' '));
+    assert!(app.is_selection_full());
+    assert!(app.board_state(false).full);
+    assert!(!app.board_state(true).full);
+    app.on_key(Key::Char(' '));
+    assert!(!app.is_selection_full());
+  }
+
+  #[test]
+  fn test_app_impacts() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    let ship_cell = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .find(|c| app.game().computer().player_board().status_at(*c) == Status::Live)
+      .unwrap();
+    app.tab_mut().active_row = ship_cell.0;
+    app.tab_mut().active_column = ship_cell.1;
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    let impact = app.board_state(false).impacts[&ship_cell];
+    assert_eq!(impact.1, 0);
+    assert!(app.board_state(true).impacts.is_empty());
+    app.on_tick();
+    assert_eq!(app.board_state(false).impacts[&ship_cell].1, 1);
+    (1..IMPACT_FRAMES).for_each(|_| app.on_tick());
+    assert!(app.board_state(false).impacts.is_empty());
+  }
+
+  #[test]
+  fn test_app_log() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    for col in 0..8 {
+      app.tab_mut().active_column = col;
+      app.on_key(Key::Char(' '));
+      app.on_key(Key::Char('\n'));
+      (0..8).for_each(|_| app.on_tick());
+    }
+    let log = app.log();
+    assert_eq!(log.len(), 16);
+    assert!(log[0].1.starts_with("00:00 1. You: "));
+    assert!(log[1].1.starts_with("00:00 2. Computer: "));
+
+    assert_eq!(app.tab().log_scroll, None);
+    app.on_key(Key::PageUp);
+    assert_eq!(app.tab().log_scroll, Some(5));
+    (0..3).for_each(|_| app.on_key(Key::PageUp));
+    assert_eq!(app.tab().log_scroll, Some(15));
+    app.on_key(Key::PageDown);
+    assert_eq!(app.tab().log_scroll, Some(10));
+    app.on_key(Key::PageDown);
+    app.on_key(Key::PageDown);
+    assert_eq!(app.tab().log_scroll, None);
+
+    // turns played before the clock started have no time
+    let game = app.game().clone();
+    app.resume(game, Duration::from_secs(30));
+    assert!(app.log()[0].1.starts_with("1. You: "));
+  }
+
+  #[test]
+  fn test_app_first() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.first = First::Bot;
+    app.balanced = true;
+    app.deal_first();
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().text,
+      "Computer fires first"
+    );
+    app.on_key(Key::Char('\n'));
+    assert!(app.tab().game.timeline().is_empty());
+
+    (0..8).for_each(|_| app.on_tick());
+    assert_eq!(app.tab().game.timeline()[0].player, 1);
+    // the shot more for firing second
+    assert_eq!(app.shots_left(), (2, 2));
+
+    // a rematch tosses again
+    app.first = First::Me;
+    app.rematch();
+    assert!(app.tab().game.is_user_turn());
+    assert_eq!(app.shots_left(), (1, 1));
+  }
+
+  #[test]
+  fn test_app_seed() {
+    let play = || {
+      let mut app = App::new("test".into(), Rule::Default, Difficulty::Expert);
+      app.set_seed(7);
+      for _ in 0..3 {
+        app.on_key(Key::Char(' '));
+        app.on_key(Key::Char('l'));
+        app.on_key(Key::Char('\n'));
+        (0..8).for_each(|_| app.on_tick());
+      }
+      app
+    };
+    let (app, again) = (play(), play());
+    assert_eq!(app.tab().game.timeline().len(), 6);
+    assert_eq!(app.tab().game.timeline(), again.tab().game.timeline());
+    assert_eq!(app.fleet(), again.fleet());
+
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Expert);
+    app.set_time_attack(None);
+    app.set_seed(7);
+    assert!(app.is_time_attack());
+    assert_eq!(app.fleet(), again.fleet());
+  }
+
+  #[test]
+  fn test_app_time_attack() {
+    let path = std::env::temp_dir().join(format!("battleship-{}.stats", uuid::Uuid::new_v4()));
+    let mut harness = Harness::new(Rule::Default, Difficulty::Easy);
+    let app = &mut harness.app;
+    app.set_stats(StatsFile::new(path.clone()));
+    app.set_time_attack(None);
+    let board = app.tab().game.computer().player_board();
+    let fleet = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .filter(|c| board.find_position_and_ship(*c).1.is_some())
+      .collect::<Vec<_>>();
+
+    for c in fleet {
+      app.tab_mut().selected_coordinates.insert(c);
+      app.on_key(Key::Char('\n'));
+      // the computer never fires back
+      (0..8).for_each(|_| app.on_tick());
+      assert!(app.tab().game.is_user_turn());
+    }
+    assert_eq!(app.remaining(), None);
+    let results = app.results().unwrap();
+    assert!(results[0].starts_with("Fleet sunk in"));
+    assert_eq!(results[1], "Ships sunk 4/4");
+    assert_eq!(results[3], "Accuracy 100%");
+    // the attack tops a leaderboard of its own, away from the fastest wins
+    match app.leaderboard() {
+      Some((Leaderboard::Attacks(attacks), Some(0))) => {
+        assert_eq!(attacks[0].shots, app.tab().game.shots_and_hits(0).0)
+      }
+      board => panic!("not on the time attack leaderboard: {:?}", board),
+    }
+    assert!(harness.draw().frame_contains("Fastest time attacks"));
+    let stats = StatsFile::new(path.clone()).load().unwrap();
+    assert_eq!((stats.won, stats.leaderboard.len()), (1, 0));
+    std::fs::remove_file(&path).unwrap();
+
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_stats(StatsFile::new(path.clone()));
+    app.set_time_attack(Some(Duration::ZERO));
+    assert_eq!(app.results(), None);
+    app.on_tick();
+    assert_eq!(app.tab().game.winner(), Some(1));
+    assert_eq!(app.results().unwrap()[0], "Out of time");
+    assert!(app.tab().clock.is_stopped());
+    // running out of time is a loss
+    assert_eq!(StatsFile::new(path.clone()).load().unwrap().lost, 1);
+    std::fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn test_app_gauntlet() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Hard);
+    app.set_gauntlet();
+    assert_eq!(app.tab().game.difficulty(), &Difficulty::Easy);
+    assert_eq!(app.misses_left(), Some(50));
+
+    let fleet = app.tab().game.computer().player_board().placements();
+    app.tab_mut().selected_coordinates = fleet.iter().flat_map(|p| p.cells()).collect();
+    app.on_key(Key::Char('\n'));
+    assert!(!app.is_won());
+    assert!(app.tab().alerts.to_string().contains("Fleet 1 sunk"));
+    assert_eq!(app.tab().gauntlet, Some(Gauntlet { cleared: 1 }));
+    assert_eq!(app.tab().game.difficulty(), &Difficulty::Hard);
+    assert_ne!(app.tab().game.computer().player_board().placements(), fleet);
+
+    // miss until the allowance runs out
+    let fleet = app.tab().game.computer().player_board().placements();
+    let ship_cells = fleet
+      .iter()
+      .flat_map(|p| p.cells())
+      .collect::<BTreeSet<_>>();
+    let misses = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .filter(|c| !ship_cells.contains(c))
+      .collect::<Vec<_>>();
+    for c in misses.into_iter().take(45) {
+      if app.is_won() {
+        break;
+      }
+      app.tab_mut().selected_coordinates.insert(c);
+      app.on_key(Key::Char('\n'));
+      while !app.tab().game.is_user_turn() && !app.is_won() {
+        app.on_tick();
+      }
+    }
+    assert_eq!(app.tab().game.winner(), Some(1));
+    assert!(
+      app.tab().alerts.to_string().contains("Out of misses")
+        || app.tab().alerts.to_string().contains("lost")
+    );
+    app.on_tick();
+    assert_eq!(app.game_over().unwrap()[1], "Fleets sunk 1");
+  }
+
+  #[test]
+  fn test_app_stats() {
+    let path = std::env::temp_dir().join(format!("battleship-{}.stats", uuid::Uuid::new_v4()));
+    let stats = || StatsFile::new(path.clone()).load().unwrap();
+
+    // quitting before the first shot is not abandoning a match
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_stats(StatsFile::new(path.clone()));
+    app.on_key(Key::Char('q'));
+    assert_eq!(stats().abandoned, 0);
+
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_stats(StatsFile::new(path.clone()));
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.on_key(Key::Char('q'));
+    assert!(app.should_quit);
+    assert_eq!(stats().abandoned, 1);
+
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_stats(StatsFile::new(path.clone()));
+    app.count_abandoned = false;
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.on_key(Key::Char('q'));
+    assert_eq!(stats().abandoned, 1);
+
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_stats(StatsFile::new(path.clone()));
+    app.tab_mut().game.forfeit(1);
+    app.on_tick();
+    assert!(app
+      .game_over()
+      .unwrap()
+      .contains(&"All time: wins 1 | losses 1".into()));
+    assert_eq!(stats().rules["Default"].won, 1);
+    assert!(stats().fastest.is_some());
+    app.on_key(Key::Char('q'));
+    assert_eq!(stats().abandoned, 1);
+
+    std::fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn test_app_sandbox() {
+    let dir = std::env::temp_dir().join(format!("battleship-{}", uuid::Uuid::new_v4()));
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Hard);
+    app.set_presets(PresetFile::new(dir.join("presets.toml")));
+
+    app.on_key(Key::Char('o'));
+    assert!(app.is_paused());
+    // the rule, then the mode
+    app.on_key(Key::Char('l'));
+    app.on_key(Key::Char('j'));
+    app.on_key(Key::Char('j'));
+    app.on_key(Key::Char('l'));
+
+    // q is part of the name rather than quitting
+    app.on_key(Key::Char('w'));
+    "quick!".chars().for_each(|c| app.on_key(Key::Char(c)));
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.tab().alerts.to_string(), "Saved preset quick");
+    assert!(!app.should_quit);
+    let presets = PresetFile::new(dir.join("presets.toml")).load().unwrap();
+    assert_eq!(presets["quick"].rule, Rule::Fury);
+    assert_eq!(presets["quick"].mode, Mode::TimeAttack);
+
+    app.on_key(Key::Char('\n'));
+    assert!(app.sandbox.is_none());
+    assert!(!app.is_paused());
+    assert!(app.is_time_attack());
+    assert_eq!(app.preset(), presets["quick"]);
+
+    // closing the sandbox resumes the game as it was
+    app.on_key(Key::Char('o'));
+    app.on_key(Key::Char('l'));
+    app.on_key(Key::Esc);
+    assert!(app.sandbox.is_none());
+    assert!(!app.is_paused());
+    assert_eq!(app.preset().rule, Rule::Fury);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn test_app_placement() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.start_placement();
+    assert_eq!(app.tab().focus, Focus::Player);
+
+    // the second ship can't go on top of the first
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char(' '));
+    assert!(app.tab().alerts.to_string().contains("overlaps"));
+    "jjjr ".chars().for_each(|c| app.on_key(Key::Char(c)));
+    "jjjjjj ".chars().for_each(|c| app.on_key(Key::Char(c)));
+    // firing keys drop ships rather than fire while placing
+    "llllll\n".chars().for_each(|c| app.on_key(Key::Char(c)));
+
+    assert!(app.tab().placing.is_none());
+    assert_eq!(app.tab().focus, Focus::Opponent);
+    let fleet = app.tab().game.player().player_board().placements();
+    let origins = fleet.iter().map(|p| p.origin).collect::<Vec<_>>();
+    assert_eq!(origins, vec![(0, 0), (3, 0), (6, 0), (0, 6)]);
+    assert_eq!(fleet[1].rotation, 180);
+    assert!(app.tab().game.timeline().is_empty());
+  }
+
+  #[test]
+  fn test_app_placement_mines() {
+    let mut app = App::new("test".into(), Rule::Mines, Difficulty::Easy);
+    let computer = app.tab().game.mines(1).clone();
+    app.start_placement();
+    app.on_key(Key::Char('S'));
+    assert_eq!(app.input_mode(), InputMode::Placing);
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().text,
+      "Fleet placed, now hide your 2 mines"
+    );
+
+    let board = app.tab().placing.as_ref().unwrap().board();
+    let cells = (0..ROWS).flat_map(|r| (0..COLS).map(move |c| (r, c)));
+    let ship = cells.clone().find(|c| board.status_at(*c) == Status::Live);
+    let empty = cells
+      .filter(|c| board.status_at(*c) == Status::Space)
+      .take(MINES)
+      .collect::<Vec<_>>();
+    let mut at = (0, 0);
+    let mut move_to = |app: &mut App, (r, c): Coordinate| {
+      let rows = if r > at.0 { "j" } else { "k" }.repeat(r.abs_diff(at.0));
+      let cols = if c > at.1 { "l" } else { "h" }.repeat(c.abs_diff(at.1));
+      (rows + &cols)
+        .chars()
+        .for_each(|k| app.on_key(Key::Char(k)));
+      at = (r, c);
+    };
+    move_to(&mut app, ship.unwrap());
+    app.on_key(Key::Char(' '));
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().text,
+      "A mine goes in an empty cell"
+    );
+    move_to(&mut app, empty[0]);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char(' '));
+    assert_eq!(
+      app.tab().alerts.iter().last().unwrap().text,
+      "There is a mine there already"
+    );
+    move_to(&mut app, empty[1]);
+    app.on_key(Key::Char(' '));
+
+    assert!(app.tab().placing.is_none());
+    assert_eq!(app.tab().game.mines(0), &empty.into_iter().collect());
+    // the computer hid its own while setting up
+    assert_eq!(app.tab().game.mines(1), &computer);
+  }
+
+  #[test]
+  fn test_app_import() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    game.forfeit(1);
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.import(notation::parse(&notation::write(&game)).unwrap());
+
+    assert!(app.is_won());
+    assert_eq!(app.tab().devtools, Some(0));
+    app.on_tick();
+    assert_eq!(app.tab().alerts.to_string(), "Imported a match you won");
+  }
+
+  #[test]
+  fn test_app_idle() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    assert!(!app.is_idle());
+    app.on_focus(false);
+    assert!(app.is_idle());
+    // a key means the terminal has the focus after all
+    app.on_key(Key::Char('l'));
+    assert!(!app.is_idle());
+
+    // a match hosted in another tab keeps the app awake
+    app.on_focus(false);
+    app.wait_for_opponent(4000);
+    assert!(!app.is_idle());
+    app.switch_tab(1);
+    assert!(!app.is_idle());
+  }
+
+  #[test]
+  fn test_app_bot_cmd() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.tab_mut().game.set_first(1);
+    // takes its time over the first turn and answers no other
+    let bot = r#"read line; sleep 1; echo '{"shots":["A1"]}'; read line"#;
+    app.set_bot(bot.into()).unwrap();
+    let start = Instant::now();
+    app.on_tick();
+    // the screen goes on while the bot thinks
+    assert!(start.elapsed() < Duration::from_millis(500));
+    assert!(app.tab().game.timeline().is_empty());
+    app.on_focus(false);
+    assert!(!app.is_idle());
+    while app.tab().game.timeline().is_empty() && start.elapsed() < Duration::from_secs(5) {
+      std::thread::sleep(Duration::from_millis(50));
+      app.on_tick();
+    }
+    assert_eq!(
+      app.tab().game.timeline()[0].shots,
+      [(0, 0)].iter().copied().collect()
+    );
+    // the answer it gave was fired without asking it again
+    assert_eq!(app.tab().bot.as_ref().and_then(BotCmd::take_failure), None);
+  }
+
+  #[test]
+  fn test_app_replay() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    for _ in 0..2 {
+      let shots = game.legal_shots(0).into_iter().take(1).collect();
+      game.fire(&shots, false);
+      game.bot_fire();
+    }
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.open_replay(notation::parse(&notation::write(&game)).unwrap());
+    assert!(app.tab().game.timeline().is_empty());
+
+    app.on_key(Key::Right);
+    app.on_key(Key::Char('l'));
+    assert_eq!(app.tab().game.timeline(), &game.timeline()[..2]);
+    // nobody fires while replaying
+    (0..16).for_each(|_| app.on_tick());
+    assert_eq!(app.tab().game.timeline().len(), 2);
+    app.on_key(Key::Char('G'));
+    assert_eq!(app.tab().game.timeline(), game.timeline());
+    app.on_key(Key::Char('h'));
+    assert_eq!(app.tab().replay.as_ref().map(|r| r.step), Some(3));
+    app.on_key(Key::Char('0'));
+    assert!(app.tab().game.timeline().is_empty());
+    // the scrubber jumps by ten turns, as far as the match goes
+    app.on_key(Key::Char('j'));
+    assert_eq!(app.tab().game.timeline(), game.timeline());
+    app.on_key(Key::Up);
+    assert!(app.tab().game.timeline().is_empty());
+    assert_eq!(
+      app.tab().replay.as_ref().map(|r| r.thumbnails.len()),
+      Some(4)
+    );
+    app.on_key(Key::Char('q'));
+    assert!(app.should_quit);
+  }
+
+  #[test]
+  fn test_app_game_over() {
+    let mut app = App::new("test".into(), Rule::Fury, Difficulty::Easy);
+    let fleet = app.tab().game.computer().player_board().placements();
+    let unseen = *fleet[0].cells().iter().next().unwrap();
+    for placement in &fleet[1..] {
+      app
+        .tab_mut()
+        .game
+        .fire(&placement.cells().into_iter().collect(), false);
+      app.tab_mut().game.bot_fire();
+    }
+    app.tab_mut().game.forfeit(0);
+    assert!(app.game_over().is_none());
+    app.on_tick();
+
+    let lines = app.game_over().unwrap();
+    assert!(lines[0].starts_with("Computer won in "));
+    assert_eq!(lines[1], "Ships sunk 3/4");
+    assert_eq!(
+      lines.last().unwrap(),
+      "<r> rematch | <R> run it back | <n> note | <b> how you were hunted | <q> quit"
+    );
+    // the ship left afloat shows, on cells never fired upon
+    assert_eq!(app.board(false).status_at(unseen), Status::Live);
+    app.on_key(Key::Char('l'));
+    assert_eq!(app.active(), (0, 0));
+
+    // a note takes every key until it's kept
+    for key in "n\"bot missed a kill rx".chars() {
+      app.on_key(Key::Char(key));
+    }
+    app.on_key(Key::Backspace);
+    assert!(app
+      .game_over()
+      .unwrap()
+      .contains(&"Note: bot missed a kill r_".into()));
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.note(), Some("bot missed a kill r"));
+    assert!(app.game_over().is_some());
+
+    app.on_key(Key::Char('r'));
+    assert!(app.game_over().is_none());
+    assert!(app.tab().game.timeline().is_empty());
+    assert_eq!(app.rule(), &Rule::Fury);
+    assert_eq!(app.board(false).status_at(unseen), Status::Space);
+    assert_eq!(app.tab().alerts.to_string(), "Rematch! Your turn");
+  }
+
+  #[test]
+  fn test_app_bot_view() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.on_key(Key::Char('b'));
+    assert!(app.tab().bot_view.is_none());
+
+    let shots = app.tab().game.legal_shots(0).into_iter().take(1).collect();
+    app.tab_mut().game.fire(&shots, false);
+    app.tab_mut().game.bot_fire();
+    app.tab_mut().game.forfeit(0);
+    app.on_tick();
+    assert!(app.game_over().unwrap().last().unwrap().contains("<b>"));
+    app.on_key(Key::Char('b'));
+    assert!(app.tab().bot_view.is_some());
+    // the view takes the movement keys
+    app.on_key(Key::Char('h'));
+    assert_eq!(app.tab().active_column, 0);
+    app.on_key(Key::Esc);
+    assert!(app.tab().bot_view.is_none());
+    assert!(!app.should_quit);
+  }
+
+  #[test]
+  fn test_app_placement_advice() {
+    let path = std::env::temp_dir().join(format!("battleship-{}.stats", uuid::Uuid::new_v4()));
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_stats(StatsFile::new(path.clone()));
+    while !app.is_won() {
+      let shots = app.tab().game.legal_shots(0).into_iter().take(1).collect();
+      app.tab_mut().game.fire(&shots, false);
+      if !app.is_won() {
+        app.tab_mut().game.bot_fire();
+      }
+    }
+    app.on_tick();
+    let record = FleetRecord::of(&app.tab().game);
+    let stats = StatsFile::new(path.clone());
+    assert_eq!(stats.load().unwrap().fleets, vec![record.clone()]);
+    assert_eq!(app.placement_tips().len(), 1);
+
+    stats.record_fleet(record.clone()).unwrap();
+    stats.record_fleet(record).unwrap();
+    app.on_key(Key::Char('b'));
+    let lines = app.tab().bot_view.as_ref().unwrap().lines();
+    assert!(lines.iter().any(|l| l.starts_with("The computer finds")));
+
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_stats(StatsFile::new(path.clone()));
+    let before = app.fleet();
+    app.avoid_habits().unwrap();
+    assert_ne!(app.fleet(), before);
+    std::fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn test_app_save() {
+    let dir = std::env::temp_dir().join(format!("battleship-{}", uuid::Uuid::new_v4()));
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_save_file(SaveFile::new(dir.join("save.json")));
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.on_key(Key::Ctrl('s'));
+    assert!(app.tab().alerts.to_string().ends_with("Match saved"));
+
+    let mut resumed = App::new("test".into(), Rule::Fury, Difficulty::Hard);
+    let (game, elapsed) = SaveFile::new(dir.join("save.json"))
+      .load()
+      .unwrap()
+      .restore()
+      .unwrap();
+    resumed.resume(game, elapsed + Duration::from_secs(60));
+    assert_eq!(resumed.rule(), &Rule::Default);
+    assert_eq!(resumed.history(), app.history());
+    assert!(resumed.elapsed() >= Duration::from_secs(60));
+    fs::remove_dir_all(dir).unwrap();
+
+    app.set_gauntlet();
+    app.on_key(Key::Ctrl('s'));
+    assert!(app
+      .tab()
+      .alerts
+      .to_string()
+      .ends_with("against the computer can be saved"));
+  }
+
+  #[test]
+  fn test_app_versus() {
+    let mut host = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    let mut guest = App::new("test".into(), Rule::Fury, Difficulty::Easy);
+    let ([(mut host_peer, hosted), (mut guest_peer, joined)], addr) =
+      net::loopback(Rule::Default, [host.fleet(), guest.fleet()]);
+    let (host_tx, host_rx) = mpsc::channel();
+    let (guest_tx, guest_rx) = mpsc::channel();
+    host_peer.listen(host_tx, 0);
+    guest_peer.listen(guest_tx, 0);
+    host.set_peer(host_peer, hosted);
+    guest.set_peer(guest_peer, joined);
+    let receive = |rx: &mpsc::Receiver<Event<Key>>| match rx.recv() {
+      Ok(Event::Net(0, message)) => message,
+      _ => panic!("expected a message"),
+    };
+
+    assert_eq!(guest.rule(), &Rule::Default);
+    assert!(!guest.game().is_user_turn());
+    // the computer doesn't fire in place of the other player
+    (0..8).for_each(|_| guest.on_tick());
+    assert!(guest.game().timeline().is_empty());
+
+    // neither side sees where the other's ships are
+    assert_eq!(host.game().fleet(1).cells_remaining, 0);
+    assert_eq!(guest.game().fleet(1).alive, 4);
+
+    host.on_key(Key::Char(' '));
+    host.on_key(Key::Char('\n'));
+    // the salvo waits on its answer
+    assert!(host.game().timeline().is_empty());
+    host.on_key(Key::Char(' '));
+    host.on_key(Key::Char('\n'));
+    assert!(host
+      .tab()
+      .alerts
+      .to_string()
+      .ends_with("Waiting on your opponent's answer"));
+    guest.on_net(0, receive(&guest_rx));
+    assert!(guest.game().is_user_turn());
+    // the answer comes with the hash of what both sides found, which they agree on
+    let answer = receive(&host_rx);
+    let hash = guest.game().found_hash();
+    assert!(matches!(answer, Message::Answer(_, Some(theirs)) if theirs == hash));
+    host.on_net(0, answer);
+    assert_eq!(host.game().found_hash(), hash);
+    assert!(!host.game().is_user_turn());
+    // each side sees the match from its own seat
+    let (theirs, ours) = (&guest.game().timeline()[0], &host.game().timeline()[0]);
+    assert_eq!((theirs.player, ours.player), (1, 0));
+    assert_eq!(theirs.response, ours.response);
+
+    // shots out of turn are turned down, and so are answers to turns nobody played
+    guest.on_net(0, Message::Fire([(1, 1)].iter().cloned().collect()));
+    assert!(guest
+      .tab()
+      .alerts
+      .to_string()
+      .contains("played out of turn"));
+    assert_eq!(guest.game().timeline().len(), 1);
+    host.on_net(0, Message::Answer(Answer::Sonar(true), None));
+    assert!(host
+      .tab()
+      .alerts
+      .to_string()
+      .contains("answered a turn you didn't play"));
+
+    // a spectator replays the match so far and can't play
+    let mut spectator = App::new("test".into(), Rule::Fury, Difficulty::Easy);
+    let (mut spectator_peer, watched) = Peer::watch(&addr).unwrap();
+    let (spectator_tx, spectator_rx) = mpsc::channel();
+    spectator_peer.listen(spectator_tx, 0);
+    spectator.watch(spectator_peer, watched);
+    spectator.on_net(0, receive(&spectator_rx));
+    assert!(spectator.game().timeline().is_empty());
+    spectator.on_net(0, receive(&spectator_rx));
+    assert_eq!(
+      spectator.history(),
+      vec![host.history()[0].replace("You", "Host")]
+    );
+    spectator.on_key(Key::Char(' '));
+    spectator.on_key(Key::Char('\n'));
+    assert_eq!(spectator.game().timeline().len(), 1);
+
+    // an answer the sides disagree on is reported, once
+    guest.on_key(Key::Char(' '));
+    guest.on_key(Key::Char('\n'));
+    host.on_net(0, receive(&host_rx));
+    let answer = match receive(&guest_rx) {
+      Message::Answer(answer, Some(hash)) => Message::Answer(answer, Some(hash ^ 1)),
+      message => panic!("expected an answer, got {}", message),
+    };
+    guest.on_net(0, answer);
+    assert_eq!(guest.game().timeline().len(), 2);
+    assert!(guest
+      .tab()
+      .alerts
+      .to_string()
+      .ends_with("Your opponent disagrees on what turn 2 found"));
+    spectator.on_net(0, receive(&spectator_rx));
+    spectator.on_net(0, receive(&spectator_rx));
+    assert_eq!(spectator.game().found_hash(), host.game().found_hash());
+    assert!(!spectator.tab().alerts.to_string().contains("disagree"));
+
+    guest.on_key(Key::Char('q'));
+    host.on_net(0, receive(&host_rx));
+    assert_eq!(host.game().winner(), Some(0));
+    assert_eq!(host.opponent_name(), "Opponent");
+    spectator.on_net(0, receive(&spectator_rx));
+    assert!(spectator
+      .tab()
+      .alerts
+      .to_string()
+      .ends_with("The match was stopped"));
+  }
+
+  #[test]
+  fn test_app_versus_turn_log() {
+    let logs =
+      [0, 1].map(|_| std::env::temp_dir().join(format!("battleship-{}.log", uuid::Uuid::new_v4())));
+    let connect = |host: &mut App, guest: &mut App| {
+      let ([(mut host_peer, hosted), (mut guest_peer, joined)], _) = net::loopback_resumed(
+        Rule::Default,
+        [host.fleet(), guest.fleet()],
+        [host.commitments(), guest.commitments()],
+      );
+      let (host_tx, host_rx) = mpsc::channel();
+      let (guest_tx, guest_rx) = mpsc::channel();
+      host_peer.listen(host_tx, 0);
+      guest_peer.listen(guest_tx, 0);
+      host.set_peer(host_peer, hosted);
+      guest.set_peer(guest_peer, joined);
+      (host_rx, guest_rx)
+    };
+    let receive = |app: &mut App, rx: &mpsc::Receiver<Event<Key>>| match rx.recv() {
+      Ok(Event::Net(0, message)) => app.on_net(0, message),
+      Ok(Event::Dropped(0)) => app.on_dropped(0),
+      _ => panic!("expected a message"),
+    };
+    let start = |log: &PathBuf| {
+      let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+      app.set_turn_log(TurnLog::new(log.clone())).unwrap();
+      app
+    };
+
+    // the guest answers the host's turn, and the host goes down before the answer comes in
+    let (mut host, mut guest) = (start(&logs[0]), start(&logs[1]));
+    let (host_rx, guest_rx) = connect(&mut host, &mut guest);
+    host.on_key(Key::Char(' '));
+    host.on_key(Key::Char('\n'));
+    receive(&mut guest, &guest_rx);
+    assert_eq!(guest.game().timeline().len(), 1);
+    drop((host, host_rx));
+    receive(&mut guest, &guest_rx);
+    assert!(!guest.is_won());
+    assert!(guest
+      .tab()
+      .alerts
+      .to_string()
+      .ends_with("start again with the turn log to go on"));
+    drop((guest, guest_rx));
+
+    // both start again from their logs, and the guest sends the turn the host missed again
+    let (mut host, mut guest) = (start(&logs[0]), start(&logs[1]));
+    assert!(host.game().timeline().is_empty());
+    assert_eq!(guest.game().timeline().len(), 1);
+    assert!(host.commitments().is_some());
+    let (host_rx, guest_rx) = connect(&mut host, &mut guest);
+    receive(&mut host, &host_rx);
+    receive(&mut guest, &guest_rx);
+    receive(&mut host, &host_rx);
+    receive(&mut host, &host_rx);
+    assert_eq!(host.game().timeline().len(), 1);
+    assert_eq!(
+      host.game().timeline()[0].response,
+      guest.game().timeline()[0].response
+    );
+    assert_eq!(host.game().found_hash(), guest.game().found_hash());
+    assert!(!host.game().is_user_turn());
+
+    // and the match goes on where it was
+    guest.on_key(Key::Char(' '));
+    guest.on_key(Key::Char('\n'));
+    receive(&mut host, &host_rx);
+    receive(&mut guest, &guest_rx);
+    assert_eq!(guest.game().timeline().len(), 2);
+    assert_eq!(host.game().found_hash(), guest.game().found_hash());
+    for app in [&host, &guest].iter() {
+      assert!(!app.tab().alerts.to_string().contains("disagree"));
+    }
+
+    // leaving ends the match on both sides, there's nothing left to resume
+    guest.on_key(Key::Char('q'));
+    receive(&mut host, &host_rx);
+    assert_eq!(host.game().winner(), Some(0));
+    assert!(!logs[1].exists());
+    for log in logs.iter().filter(|log| log.exists()) {
+      fs::remove_file(log).unwrap();
+    }
+  }
+
+  #[test]
+  fn test_app_versus_fleets() {
+    let mut host = App::new("test".into(), Rule::Fury, Difficulty::Easy);
+    let mut guest = App::new("test".into(), Rule::Fury, Difficulty::Easy);
+    let fleets = [host.fleet(), guest.fleet()];
+    let ([(mut host_peer, hosted), (mut guest_peer, joined)], addr) =
+      net::loopback(Rule::Fury, fleets.clone());
+    let (host_tx, host_rx) = mpsc::channel();
+    let (guest_tx, guest_rx) = mpsc::channel();
+    host_peer.listen(host_tx, 0);
+    guest_peer.listen(guest_tx, 0);
+    host.set_peer(host_peer, hosted);
+    guest.set_peer(guest_peer, joined);
+    let (mut spectator_peer, watched) = Peer::watch(&addr).unwrap();
+    let (spectator_tx, spectator_rx) = mpsc::channel();
+    spectator_peer.listen(spectator_tx, 0);
+    let mut spectator = App::new("test".into(), Rule::Fury, Difficulty::Easy);
+    spectator.watch(spectator_peer, watched);
+    let receive = |app: &mut App, rx: &mpsc::Receiver<Event<Key>>| match rx.recv() {
+      Ok(Event::Net(0, message)) => app.on_net(0, message),
+      _ => panic!("expected a message"),
+    };
+
+    // every turn goes out, is answered and settled
+    while !host.is_won() {
+      let (shooter, shooter_rx, defender, defender_rx) = if host.game().is_user_turn() {
+        (&mut host, &host_rx, &mut guest, &guest_rx)
+      } else {
+        (&mut guest, &guest_rx, &mut host, &host_rx)
+      };
+      shooter.tab_mut().selected_coordinates =
+        shooter.game().legal_shots(0).into_iter().take(1).collect();
+      shooter.on_fire();
+      receive(defender, defender_rx);
+      receive(shooter, shooter_rx);
+      receive(&mut spectator, &spectator_rx);
+      receive(&mut spectator, &spectator_rx);
+    }
+    assert!(guest.is_won());
+    assert_eq!(spectator.game().winner(), host.game().winner());
+    for app in [&host, &guest, &spectator].iter() {
+      assert!(!app.tab().alerts.to_string().contains("disagree"));
+    }
+
+    // both fleets come out at the end and every answer is checked against them
+    host.on_tick();
+    guest.on_tick();
+    receive(&mut host, &host_rx);
+    receive(&mut guest, &guest_rx);
+    for app in [&host, &guest].iter() {
+      assert!(app
+        .tab()
+        .alerts
+        .to_string()
+        .ends_with("Both fleets are in the open and check out"));
+    }
+    assert_eq!(
+      host.game().computer().player_board().placements(),
+      fleets[1]
+    );
+    assert_eq!(
+      guest.game().computer().player_board().placements(),
+      fleets[0]
+    );
+    receive(&mut spectator, &spectator_rx);
+    receive(&mut spectator, &spectator_rx);
+    assert_eq!(
+      spectator.game().player().player_board().placements(),
+      fleets[0]
+    );
+    assert_eq!(spectator.game().winner(), host.game().winner());
+
+    // a fleet that isn't the one committed to is turned down
+    host.on_net(0, Message::Fleet("8f14".into(), fleets[1].clone()));
+    assert!(host
+      .tab()
+      .alerts
+      .to_string()
+      .contains("The fleets don't check out: The fleet isn't the one committed to"));
+  }
+
+  #[test]
+  fn test_app_tabs() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.on_key(Key::F(2));
+    assert_eq!(app.tabs(), vec!["F1 Computer", "[F2 Computer]"]);
+    assert!(app.game().timeline().is_empty());
+    assert!(!app.is_paused());
+    // only the next free key opens a tab
+    app.on_key(Key::F(5));
+    assert_eq!(app.tabs().len(), 2);
+
+    app.on_key(Key::F(1));
+    assert_eq!(app.game().timeline().len(), 1);
+    // the computer waited while the user was away
+    assert!(app.is_paused());
+    (0..16).for_each(|_| app.on_tick());
+    assert_eq!(app.game().timeline().len(), 1);
+  }
+
+  #[test]
+  fn test_app_tabs_bot() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.tab_mut().game.set_first(1);
+    let bot = r#"read line; sleep 5; echo '{"shots":["A1"]}'"#;
+    app.set_bot(bot.into()).unwrap();
+    app.on_tick();
+    assert!(app.tab().bot.as_ref().is_some_and(BotCmd::is_thinking));
+    // a new tab starts a bot of its own rather than sharing the busy one
+    app.on_key(Key::F(2));
+    assert!(app.tab().bot.as_ref().is_some_and(|bot| !bot.is_thinking()));
+    app.on_key(Key::F(1));
+    assert!(app.tab().bot.as_ref().is_some_and(BotCmd::is_thinking));
+  }
+
+  #[test]
+  fn test_app_session() {
+    let dir = std::env::temp_dir().join(format!("battleship-{}", uuid::Uuid::new_v4()));
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.set_session_file(SessionFile::new(dir.join("session.json")));
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    // an untouched match isn't worth keeping
+    app.on_key(Key::F(2));
+    app.on_key(Key::F(3));
+    app.wait_for_opponent(4000);
+    app.on_key(Key::Char('q'));
+    app.save_session().unwrap();
+
+    let mut restarted = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    let mut hosted = vec![];
+    let session = SessionFile::new(dir.join("session.json")).load().unwrap();
+    restarted
+      .restore_session(session, |tab, port, _, _| hosted.push((tab, port)))
+      .unwrap();
+    assert_eq!(hosted, vec![(2, 4000)]);
+    assert_eq!(
+      restarted.tabs(),
+      vec!["[F1 Computer]", "F2 Computer*", "F3 Waiting*"]
+    );
+    restarted.on_key(Key::F(2));
+    assert_eq!(restarted.game().timeline().len(), 1);
+    assert!(restarted.is_paused());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_app_tabs_network() {
+    let mut host = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    let mut guest = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    let ([hosted, (mut guest_peer, joined)], _) =
+      net::loopback(Rule::Default, [host.fleet(), guest.fleet()]);
+    let (guest_tx, guest_rx) = mpsc::channel();
+    guest_peer.listen(guest_tx, 0);
+    guest.set_peer(guest_peer, joined);
+
+    host.wait_for_opponent(4000);
+    assert!(host.is_waiting());
+    host.on_key(Key::Char('\n'));
+    (0..16).for_each(|_| host.on_tick());
+    assert!(host.game().timeline().is_empty());
+
+    // the other player joins while the user plays the computer
+    host.on_key(Key::F(2));
+    host.on_hosted(0, Ok(hosted));
+    assert_eq!(host.tabs(), vec!["F1 Opponent*", "[F2 Computer]"]);
+    host.on_key(Key::F(1));
+    assert!(!host.is_waiting());
+    assert_eq!(host.tabs(), vec!["[F1 Opponent]", "F2 Computer"]);
+    host.on_key(Key::Char(' '));
+    host.on_key(Key::Char('\n'));
+    host.on_key(Key::F(2));
+
+    match guest_rx.recv() {
+      Ok(Event::Net(0, message)) => guest.on_net(0, message),
+      _ => panic!("expected a message"),
+    }
+    assert!(guest.game().is_user_turn());
+    // leaving says goodbye in every tab
+    host.on_key(Key::Char('q'));
+    assert!(host.should_quit);
+    assert!(matches!(guest_rx.recv(), Ok(Event::Net(0, Message::Bye))));
+  }
+
+  #[test]
+  fn test_app_devtools() {
+    let mut app = App::new("test".into(), Rule::Default, Difficulty::Easy);
+    app.on_key(Key::Char('D'));
+    assert_eq!(app.turn_changes(), vec!["No turns played yet"]);
+
+    // keys scroll the list instead of moving the cursor
+    app.on_key(Key::Char('j'));
+    assert_eq!(app.tab().devtools, Some(0));
+    assert_eq!(app.active(), (0, 0));
+    app.on_key(Key::Esc);
+    assert_eq!(app.tab().devtools, None);
+
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    let lines = app.turn_changes();
+    assert_eq!(lines[0], "Turn 1 by You: A1");
+    assert!(lines[1].starts_with("  Computer fleet A1 "));
+    app.on_key(Key::Char('D'));
+    app.on_key(Key::Char('j'));
+    assert_eq!(app.tab().devtools, Some(1));
+  }
+
+  #[test]
+  fn test_app_render_boards() {
+    let mut harness = Harness::new(Rule::Default, Difficulty::Easy);
+
+    assert!(
+      harness.frame_contains("Battleship.rs | Rule: Default (00:00) | Turn 1 | Shots 1/1 | A1")
+    );
+    assert!(harness.frame_contains("You"));
+    assert!(harness.frame_contains("Computer"));
+    assert!(harness.frame_contains("fire: <enter>"));
+    assert!(harness.frame_contains("Cursor: A1 — unexplored"));
+    assert!(harness.frame_contains("──A────B────C──"));
+    assert!(harness.frame_contains("──I────J──"));
+    let frame = harness.frame();
+    let numbered = |n: &str| frame.iter().map(|l| l.matches(n).count()).sum::<usize>();
+    // on both boards
+    assert_eq!(numbered(" 1│"), 2);
+    assert_eq!(numbered("10│"), 2);
+
+    // a pending count shows in the corner until it's used
+    let frame = harness.keys("7").frame();
+    assert!(frame[frame.len() - 2].ends_with("7 │"));
+    let frame = harness.keys("j").frame();
+    assert!(!frame[frame.len() - 2].ends_with("7 │"));
+  }
+
+  #[test]
+  fn test_app_render_title() {
+    let mut harness = Harness::new(Rule::Fury, Difficulty::Easy);
+    harness.keys(" l ");
+    assert!(harness.frame_contains("Turn 1 | Shots 2/4"));
+
+    // let the alerts covering the title expire
+    harness.keys("\n").wait_for_turn().ticks(8);
+    assert!(harness.frame_contains("Turn 2 | Shots 4/4 | B1 | Accuracy"));
+    assert!(harness.frame_contains("[1] sonar 1 | [2] ping ready | [3] airstrike 0"));
+
+    // narrow terminals drop the least important sections
+    harness.resize(40, 40);
+    assert!(harness.frame_contains("Battleship.rs | Rule: Fury (00:00)"));
+    assert!(!harness.frame_contains("Turn"));
+    harness.resize(20, 40);
+    assert!(harness.frame_contains("Rule: Fury (00:00)"));
+  }
+
+  #[test]
+  fn test_app_render_layout() {
+    let mut harness = Harness::new(Rule::Default, Difficulty::Easy);
+    assert!(!harness.frame_contains("Log"));
+
+    harness.keys(" \n").wait_for_turn().resize(180, 40);
+    assert!(harness.frame_contains("You       shots   1 | hits"));
+    assert!(harness.frame_contains("00:00 1. You: A1"));
+    assert!(harness.frame_contains("2. Computer: "));
+
+    harness.app.layout = LayoutMode::Standard;
+    assert!(!harness.draw().frame_contains("1. You: A1"));
+    // scrolling back opens the log over the boards
+    assert!(harness
+      .press(Key::PageUp)
+      .frame_contains("00:00 1. You: A1"));
+    assert!(!harness.press(Key::PageDown).frame_contains("1. You: A1"));
+
+    // tall and narrow terminals stack the boards
+    harness.app.layout = LayoutMode::Auto;
+    harness.resize(60, 80);
+    let row_of = |harness: &Harness, title: &str| {
+      harness
+        .frame()
+        .iter()
+        .position(|line| line.trim_matches(|c| c == '│' || c == ' ') == title)
+    };
+    let (you, computer) = (row_of(&harness, "You"), row_of(&harness, "Computer"));
+    assert!(you.is_some() && computer > you);
+    assert!(harness.frame_contains("<enter> fire"));
+    // too short to stack them, the boards go side by side again
+    harness.resize(60, 40);
+    assert_eq!(row_of(&harness, "Computer"), None);
+  }
+
+  #[test]
+  fn test_app_render_alerts() {
+    let mut harness = Harness::new(Rule::Default, Difficulty::Easy);
+    harness.tick().keys("\n");
+    assert!(harness.frame_contains("Select opponent coordinates to hit"));
+
+    let mut harness = Harness::new(Rule::Default, Difficulty::Easy);
+    harness.tick().keys(" l ");
+    assert_eq!(
+      harness.app.tab().alerts.to_string(),
+      "Maximum shots for rule selected"
+    );
+    assert!(harness.frame_contains("Maximum shots for rule selected"));
+    // warnings expire after three seconds
+    harness.ticks(11);
+    assert!(harness.frame_contains("Maximum shots for rule selected"));
+    harness.tick();
+    assert!(harness.app.tab().alerts.is_empty());
+    assert!(!harness.frame_contains("Maximum shots for rule selected"));
+
+    harness.keys("\n");
+    assert!(!harness.app.game().is_user_turn());
+    assert!(harness.frame_contains("You have"));
+    harness.wait_for_turn();
+    let latest = harness.app.tab().alerts.iter().last().unwrap();
+    assert!(latest.text.starts_with("Computer have"));
+  }
+
+  #[test]
+  fn test_app_render_game_over() {
+    let path = std::env::temp_dir().join(format!("battleship-{}.stats", uuid::Uuid::new_v4()));
+    let mut harness = Harness::new(Rule::Default, Difficulty::Easy);
+    harness.app.set_stats(StatsFile::new(path.clone()));
+    harness.tick();
+
+    let board = harness.app.tab().game.computer().player_board();
+    let cells = board
+      .placements()
+      .into_iter()
+      .flat_map(|placement| placement.cells())
+      .collect::<Vec<_>>();
+    for cell in cells {
+      harness.wait_for_turn().move_to(cell).keys(" \n");
+    }
+    assert!(harness.app.is_won());
+
+    harness.tick();
+    assert!(harness.frame_contains("You won"));
+    // the first win tops the leaderboard
+    assert!(harness.frame_contains("Fastest wins"));
+    assert!(harness.frame_contains("Default    Easy"));
+
+    harness.keys("q");
+    assert!(harness.app.should_quit);
+    std::fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn test_debouncer_disabled() {
+    let mut debouncer = Debouncer::new(Duration::ZERO);
+    let start = Instant::now();
+
+    assert!(debouncer.allow(Action::Fire, start));
+    assert!(debouncer.allow(Action::Fire, start));
   }
 }
@@ -0,0 +1,569 @@
+//! Bots written in any language, run with `--bot-cmd`. The program is started once and
+//! asked for its shots every turn over a line based JSON protocol: a line with what it
+//! knows of the board goes to its standard input, and a line with its shots is read back
+//! from its standard output.
+//!
+//! ```text
+//! > {"rule":"Salvo","budget":2,"board":["   x      ",...],"legal":["A1","B1",...]}
+//! < {"shots":["C1","D1"]}
+//! ```
+//!
+//! The rows of the board are drawn with the plain glyphs of the cells, a space for a cell
+//! not fired upon yet. A bot that doesn't answer in time or answers with anything but legal
+//! shots is let go of, its program stopped, and the computer picks the shots for it from
+//! then on. In a bot match it is disqualified instead and forfeits the match, and how long
+//! it may think and how much memory its program may take up can be set for the match.
+//!
+//! In the game the bot is asked as soon as the computer's turn comes, and its answer is
+//! looked for on every tick so the screen goes on while it thinks.
+
+use std::{
+  cell::RefCell,
+  collections::BTreeSet,
+  fmt::{self, Display},
+  io::{self, BufRead, BufReader, Write},
+  process::{Child, ChildStdin, Command, Stdio},
+  rc::Rc,
+  sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
+  thread,
+  time::{Duration, Instant},
+};
+
+use battleship_core::strategy::{BoardView, HuntTarget, Strategy};
+use serde::{Deserialize, Serialize};
+
+use super::game::{
+  a1_notation, collect_salvo, parse_a1, Board, Coordinate, Game, Rule, COLS, ROWS,
+};
+
+/// How long a bot may think about a turn, unless told otherwise
+pub const BOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a bot is allowed while it plays
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Limits {
+  /// how long it may think about a turn
+  pub time: Duration,
+  /// the memory its program may take up in MiB, as much as it wants if none
+  pub memory: Option<u64>,
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Self {
+      time: BOT_TIMEOUT,
+      memory: None,
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct Request {
+  rule: String,
+  budget: usize,
+  board: Vec<String>,
+  legal: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+  shots: Vec<String>,
+}
+
+/// Why a bot was let go of
+#[derive(PartialEq, Debug, Clone)]
+pub enum BotError {
+  /// it didn't answer within the time it had
+  Timeout(Duration),
+  /// it answered with anything but legal shots, or stopped answering
+  Protocol(String),
+}
+
+impl Display for BotError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      BotError::Timeout(time) => {
+        write!(f, "didn't answer within {} seconds", time.as_secs_f64())
+      }
+      BotError::Protocol(reason) => write!(f, "{}", reason),
+    }
+  }
+}
+
+/// The bot's program, shared by every copy of the match it plays
+#[derive(Clone)]
+pub struct BotCmd {
+  process: Rc<RefCell<Process>>,
+}
+
+struct Process {
+  child: Child,
+  stdin: Option<ChildStdin>,
+  lines: Receiver<String>,
+  limits: Limits,
+  /// the request the bot is thinking about and when it was sent
+  pending: Option<(String, Instant)>,
+  /// the request the bot answered while the game went on, and its answer
+  answered: Option<(String, String)>,
+  /// why the bot was let go of, once it was
+  failure: Option<BotError>,
+  reported: bool,
+}
+
+impl Drop for Process {
+  fn drop(&mut self) {
+    // the end of its input tells the bot the session is over
+    self.stdin.take();
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
+
+impl BotCmd {
+  /// Starts the program through the shell, so it can be given arguments, with its errors
+  /// going where `stderr` says
+  pub fn spawn(command: &str, stderr: Stdio) -> io::Result<Self> {
+    Self::spawn_with(command, stderr, Limits::default())
+  }
+
+  /// Starts the program like `spawn`, held to the limits. The memory is capped by the shell
+  /// before the program starts, and the program doesn't run where it can't be.
+  pub fn spawn_with(command: &str, stderr: Stdio, limits: Limits) -> io::Result<Self> {
+    let script = match limits.memory {
+      Some(memory) => format!("ulimit -v {} || exit 1\n{}", memory * 1024, command),
+      None => command.to_string(),
+    };
+    let mut child = Command::new("sh")
+      .arg("-c")
+      .arg(script)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(stderr)
+      .spawn()?;
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take().ok_or(io::ErrorKind::BrokenPipe)?;
+    let (sender, lines) = mpsc::channel();
+    // read on a thread of its own, so a bot that hangs can't hang the game
+    thread::spawn(move || {
+      for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        if sender.send(line).is_err() {
+          break;
+        }
+      }
+    });
+    Ok(Self {
+      process: Rc::new(RefCell::new(Process {
+        child,
+        stdin,
+        lines,
+        limits,
+        pending: None,
+        answered: None,
+        failure: None,
+        reported: false,
+      })),
+    })
+  }
+
+  /// Asks the bot for as many shots as the budget from the legal cells, given what it knows
+  /// of the board, unless it answered that already. Its shots are checked against the legal
+  /// cells and the budget.
+  pub fn ask(
+    &self,
+    board: &Board,
+    legal: &BTreeSet<Coordinate>,
+    rule: Rule,
+    budget: usize,
+  ) -> Result<BTreeSet<Coordinate>, BotError> {
+    let mut process = self.process.borrow_mut();
+    if let Some(failure) = &process.failure {
+      return Err(failure.clone());
+    }
+    let request = request(board, legal, rule, budget);
+    let line = match process.answered.take() {
+      Some((asked, line)) if asked == request => Ok(line),
+      _ => process.exchange(&request),
+    };
+    let shots = line.and_then(|line| {
+      let shots = parse_reply(&line).map_err(BotError::Protocol)?;
+      if let Some(shot) = shots.iter().find(|c| !legal.contains(c)) {
+        return Err(BotError::Protocol(format!(
+          "fired upon {}, which isn't legal",
+          a1_notation(*shot)
+        )));
+      }
+      if shots.is_empty() || shots.len() > budget {
+        return Err(BotError::Protocol(format!(
+          "fired {} shots with {} to fire",
+          shots.len(),
+          budget
+        )));
+      }
+      Ok(shots)
+    });
+    if let Err(failure) = &shots {
+      process.let_go(failure.clone());
+    }
+    shots
+  }
+
+  /// Gets the bot thinking about the computer's turn in the match without waiting on it.
+  /// Tells whether the computer can fire without holding up the game: the bot answered,
+  /// was let go of, or isn't asked this turn.
+  pub fn prepare(&self, game: &Game) -> bool {
+    let mut process = self.process.borrow_mut();
+    if process.failure.is_some() {
+      return true;
+    }
+    let request = match rehearse(game) {
+      Some(request) => request,
+      None => return true,
+    };
+    // an answer is only ever taken for the request it was sent for, the one of a match
+    // left in another tab included
+    if let Some((asked, sent)) = process.pending.clone() {
+      match process.lines.try_recv() {
+        Ok(line) => {
+          process.pending = None;
+          process.answered = Some((asked, line));
+        }
+        Err(TryRecvError::Empty) if sent.elapsed() < process.limits.time => return false,
+        Err(TryRecvError::Empty) => {
+          let time = process.limits.time;
+          process.let_go(BotError::Timeout(time));
+          return true;
+        }
+        Err(TryRecvError::Disconnected) => {
+          let exited = process.exited();
+          process.let_go(exited);
+          return true;
+        }
+      }
+    }
+    if matches!(&process.answered, Some((asked, _)) if *asked == request) {
+      return true;
+    }
+    match process.send(&request) {
+      Ok(()) => {
+        process.pending = Some((request, Instant::now()));
+        false
+      }
+      Err(failure) => {
+        process.let_go(failure);
+        true
+      }
+    }
+  }
+
+  /// Whether the bot was asked for its shots and hasn't answered yet
+  pub fn is_thinking(&self) -> bool {
+    self.process.borrow().pending.is_some()
+  }
+
+  /// Lets go of the bot for the reason
+  fn fail(&self, failure: BotError) {
+    self.process.borrow_mut().let_go(failure);
+  }
+
+  /// Why the bot was let go of, the first time it's asked after it was
+  pub fn take_failure(&self) -> Option<BotError> {
+    let mut process = self.process.borrow_mut();
+    if process.reported {
+      return None;
+    }
+    process.reported = process.failure.is_some();
+    process.failure.clone()
+  }
+}
+
+impl Process {
+  fn send(&mut self, request: &str) -> Result<(), BotError> {
+    let stdin = self
+      .stdin
+      .as_mut()
+      .ok_or_else(|| BotError::Protocol("has no input".into()))?;
+    writeln!(stdin, "{}", request)
+      .and_then(|_| stdin.flush())
+      .map_err(|_| BotError::Protocol("stopped reading its input".into()))
+  }
+
+  fn exchange(&mut self, request: &str) -> Result<String, BotError> {
+    // the answer to a request sent before is of no use any more, but must not be taken
+    // for the answer to this one
+    if self.pending.take().is_some() {
+      self.receive()?;
+    }
+    self.send(request)?;
+    self.receive()
+  }
+
+  fn receive(&mut self) -> Result<String, BotError> {
+    match self.lines.recv_timeout(self.limits.time) {
+      Ok(line) => Ok(line),
+      Err(RecvTimeoutError::Timeout) => Err(BotError::Timeout(self.limits.time)),
+      Err(RecvTimeoutError::Disconnected) => Err(self.exited()),
+    }
+  }
+
+  /// Why the bot stopped answering, which may be running out of the memory it had
+  fn exited(&self) -> BotError {
+    BotError::Protocol(match self.limits.memory {
+      Some(memory) => format!("exited, maybe out of its {} MiB of memory", memory),
+      None => "exited".into(),
+    })
+  }
+
+  /// Lets go of the bot for the reason and stops its program, which is asked nothing more
+  fn let_go(&mut self, failure: BotError) {
+    self.failure = Some(failure);
+    self.pending = None;
+    self.answered = None;
+    self.stdin.take();
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
+
+/// Writes down what the computer's strategy is asked in its turn, firing upon the first
+/// legal cells in its place
+#[derive(Clone, Default)]
+struct Rehearsal(Rc<RefCell<Option<String>>>);
+
+impl Strategy for Rehearsal {
+  fn next_shots(&mut self, view: &BoardView, budget: usize) -> BTreeSet<Coordinate> {
+    *self.0.borrow_mut() = Some(request(view.board, view.legal, view.rule, budget));
+    view.legal.iter().take(budget).copied().collect()
+  }
+}
+
+/// The request the bot is sent in the computer's coming turn of the match, none when the
+/// computer uses a power-up instead of asking it
+fn rehearse(game: &Game) -> Option<String> {
+  let rehearsal = Rehearsal::default();
+  let mut game = game.clone();
+  game.set_strategy(Box::new(rehearsal.clone()));
+  game.bot_fire();
+  let request = rehearsal.0.borrow_mut().take();
+  request
+}
+
+impl Strategy for BotCmd {
+  fn next_shots(&mut self, view: &BoardView, budget: usize) -> BTreeSet<Coordinate> {
+    self
+      .ask(view.board, view.legal, view.rule, budget)
+      .unwrap_or_else(|_| HuntTarget.next_shots(view, budget))
+  }
+}
+
+/// The line telling the bot what it knows, as one JSON object
+fn request(board: &Board, legal: &BTreeSet<Coordinate>, rule: Rule, budget: usize) -> String {
+  let request = Request {
+    rule: rule.to_string(),
+    budget,
+    board: (0..ROWS)
+      .map(|row| {
+        (0..COLS)
+          .map(|col| board.status_at((row, col)).as_char())
+          .collect()
+      })
+      .collect(),
+    legal: legal.iter().map(|c| a1_notation(*c)).collect(),
+  };
+  serde_json::to_string(&request).unwrap_or_default()
+}
+
+/// The shots of the bot's answer
+fn parse_reply(line: &str) -> Result<BTreeSet<Coordinate>, String> {
+  let reply = serde_json::from_str::<Reply>(line)
+    .map_err(|err| format!("answered {:?}, which isn't a reply: {}", line, err))?;
+  let shots = reply
+    .shots
+    .iter()
+    .map(|shot| parse_a1(shot).ok_or_else(|| format!("fired upon {:?}, which isn't a cell", shot)))
+    .collect::<Result<Vec<_>, _>>()?;
+  collect_salvo(&shots).map_err(|err| format!("fired a salvo that isn't legal, {}", err))
+}
+
+/// Plays the match out with the bot firing for the user. A bot let go of is disqualified
+/// and forfeits the match.
+pub fn play_out(bot: &BotCmd, game: &mut Game) {
+  while !game.is_won() {
+    if !game.is_user_turn() {
+      game.bot_fire();
+      continue;
+    }
+    let legal = game.legal_shots(0);
+    let budget = game.shot_budget(0).min(legal.len());
+    let board = game.player().opponent_board();
+    let shots = bot.ask(board, &legal, game.rule, budget).and_then(|shots| {
+      game.validate_salvo(&shots).map(|_| shots).map_err(|err| {
+        let failure = BotError::Protocol(format!("fired a salvo that isn't legal, {}", err));
+        bot.fail(failure.clone());
+        failure
+      })
+    });
+    match shots {
+      Ok(shots) => {
+        game.fire(&shots, false);
+      }
+      Err(_) => game.forfeit(0),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::{Difficulty, FleetType};
+
+  /// Fires upon the first legal cell every turn
+  const SWEEP: &str = r#"while read line; do echo "{\"shots\":[$(echo "$line" | sed 's/.*"legal":\[\("[A-J][0-9]*"\).*/\1/')]}"; done"#;
+
+  fn bot_turn(game: &mut Game) -> BTreeSet<Coordinate> {
+    let shots = game.legal_shots(0).into_iter().take(1).collect();
+    game.fire(&shots, false);
+    game.bot_fire();
+    game.timeline().last().unwrap().shots.clone()
+  }
+
+  #[test]
+  fn test_bot_cmd_protocol() {
+    let game = Game::new(Rule::Salvo, Difficulty::Easy, 0);
+    let board = game.player().opponent_board();
+    let legal = [(0, 0), (2, 1)].iter().copied().collect();
+    let line = request(board, &legal, Rule::Salvo, 2);
+    assert!(line.starts_with(r#"{"rule":"Salvo","budget":2,"board":["          ","#));
+    assert!(line.ends_with(r#""legal":["A1","B3"]}"#));
+
+    assert_eq!(parse_reply(r#"{"shots":["B3","a1"]}"#), Ok(legal.clone()));
+    assert!(parse_reply(r#"{"shots":["K1"]}"#).is_err());
+    // the same cell twice is refused rather than fired upon once
+    assert_eq!(
+      parse_reply(r#"{"shots":["A1","a1"]}"#),
+      Err("fired a salvo that isn't legal, A1 is fired upon more than once".into())
+    );
+    assert!(parse_reply("A1").is_err());
+  }
+
+  #[test]
+  fn test_bot_cmd_strategy() {
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 3);
+    let bot = BotCmd::spawn(SWEEP, Stdio::null()).unwrap();
+    game.set_strategy(Box::new(bot.clone()));
+    assert_eq!(bot_turn(&mut game), [(0, 0)].iter().copied().collect());
+    assert_eq!(bot_turn(&mut game), [(0, 1)].iter().copied().collect());
+    assert_eq!(bot.take_failure(), None);
+
+    // a bot that talks nonsense is let go of, and the computer fires for it
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 3);
+    let bot = BotCmd::spawn("read line; echo Z9", Stdio::null()).unwrap();
+    game.set_strategy(Box::new(bot.clone()));
+    assert_eq!(bot_turn(&mut game).len(), 1);
+    let failure = bot.take_failure().unwrap().to_string();
+    assert!(failure.starts_with("answered \"Z9\""));
+    assert_eq!(bot_turn(&mut game).len(), 1);
+    assert_eq!(bot.take_failure(), None);
+  }
+
+  #[test]
+  fn test_bot_cmd_prepare() {
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 3);
+    game.set_first(1);
+    // answers its first request and no other
+    let bot = BotCmd::spawn(
+      r#"read line; echo '{"shots":["B2"]}'; read line"#,
+      Stdio::null(),
+    )
+    .unwrap();
+    assert!(!bot.prepare(&game));
+    assert!(bot.is_thinking());
+    let start = Instant::now();
+    while !bot.prepare(&game) && start.elapsed() < BOT_TIMEOUT {
+      thread::yield_now();
+    }
+    assert!(!bot.is_thinking());
+    game.set_strategy(Box::new(bot.clone()));
+    game.bot_fire();
+    assert_eq!(game.timeline()[0].shots, [(1, 1)].iter().copied().collect());
+    assert_eq!(bot.take_failure(), None);
+
+    // a bot still thinking doesn't hold the game up, and is stopped once let go of
+    let bot = BotCmd::spawn("read line; sleep 30", Stdio::null()).unwrap();
+    let start = Instant::now();
+    assert!(!bot.prepare(&game));
+    assert!(!bot.prepare(&game));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    bot.fail(BotError::Timeout(BOT_TIMEOUT));
+    assert!(bot.prepare(&game));
+    assert!(matches!(
+      bot.process.borrow_mut().child.try_wait(),
+      Ok(Some(_))
+    ));
+  }
+
+  #[test]
+  fn test_bot_cmd_play_out() {
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 5);
+    let bot = BotCmd::spawn(SWEEP, Stdio::null()).unwrap();
+    play_out(&bot, &mut game);
+    assert!(game.is_won());
+    assert_eq!(bot.take_failure(), None);
+
+    // a single shot is short of a salvo, the bot is disqualified in its first turn
+    let mut game = Game::seeded(Rule::Salvo, Difficulty::Easy, FleetType::Standard, 5);
+    let bot = BotCmd::spawn(SWEEP, Stdio::null()).unwrap();
+    play_out(&bot, &mut game);
+    assert_eq!(game.winner(), Some(1));
+    assert!(game.timeline().is_empty());
+    assert_eq!(
+      bot.take_failure().unwrap().to_string(),
+      "fired a salvo that isn't legal, all 4 shots must be fired this turn"
+    );
+  }
+
+  #[test]
+  fn test_bot_cmd_limits() {
+    let limits = Limits {
+      time: Duration::from_millis(200),
+      memory: Some(64),
+    };
+    // the program runs with its memory capped
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 5);
+    let bot = BotCmd::spawn_with(
+      r#"read line; echo "{\"shots\":[\"$(ulimit -v)\"]}""#,
+      Stdio::null(),
+      limits,
+    )
+    .unwrap();
+    play_out(&bot, &mut game);
+    assert_eq!(
+      bot.take_failure().unwrap().to_string(),
+      "fired upon \"65536\", which isn't a cell"
+    );
+
+    // a bot too slow for the time it has is disqualified and stopped
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 5);
+    let bot = BotCmd::spawn_with("read line; sleep 30", Stdio::null(), limits).unwrap();
+    let start = Instant::now();
+    play_out(&bot, &mut game);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(game.winner(), Some(1));
+    assert_eq!(
+      bot.take_failure().unwrap().to_string(),
+      "didn't answer within 0.2 seconds"
+    );
+    assert!(matches!(
+      bot.process.borrow_mut().child.try_wait(),
+      Ok(Some(_))
+    ));
+
+    // a bot exiting under a memory cap may have run out of it
+    let mut game = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Standard, 5);
+    let bot = BotCmd::spawn_with("exit 1", Stdio::null(), limits).unwrap();
+    play_out(&bot, &mut game);
+    assert_eq!(
+      bot.take_failure().unwrap().to_string(),
+      "exited, maybe out of its 64 MiB of memory"
+    );
+  }
+}
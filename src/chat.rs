@@ -0,0 +1,153 @@
+//! Plays by committee: viewers of a Twitch channel vote on the next shot in its chat, and
+//! once the voting window closes the most voted cells are fired. Only built with the `chat`
+//! feature.
+
+use std::{
+  collections::BTreeMap,
+  io::{self, BufRead, BufReader, Write},
+  net::TcpStream,
+  sync::mpsc::Sender,
+  thread,
+};
+
+use termion::event::Key;
+
+use super::{
+  event::Event,
+  game::{parse_a1, Coordinate, Game},
+};
+
+const TWITCH_IRC: (&str, u16) = ("irc.chat.twitch.tv", 6667);
+
+/// Joins the chat of the channel anonymously and sends its messages along with the keys
+pub fn connect(channel: &str, tx: Sender<Event<Key>>) -> io::Result<()> {
+  let mut stream = TcpStream::connect(TWITCH_IRC)?;
+  // Twitch lets anyone read a chat under a justinfan nick, no token needed
+  write!(
+    stream,
+    "NICK justinfan{}\r\nJOIN #{}\r\n",
+    rand::random::<u16>(),
+    channel.to_lowercase()
+  )?;
+  let reader = BufReader::new(stream.try_clone()?);
+  thread::spawn(move || {
+    for line in reader.lines().map_while(Result::ok) {
+      if line.starts_with("PING") {
+        let _ = write!(stream, "{}\r\n", line.replacen("PING", "PONG", 1));
+      } else if let Some((user, text)) = parse_privmsg(&line) {
+        if tx.send(Event::Chat(user, text)).is_err() {
+          return;
+        }
+      }
+    }
+  });
+  Ok(())
+}
+
+/// The sender and text of a chat message, `:nick!nick@nick.tmi.twitch.tv PRIVMSG #chan :text`
+fn parse_privmsg(line: &str) -> Option<(String, String)> {
+  let rest = line.strip_prefix(':')?;
+  let (prefix, rest) = rest.split_once(' ')?;
+  let rest = rest.strip_prefix("PRIVMSG ")?;
+  let (_, text) = rest.split_once(" :")?;
+  let user = prefix.split('!').next()?;
+  Some((user.into(), text.trim_end().into()))
+}
+
+pub struct ChatVote {
+  /// ticks the voting stays open for
+  pub window: u16,
+  pub ticks_left: u16,
+  /// the latest vote of each viewer
+  votes: BTreeMap<String, Coordinate>,
+}
+
+impl ChatVote {
+  pub fn new(window: u16) -> Self {
+    Self {
+      window,
+      ticks_left: window,
+      votes: BTreeMap::new(),
+    }
+  }
+
+  /// Counts a message naming a cell the user can fire upon as its sender's vote
+  pub fn vote(&mut self, game: &Game, user: String, text: &str) {
+    if let Some(cell) = parse_a1(text.trim()) {
+      if game.check_shot(0, cell).is_ok() {
+        self.votes.insert(user, cell);
+      }
+    }
+  }
+
+  /// Votes per cell, the most voted first
+  pub fn tally(&self) -> Vec<(Coordinate, usize)> {
+    let mut counts = BTreeMap::new();
+    for cell in self.votes.values() {
+      *counts.entry(*cell).or_insert(0) += 1;
+    }
+    let mut tally = counts.into_iter().collect::<Vec<_>>();
+    tally.sort_by(|(_, a), (_, b)| b.cmp(a));
+    tally
+  }
+
+  /// Counts the window down, returning the cells voted for once it closes. A window
+  /// without votes starts over.
+  pub fn tick(&mut self, budget: usize) -> Option<Vec<Coordinate>> {
+    self.ticks_left = self.ticks_left.saturating_sub(1);
+    if self.ticks_left > 0 {
+      return None;
+    }
+    self.ticks_left = self.window;
+    let cells = self
+      .tally()
+      .into_iter()
+      .take(budget)
+      .map(|(cell, _)| cell)
+      .collect::<Vec<_>>();
+    self.votes.clear();
+    Some(cells).filter(|cells| !cells.is_empty())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::{Difficulty, Rule};
+
+  #[test]
+  fn test_parse_privmsg() {
+    assert_eq!(
+      parse_privmsg(":ann!ann@ann.tmi.twitch.tv PRIVMSG #deepu :b7\r"),
+      Some(("ann".into(), "b7".into()))
+    );
+    assert_eq!(
+      parse_privmsg(":tmi.twitch.tv 001 justinfan1 :Welcome"),
+      None
+    );
+  }
+
+  #[test]
+  fn test_chat_vote() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    game.fire(&[(0, 0)].iter().cloned().collect(), false);
+    game.bot_fire();
+
+    let mut vote = ChatVote::new(3);
+    vote.vote(&game, "ann".into(), "B2");
+    vote.vote(&game, "bob".into(), "C3");
+    vote.vote(&game, "cid".into(), "C3");
+    // cells fired upon already and chatter don't count
+    vote.vote(&game, "dan".into(), "A1");
+    vote.vote(&game, "eve".into(), "gg");
+    // a second vote replaces the first
+    vote.vote(&game, "ann".into(), "C3");
+    assert_eq!(vote.tally(), vec![((2, 2), 3)]);
+
+    assert_eq!(vote.tick(1), None);
+    assert_eq!(vote.tick(1), None);
+    assert_eq!(vote.tick(1), Some(vec![(2, 2)]));
+    assert!(vote.tally().is_empty());
+    (0..3).for_each(|_| assert_eq!(vote.tick(1), None));
+  }
+}
@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+/// Game clock that leaves out the time spent paused and freezes once stopped
+pub struct Clock {
+  started: Instant,
+  paused_at: Option<Instant>,
+  paused: Duration,
+  stopped: Option<Duration>,
+  /// time on the clock each turn was played at, none for the turns played before the
+  /// clock knew of them
+  laps: Vec<Option<Duration>>,
+}
+
+impl Clock {
+  pub fn start(now: Instant) -> Self {
+    Self {
+      started: now,
+      paused_at: None,
+      paused: Duration::ZERO,
+      stopped: None,
+      laps: vec![],
+    }
+  }
+
+  /// A clock that already ran for the given time, for a match picked up again
+  pub fn start_at(now: Instant, elapsed: Duration) -> Self {
+    Self {
+      started: now.checked_sub(elapsed).unwrap_or(now),
+      ..Self::start(now)
+    }
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.paused_at.is_some()
+  }
+
+  pub fn is_stopped(&self) -> bool {
+    self.stopped.is_some()
+  }
+
+  pub fn pause(&mut self, now: Instant) {
+    if self.paused_at.is_none() {
+      self.paused_at = Some(now);
+    }
+  }
+
+  pub fn resume(&mut self, now: Instant) {
+    if let Some(paused_at) = self.paused_at.take() {
+      self.paused += now.duration_since(paused_at);
+    }
+  }
+
+  /// Freezes the clock at the time elapsed so far
+  pub fn stop(&mut self, now: Instant) -> Duration {
+    let elapsed = self.elapsed(now);
+    self.stopped = Some(elapsed);
+    elapsed
+  }
+
+  /// Marks the turns played since the last lap with the time on the clock, up to the given
+  /// number of turns in all
+  pub fn lap(&mut self, now: Instant, turns: usize) {
+    let elapsed = self.elapsed(now);
+    self.laps.resize(turns.max(self.laps.len()), Some(elapsed));
+  }
+
+  /// Leaves the turns played before the clock started without a time, for a match picked up
+  /// again
+  pub fn skip_laps(&mut self, turns: usize) {
+    self.laps.resize(turns.max(self.laps.len()), None);
+  }
+
+  /// The time on the clock the turn of the index was played at, when it's known
+  pub fn lap_at(&self, turn: usize) -> Option<Duration> {
+    self.laps.get(turn).copied().flatten()
+  }
+
+  pub fn elapsed(&self, now: Instant) -> Duration {
+    if let Some(stopped) = self.stopped {
+      return stopped;
+    }
+    let now = self.paused_at.unwrap_or(now);
+    now.duration_since(self.started).saturating_sub(self.paused)
+  }
+}
+
+/// Formats a duration as mm:ss, or hh:mm:ss from an hour on
+pub fn format_duration(duration: Duration) -> String {
+  let secs = duration.as_secs();
+  if secs >= 3600 {
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+  } else {
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_clock_excludes_pauses() {
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    let mut clock = Clock::start(start);
+
+    assert_eq!(clock.elapsed(at(10)), Duration::from_secs(10));
+
+    clock.pause(at(10));
+    assert!(clock.is_paused());
+    assert_eq!(clock.elapsed(at(25)), Duration::from_secs(10));
+    clock.resume(at(30));
+    assert!(!clock.is_paused());
+    assert_eq!(clock.elapsed(at(35)), Duration::from_secs(15));
+
+    assert_eq!(clock.stop(at(40)), Duration::from_secs(20));
+    assert!(clock.is_stopped());
+    assert_eq!(clock.elapsed(at(100)), Duration::from_secs(20));
+  }
+
+  #[test]
+  fn test_clock_laps() {
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    let mut clock = Clock::start_at(start, Duration::from_secs(60));
+    clock.skip_laps(2);
+    clock.lap(at(5), 3);
+    clock.lap(at(9), 5);
+    assert_eq!(clock.lap_at(1), None);
+    assert_eq!(clock.lap_at(2), Some(Duration::from_secs(65)));
+    assert_eq!(clock.lap_at(4), Some(Duration::from_secs(69)));
+    assert_eq!(clock.lap_at(5), None);
+  }
+
+  #[test]
+  fn test_format_duration() {
+    assert_eq!(format_duration(Duration::from_secs(0)), "00:00");
+    assert_eq!(format_duration(Duration::from_secs(75)), "01:15");
+    assert_eq!(format_duration(Duration::from_secs(3599)), "59:59");
+    assert_eq!(format_duration(Duration::from_secs(3725)), "01:02:05");
+  }
+}
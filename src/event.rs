@@ -1,46 +1,165 @@
-use std::{io, sync::mpsc, thread, time::Duration};
+use std::{
+  cell::Cell,
+  io::{self, Write},
+  sync::mpsc,
+  thread,
+  time::Duration,
+};
 
-use termion::{event::Key, input::TermRead};
+use termion::{
+  event::{self as term, Key},
+  input::TermRead,
+};
+
+use super::net::{Message, Peer, Versus};
+
+/// Time between two ticks while nobody looks at the terminal
+const IDLE_TICK_RATE: Duration = Duration::from_secs(2);
 
 pub enum Event<I> {
   Input(I),
   Tick,
+  /// the terminal gained or lost the focus, where it reports it
+  Focus(bool),
+  /// a chat message and its sender
+  #[cfg(feature = "chat")]
+  Chat(String, String),
+  /// a message from the other player of the network match in the tab
+  Net(usize, Message),
+  /// the connection to the other player of the network match in the tab dropped
+  Dropped(usize),
+  /// the other player joined the match hosted in the tab, or hosting it failed
+  Hosted(usize, Result<(Peer, Versus), String>),
 }
 
 /// A small event handler that wrap termion input and tick events. Each event
 /// type is handled in its own thread and returned to a common `Receiver`
 pub struct Events {
   rx: mpsc::Receiver<Event<Key>>,
+  tx: mpsc::Sender<Event<Key>>,
+  /// tells the tick thread whether to slow down
+  idle_tx: mpsc::Sender<bool>,
+  idle: Cell<bool>,
 }
 
 impl Events {
   pub fn new(tick_rate: Duration) -> Events {
     let (tx, rx) = mpsc::channel();
+    let (idle_tx, idle_rx) = mpsc::channel();
 
     let tx_clone = tx.clone();
+    let external = tx.clone();
 
     thread::spawn(move || {
       let stdin = io::stdin();
-      for key in stdin.keys().flatten() {
-        if let Err(err) = tx_clone.send(Event::Input(key)) {
+      for event in stdin.events().flatten() {
+        let event = match event {
+          term::Event::Key(key) => Event::Input(key),
+          term::Event::Unsupported(bytes) => match focus_change(&bytes) {
+            Some(focused) => Event::Focus(focused),
+            None => continue,
+          },
+          term::Event::Mouse(_) => continue,
+        };
+        if let Err(err) = tx_clone.send(event) {
           eprintln!("{}", err);
           return;
         }
       }
     });
 
-    thread::spawn(move || loop {
-      if let Err(err) = tx.send(Event::Tick) {
-        eprintln!("{}", err);
-        break;
+    thread::spawn(move || {
+      let mut idle = false;
+      loop {
+        if let Err(err) = tx.send(Event::Tick) {
+          eprintln!("{}", err);
+          break;
+        }
+        let rate = if idle { IDLE_TICK_RATE } else { tick_rate };
+        // waking up at once when the app is no longer idle, for a tick right away
+        match idle_rx.recv_timeout(rate) {
+          Ok(now) => idle = now,
+          Err(mpsc::RecvTimeoutError::Timeout) => {}
+          Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
       }
-      thread::sleep(tick_rate);
     });
 
-    Events { rx }
+    Events {
+      rx,
+      tx: external,
+      idle_tx,
+      idle: Cell::new(false),
+    }
+  }
+
+  /// Another source of events, like a chat or the other player
+  pub fn sender(&self) -> mpsc::Sender<Event<Key>> {
+    self.tx.clone()
   }
 
   pub fn next(&self) -> Result<Event<Key>, mpsc::RecvError> {
     self.rx.recv()
   }
+
+  /// Ticks far less often while the app is idle, and at the usual rate again right away
+  /// once it isn't
+  pub fn set_idle(&self, idle: bool) {
+    if self.idle.replace(idle) != idle {
+      // the tick thread only stops along with the app
+      let _ = self.idle_tx.send(idle);
+    }
+  }
+}
+
+/// Whether the terminal gained or lost the focus, from the sequence it reports it with
+fn focus_change(bytes: &[u8]) -> Option<bool> {
+  match bytes {
+    b"\x1b[I" => Some(true),
+    b"\x1b[O" => Some(false),
+    _ => None,
+  }
+}
+
+/// A terminal that reports when it gains and loses the focus, until it's dropped.
+/// Terminals that can't just never report it.
+pub struct FocusTerminal<W: Write> {
+  inner: W,
+}
+
+impl<W: Write> FocusTerminal<W> {
+  pub fn from(mut inner: W) -> io::Result<Self> {
+    inner.write_all(b"\x1b[?1004h")?;
+    inner.flush()?;
+    Ok(Self { inner })
+  }
+}
+
+impl<W: Write> Drop for FocusTerminal<W> {
+  fn drop(&mut self) {
+    let _ = self.inner.write_all(b"\x1b[?1004l");
+    let _ = self.inner.flush();
+  }
+}
+
+impl<W: Write> Write for FocusTerminal<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.inner.write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_focus_change() {
+    assert_eq!(focus_change(b"\x1b[I"), Some(true));
+    assert_eq!(focus_change(b"\x1b[O"), Some(false));
+    assert_eq!(focus_change(b"\x1b[Z"), None);
+  }
 }
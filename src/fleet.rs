@@ -0,0 +1,133 @@
+//! Fleets of ships the user drew themselves, read from a TOML file given with
+//! `--fleet-file`. Every ship is a grid of `*` for its cells and `.` for the gaps:
+//!
+//! ```toml
+//! [[ship]]
+//! name = "Tug"
+//! shape = [
+//!   "**.",
+//!   ".**",
+//! ]
+//! ```
+//!
+//! A ship may also be given an ability, `ability = "strike"` for a carrier that launches
+//! a strike on three cells in a line every five turns while it is afloat, or
+//! `ability = "ping"` for a submarine that tells how far the nearest ship is.
+//!
+//! Both sides get the same ships. A match written down with such a fleet keeps the shapes,
+//! so it can be resumed or replayed without the file.
+
+use std::{
+  fs,
+  io::{self, ErrorKind},
+  path::Path,
+};
+
+use serde::Deserialize;
+
+use super::game::{FleetType, ShipType};
+
+#[derive(Deserialize)]
+struct FleetFile {
+  ship: Vec<ShipEntry>,
+}
+
+#[derive(Deserialize)]
+struct ShipEntry {
+  name: String,
+  shape: Vec<String>,
+  #[serde(default)]
+  ability: Option<String>,
+}
+
+pub fn load(path: &Path) -> io::Result<FleetType> {
+  parse(&fs::read_to_string(path)?)
+}
+
+fn parse(content: &str) -> io::Result<FleetType> {
+  let invalid = |err: String| io::Error::new(ErrorKind::InvalidData, format!("fleet: {}", err));
+  let file: FleetFile = toml::from_str(content).map_err(|err| invalid(err.to_string()))?;
+  let ships = file
+    .ship
+    .iter()
+    .map(|ship| {
+      let rows = ship.shape.iter().map(|row| row.trim()).collect::<Vec<_>>();
+      let ship_type = ShipType::custom(&ship.name, &rows)?;
+      Ok(match &ship.ability {
+        Some(ability) => ship_type.with_ability(ability.parse()?),
+        None => ship_type,
+      })
+    })
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(invalid)?;
+  FleetType::custom(ships).map_err(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::Ability;
+
+  #[test]
+  fn test_fleet_parse() {
+    let fleet = parse(
+      r#"
+        [[ship]]
+        name = "Tug"
+        shape = ["**.", ".**"]
+
+        [[ship]]
+        name = "Raft"
+        shape = ["**"]
+
+        [[ship]]
+        name = "Carrier"
+        shape = ["***"]
+        ability = "strike"
+      "#,
+    )
+    .unwrap();
+    let ships = fleet.ships();
+    assert_eq!(ships.len(), 3);
+    assert_eq!(ships[0].name(), "Tug");
+    assert_eq!(ships[0].to_string(), "Tug=**.|.**");
+    assert_eq!(ships[0].to_string().parse::<ShipType>().unwrap(), ships[0]);
+    assert_eq!(ships[2].ability(), Some(Ability::Strike));
+    assert_eq!(ships[2].to_string(), "Carrier=***+strike");
+    assert_eq!(ships[2].to_string().parse::<ShipType>().unwrap(), ships[2]);
+
+    let err = |content: &str| parse(content).unwrap_err().to_string();
+    assert_eq!(
+      err("[[ship]]\nname = \"Tug\"\nshape = [\"*#\"]"),
+      "fleet: the Tug has an unknown cell '#'"
+    );
+    assert_eq!(
+      err("[[ship]]\nname = \"Tug\"\nshape = [\"**\", \"*\"]"),
+      "fleet: the Tug isn't drawn as rows of equal length"
+    );
+    assert_eq!(
+      err("[[ship]]\nname = \"Tug\"\nshape = [\"..\"]"),
+      "fleet: the Tug has no cells"
+    );
+    assert_eq!(
+      err("[[ship]]\nname = \"Long\"\nshape = [\"***********\"]"),
+      "fleet: the Long spans 1x11 cells, more than fits the board"
+    );
+    assert_eq!(
+      err("[[ship]]\nname = \"Tug boat\"\nshape = [\"**\"]"),
+      "fleet: invalid ship name \"Tug boat\""
+    );
+    assert_eq!(
+      err("[[ship]]\nname = \"Tug\"\nshape = [\"**\"]\nability = \"fly\""),
+      "fleet: unknown ability fly"
+    );
+    let slab = format!(
+      "[[ship]]\nname = \"Slab\"\nshape = {:?}",
+      vec!["*******"; 8]
+    );
+    assert_eq!(
+      err(&slab),
+      "fleet: the ships take up 56 cells with their outlines, more than half of the board"
+    );
+  }
+}
@@ -0,0 +1,52 @@
+//! Endless mode where a fresh fleet takes the place of every fleet the user sinks.
+
+use super::game::Difficulty;
+
+/// Misses allowed against the first fleet
+const FIRST_MISSES: usize = 50;
+/// How many misses fewer each further fleet allows
+const MISSES_STEP: usize = 5;
+const MIN_MISSES: usize = 15;
+
+#[derive(PartialEq, Debug, Default)]
+pub struct Gauntlet {
+  /// fleets sunk so far
+  pub cleared: usize,
+}
+
+impl Gauntlet {
+  /// The first fleet fires at random, the ones after it hunt down hits
+  pub fn difficulty(&self) -> Difficulty {
+    if self.cleared == 0 {
+      Difficulty::Easy
+    } else {
+      Difficulty::Hard
+    }
+  }
+
+  /// Misses the user may make against the current fleet before the gauntlet ends
+  pub fn miss_allowance(&self) -> usize {
+    FIRST_MISSES
+      .saturating_sub(self.cleared * MISSES_STEP)
+      .max(MIN_MISSES)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_gauntlet_gets_harder() {
+    let mut gauntlet = Gauntlet::default();
+    assert_eq!(gauntlet.difficulty(), Difficulty::Easy);
+    assert_eq!(gauntlet.miss_allowance(), 50);
+
+    gauntlet.cleared = 2;
+    assert_eq!(gauntlet.difficulty(), Difficulty::Hard);
+    assert_eq!(gauntlet.miss_allowance(), 40);
+
+    gauntlet.cleared = 100;
+    assert_eq!(gauntlet.miss_allowance(), MIN_MISSES);
+  }
+}
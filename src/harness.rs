@@ -0,0 +1,119 @@
+//! A scripted test harness that drives the full TUI against a `TestBackend`.
+//!
+//! The harness mirrors the main loop: every key press or tick is followed by a
+//! redraw, so tests can assert both on the rendered frame and on the game state.
+
+use termion::event::Key;
+use tui::{backend::TestBackend, buffer::Buffer, Terminal};
+use unicode_width::UnicodeWidthStr;
+
+use super::{
+  app::App,
+  game::{Coordinate, Difficulty, Rule, COLS, ROWS},
+  ui,
+};
+
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 40;
+
+pub struct Harness {
+  pub app: App,
+  terminal: Terminal<TestBackend>,
+}
+
+impl Harness {
+  pub fn new(rule: Rule, difficulty: Difficulty) -> Self {
+    let terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+    let mut harness = Self {
+      app: App::new("Battleship.rs".into(), rule, difficulty),
+      terminal,
+    };
+    harness.draw();
+    harness
+  }
+
+  pub fn draw(&mut self) -> &mut Self {
+    let app = &mut self.app;
+    self.terminal.draw(|f| ui::draw(f, app)).unwrap();
+    self
+  }
+
+  pub fn press(&mut self, key: Key) -> &mut Self {
+    self.app.on_key(key);
+    self.draw()
+  }
+
+  /// Sends every char of the script as a key press, so `"jj l\n"` moves down
+  /// twice, selects, moves right and fires.
+  pub fn keys(&mut self, script: &str) -> &mut Self {
+    for c in script.chars() {
+      self.press(Key::Char(c));
+    }
+    self
+  }
+
+  pub fn tick(&mut self) -> &mut Self {
+    self.app.on_tick();
+    self.draw()
+  }
+
+  pub fn ticks(&mut self, count: usize) -> &mut Self {
+    for _ in 0..count {
+      self.tick();
+    }
+    self
+  }
+
+  /// Moves the cursor to the given opponent board coordinate from wherever it is.
+  pub fn move_to(&mut self, (row, col): Coordinate) -> &mut Self {
+    let script = format!(
+      "{}{}{}{}",
+      "k".repeat(ROWS),
+      "h".repeat(COLS),
+      "j".repeat(row),
+      "l".repeat(col)
+    );
+    self.keys(&script)
+  }
+
+  /// Ticks until it is the user's turn again, as the bot only fires on some frames.
+  pub fn wait_for_turn(&mut self) -> &mut Self {
+    while !self.app.game().is_user_turn() && !self.app.is_won() {
+      self.tick();
+    }
+    self
+  }
+
+  pub fn resize(&mut self, width: u16, height: u16) -> &mut Self {
+    self.terminal.backend_mut().resize(width, height);
+    self.draw()
+  }
+
+  pub fn buffer(&self) -> &Buffer {
+    self.terminal.backend().buffer()
+  }
+
+  /// The last rendered frame as plain text lines, skipping the cells hidden by wide symbols.
+  pub fn frame(&self) -> Vec<String> {
+    let buffer = self.buffer();
+    buffer
+      .content()
+      .chunks(buffer.area().width as usize)
+      .map(|cells| {
+        let mut line = String::new();
+        let mut skip = 0;
+        for cell in cells {
+          if skip == 0 {
+            line.push_str(&cell.symbol);
+          }
+          skip = std::cmp::max(skip, cell.symbol.width()).saturating_sub(1);
+        }
+        line
+      })
+      .collect()
+  }
+
+  pub fn frame_contains(&self, text: &str) -> bool {
+    self.frame().iter().any(|line| line.contains(text))
+  }
+}
@@ -0,0 +1,80 @@
+//! How the commands run without the terminal UI, `verify-replay` and `bot-match`, end: with
+//! an exit code that stays the same from one release to the next, and a result line of
+//! JSON printed last, so scripts and tournament harnesses can depend on both.
+
+use std::process;
+
+use serde_json::{json, Value};
+
+/// How a command run without the terminal UI ended
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Exit {
+  /// the command did what it was asked
+  Success,
+  /// a match couldn't be read or played, or didn't play out as recorded
+  EngineError,
+  /// the command was given the wrong options
+  UsageError,
+  /// a bot didn't answer in time
+  Timeout,
+  /// a bot answered with anything but legal shots, or stopped answering
+  ProtocolError,
+}
+
+impl Exit {
+  pub fn code(&self) -> i32 {
+    match self {
+      Exit::Success => 0,
+      Exit::EngineError => 1,
+      Exit::UsageError => 2,
+      Exit::Timeout => 3,
+      Exit::ProtocolError => 4,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Exit::Success => "success",
+      Exit::EngineError => "engine-error",
+      Exit::UsageError => "usage-error",
+      Exit::Timeout => "timeout",
+      Exit::ProtocolError => "protocol-error",
+    }
+  }
+}
+
+/// The result line, the status and the code along with what the command has to tell
+fn result_line(exit: Exit, fields: Value) -> String {
+  let mut result = json!({ "status": exit.name(), "code": exit.code() });
+  if let (Some(result), Value::Object(fields)) = (result.as_object_mut(), fields) {
+    result.extend(fields);
+  }
+  result.to_string()
+}
+
+/// Prints the result line and exits with the code
+pub fn finish(exit: Exit, fields: Value) -> ! {
+  println!("{}", result_line(exit, fields));
+  process::exit(exit.code())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_result_line() {
+    assert_eq!(
+      result_line(Exit::Success, json!({ "turns": 12 })),
+      r#"{"code":0,"status":"success","turns":12}"#
+    );
+    assert_eq!(
+      result_line(Exit::Timeout, json!({ "error": "too slow" })),
+      r#"{"code":3,"error":"too slow","status":"timeout"}"#
+    );
+    assert_eq!(
+      result_line(Exit::ProtocolError, Value::Null),
+      r#"{"code":4,"status":"protocol-error"}"#
+    );
+  }
+}
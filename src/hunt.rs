@@ -0,0 +1,146 @@
+//! How the computer saw the user's fleet over a finished match: what it had found and
+//! where it expected the rest of the ships after each turn, rebuilt from its memory.
+
+use super::{
+  game::{Game, Status, COLS, ROWS},
+  replay::Replay,
+};
+
+const COLUMN_LETTERS: &str = "ABCDEFGHIJ";
+
+/// Width the tips are wrapped to, to fit the popup
+const TIP_WIDTH: usize = 36;
+
+pub struct BotView {
+  replay: Replay,
+  /// habits of the user's placement over the recent matches
+  tips: Vec<String>,
+}
+
+impl BotView {
+  /// Opens the view at the end of the match
+  pub fn new(game: Game, tips: Vec<String>) -> Self {
+    let step = game.timeline().len();
+    Self {
+      replay: Replay::new(game, step),
+      tips,
+    }
+  }
+
+  pub fn go(&mut self, by: isize) {
+    self.replay.go(by);
+  }
+
+  pub fn go_to_start(&mut self) {
+    self.replay.go_to(0);
+  }
+
+  pub fn go_to_end(&mut self) {
+    self.replay.go_to(self.replay.len());
+  }
+
+  /// The computer's view of the user's board after the current turn, unexplored cells
+  /// showing how likely it held them to hide a ship from 1 to 9
+  pub fn lines(&self) -> Vec<String> {
+    let game = self.replay.at();
+    let computer = game.computer();
+    let memory = computer.memory();
+    let heat = memory.heatmap();
+    let ruled_out = memory.ruled_out();
+    let hottest = heat.values().copied().max().unwrap_or_default().max(1);
+
+    let mut lines = vec![format!(
+      "Turn {} of {}",
+      self.replay.step,
+      self.replay.len()
+    )];
+    let letters = COLUMN_LETTERS.chars().map(String::from).collect::<Vec<_>>();
+    lines.push(format!("   {}", letters.join(" ")));
+    for row in 0..ROWS {
+      let cells = (0..COLS)
+        .map(|col| {
+          let cell = (row, col);
+          match computer.opponent_board().status_at(cell) {
+            Status::Space | Status::Live if ruled_out.contains(&cell) => '·',
+            Status::Space | Status::Live => match heat.get(&cell) {
+              Some(h) => std::char::from_digit((1 + h * 8 / hottest) as u32, 10).unwrap_or('9'),
+              None => '·',
+            },
+            status => status.as_char(),
+          }
+        })
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>();
+      lines.push(format!("{:>2} {}", row + 1, cells.join(" ")));
+    }
+    lines.push(String::new());
+    lines.push("x hit  X sunk  . miss  · no ship".into());
+    lines.push("1-9 where it expected your ships".into());
+    if !self.tips.is_empty() {
+      lines.push(String::new());
+      lines.extend(self.tips.iter().flat_map(|tip| wrap(tip, TIP_WIDTH)));
+    }
+    lines.push("🠔 🠖 turn | g first | G last | <esc> close".into());
+    lines
+  }
+}
+
+/// Breaks the text into lines of whole words no longer than the width, unless a word is
+fn wrap(text: &str, width: usize) -> Vec<String> {
+  let mut lines = vec![];
+  let mut line = String::new();
+  for word in text.split_whitespace() {
+    if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+      lines.push(std::mem::take(&mut line));
+    }
+    if !line.is_empty() {
+      line.push(' ');
+    }
+    line.push_str(word);
+  }
+  if !line.is_empty() {
+    lines.push(line);
+  }
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::{Difficulty, FleetType, Rule};
+
+  #[test]
+  fn test_bot_view() {
+    let mut game = Game::seeded(Rule::Default, Difficulty::Hard, FleetType::Standard, 7);
+    while !game.is_won() {
+      if game.is_user_turn() {
+        let shots = game.legal_shots(0).into_iter().take(1).collect();
+        game.fire(&shots, false);
+      } else {
+        game.bot_fire();
+      }
+    }
+    let mut view = BotView::new(game, vec![]);
+    let end = view.lines();
+    assert!(end[0].ends_with(&format!("of {}", view.replay.len())));
+    assert_eq!(end[1], "   A B C D E F G H I J");
+    assert_eq!(end.len(), ROWS + 6);
+
+    view.go_to_start();
+    let start = view.lines();
+    assert!(start[0].starts_with("Turn 0 of"));
+    // nothing is known before the first shot
+    assert!(start[2..2 + ROWS]
+      .iter()
+      .all(|line| !line[3..].contains(['x', 'X', '.'])));
+
+    assert_eq!(
+      wrap("The computer finds your Scout first", 16),
+      vec!["The computer", "finds your Scout", "first"]
+    );
+
+    view.go(1);
+    view.go(-2);
+    assert_eq!(view.lines(), start);
+  }
+}
@@ -0,0 +1,444 @@
+//! The keys the game is played with. They can be changed in `keys.toml` in the config
+//! directory, which names the keys of each action it rebinds:
+//!
+//! ```toml
+//! fire = ["enter", "x"]
+//! quit = "ctrl-q"
+//! ```
+//!
+//! An action named in the file loses its usual keys. Counts on the digits, tabs on the
+//! function keys, the ability bar on `alt-1` to `alt-4` and quitting on `ctrl-c` stay where
+//! they are. The actions of the end screen, like `rematch`, may share keys with the ones of
+//! play, like `rotate`, as they're never pressed at the same time.
+
+use std::{
+  collections::{BTreeMap, HashMap},
+  fs,
+  io::{self, ErrorKind},
+  path::PathBuf,
+};
+
+use termion::event::Key;
+
+/// What a key press asks the app to do
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Action {
+  Count(u8),
+  Quit,
+  Up,
+  Down,
+  Left,
+  Right,
+  RowStart,
+  RowEnd,
+  Top,
+  Bottom,
+  Center,
+  SwitchFocus,
+  Inspect,
+  Sandbox,
+  Devtools,
+  BotView,
+  /// play the match just over again on the same fleets
+  RunBack,
+  /// a fresh match like the one just over
+  Rematch,
+  /// write a note on the match just over
+  Note,
+  /// move to the match in the tab
+  Tab(usize),
+  Rotate,
+  Save,
+  Pause,
+  Cancel,
+  Select,
+  Fire,
+  Sonar,
+  Ping,
+  Airstrike,
+  Strike,
+  /// aim the power-up in the slot of the ability bar
+  Ability(usize),
+  /// flip the ships placed so far left to right
+  MirrorAcross,
+  /// flip the ships placed so far top to bottom
+  MirrorDown,
+  /// place the rest of the fleet symmetrically
+  Fill,
+  /// make the ship being placed the flagship
+  Flagship,
+  /// name the combination of the sandbox to save it as a preset
+  NamePreset,
+  /// scroll the log back to older turns
+  LogUp,
+  /// scroll the log on to newer turns
+  LogDown,
+}
+
+impl Action {
+  pub fn is_movement(&self) -> bool {
+    matches!(
+      self,
+      Action::Up | Action::Down | Action::Left | Action::Right
+    )
+  }
+
+  /// Where the keys of the action do something
+  fn scope(&self) -> Scope {
+    match self {
+      Action::Rematch | Action::Note => Scope::GameOver,
+      Action::Quit | Action::RunBack | Action::BotView | Action::Devtools | Action::Save => {
+        Scope::Any
+      }
+      _ => Scope::Play,
+    }
+  }
+}
+
+/// Where the keys of an action do something, two actions may share a key where they don't
+/// meet
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+enum Scope {
+  Any,
+  /// the boards of a match in play, and the views over them
+  Play,
+  /// the end screen of a match
+  GameOver,
+}
+
+impl Scope {
+  fn meets(&self, other: &Scope) -> bool {
+    self == other || *self == Scope::Any || *other == Scope::Any
+  }
+}
+
+/// The actions that can be rebound, by the name the keys file knows them by, with their
+/// usual keys
+const BINDINGS: [(&str, Action, &[Key]); 35] = [
+  ("quit", Action::Quit, &[Key::Char('q')]),
+  ("up", Action::Up, &[Key::Up, Key::Char('k')]),
+  ("down", Action::Down, &[Key::Down, Key::Char('j')]),
+  ("left", Action::Left, &[Key::Left, Key::Char('h')]),
+  ("right", Action::Right, &[Key::Right, Key::Char('l')]),
+  ("row-start", Action::RowStart, &[Key::Char('^')]),
+  ("row-end", Action::RowEnd, &[Key::Char('$')]),
+  ("top", Action::Top, &[Key::Char('g')]),
+  ("bottom", Action::Bottom, &[Key::Char('G')]),
+  ("center", Action::Center, &[Key::Char('c')]),
+  ("switch-focus", Action::SwitchFocus, &[Key::Char('\t')]),
+  ("inspect", Action::Inspect, &[Key::Char('i')]),
+  ("sandbox", Action::Sandbox, &[Key::Char('o')]),
+  ("devtools", Action::Devtools, &[Key::Char('D')]),
+  ("bot-view", Action::BotView, &[Key::Char('b')]),
+  ("rotate", Action::Rotate, &[Key::Char('r')]),
+  ("run-back", Action::RunBack, &[Key::Char('R')]),
+  ("rematch", Action::Rematch, &[Key::Char('r')]),
+  ("note", Action::Note, &[Key::Char('n')]),
+  ("save", Action::Save, &[Key::Ctrl('s')]),
+  ("pause", Action::Pause, &[Key::Char('p'), Key::Char('?')]),
+  ("cancel", Action::Cancel, &[Key::Esc]),
+  ("select", Action::Select, &[Key::Char(' ')]),
+  ("fire", Action::Fire, &[Key::Char('\n')]),
+  ("sonar", Action::Sonar, &[Key::Char('s')]),
+  ("ping", Action::Ping, &[Key::Char('n')]),
+  ("airstrike", Action::Airstrike, &[Key::Char('a')]),
+  ("strike", Action::Strike, &[Key::Char('f')]),
+  ("mirror-across", Action::MirrorAcross, &[Key::Char('m')]),
+  ("mirror-down", Action::MirrorDown, &[Key::Char('M')]),
+  ("fill", Action::Fill, &[Key::Char('S')]),
+  ("flagship", Action::Flagship, &[Key::Char('F')]),
+  ("name-preset", Action::NamePreset, &[Key::Char('w')]),
+  ("log-up", Action::LogUp, &[Key::PageUp]),
+  ("log-down", Action::LogDown, &[Key::PageDown]),
+];
+
+/// The keys no keys file can take, with the name of what they do in its errors
+fn fixed(key: Key) -> Option<(&'static str, Action)> {
+  match key {
+    Key::Char(c @ '0'..='9') => c.to_digit(10).map(|d| ("count", Action::Count(d as u8))),
+    Key::Ctrl('c') => Some(("quit", Action::Quit)),
+    Key::F(n) => Some(("tab", Action::Tab(usize::from(n).saturating_sub(1)))),
+    Key::Alt(c @ '1'..='4') => c
+      .to_digit(10)
+      .map(|d| ("ability", Action::Ability(d as usize - 1))),
+    _ => None,
+  }
+}
+
+/// Which action each key stands for, in play and on the end screen
+#[derive(Debug, Clone)]
+pub struct Keymap {
+  bindings: HashMap<Key, Action>,
+  game_over: HashMap<Key, Action>,
+}
+
+impl Default for Keymap {
+  fn default() -> Self {
+    Self::with(&BTreeMap::new()).expect("the usual keys don't clash")
+  }
+}
+
+impl Keymap {
+  /// The usual keys with the ones of the named actions swapped for the given ones, refusing a
+  /// key bound to two actions
+  fn with(rebound: &BTreeMap<String, Vec<Key>>) -> Result<Self, String> {
+    if let Some(name) = rebound
+      .keys()
+      .find(|name| !BINDINGS.iter().any(|(n, ..)| n == name))
+    {
+      return Err(format!("unknown action {}", name));
+    }
+    let mut bindings = HashMap::new();
+    let mut game_over = HashMap::new();
+    let mut names: HashMap<Key, Vec<(&str, Scope)>> = HashMap::new();
+    for (name, action, keys) in BINDINGS.iter() {
+      let scope = action.scope();
+      for key in rebound.get(*name).map_or(*keys, |keys| keys.as_slice()) {
+        let taken = fixed(*key).map(|(name, _)| name).or_else(|| {
+          names
+            .get(key)
+            .and_then(|taken| taken.iter().find(|(_, other)| other.meets(&scope)))
+            .map(|(name, _)| *name)
+        });
+        if let Some(other) = taken {
+          return Err(format!(
+            "the key {} is bound to both {} and {}",
+            key_name(*key),
+            other,
+            name
+          ));
+        }
+        if scope != Scope::GameOver {
+          bindings.insert(*key, *action);
+        }
+        if scope != Scope::Play {
+          game_over.insert(*key, *action);
+        }
+        names.entry(*key).or_default().push((*name, scope));
+      }
+    }
+    Ok(Self {
+      bindings,
+      game_over,
+    })
+  }
+
+  pub fn action(&self, key: Key) -> Option<Action> {
+    fixed(key)
+      .map(|(_, action)| action)
+      .or_else(|| self.bindings.get(&key).copied())
+  }
+
+  /// The action of the key on the end screen of a match
+  pub fn game_over_action(&self, key: Key) -> Option<Action> {
+    fixed(key)
+      .map(|(_, action)| action)
+      .or_else(|| self.game_over.get(&key).copied())
+  }
+}
+
+/// A key by its name in the keys file, like `k`, `enter`, `ctrl-q` or `f5`
+fn parse_key(name: &str) -> Result<Key, String> {
+  let single = |rest: &str| {
+    let mut chars = rest.chars();
+    match (chars.next(), chars.next()) {
+      (Some(c), None) => Some(c),
+      _ => None,
+    }
+  };
+  let key = match name {
+    "enter" => Key::Char('\n'),
+    "space" => Key::Char(' '),
+    "tab" => Key::Char('\t'),
+    "esc" => Key::Esc,
+    "backspace" => Key::Backspace,
+    "up" => Key::Up,
+    "down" => Key::Down,
+    "left" => Key::Left,
+    "right" => Key::Right,
+    "pageup" => Key::PageUp,
+    "pagedown" => Key::PageDown,
+    _ => match single(name) {
+      Some(c) => Key::Char(c),
+      None => {
+        if let Some(c) = name.strip_prefix("ctrl-").and_then(single) {
+          Key::Ctrl(c)
+        } else if let Some(c) = name.strip_prefix("alt-").and_then(single) {
+          Key::Alt(c)
+        } else if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+          Key::F(n)
+        } else {
+          return Err(format!("unknown key {:?}", name));
+        }
+      }
+    },
+  };
+  Ok(key)
+}
+
+/// The name of a key as the keys file writes it
+fn key_name(key: Key) -> String {
+  match key {
+    Key::Char('\n') => "enter".into(),
+    Key::Char(' ') => "space".into(),
+    Key::Char('\t') => "tab".into(),
+    Key::Esc => "esc".into(),
+    Key::Backspace => "backspace".into(),
+    Key::Up => "up".into(),
+    Key::Down => "down".into(),
+    Key::Left => "left".into(),
+    Key::Right => "right".into(),
+    Key::PageUp => "pageup".into(),
+    Key::PageDown => "pagedown".into(),
+    Key::Char(c) => c.to_string(),
+    Key::Ctrl(c) => format!("ctrl-{}", c),
+    Key::Alt(c) => format!("alt-{}", c),
+    Key::F(n) => format!("f{}", n),
+    key => format!("{:?}", key),
+  }
+}
+
+fn parse(content: &str) -> Result<Keymap, String> {
+  let entries: BTreeMap<String, toml::Value> =
+    toml::from_str(content).map_err(|err| err.to_string())?;
+  let mut rebound = BTreeMap::new();
+  for (action, value) in entries {
+    let names = match value {
+      toml::Value::String(name) => vec![name],
+      toml::Value::Array(names) => names
+        .into_iter()
+        .map(|name| match name {
+          toml::Value::String(name) => Ok(name),
+          _ => Err(format!("the keys of {} aren't all names", action)),
+        })
+        .collect::<Result<_, _>>()?,
+      _ => return Err(format!("the keys of {} aren't names", action)),
+    };
+    let keys = names
+      .iter()
+      .map(|name| parse_key(name))
+      .collect::<Result<Vec<_>, _>>()?;
+    rebound.insert(action, keys);
+  }
+  Keymap::with(&rebound)
+}
+
+/// The file with the user's keys
+pub struct KeyFile {
+  path: PathBuf,
+}
+
+impl KeyFile {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  /// The keys file in the user's config directory, if the platform has one
+  pub fn in_config_dir() -> Option<Self> {
+    dirs::config_dir().map(|dir| Self::new(dir.join("battleship-rs").join("keys.toml")))
+  }
+
+  /// The keymap of the file, the usual one when there is no file
+  pub fn load(&self) -> io::Result<Keymap> {
+    if !self.path.exists() {
+      return Ok(Keymap::default());
+    }
+    parse(&fs::read_to_string(&self.path)?)
+      .map_err(|err| io::Error::new(ErrorKind::InvalidData, format!("keys: {}", err)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use uuid::Uuid;
+
+  use super::*;
+
+  #[test]
+  fn test_keymap_default() {
+    let keymap = Keymap::default();
+    assert_eq!(keymap.action(Key::Char('k')), Some(Action::Up));
+    assert_eq!(keymap.action(Key::Char('\n')), Some(Action::Fire));
+    assert_eq!(keymap.action(Key::Char('7')), Some(Action::Count(7)));
+    assert_eq!(keymap.action(Key::F(2)), Some(Action::Tab(1)));
+    assert_eq!(keymap.action(Key::Alt('4')), Some(Action::Ability(3)));
+    assert_eq!(keymap.action(Key::Ctrl('c')), Some(Action::Quit));
+    assert_eq!(keymap.action(Key::Char('z')), None);
+    assert_eq!(keymap.action(Key::Char('^')), Some(Action::RowStart));
+    // the same key does another thing on the end screen
+    assert_eq!(keymap.action(Key::Char('r')), Some(Action::Rotate));
+    assert_eq!(
+      keymap.game_over_action(Key::Char('r')),
+      Some(Action::Rematch)
+    );
+    assert_eq!(keymap.game_over_action(Key::Char('n')), Some(Action::Note));
+    assert_eq!(keymap.game_over_action(Key::Char('q')), Some(Action::Quit));
+    assert_eq!(keymap.game_over_action(Key::Char('k')), None);
+  }
+
+  #[test]
+  fn test_keymap_parse() {
+    let keymap = parse("fire = [\"enter\", \"x\"]\nquit = \"ctrl-q\"\nup = [\"w\"]").unwrap_err();
+    // w names a preset in the sandbox
+    assert_eq!(keymap, "the key w is bound to both up and name-preset");
+
+    let keymap = parse("fire = [\"enter\", \"x\"]\nquit = \"ctrl-q\"\nup = \"up\"").unwrap();
+    assert_eq!(keymap.action(Key::Char('x')), Some(Action::Fire));
+    assert_eq!(keymap.action(Key::Ctrl('q')), Some(Action::Quit));
+    assert_eq!(keymap.action(Key::Char('q')), None);
+    assert_eq!(keymap.action(Key::Char('k')), None);
+    assert_eq!(keymap.action(Key::Up), Some(Action::Up));
+
+    assert_eq!(
+      parse("fire = \"s\"").unwrap_err(),
+      "the key s is bound to both fire and sonar"
+    );
+    assert_eq!(
+      parse("fire = \"5\"").unwrap_err(),
+      "the key 5 is bound to both count and fire"
+    );
+    assert_eq!(parse("jump = \"x\"").unwrap_err(), "unknown action jump");
+    assert_eq!(
+      parse("fire = \"shift-x\"").unwrap_err(),
+      "unknown key \"shift-x\""
+    );
+    assert!(parse("fire = 3").is_err());
+
+    // moving rotate leaves rematch where it was
+    let keymap = parse("rotate = \"t\"\nnote = \"x\"").unwrap();
+    assert_eq!(keymap.action(Key::Char('t')), Some(Action::Rotate));
+    assert_eq!(keymap.action(Key::Char('r')), None);
+    assert_eq!(
+      keymap.game_over_action(Key::Char('r')),
+      Some(Action::Rematch)
+    );
+    assert_eq!(keymap.game_over_action(Key::Char('x')), Some(Action::Note));
+    assert_eq!(keymap.game_over_action(Key::Char('n')), None);
+    assert_eq!(
+      parse("rematch = \"q\"").unwrap_err(),
+      "the key q is bound to both quit and rematch"
+    );
+    assert_eq!(parse_key("f12"), Ok(Key::F(12)));
+    assert_eq!(parse_key("alt-x"), Ok(Key::Alt('x')));
+  }
+
+  #[test]
+  fn test_keymap_file() {
+    let dir = env::temp_dir().join(format!("battleship-{}", Uuid::new_v4()));
+    let file = KeyFile::new(dir.join("keys.toml"));
+    assert_eq!(
+      file.load().unwrap().action(Key::Char('q')),
+      Some(Action::Quit)
+    );
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&file.path, "select = \"x\"\nfire = \"x\"\n").unwrap();
+    assert_eq!(
+      file.load().unwrap_err().to_string(),
+      "keys: the key x is bound to both select and fire"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,208 @@
+//! Finds matches hosted on the local network. A host answers the UDP broadcasts of players
+//! looking for a match on a well known port, and `--join auto` lists the hosts that answered
+//! in a lobby to pick one from.
+
+use std::{
+  io::{self, ErrorKind},
+  net::{Ipv4Addr, SocketAddr, UdpSocket},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+use termion::event::Key;
+
+use super::game::Rule;
+
+/// The port hosts listen on for players looking for a match
+const DISCOVERY_PORT: u16 = 7879;
+const QUERY: &str = "BATTLESHIP?";
+/// How long to wait for the hosts to answer
+const WAIT: Duration = Duration::from_secs(1);
+
+/// A match waiting for an opponent
+#[derive(PartialEq, Debug, Clone)]
+pub struct Host {
+  pub addr: SocketAddr,
+  pub rule: Rule,
+}
+
+/// Answers players looking for a match until dropped
+pub struct Announcement {
+  stop: Arc<AtomicBool>,
+}
+
+impl Drop for Announcement {
+  fn drop(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+  }
+}
+
+/// Tells players on the local network about the match hosted on the port
+pub fn announce(port: u16, rule: Rule) -> io::Result<Announcement> {
+  answer(DISCOVERY_PORT, port, rule)
+}
+
+fn answer(discovery_port: u16, port: u16, rule: Rule) -> io::Result<Announcement> {
+  let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, discovery_port))?;
+  // wake up now and then to notice the announcement is over
+  socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+  let stop = Arc::new(AtomicBool::new(false));
+  let stopped = Arc::clone(&stop);
+  thread::spawn(move || {
+    let mut buf = [0; 64];
+    while !stopped.load(Ordering::Relaxed) {
+      if let Ok((len, from)) = socket.recv_from(&mut buf) {
+        if &buf[..len] == QUERY.as_bytes() {
+          let _ = socket.send_to(format!("BATTLESHIP {} {}", port, rule).as_bytes(), from);
+        }
+      }
+    }
+  });
+  Ok(Announcement { stop })
+}
+
+/// Asks the local network for hosted matches
+pub fn discover() -> io::Result<Vec<Host>> {
+  query((Ipv4Addr::BROADCAST, DISCOVERY_PORT).into())
+}
+
+fn query(to: SocketAddr) -> io::Result<Vec<Host>> {
+  let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+  socket.set_broadcast(true)?;
+  socket.send_to(QUERY.as_bytes(), to)?;
+
+  let deadline = Instant::now() + WAIT;
+  let mut hosts = vec![];
+  let mut buf = [0; 64];
+  while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+    socket.set_read_timeout(Some(left.max(Duration::from_millis(1))))?;
+    let (len, from) = match socket.recv_from(&mut buf) {
+      Ok(received) => received,
+      Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => break,
+      Err(err) => return Err(err),
+    };
+    if let Some(host) = parse_answer(&String::from_utf8_lossy(&buf[..len]), from) {
+      if !hosts.contains(&host) {
+        hosts.push(host);
+      }
+    }
+  }
+  Ok(hosts)
+}
+
+/// The host behind an answer like `BATTLESHIP 7878 Fury`, at the port it names
+fn parse_answer(answer: &str, from: SocketAddr) -> Option<Host> {
+  let mut words = answer.split_whitespace();
+  if words.next() != Some("BATTLESHIP") {
+    return None;
+  }
+  let port = words.next()?.parse().ok()?;
+  let rule = words.next()?.parse().ok()?;
+  Some(Host {
+    addr: SocketAddr::new(from.ip(), port),
+    rule,
+  })
+}
+
+/// What the user did in the lobby
+#[derive(PartialEq, Debug)]
+pub enum Choice {
+  Join(SocketAddr),
+  Refresh,
+  Quit,
+}
+
+/// The hosts found on the network, one of which is picked
+#[derive(Default)]
+pub struct Lobby {
+  pub hosts: Vec<Host>,
+  pub selected: usize,
+}
+
+impl Lobby {
+  pub fn new(hosts: Vec<Host>) -> Self {
+    Self { hosts, selected: 0 }
+  }
+
+  pub fn on_key(&mut self, key: Key) -> Option<Choice> {
+    match key {
+      Key::Up | Key::Char('k') => self.selected = self.selected.saturating_sub(1),
+      Key::Down | Key::Char('j') => {
+        self.selected = (self.selected + 1).min(self.hosts.len().saturating_sub(1))
+      }
+      Key::Char('\n') => return self.hosts.get(self.selected).map(|h| Choice::Join(h.addr)),
+      Key::Char('r') => return Some(Choice::Refresh),
+      Key::Esc | Key::Char('q') | Key::Ctrl('c') => return Some(Choice::Quit),
+      _ => {}
+    }
+    None
+  }
+
+  /// One line per host, as listed on the lobby screen
+  pub fn lines(&self) -> Vec<String> {
+    if self.hosts.is_empty() {
+      return vec!["No matches found, <r> to look again".into()];
+    }
+    self
+      .hosts
+      .iter()
+      .enumerate()
+      .map(|(index, host)| {
+        let marker = if index == self.selected { ">" } else { " " };
+        format!("{} {:<21} {}", marker, host.addr, host.rule)
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_discovery() {
+    let probe = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let discovery_port = probe.local_addr().unwrap().port();
+    drop(probe);
+
+    let announcement = answer(discovery_port, 7878, Rule::Fury).unwrap();
+    let hosts = query((Ipv4Addr::LOCALHOST, discovery_port).into()).unwrap();
+    assert_eq!(
+      hosts,
+      vec![Host {
+        addr: (Ipv4Addr::LOCALHOST, 7878).into(),
+        rule: Rule::Fury
+      }]
+    );
+    drop(announcement);
+
+    let from = (Ipv4Addr::LOCALHOST, 1).into();
+    assert_eq!(parse_answer("BATTLESHIP 7878", from), None);
+    assert_eq!(parse_answer("HELLO 7878 Fury", from), None);
+  }
+
+  #[test]
+  fn test_lobby_keys() {
+    let host = |port| Host {
+      addr: (Ipv4Addr::LOCALHOST, port).into(),
+      rule: Rule::Default,
+    };
+    let mut lobby = Lobby::new(vec![host(1), host(2)]);
+    assert_eq!(lobby.on_key(Key::Char('j')), None);
+    assert_eq!(lobby.on_key(Key::Char('j')), None);
+    assert_eq!(lobby.lines()[1], "> 127.0.0.1:2           Default");
+    assert_eq!(
+      lobby.on_key(Key::Char('\n')),
+      Some(Choice::Join(host(2).addr))
+    );
+    assert_eq!(lobby.on_key(Key::Char('r')), Some(Choice::Refresh));
+
+    let mut empty = Lobby::default();
+    assert_eq!(empty.on_key(Key::Char('\n')), None);
+    assert_eq!(empty.on_key(Key::Char('q')), Some(Choice::Quit));
+  }
+}
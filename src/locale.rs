@@ -0,0 +1,85 @@
+//! Detects what the locale and terminal are capable of, to pick sensible defaults.
+//!
+//! Only the glyphs are picked this way. The language and the 24-hour clock the locale
+//! implies have nothing to apply to yet: there is no translation layer, and the log shows
+//! the time on the match clock rather than the time of day.
+
+use std::env;
+
+use structopt::clap::arg_enum;
+
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Glyphs {
+        Auto,  // emoji when the terminal and locale can show them
+        Emoji,
+        Ascii,
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Locale {
+  pub emoji: bool,
+}
+
+impl Locale {
+  pub fn detect(glyphs: Glyphs) -> Self {
+    Self::from_vars(glyphs, |name| env::var(name).ok())
+  }
+
+  fn from_vars(glyphs: Glyphs, var: impl Fn(&str) -> Option<String>) -> Self {
+    let emoji = match glyphs {
+      Glyphs::Emoji => true,
+      Glyphs::Ascii => false,
+      Glyphs::Auto => {
+        // the first of these that is set decides the character set, as in POSIX
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+          .iter()
+          .filter_map(|name| var(name))
+          .find(|value| !value.is_empty())
+          .unwrap_or_default()
+          .to_lowercase();
+        let term = var("TERM").unwrap_or_default();
+        let utf8 = locale.contains("utf-8") || locale.contains("utf8");
+        // the linux console and dumb terminals have no glyphs for emoji
+        utf8 && term != "linux" && term != "dumb"
+      }
+    };
+    Self { emoji }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use super::*;
+
+  fn detect(glyphs: Glyphs, vars: &[(&str, &str)]) -> Locale {
+    let vars = vars
+      .iter()
+      .map(|(k, v)| (k.to_string(), v.to_string()))
+      .collect::<HashMap<_, _>>();
+    Locale::from_vars(glyphs, |name| vars.get(name).cloned())
+  }
+
+  #[test]
+  fn test_locale_detect_emoji() {
+    let utf8 = [("LANG", "de_DE.UTF-8"), ("TERM", "xterm-256color")];
+    assert!(detect(Glyphs::Auto, &utf8).emoji);
+    assert!(!detect(Glyphs::Ascii, &utf8).emoji);
+
+    assert!(!detect(Glyphs::Auto, &[("LANG", "C"), ("TERM", "xterm")]).emoji);
+    assert!(!detect(Glyphs::Auto, &[("LANG", "en_US.utf8"), ("TERM", "linux")]).emoji);
+    assert!(!detect(Glyphs::Auto, &[]).emoji);
+    assert!(detect(Glyphs::Emoji, &[]).emoji);
+
+    // LC_ALL wins over LANG
+    let vars = [
+      ("LC_ALL", "POSIX"),
+      ("LANG", "en_US.UTF-8"),
+      ("TERM", "xterm"),
+    ];
+    assert!(!detect(Glyphs::Auto, &vars).emoji);
+  }
+}
@@ -1,25 +1,72 @@
+mod advice;
+mod alert;
 mod app;
+mod bot_cmd;
+#[cfg(feature = "chat")]
+mod chat;
+mod clock;
 mod event;
-mod game;
+mod fleet;
+mod gauntlet;
+#[cfg(test)]
+mod harness;
+mod headless;
+mod hunt;
+mod keymap;
+mod lobby;
+mod locale;
+mod net;
+mod persona;
+mod placement;
+mod preset;
+mod replay;
+mod sandbox;
+mod save;
+mod session;
+mod skin;
+mod stats;
+mod status;
+mod theme;
 mod ui;
+mod widget;
 
 use std::{
   error::Error,
+  fs,
   io::{self, stdout, Write},
+  path::{Path, PathBuf},
+  process::{self, Stdio},
   time::Duration,
 };
 
-use app::App;
-use event::{Event, Events};
-use game::{Difficulty, Rule};
+use app::{App, Debouncer};
+use battleship_core::{features, game, notation, turn_log};
+use bot_cmd::{BotCmd, BotError, Limits};
+use event::{Event, Events, FocusTerminal};
+use game::{Difficulty, First, FleetType, Game, Rule};
+use headless::Exit;
+use keymap::KeyFile;
+use lobby::{Choice, Lobby};
+use locale::{Glyphs, Locale};
+use net::Peer;
+use persona::Persona;
+use preset::{Mode, PresetFile};
+use save::SaveFile;
+use serde_json::{json, Value};
+use session::SessionFile;
+use skin::Skin;
+use stats::StatsFile;
+use status::StatusServer;
 use structopt::StructOpt;
 use termion::{
-  event::Key,
   input::MouseTerminal,
   raw::IntoRawMode,
   screen::{AlternateScreen, ToMainScreen},
 };
+use theme::{Theme, ThemeFile};
 use tui::{backend::TermionBackend, Terminal};
+use turn_log::TurnLog;
+use ui::LayoutMode;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "battleship-rs", about = "A Battleship game in Rust")]
@@ -30,6 +77,151 @@ struct Opt {
   /// Game rule
   #[structopt(short, long, possible_values = &Difficulty::variants(), case_insensitive = true, default_value = "Hard")]
   pub difficulty: Difficulty,
+  /// Cell glyphs, detected from the locale and terminal by default
+  #[structopt(long, possible_values = &Glyphs::variants(), case_insensitive = true, default_value = "Auto")]
+  pub glyphs: Glyphs,
+  /// Screen layout, wide with a log panel next to the boards when the terminal fits it
+  #[structopt(long, possible_values = &LayoutMode::variants(), case_insensitive = true, default_value = "Auto")]
+  pub layout: LayoutMode,
+  /// Personality of the computer
+  #[structopt(long, possible_values = &Persona::variants(), case_insensitive = true, default_value = "Captain")]
+  pub persona: Persona,
+  /// Dress for the fleets, renaming the ships and redrawing the boards without changing
+  /// the game
+  #[structopt(long, possible_values = &Skin::variants(), case_insensitive = true, default_value = "Naval")]
+  pub skin: Skin,
+  /// Colours to draw in, one of classic, ocean, monochrome and high-contrast or one from
+  /// themes.toml in the config directory
+  #[structopt(long, default_value = "classic")]
+  pub theme: String,
+  /// Draw without colours, in ASCII, telling cells apart by their characters and borders
+  #[structopt(long, alias = "ascii", conflicts_with_all = &["theme", "glyphs"])]
+  pub no_color: bool,
+  /// Play a named preset, one of classic, blitz and chaos or one saved from the sandbox
+  #[structopt(long, conflicts_with_all = &["time-attack", "gauntlet"])]
+  pub preset: Option<String>,
+  /// Place your own ships before the first shot instead of having them placed at random
+  #[structopt(long, conflicts_with = "time-attack")]
+  pub place: bool,
+  /// Ships of the fleets, the standard shapes or the straight ships of the classic game
+  #[structopt(long, possible_values = &FleetType::variants(), case_insensitive = true, conflicts_with_all = &["resume", "replay", "watch"])]
+  pub fleet: Option<FleetType>,
+  /// Play with ships of your own shapes, drawn in a TOML file
+  #[structopt(long, parse(from_os_str), conflicts_with_all = &["fleet", "resume", "replay", "watch"])]
+  pub fleet_file: Option<PathBuf>,
+  /// Place your ships at random away from where the computer found them in your recent
+  /// matches
+  #[structopt(long, conflicts_with_all = &["place", "resume", "replay", "watch", "time-attack"])]
+  pub avoid_habits: bool,
+  /// Deal the fleets and drive the computer's shots from this seed, the same seed plays the
+  /// same match
+  #[structopt(long, conflicts_with_all = &["resume", "replay", "join", "watch"])]
+  pub seed: Option<u64>,
+  /// Refuse to select cells that were already fired upon
+  #[structopt(long)]
+  pub strict: bool,
+  /// Mark the cells of the opponent's board that can't hold a ship, going by your shots
+  #[structopt(long)]
+  pub auto_mark: bool,
+  /// Reveal what your shots did one cell at a time, with the terminal bell for each
+  #[structopt(long)]
+  pub suspense: bool,
+  /// Who fires first against the computer, a coin toss before every match with random
+  #[structopt(long, possible_values = &First::variants(), case_insensitive = true, default_value = "Me")]
+  pub first: First,
+  /// Give the side firing second a shot more in their first turn
+  #[structopt(long)]
+  pub balance: bool,
+  /// Ignore repeats of the same key within this many milliseconds
+  #[structopt(long, default_value = "100")]
+  pub debounce: u64,
+  /// Sink the computer's fleet against the clock, the computer doesn't fire back
+  #[structopt(long, conflicts_with = "turn-log")]
+  pub time_attack: bool,
+  /// Seconds allowed for a time attack, the clock counts down from them
+  #[structopt(long, requires = "time-attack")]
+  pub time_limit: Option<u64>,
+  /// Keep sending in harder fleets as long as you survive and don't miss too often
+  #[structopt(long, conflicts_with_all = &["turn-log", "time-attack"])]
+  pub gauntlet: bool,
+  /// Keep lifetime statistics in this file rather than the one in the data directory
+  #[structopt(long, parse(from_os_str))]
+  pub stats: Option<PathBuf>,
+  /// Don't count matches quit before they were decided as losses in the stats
+  #[structopt(long)]
+  pub no_abandoned: bool,
+  /// Serve a JSON summary of the match on this port of localhost, for stream overlays
+  #[structopt(long)]
+  pub status_port: Option<u16>,
+  /// Let web pages of this origin read the summary of --status-port, like
+  /// http://localhost:8080, which no page can otherwise
+  #[structopt(long, requires = "status-port")]
+  pub status_origin: Option<String>,
+  /// Let the chat of this Twitch channel vote on your shots
+  #[cfg(feature = "chat")]
+  #[structopt(long)]
+  pub chat: Option<String>,
+  /// Seconds the chat has to vote on a shot, with --chat
+  #[cfg(feature = "chat")]
+  #[structopt(long, default_value = "15")]
+  pub vote_window: u64,
+  /// Host a match against another player on this port, who joins it with --join
+  #[structopt(long, conflicts_with_all = &["join", "time-attack", "gauntlet", "preset", "place"])]
+  pub host: Option<u16>,
+  /// Join the match hosted at this address, like 192.168.1.20:7878, or pick one hosted on
+  /// the local network with auto
+  #[structopt(long, conflicts_with_all = &["watch", "time-attack", "gauntlet", "preset", "place"])]
+  pub join: Option<String>,
+  /// Watch the match hosted at this address without playing
+  #[structopt(long, conflicts_with_all = &["host", "time-attack", "gauntlet", "preset", "place", "turn-log"])]
+  pub watch: Option<String>,
+  /// Write the match to this file in the text notation as it's played
+  #[structopt(long, parse(from_os_str))]
+  pub notation: Option<PathBuf>,
+  /// Step through a match written down with --notation turn by turn
+  #[structopt(long, parse(from_os_str), conflicts_with_all = &["notation", "resume", "turn-log", "time-attack", "gauntlet", "place", "host", "join", "watch"])]
+  pub replay: Option<PathBuf>,
+  /// Resume the match saved with <ctrl-s>
+  #[structopt(long, conflicts_with_all = &["turn-log", "time-attack", "gauntlet", "preset", "place", "host", "join", "watch"])]
+  pub resume: bool,
+  /// Have this program pick the computer's shots, run through the shell and asked for them
+  /// every turn with a line of JSON
+  #[structopt(long, conflicts_with_all = &["host", "join", "watch", "time-attack"])]
+  pub bot_cmd: Option<String>,
+  /// Persist the match to this file as it progresses and resume it from there if interrupted
+  #[structopt(long, parse(from_os_str))]
+  pub turn_log: Option<PathBuf>,
+  #[structopt(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+  /// Open a match written down in the text notation
+  Import {
+    #[structopt(parse(from_os_str))]
+    file: PathBuf,
+  },
+  /// Check that a match written down with --notation still plays out the same from its seed
+  VerifyReplay {
+    #[structopt(parse(from_os_str))]
+    file: PathBuf,
+  },
+  /// Show the lifetime statistics, overall and by rule
+  Stats,
+  /// Pit the program of --bot-cmd against the computer without the terminal UI, the
+  /// program firing for the user
+  BotMatch {
+    /// Matches to play, one after another, with the seed counting up from --seed
+    #[structopt(long, default_value = "1")]
+    matches: u64,
+    /// Seconds the bot may think about a turn before it's disqualified
+    #[structopt(long, default_value = "10")]
+    timeout: f64,
+    /// MiB of memory the bot's program may take up, as much as it wants if not given
+    #[structopt(long)]
+    memory: Option<u64>,
+  },
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -43,36 +235,385 @@ fn main() -> Result<(), Box<dyn Error>> {
     print!("{:?}", x);
   }));
 
-  let opt = Opt::from_args();
+  let opt = Opt::from_iter_safe(std::env::args_os()).unwrap_or_else(|err| {
+    if err.use_stderr() {
+      eprintln!("{}", err.message);
+      process::exit(Exit::UsageError.code());
+    }
+    // the help and the version
+    println!("{}", err.message);
+    process::exit(Exit::Success.code());
+  });
+  if let Some(Command::VerifyReplay { file }) = &opt.command {
+    match verify_replay(file) {
+      Ok(turns) => {
+        println!("All {} turns play out as recorded", turns);
+        headless::finish(Exit::Success, json!({ "turns": turns }));
+      }
+      Err(err) => {
+        eprintln!("Error: {}", err);
+        headless::finish(Exit::EngineError, json!({ "error": err.to_string() }));
+      }
+    }
+  }
+  if let Some(Command::BotMatch {
+    matches,
+    timeout,
+    memory,
+  }) = &opt.command
+  {
+    let limits = match Duration::try_from_secs_f64(*timeout) {
+      Ok(time) if !time.is_zero() => Limits {
+        time,
+        memory: *memory,
+      },
+      _ => {
+        let error = format!("A bot needs some time to think, not {} seconds", timeout);
+        eprintln!("{}", error);
+        headless::finish(Exit::UsageError, json!({ "error": error }));
+      }
+    };
+    let (exit, fields) = bot_match(&opt, *matches, limits);
+    headless::finish(exit, fields);
+  }
+  let stats = opt
+    .stats
+    .clone()
+    .map(StatsFile::new)
+    .or_else(StatsFile::in_data_dir);
+  if let Some(Command::Stats) = &opt.command {
+    let stats = stats.ok_or("No data directory to keep the stats in")?;
+    for line in stats.load()?.lines() {
+      println!("{}", line);
+    }
+    return Ok(());
+  }
+
+  let locale = Locale::detect(if opt.no_color {
+    Glyphs::Ascii
+  } else {
+    opt.glyphs
+  });
+  let title = if locale.emoji {
+    " 🚀 Battleship.rs 🚀 "
+  } else {
+    " Battleship.rs "
+  };
+  let mut app = App::new(title.into(), opt.rule, opt.difficulty);
+  app.emoji = locale.emoji;
+  app.layout = opt.layout;
+  app.strict = opt.strict;
+  app.auto_mark = opt.auto_mark;
+  app.suspense = opt.suspense;
+  app.persona = opt.persona;
+  app.skin = opt.skin;
+  if let Some(command) = &opt.bot_cmd {
+    app.set_bot(command.clone())?;
+  }
+  let themes = theme::themes(ThemeFile::in_config_dir().as_ref())?;
+  app.theme = *themes.get(&opt.theme).ok_or_else(|| {
+    let names = themes.keys().cloned().collect::<Vec<_>>();
+    format!(
+      "Unknown theme {}, choose one of {}",
+      opt.theme,
+      names.join(", ")
+    )
+  })?;
+  if opt.no_color {
+    app.theme = Theme::no_color();
+  }
+  app.theme.board = opt.skin.board(app.theme.board);
+  if let Some(keys) = KeyFile::in_config_dir() {
+    app.keymap = keys.load()?;
+  }
+  app.debouncer = Debouncer::new(Duration::from_millis(opt.debounce));
+  if opt.time_attack {
+    app.set_time_attack(opt.time_limit.map(Duration::from_secs));
+  }
+  if opt.gauntlet {
+    app.set_gauntlet();
+  }
+  if let Some(stats) = stats {
+    app.set_stats(stats);
+  }
+  app.count_abandoned = !opt.no_abandoned;
+  let preset_file = PresetFile::in_config_dir();
+  if let Some(name) = opt.preset {
+    let presets = preset::presets(preset_file.as_ref())?;
+    let preset = presets.get(&name).ok_or_else(|| {
+      let names = presets.keys().cloned().collect::<Vec<_>>();
+      format!(
+        "Unknown preset {}, choose one of {}",
+        name,
+        names.join(", ")
+      )
+    })?;
+    if preset.mode != Mode::Normal && opt.turn_log.is_some() {
+      return Err(
+        format!(
+          "The {} mode of preset {} can't be kept in a turn log",
+          preset.mode, name
+        )
+        .into(),
+      );
+    }
+    app.apply_preset(preset);
+    app.strict |= opt.strict;
+  }
+  if let Some(presets) = preset_file {
+    app.set_presets(presets);
+  }
+  if let Some(seed) = opt.seed {
+    app.set_seed(seed);
+  }
+  if let Some(fleet_type) = opt.fleet.clone() {
+    app.set_fleet_type(fleet_type);
+  }
+  if let Some(path) = &opt.fleet_file {
+    app.set_fleet_type(fleet::load(path)?);
+  }
+  app.first = opt.first;
+  app.balanced = opt.balance;
+  app.deal_first();
+  if opt.avoid_habits {
+    app.avoid_habits()?;
+  }
+  if opt.place {
+    app.start_placement();
+  }
+  if let Some(port) = opt.status_port {
+    app.set_status_server(StatusServer::start(port, opt.status_origin.clone())?);
+  }
+  if let Some(path) = opt.notation {
+    app.set_notation(path);
+  }
+  if let Some(saves) = SaveFile::in_home_dir() {
+    if opt.resume {
+      let (game, elapsed) = saves.load()?.restore()?;
+      app.resume(game, elapsed);
+    }
+    app.set_save_file(saves);
+  }
+  if let Some(Command::Import { file }) = opt.command {
+    if opt.resume {
+      return Err("An imported match can't be resumed".into());
+    }
+    if opt.turn_log.is_some() {
+      return Err("An imported match can't be kept in a turn log".into());
+    }
+    if opt.host.is_some() || opt.join.is_some() || opt.watch.is_some() {
+      return Err("An imported match can't be played over the network".into());
+    }
+    if opt.seed.is_some() || opt.fleet.is_some() || opt.fleet_file.is_some() {
+      return Err("An imported match can't be dealt again".into());
+    }
+    if opt.replay.is_some() {
+      return Err("A match can't be imported and replayed at once".into());
+    }
+    app.import(notation::parse(&fs::read_to_string(file)?)?);
+  }
+  if let Some(file) = opt.replay {
+    app.open_replay(notation::parse(&fs::read_to_string(file)?)?);
+  }
+  if let Some(path) = opt.turn_log {
+    app.set_turn_log(TurnLog::new(path))?;
+    if app.commitments().is_some() && opt.host.is_none() && opt.join.is_none() {
+      return Err(
+        "The turn log has a match over the network, go on with it with --host or --join".into(),
+      );
+    }
+  }
+  // another host on this machine has the discovery port, it can still be joined directly
+  let _announcement = opt
+    .host
+    .and_then(|port| lobby::announce(port, *app.rule()).ok());
 
   // time in ms between two ticks is 250ms.
   let events = Events::new(Duration::from_millis(250));
+  // the hosted match is in the first tab, other tabs play the computer while it waits
+  if let Some(port) = opt.host {
+    net::check_rule(*app.rule())?;
+    let resumed = app.commitments();
+    Peer::host_in_background(port, *app.rule(), app.fleet(), resumed, events.sender(), 0);
+    app.wait_for_opponent(port);
+  }
+  // the matches left open at the last quit come back in tabs after the one started
+  if let Some(sessions) = SessionFile::in_home_dir() {
+    let tx = events.sender();
+    app.restore_session(sessions.load()?, |tab, port, rule, fleet| {
+      Peer::host_in_background(port, rule, fleet, None, tx.clone(), tab)
+    })?;
+    app.set_session_file(sessions);
+  }
+  if let Some(addr) = &opt.watch {
+    let (mut peer, versus) = Peer::watch(addr)?;
+    peer.listen(events.sender(), 0);
+    app.watch(peer, versus);
+  }
+  #[cfg(feature = "chat")]
+  if let Some(channel) = &opt.chat {
+    chat::connect(channel, events.sender())?;
+    // four ticks a second
+    app.chat = Some(chat::ChatVote::new((opt.vote_window * 4) as u16));
+  }
 
   let stdout = io::stdout().into_raw_mode()?;
   let stdout = MouseTerminal::from(stdout);
+  let stdout = FocusTerminal::from(stdout)?;
   let stdout = AlternateScreen::from(stdout);
   let backend = TermionBackend::new(stdout);
   let mut terminal = Terminal::new(backend)?;
 
-  let mut app = App::new(" 🚀 Battleship.rs 🚀 ".into(), opt.rule, opt.difficulty);
+  let join = match opt.join {
+    Some(join) if join == "auto" => {
+      let mut lobby = Lobby::new(lobby::discover()?);
+      loop {
+        terminal.draw(|f| ui::draw_lobby(f, &app.theme, title, &lobby))?;
+        if let Event::Input(key) = events.next()? {
+          match lobby.on_key(key) {
+            Some(Choice::Join(addr)) => break Some(addr.to_string()),
+            Some(Choice::Refresh) => lobby = Lobby::new(lobby::discover()?),
+            Some(Choice::Quit) => return Ok(()),
+            None => {}
+          }
+        }
+      }
+    }
+    join => join,
+  };
+  if let Some(addr) = join {
+    let (mut peer, versus) = Peer::join(&addr, *app.rule(), app.fleet(), app.commitments())?;
+    peer.listen(events.sender(), 0);
+    app.set_peer(peer, versus);
+  }
+
   loop {
     terminal.draw(|f| ui::draw(f, &mut app))?;
 
     match events.next()? {
-      Event::Input(key) => match key {
-        Key::Ctrl('c') | Key::Char('q') => {
-          app.should_quit = true;
-        }
-        _ => app.on_key(key),
-      },
+      Event::Input(key) => app.on_key(key),
       Event::Tick => {
         app.on_tick();
+        if app.take_bell() {
+          let backend = terminal.backend_mut();
+          write!(backend, "\x07")?;
+          backend.flush()?;
+        }
+      }
+      #[cfg(feature = "chat")]
+      Event::Chat(user, text) => app.on_chat(user, &text),
+      Event::Net(tab, message) => app.on_net(tab, message),
+      Event::Dropped(tab) => app.on_dropped(tab),
+      Event::Hosted(tab, Ok((mut peer, versus))) => {
+        peer.listen(events.sender(), tab);
+        app.on_hosted(tab, Ok((peer, versus)));
       }
+      Event::Hosted(tab, Err(err)) => app.on_hosted(tab, Err(err)),
+      Event::Focus(focused) => app.on_focus(focused),
     }
     if app.should_quit {
       break;
     }
+    events.set_idle(app.is_idle());
   }
 
+  app.save_session()?;
   Ok(())
 }
+
+/// Plays the match written down in the file again from its seed, for the number of turns
+fn verify_replay(file: &Path) -> Result<usize, Box<dyn Error>> {
+  Ok(replay::verify(&notation::parse(&fs::read_to_string(
+    file,
+  )?)?)?)
+}
+
+/// Pits the bot against the computer for the matches, held to the limits, for how it ended
+/// and the results. A bot that breaks the rules is disqualified and plays no more matches.
+fn bot_match(opt: &Opt, matches: u64, limits: Limits) -> (Exit, Value) {
+  let command = match &opt.bot_cmd {
+    Some(command) => command,
+    None => {
+      let error = "A bot match needs a bot, run with --bot-cmd";
+      eprintln!("{}", error);
+      return (Exit::UsageError, json!({ "error": error }));
+    }
+  };
+  let bot = match BotCmd::spawn_with(command, Stdio::inherit(), limits) {
+    Ok(bot) => bot,
+    Err(err) => {
+      eprintln!("Error: {}", err);
+      return (Exit::EngineError, json!({ "error": err.to_string() }));
+    }
+  };
+  let fleet_type = match &opt.fleet_file {
+    Some(path) => match fleet::load(path) {
+      Ok(fleet_type) => fleet_type,
+      Err(err) => {
+        eprintln!("Error: {}", err);
+        return (Exit::UsageError, json!({ "error": err.to_string() }));
+      }
+    },
+    None => opt.fleet.clone().unwrap_or(FleetType::Standard),
+  };
+  let mut won = 0;
+  let mut results = vec![];
+  let mut failure = None;
+  for index in 0..matches {
+    let seed = match opt.seed {
+      Some(seed) => seed.wrapping_add(index),
+      None => rand::random(),
+    };
+    let mut game = Game::seeded(opt.rule, opt.difficulty, fleet_type.clone(), seed);
+    bot_cmd::play_out(&bot, &mut game);
+    let mut result = json!({ "match": index + 1, "rounds": game.round() });
+    if let Some(error) = bot.take_failure() {
+      println!(
+        "Match {}: the bot was disqualified in round {}, it {}",
+        index + 1,
+        game.round(),
+        error
+      );
+      result["winner"] = json!("computer");
+      result["disqualified"] = json!(error.to_string());
+      results.push(result);
+      failure = Some(error);
+      break;
+    }
+    let winner = if game.winner() == Some(0) {
+      won += 1;
+      "bot"
+    } else {
+      "computer"
+    };
+    println!(
+      "Match {}: the {} won in {} rounds",
+      index + 1,
+      winner,
+      game.round()
+    );
+    result["winner"] = json!(winner);
+    results.push(result);
+  }
+  let played = results.len() as u64;
+  println!("The bot won {} of {} matches", won, played);
+  let tally = json!({
+    "matches": matches,
+    "played": played,
+    "won": won,
+    "lost": played - won,
+    "results": results,
+  });
+  match failure {
+    None => (Exit::Success, tally),
+    Some(error) => {
+      let exit = match error {
+        BotError::Timeout(_) => Exit::Timeout,
+        BotError::Protocol(_) => Exit::ProtocolError,
+      };
+      let mut fields = tally;
+      fields["error"] = json!(format!("The bot {}", error));
+      (exit, fields)
+    }
+  }
+}
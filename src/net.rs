@@ -0,0 +1,826 @@
+//! Two players over TCP, each with their own instance of the game. The host listens and
+//! fires first, the other player joins it. Each side keeps its fleet to itself until the
+//! match is over: it greets the other with the ships it plays with and a hash committing
+//! to where they are, answers every turn played upon it with what the turn found, and
+//! sends the fleet along with the salt of the hash at the end, so the other side can
+//! check every answer against it. Anyone else connecting to the host watches the match.
+//!
+//! Messages are lines of text:
+//!
+//! ```text
+//! HELLO Default X,V,H,I 5d41402abc4b2a76b9719d911017c592...
+//! FIRE B7,C3
+//! RESULT B7=. C3=x SUNK=V HASH=9d5ed678fe57bcca
+//! POWER C4?
+//! SONAR yes HASH=610bd7f2c1a3e904
+//! PING 3 HASH=1f3870be274f6c49
+//! FLEET 8f14e45fceea167a X@A1/90 V@D1/90 H@A6/90 I@F6/90
+//! SYNC 4 9d5ed678fe57bcca
+//! WATCH
+//! BYE
+//! ```
+//!
+//! A power-up used in place of firing is sent as the cell it was used on and its mark, and
+//! answered with what it found, `SONAR yes` or `no` for a sonar and how far the nearest
+//! ship is, or `none`, for a ping. A strike is answered like a salvo.
+//!
+//! Every answer ends with the hash of what each side found of the other's fleet once the
+//! turn is played, which the side receiving it checks its own match against.
+//!
+//! The player joining greets the host first, and the host answers with the rule of the
+//! match, or says `BYE` when their ships aren't the same.
+//!
+//! A match kept in a turn log goes on after either side was interrupted: both connect
+//! again committed to the same fleets as before, and say how many turns they played and
+//! the hash of what both found with `SYNC`. A side a turn ahead sends that turn and its
+//! answer again. A spectator says `WATCH` instead
+//! and is sent the greetings of the host and the other player, in that order, and every
+//! turn and answer since.
+//!
+//! The rules that draw on both fleets to play a turn, the mines, hazards and drift, can't
+//! be played this way.
+
+use std::{
+  collections::BTreeSet,
+  fmt::{self, Display},
+  io::{self, BufRead, BufReader, ErrorKind, Read, Write},
+  net::{Shutdown, TcpListener, TcpStream},
+  str::FromStr,
+  sync::{
+    mpsc::{self, Sender},
+    Arc, Mutex,
+  },
+  thread,
+  time::Duration,
+};
+
+use sha2::{Digest, Sha256};
+use termion::event::Key;
+
+use super::{
+  event::Event,
+  game::{
+    a1_notation, collect_salvo, parse_a1, parse_power_up, parse_ships, write_power_up, write_ships,
+    Answer, Coordinate, FleetType, Placement, Play, PowerUp, Rule,
+  },
+  notation::{parse_fleet, write_fleet},
+  turn_log::Commitments,
+};
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Message {
+  /// the rule of the match, only the host's counts, the ships of the sender and the hash
+  /// committing to where they are
+  Hello(Rule, FleetType, String),
+  Fire(BTreeSet<Coordinate>),
+  PowerUp(PowerUp, Coordinate),
+  /// what the last turn played upon the fleet of the sender found, and the hash of what
+  /// both sides found since, if sent
+  Answer(Answer, Option<u64>),
+  /// the fleet of the sender and the salt of the hash committing to it, once the match
+  /// is over
+  Fleet(String, Vec<Placement>),
+  /// how many turns the sender played of the match it goes on with, and the hash of what
+  /// both sides found in them
+  Sync(usize, u64),
+  /// asks the host to follow the match
+  Watch,
+  Bye,
+}
+
+impl Message {
+  /// The turn the message plays, if it plays one
+  pub fn play(&self) -> Option<Play> {
+    match self {
+      Message::Fire(shots) => Some(Play::Salvo(shots.clone())),
+      Message::PowerUp(power_up, cell) => Some(Play::PowerUp(*power_up, *cell)),
+      _ => None,
+    }
+  }
+}
+
+impl From<&Play> for Message {
+  fn from(play: &Play) -> Self {
+    match play {
+      Play::Salvo(shots) => Message::Fire(shots.clone()),
+      Play::PowerUp(power_up, cell) => Message::PowerUp(*power_up, *cell),
+    }
+  }
+}
+
+impl Display for Message {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Message::Hello(rule, fleet_type, commitment) => write!(
+        f,
+        "HELLO {} {} {}",
+        rule,
+        write_ships(&fleet_type.ships()),
+        commitment
+      ),
+      Message::Fire(shots) => {
+        let shots = shots.iter().map(|c| a1_notation(*c)).collect::<Vec<_>>();
+        write!(f, "FIRE {}", shots.join(","))
+      }
+      Message::PowerUp(power_up, cell) => {
+        write!(f, "POWER {}", write_power_up((*power_up, *cell)))
+      }
+      Message::Answer(answer, hash) => {
+        write!(f, "{}", answer)?;
+        match hash {
+          Some(hash) => write!(f, " HASH={:016x}", hash),
+          None => Ok(()),
+        }
+      }
+      Message::Fleet(salt, fleet) => write!(f, "FLEET {} {}", salt, write_fleet(fleet)),
+      Message::Sync(turns, hash) => write!(f, "SYNC {} {:016x}", turns, hash),
+      Message::Watch => write!(f, "WATCH"),
+      Message::Bye => write!(f, "BYE"),
+    }
+  }
+}
+
+/// The hash ending an answer, if there's one, and the answer before it
+fn split_hash(rest: &str) -> Result<(&str, Option<u64>), String> {
+  match rest.rsplit_once("HASH=") {
+    Some((rest, hash)) => u64::from_str_radix(hash, 16)
+      .map(|hash| (rest.trim_end(), Some(hash)))
+      .map_err(|_| format!("invalid hash {}", hash)),
+    None => Ok((rest, None)),
+  }
+}
+
+impl FromStr for Message {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (kind, rest) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+    let cell = |c: &str| parse_a1(c).ok_or_else(|| format!("invalid cell {}", c));
+    match kind {
+      "HELLO" => {
+        let mut parts = rest.split_whitespace();
+        let (rule, ships, commitment) = match (parts.next(), parts.next(), parts.next()) {
+          (Some(rule), Some(ships), Some(commitment)) => (rule, ships, commitment),
+          _ => return Err(format!("invalid greeting {}", rest)),
+        };
+        let fleet_type = FleetType::of_ships(parse_ships(ships)?);
+        Ok(Message::Hello(rule.parse()?, fleet_type, commitment.into()))
+      }
+      "FIRE" => {
+        let shots = rest.split(',').map(cell).collect::<Result<Vec<_>, _>>()?;
+        Ok(Message::Fire(
+          collect_salvo(&shots).map_err(|err| err.to_string())?,
+        ))
+      }
+      "POWER" => parse_power_up(rest)
+        .map(|(power_up, cell)| Message::PowerUp(power_up, cell))
+        .ok_or_else(|| format!("invalid power-up {}", rest)),
+      "RESULT" | "SONAR" | "PING" => {
+        let (rest, hash) = split_hash(rest)?;
+        Ok(Message::Answer(format!("{} {}", kind, rest).parse()?, hash))
+      }
+      "FLEET" => {
+        let (salt, fleet) = rest.split_once(' ').unwrap_or((rest, ""));
+        Ok(Message::Fleet(salt.into(), parse_fleet(fleet)?))
+      }
+      "SYNC" => match rest.split_once(' ') {
+        Some((turns, hash)) => match (turns.parse(), u64::from_str_radix(hash, 16)) {
+          (Ok(turns), Ok(hash)) => Ok(Message::Sync(turns, hash)),
+          _ => Err(format!("invalid sync {}", rest)),
+        },
+        None => Err(format!("invalid sync {}", rest)),
+      },
+      "WATCH" => Ok(Message::Watch),
+      "BYE" => Ok(Message::Bye),
+      _ => Err(format!("unknown message {}", s.trim())),
+    }
+  }
+}
+
+/// The hash a side commits to its fleet with before the first shot, hiding where the
+/// ships are behind the salt until both are sent at the end
+pub fn commitment(salt: &str, fleet: &[Placement]) -> String {
+  Sha256::digest(format!("{} {}", salt, write_fleet(fleet)))
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect()
+}
+
+/// Whether the rule can be played without seeing the other side's fleet
+pub fn check_rule(rule: Rule) -> io::Result<()> {
+  match rule {
+    Rule::Mines | Rule::Hazards | Rule::Drift => Err(io::Error::new(
+      ErrorKind::InvalidInput,
+      format!("The {} rule can't be played over the network", rule),
+    )),
+    _ => Ok(()),
+  }
+}
+
+/// A match both sides agreed on when connecting
+pub struct Versus {
+  pub rule: Rule,
+  /// the ships both fleets are made of
+  pub fleet_type: FleetType,
+  /// the fleet of this side, none for a spectator
+  pub fleet: Option<Vec<Placement>>,
+  /// whether this side hosts the match, and fires first
+  pub host: bool,
+}
+
+/// The longest line read from the other side, longer ones end the connection
+const MAX_LINE: u64 = 4096;
+
+/// How long a spectator has to take in a message before it's let go
+const SPECTATOR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reads a line of at most `MAX_LINE` bytes, none at the end of the connection
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+  let mut line = String::new();
+  let read = reader.by_ref().take(MAX_LINE).read_line(&mut line)?;
+  if read == 0 {
+    Ok(None)
+  } else if !line.ends_with('\n') && read as u64 == MAX_LINE {
+    Err(io::Error::new(ErrorKind::InvalidData, "line too long"))
+  } else {
+    Ok(Some(line))
+  }
+}
+
+#[derive(Default)]
+struct Audience {
+  /// every message of the match so far, for spectators joining late
+  transcript: Vec<Message>,
+  /// the messages on their way to each spectator
+  queues: Vec<Sender<String>>,
+}
+
+/// Read-only connections to the host following the match
+#[derive(Clone, Default)]
+pub struct Spectators(Arc<Mutex<Audience>>);
+
+impl Spectators {
+  /// Writes to the spectator on a thread of its own, so a slow one holds up nobody
+  fn add(&self, mut stream: TcpStream) {
+    let _ = stream.set_write_timeout(Some(SPECTATOR_TIMEOUT));
+    let (queue, lines) = mpsc::channel::<String>();
+    if let Ok(mut audience) = self.0.lock() {
+      for message in &audience.transcript {
+        let _ = queue.send(message.to_string());
+      }
+      audience.queues.push(queue);
+    }
+    // the thread ends, and lets the spectator go, once a write fails
+    thread::spawn(move || {
+      for line in lines {
+        if writeln!(stream, "{}", line).is_err() {
+          return;
+        }
+      }
+    });
+  }
+
+  /// Sends the message to the spectators, and to the ones still to come
+  fn record(&self, message: Message) {
+    if let Ok(mut audience) = self.0.lock() {
+      // spectators that went away are let go
+      let line = message.to_string();
+      audience
+        .queues
+        .retain(|queue| queue.send(line.clone()).is_ok());
+      audience.transcript.push(message);
+    }
+  }
+
+  /// Lets spectators in for as long as the host plays
+  fn serve(&self, listener: TcpListener) {
+    let spectators = self.clone();
+    thread::spawn(move || {
+      for stream in listener.incoming().flatten() {
+        // a connection that doesn't say what it wants in time is dropped
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+        if let Ok(Message::Watch) = first_message(&stream) {
+          let _ = stream.set_read_timeout(None);
+          spectators.add(stream);
+        }
+      }
+    });
+  }
+}
+
+fn first_message(stream: &TcpStream) -> io::Result<Message> {
+  read_line(&mut BufReader::new(stream.try_clone()?))?
+    .unwrap_or_default()
+    .parse()
+    .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+/// The connection to the other player, or to the host for a spectator
+pub struct Peer {
+  stream: TcpStream,
+  /// the incoming messages, until they're handed over to the event loop
+  reader: Option<BufReader<TcpStream>>,
+  /// who watches the match, when hosting it
+  spectators: Option<Spectators>,
+  /// the fleet of this side and the salt of its commitment, none for a spectator
+  secret: Option<(String, Vec<Placement>)>,
+  /// what the fleets were committed to, this side's or the host's first
+  commitments: [String; 2],
+  /// the fleets known so far, in the same order
+  fleets: [Option<Vec<Placement>>; 2],
+  /// whether the fleet of this side was sent
+  sent: bool,
+  /// the turn waiting on its answer
+  awaiting: Option<Play>,
+  /// the turn both sides first disagreed on, if they did
+  desynced: Option<usize>,
+  /// whether the other side is a turn ahead of a match resumed, which it sends again
+  behind: bool,
+}
+
+impl Peer {
+  /// Waits on the port for the other player to join and tells them the rule of the match.
+  /// Spectators can come in before and after them. A match resumed from the commitments
+  /// it was played with only lets in the player committed to it.
+  pub fn host(
+    port: u16,
+    rule: Rule,
+    fleet: Vec<Placement>,
+    resumed: Option<Commitments>,
+  ) -> io::Result<(Self, Versus)> {
+    check_rule(rule)?;
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    let spectators = Spectators::default();
+    let fleet_type = FleetType::of(&fleet);
+    let committed = |theirs: &String| resumed.as_ref().is_none_or(|c| c.theirs == *theirs);
+    let (mut peer, theirs) = loop {
+      let (stream, _) = listener.accept()?;
+      match first_message(&stream)? {
+        // a player with other ships, or of another match, is turned away
+        Message::Hello(_, ships, theirs) if !ships.matches(&fleet_type) || !committed(&theirs) => {
+          let _ = writeln!(&stream, "{}", Message::Bye);
+        }
+        Message::Hello(_, _, theirs) => {
+          let salt = resumed.as_ref().map(|c| c.salt.clone());
+          break (Self::new(stream, Some(fleet), salt)?, theirs);
+        }
+        Message::Watch => spectators.add(stream),
+        _ => {}
+      }
+    };
+    peer.commitments[1] = theirs.clone();
+    let ours = peer.commitments[0].clone();
+    peer.send(&Message::Hello(rule, fleet_type.clone(), ours.clone()))?;
+    spectators.record(Message::Hello(rule, fleet_type.clone(), ours));
+    spectators.record(Message::Hello(rule, fleet_type.clone(), theirs));
+    spectators.serve(listener);
+    peer.spectators = Some(spectators);
+    let fleet = peer.secret.as_ref().map(|(_, fleet)| fleet.clone());
+    Ok((
+      peer,
+      Versus {
+        rule,
+        fleet_type,
+        fleet,
+        host: true,
+      },
+    ))
+  }
+
+  /// Hosts the match like `host` without waiting for the other player, who comes in as an
+  /// event for the tab
+  pub fn host_in_background(
+    port: u16,
+    rule: Rule,
+    fleet: Vec<Placement>,
+    resumed: Option<Commitments>,
+    tx: Sender<Event<Key>>,
+    tab: usize,
+  ) {
+    thread::spawn(move || {
+      let hosted = Self::host(port, rule, fleet, resumed).map_err(|err| err.to_string());
+      let _ = tx.send(Event::Hosted(tab, hosted));
+    });
+  }
+
+  /// Joins the match hosted at the address, playing by the rule of the host. A match
+  /// resumed from the commitments it was played with only joins the host committed to it.
+  pub fn join(
+    addr: &str,
+    rule: Rule,
+    fleet: Vec<Placement>,
+    resumed: Option<Commitments>,
+  ) -> io::Result<(Self, Versus)> {
+    let fleet_type = FleetType::of(&fleet);
+    let salt = resumed.as_ref().map(|c| c.salt.clone());
+    let mut peer = Self::new(TcpStream::connect(addr)?, Some(fleet.clone()), salt)?;
+    let ours = peer.commitments[0].clone();
+    peer.send(&Message::Hello(rule, fleet_type.clone(), ours))?;
+    let (rule, ships, theirs) = peer.hello()?;
+    check_rule(rule)?;
+    if !ships.matches(&fleet_type) || resumed.is_some_and(|c| c.theirs != theirs) {
+      return Err(turned_away());
+    }
+    peer.commitments[1] = theirs;
+    Ok((
+      peer,
+      Versus {
+        rule,
+        fleet_type,
+        fleet: Some(fleet),
+        host: false,
+      },
+    ))
+  }
+
+  /// Follows the match hosted at the address, the host's side first and then the other
+  /// player's
+  pub fn watch(addr: &str) -> io::Result<(Self, Versus)> {
+    let mut peer = Self::new(TcpStream::connect(addr)?, None, None)?;
+    peer.send(&Message::Watch)?;
+    let (rule, fleet_type, host) = peer.hello()?;
+    let (_, _, guest) = peer.hello()?;
+    peer.commitments = [host, guest];
+    Ok((
+      peer,
+      Versus {
+        rule,
+        fleet_type,
+        fleet: None,
+        host: false,
+      },
+    ))
+  }
+
+  /// The connection over the stream, committing to the fleet with the salt given or a
+  /// new one
+  fn new(
+    stream: TcpStream,
+    fleet: Option<Vec<Placement>>,
+    salt: Option<String>,
+  ) -> io::Result<Self> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let salt = salt.unwrap_or_else(|| format!("{:032x}", rand::random::<u128>()));
+    let secret = fleet.map(|fleet| (salt, fleet));
+    let ours = secret
+      .as_ref()
+      .map(|(salt, fleet)| commitment(salt, fleet))
+      .unwrap_or_default();
+    let fleets = [secret.as_ref().map(|(_, fleet)| fleet.clone()), None];
+    Ok(Self {
+      stream,
+      reader: Some(reader),
+      spectators: None,
+      secret,
+      commitments: [ours, String::new()],
+      fleets,
+      sent: false,
+      awaiting: None,
+      desynced: None,
+      behind: false,
+    })
+  }
+
+  fn hello(&mut self) -> io::Result<(Rule, FleetType, String)> {
+    let invalid = |msg: String| io::Error::new(ErrorKind::InvalidData, msg);
+    let line = match &mut self.reader {
+      Some(reader) => read_line(reader)?.unwrap_or_default(),
+      None => String::new(),
+    };
+    match line.parse::<Message>().map_err(invalid)? {
+      Message::Hello(rule, fleet_type, commitment) => Ok((rule, fleet_type, commitment)),
+      Message::Bye => Err(turned_away()),
+      message => Err(invalid(format!("expected a greeting, got {}", message))),
+    }
+  }
+
+  pub fn send(&mut self, message: &Message) -> io::Result<()> {
+    writeln!(self.stream, "{}", message)
+  }
+
+  /// Passes a turn, its answer or the end of the match on to the spectators, if any
+  pub fn broadcast(&self, message: Message) {
+    if let Some(spectators) = &self.spectators {
+      spectators.record(message);
+    }
+  }
+
+  /// Keeps the turn until the answer to it comes in
+  pub fn expect_answer(&mut self, play: Play) {
+    self.awaiting = Some(play);
+  }
+
+  /// The turn still waiting on its answer, if any
+  pub fn awaiting(&self) -> Option<&Play> {
+    self.awaiting.as_ref()
+  }
+
+  /// The turn the answer that came in is for
+  pub fn take_awaiting(&mut self) -> Option<Play> {
+    self.awaiting.take()
+  }
+
+  /// What both sides committed to their fleets with, for a turn log to resume the match
+  /// with, none for a spectator
+  pub fn commitments(&self) -> Option<Commitments> {
+    self.secret.as_ref().map(|(salt, _)| Commitments {
+      salt: salt.clone(),
+      theirs: self.commitments[1].clone(),
+    })
+  }
+
+  /// Waits on the turn the other side is ahead by to be sent again
+  pub fn fall_behind(&mut self) {
+    self.behind = true;
+  }
+
+  /// Whether the turn the other side is ahead by is still to come
+  pub fn is_behind(&self) -> bool {
+    self.behind
+  }
+
+  /// Goes on as usual once the turn the other side was ahead by came in
+  pub fn catch_up(&mut self) {
+    self.behind = false;
+  }
+
+  /// Keeps the turn both sides disagreed on, whether it's the first they did
+  pub fn desync(&mut self, turn: usize) -> bool {
+    let first = self.desynced.is_none();
+    self.desynced.get_or_insert(turn);
+    first
+  }
+
+  /// Sends the fleet of this side and the salt of its commitment to the other side and the
+  /// spectators, once
+  pub fn send_fleet(&mut self) -> io::Result<()> {
+    let (salt, fleet) = match &self.secret {
+      Some(secret) if !self.sent => secret.clone(),
+      _ => return Ok(()),
+    };
+    let message = Message::Fleet(salt, fleet);
+    self.send(&message)?;
+    self.broadcast(message);
+    self.sent = true;
+    Ok(())
+  }
+
+  /// Checks a fleet sent at the end against the commitment made to it, and gives both
+  /// fleets once both came in, this side's or the host's first
+  pub fn open_fleet(
+    &mut self,
+    salt: &str,
+    fleet: Vec<Placement>,
+  ) -> Result<Option<[Vec<Placement>; 2]>, String> {
+    let committed = commitment(salt, &fleet);
+    let side = self
+      .commitments
+      .iter()
+      .zip(&self.fleets)
+      .position(|(commitment, sent)| *commitment == committed && sent.is_none())
+      .ok_or("The fleet isn't the one committed to at the start")?;
+    self.fleets[side] = Some(fleet);
+    Ok(match &self.fleets {
+      [Some(first), Some(second)] => Some([first.clone(), second.clone()]),
+      _ => None,
+    })
+  }
+
+  /// Sends the messages of the other player along with the keys, for the match in the tab,
+  /// and tells when the connection drops or sends a line too long
+  pub fn listen(&mut self, tx: Sender<Event<Key>>, tab: usize) {
+    let reader = match self.reader.take() {
+      Some(reader) => reader,
+      None => return,
+    };
+    thread::spawn(move || {
+      let mut reader = reader;
+      while let Ok(Some(line)) = read_line(&mut reader) {
+        // a line that doesn't parse is from a newer version, skip what isn't understood
+        if let Ok(message) = line.parse() {
+          if tx.send(Event::Net(tab, message)).is_err() {
+            return;
+          }
+        }
+      }
+      let _ = tx.send(Event::Dropped(tab));
+    });
+  }
+}
+
+impl Drop for Peer {
+  /// Ends the connection for the other side too, which the stream handed to `listen` would
+  /// otherwise hold open
+  fn drop(&mut self) {
+    let _ = self.stream.shutdown(Shutdown::Both);
+  }
+}
+
+fn turned_away() -> io::Error {
+  io::Error::new(
+    ErrorKind::InvalidData,
+    "the other side plays with other ships or another match",
+  )
+}
+
+/// Both ends of a match on localhost, the host first, and the address of the host
+#[cfg(test)]
+pub fn loopback(rule: Rule, fleets: [Vec<Placement>; 2]) -> ([(Peer, Versus); 2], String) {
+  loopback_resumed(rule, fleets, [None, None])
+}
+
+/// Both ends of a match on localhost like `loopback`, each committed as given
+#[cfg(test)]
+pub fn loopback_resumed(
+  rule: Rule,
+  fleets: [Vec<Placement>; 2],
+  commitments: [Option<Commitments>; 2],
+) -> ([(Peer, Versus); 2], String) {
+  let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+  let port = listener.local_addr().unwrap().port();
+  drop(listener);
+
+  let [host_fleet, join_fleet] = fleets;
+  let [host_commitments, join_commitments] = commitments;
+  let host = thread::spawn(move || Peer::host(port, rule, host_fleet, host_commitments).unwrap());
+  // keep knocking until the host listens
+  let addr = format!("127.0.0.1:{}", port);
+  let joined = loop {
+    match Peer::join(&addr, rule, join_fleet.clone(), join_commitments.clone()) {
+      Err(err) if err.kind() == ErrorKind::ConnectionRefused => thread::yield_now(),
+      joined => break joined.unwrap(),
+    }
+  };
+  ([host.join().unwrap(), joined], addr)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::{Difficulty, FiringResponse, Game, ShipType, Status};
+
+  fn fleet() -> Vec<Placement> {
+    Game::new(Rule::Default, Difficulty::Easy, 0)
+      .player()
+      .player_board()
+      .placements()
+  }
+
+  #[test]
+  fn test_message_round_trip() {
+    let fleet = fleet();
+    let messages = vec![
+      Message::Hello(Rule::Fury, FleetType::Standard, commitment("8f14", &fleet)),
+      Message::Fire(vec![(1, 6), (2, 2)].into_iter().collect()),
+      Message::Answer(
+        Answer::Salvo {
+          response: vec![((1, 6), Status::Miss), ((2, 2), Status::Kill)]
+            .into_iter()
+            .collect(),
+          sunk: vec![ShipType::V],
+          criticals: vec![],
+          flagship: None,
+          lost: true,
+        },
+        None,
+      ),
+      Message::PowerUp(PowerUp::Sonar, (3, 2)),
+      Message::Answer(Answer::Sonar(true), Some(0x9d5e_d678_fe57_bcca)),
+      Message::Answer(Answer::Ping(Some(3)), None),
+      Message::Answer(Answer::Ping(None), Some(1)),
+      Message::Fleet("8f14".into(), fleet.clone()),
+      Message::Sync(4, 0x9d5e_d678_fe57_bcca),
+      Message::Bye,
+    ];
+    let lines = messages.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+    assert!(lines[0].starts_with("HELLO Fury X,V,H,I "));
+    assert_eq!(lines[1], "FIRE G2,C3");
+    assert_eq!(lines[2], "RESULT G2=. C3=X SUNK=V LOST");
+    assert_eq!(lines[3], "POWER C4?");
+    assert_eq!(lines[4], "SONAR yes HASH=9d5ed678fe57bcca");
+    assert_eq!(lines[5], "PING 3");
+    assert_eq!(lines[6], "PING none HASH=0000000000000001");
+    assert_eq!(lines[8], "SYNC 4 9d5ed678fe57bcca");
+    for (line, message) in lines.iter().zip(messages) {
+      assert_eq!(line.parse::<Message>(), Ok(message));
+    }
+    assert_eq!(
+      "FIRE A1,A1".parse::<Message>(),
+      Err("A1 is fired upon more than once".into())
+    );
+    assert_eq!(
+      "RESULT HASH=2a".parse::<Message>(),
+      Ok(Message::Answer(
+        Answer::Salvo {
+          response: FiringResponse::new(),
+          sunk: vec![],
+          criticals: vec![],
+          flagship: None,
+          lost: false
+        },
+        Some(42)
+      ))
+    );
+    assert_eq!(
+      "SONAR yes HASH=zz".parse::<Message>(),
+      Err("invalid hash zz".into())
+    );
+    assert_eq!(
+      "SURRENDER".parse::<Message>(),
+      Err("unknown message SURRENDER".into())
+    );
+    // the hash gives nothing away without the salt, and holds the side to the fleet
+    assert_ne!(commitment("8f14", &fleet), commitment("8f15", &fleet));
+    assert_eq!(commitment("8f14", &fleet).len(), 64);
+  }
+
+  #[test]
+  fn test_peer_handshake() {
+    let (host_fleet, join_fleet) = (fleet(), fleet());
+    let ([(mut host, hosted), (mut peer, joined)], addr) =
+      loopback(Rule::Fury, [host_fleet.clone(), join_fleet.clone()]);
+    assert!(hosted.host);
+    assert_eq!(hosted.fleet, Some(host_fleet.clone()));
+    assert!(!joined.host);
+    // the host picks the rule, and neither side sees the other's fleet
+    assert_eq!(joined.rule, Rule::Fury);
+    assert_eq!(joined.fleet_type, FleetType::Standard);
+    assert_eq!(joined.fleet, Some(join_fleet.clone()));
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    host.listen(tx, 0);
+    peer.send(&Message::Bye).unwrap();
+    assert!(matches!(rx.recv(), Ok(Event::Net(0, Message::Bye))));
+
+    // a spectator coming in late is caught up on the turns played so far
+    let salvo = Message::Fire([(0, 0)].iter().cloned().collect());
+    host.broadcast(salvo.clone());
+    let (mut spectator, watched) = Peer::watch(&addr).unwrap();
+    assert_eq!(watched.rule, Rule::Fury);
+    assert_eq!(watched.fleet, None);
+    let (tx, rx) = std::sync::mpsc::channel();
+    spectator.listen(tx, 0);
+    host.broadcast(Message::Bye);
+    assert!(matches!(rx.recv(), Ok(Event::Net(0, message)) if message == salvo));
+    assert!(matches!(rx.recv(), Ok(Event::Net(0, Message::Bye))));
+
+    // the fleets sent at the end are held to what was committed to at the start
+    let (tx, rx) = std::sync::mpsc::channel();
+    peer.listen(tx, 0);
+    host.send_fleet().unwrap();
+    let (salt, fleet) = match rx.recv() {
+      Ok(Event::Net(0, Message::Fleet(salt, fleet))) => (salt, fleet),
+      _ => panic!("expected the fleet of the host"),
+    };
+    assert!(peer.open_fleet("8f14", fleet.clone()).is_err());
+    assert_eq!(
+      peer.open_fleet(&salt, fleet),
+      Ok(Some([join_fleet, host_fleet.clone()]))
+    );
+    assert_eq!(spectator.open_fleet(&salt, host_fleet), Ok(None));
+  }
+
+  #[test]
+  fn test_peer_line_too_long() {
+    let ([(mut host, _), (mut peer, _)], _) = loopback(Rule::Default, [fleet(), fleet()]);
+    let (tx, rx) = std::sync::mpsc::channel();
+    host.listen(tx, 0);
+    // a line without end counts as the other player leaving, not as a message
+    let line = "FIRE ".to_string() + &"A1,".repeat(MAX_LINE as usize);
+    peer.stream.write_all(line.as_bytes()).unwrap();
+    assert!(matches!(rx.recv(), Ok(Event::Dropped(0))));
+
+    // lines read up to their end
+    let mut reader = io::Cursor::new("WATCH\nBYE");
+    assert_eq!(read_line(&mut reader).unwrap(), Some("WATCH\n".into()));
+    assert_eq!(read_line(&mut reader).unwrap(), Some("BYE".into()));
+    assert_eq!(read_line(&mut reader).unwrap(), None);
+  }
+
+  #[test]
+  fn test_peer_refused() {
+    assert!(check_rule(Rule::Mines).is_err());
+    assert!(Peer::host(0, Rule::Drift, fleet(), None).is_err());
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    thread::spawn(move || Peer::host(port, Rule::Default, fleet(), None));
+    // a player with other ships is turned away
+    let classic = Game::seeded(Rule::Default, Difficulty::Easy, FleetType::Classic, 0)
+      .player()
+      .player_board()
+      .placements();
+    let addr = format!("127.0.0.1:{}", port);
+    let refused = loop {
+      match Peer::join(&addr, Rule::Default, classic.clone(), None) {
+        Err(err) if err.kind() == ErrorKind::ConnectionRefused => thread::yield_now(),
+        joined => break joined,
+      }
+    };
+    assert_eq!(
+      refused.err().map(|err| err.to_string()),
+      Some("the other side plays with other ships or another match".into())
+    );
+  }
+}
@@ -0,0 +1,108 @@
+//! The computer's personality, what it says when things happen in the game.
+
+use rand::{seq::SliceRandom, Rng};
+use structopt::clap::arg_enum;
+
+use super::game::{GameEvent, ShipType};
+
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Persona {
+        Captain, // a sore loser of a navy captain
+        Pirate,
+        Silent,  // never says a word
+    }
+}
+
+impl Persona {
+  /// What the computer says about an event, if it has anything to say
+  pub fn react<R: Rng>(&self, event: &GameEvent, rng: &mut R) -> Option<String> {
+    match event {
+      GameEvent::ShipSunk { by: 0, ship_type } => self.on_ship_lost(ship_type, rng),
+      GameEvent::CriticalSink { by: 0, ship_type } => self.on_critical(ship_type, rng),
+      GameEvent::FlagshipSunk { by: 0, ship_type } => self.on_flagship_lost(ship_type, rng),
+      _ => None,
+    }
+  }
+
+  fn on_ship_lost<R: Rng>(&self, ship_type: &ShipType, rng: &mut R) -> Option<String> {
+    let lines: &[&str] = match self {
+      Persona::Captain => &[
+        "You got my {} — lucky shot.",
+        "My {} is going down! You'll pay for that.",
+        "Fine, have my {}. The rest of the fleet won't be that easy.",
+      ],
+      Persona::Pirate => &["Arr, ye sunk me {}!", "Davy Jones takes me {}, curse ye!"],
+      Persona::Silent => &[],
+    };
+    lines
+      .choose(rng)
+      .map(|line| line.replace("{}", ship_type.name()))
+  }
+
+  fn on_flagship_lost<R: Rng>(&self, ship_type: &ShipType, rng: &mut R) -> Option<String> {
+    let lines: &[&str] = match self {
+      Persona::Captain => &[
+        "My {}, the pride of the fleet! How did you know?",
+        "Not the {}! That ship carried the admiral.",
+      ],
+      Persona::Pirate => &["Ye found me {}, and me treasure with it. Curse ye!"],
+      Persona::Silent => &[],
+    };
+    lines
+      .choose(rng)
+      .map(|line| line.replace("{}", ship_type.name()))
+  }
+
+  fn on_critical<R: Rng>(&self, ship_type: &ShipType, rng: &mut R) -> Option<String> {
+    let lines: &[&str] = match self {
+      Persona::Captain => &[
+        "Right through the middle of my {}? Nobody aims that well.",
+        "My {}, gone in one shot! Who told you where its heart was?",
+      ],
+      Persona::Pirate => &["Ye split me {} clean in two, ye devil!"],
+      Persona::Silent => &[],
+    };
+    lines
+      .choose(rng)
+      .map(|line| line.replace("{}", ship_type.name()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_persona_react() {
+    let rng = &mut rand::thread_rng();
+    let sunk = GameEvent::ShipSunk {
+      by: 0,
+      ship_type: ShipType::H,
+    };
+    assert!(Persona::Captain
+      .react(&sunk, rng)
+      .unwrap()
+      .contains("Carrier"));
+    assert!(Persona::Pirate
+      .react(&sunk, rng)
+      .unwrap()
+      .contains("Carrier"));
+    assert_eq!(Persona::Silent.react(&sunk, rng), None);
+
+    // the computer doesn't comment on its own kills
+    let sunk = GameEvent::ShipSunk {
+      by: 1,
+      ship_type: ShipType::H,
+    };
+    assert_eq!(Persona::Captain.react(&sunk, rng), None);
+
+    let critical = GameEvent::CriticalSink {
+      by: 0,
+      ship_type: ShipType::H,
+    };
+    let line = Persona::Captain.react(&critical, rng).unwrap();
+    assert!(line.contains("Carrier"));
+    assert!(!line.contains("lucky"));
+  }
+}
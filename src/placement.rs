@@ -0,0 +1,375 @@
+//! The placement phase, where the user puts their ships on the board one by one before the
+//! first shot, then hides their mines under the mines rule.
+
+use std::{cmp::Reverse, collections::BTreeSet};
+
+use super::game::{Board, Coordinate, FleetType, Placement, ShipType, COLS, ROWS};
+
+pub struct Placer {
+  /// the ships dropped so far
+  pub placed: Vec<Placement>,
+  /// the ship being moved around, none once the whole fleet is placed
+  pub current: Option<Placement>,
+  /// the ships to place, in order
+  ships: Vec<ShipType>,
+  /// index of the ship made the flagship, under the flagship rule
+  pub flagship: Option<usize>,
+  /// the mines hidden so far, in the order they were laid
+  pub mines: Vec<Coordinate>,
+  /// the cell the next mine goes in, once the whole fleet is placed and until every mine is
+  mine: Option<Coordinate>,
+  /// the mines to hide, none outside the mines rule
+  to_lay: usize,
+}
+
+impl Placer {
+  pub fn new(fleet_type: &FleetType) -> Self {
+    let mut placer = Self {
+      placed: vec![],
+      current: None,
+      ships: fleet_type.ships(),
+      flagship: None,
+      mines: vec![],
+      mine: None,
+      to_lay: 0,
+    };
+    placer.next();
+    placer
+  }
+
+  /// The placer hiding the number of mines after the fleet
+  pub fn with_mines(mut self, count: usize) -> Self {
+    self.to_lay = count;
+    self
+  }
+
+  /// Whether the fleet is placed and the mines are being hidden
+  pub fn is_laying_mines(&self) -> bool {
+    self.mine.is_some()
+  }
+
+  /// Picks up the next ship of the fleet not placed yet, or the next mine once they all are
+  fn next(&mut self) {
+    self.current = self
+      .ships
+      .get(self.placed.len())
+      .map(|ship_type| Placement {
+        ship_type: ship_type.clone(),
+        rotation: 90,
+        origin: (0, 0),
+      });
+    self.mine = match self.current {
+      None if self.mines.len() < self.to_lay => Some(self.mine.unwrap_or((0, 0))),
+      _ => None,
+    };
+  }
+
+  /// Moves the current ship or mine by the given rows and columns, keeping it on the board
+  pub fn shift(&mut self, rows: isize, cols: isize) {
+    let clamp = |v: usize, by: isize, max: usize| (v as isize + by).clamp(0, max as isize) as usize;
+    if let Some(current) = &mut self.current {
+      let (height, width) = current.size();
+      current.origin = (
+        clamp(current.origin.0, rows, ROWS - height),
+        clamp(current.origin.1, cols, COLS - width),
+      );
+    } else if let Some((r, c)) = &mut self.mine {
+      *r = clamp(*r, rows, ROWS - 1);
+      *c = clamp(*c, cols, COLS - 1);
+    }
+  }
+
+  pub fn rotate(&mut self) {
+    if let Some(current) = &mut self.current {
+      current.rotate();
+    }
+    // a long ship stood up near the bottom would stick out
+    self.shift(0, 0);
+  }
+
+  /// Cells of the current ship that sit on one already dropped, or the cell of the current
+  /// mine when it sits on a ship or another mine
+  pub fn overlaps(&self) -> BTreeSet<Coordinate> {
+    let mut placed = self
+      .placed
+      .iter()
+      .flat_map(|p| p.cells())
+      .collect::<BTreeSet<_>>();
+    if let Some(mine) = self.mine {
+      placed.extend(&self.mines);
+      return placed.into_iter().filter(|c| *c == mine).collect();
+    }
+    self
+      .current
+      .as_ref()
+      .map(|c| c.cells().intersection(&placed).cloned().collect())
+      .unwrap_or_default()
+  }
+
+  /// Drops the current ship where it is, unless it overlaps another one, or hides the
+  /// current mine in an empty cell
+  pub fn drop(&mut self) -> Result<(), String> {
+    if let Some(mine) = self.mine {
+      if self.mines.contains(&mine) {
+        return Err("There is a mine there already".into());
+      }
+      if !self.overlaps().is_empty() {
+        return Err("A mine goes in an empty cell".into());
+      }
+      self.mines.push(mine);
+      self.next();
+      return Ok(());
+    }
+    let current = match &self.current {
+      Some(current) => current,
+      None => return Ok(()),
+    };
+    if !self.overlaps().is_empty() {
+      return Err(format!(
+        "The {} overlaps another ship",
+        current.ship_type.name()
+      ));
+    }
+    self.placed.push(current.clone());
+    self.next();
+    Ok(())
+  }
+
+  /// Picks the last hidden mine or, with none hidden, the last dropped ship up again
+  pub fn undo(&mut self) {
+    if let Some(last) = self.mines.pop() {
+      self.mine = Some(last);
+    } else if let Some(last) = self.placed.pop() {
+      self.current = Some(last);
+      self.mine = None;
+    }
+  }
+
+  /// Flips the ships dropped so far and the current one to the other side of the board, left
+  /// to right with `across` or top to bottom otherwise, leaving them be when one of them has
+  /// a shape that turns into no rotation of itself
+  pub fn mirror(&mut self, across: bool) -> Result<(), String> {
+    let flip = |p: &Placement| {
+      p.mirrored(across)
+        .ok_or_else(|| format!("The {} can't be mirrored", p.ship_type.name()))
+    };
+    let placed = self
+      .placed
+      .iter()
+      .map(flip)
+      .collect::<Result<Vec<_>, _>>()?;
+    let current = self.current.as_ref().map(flip).transpose()?;
+    let cell = |(r, c): Coordinate| {
+      if across {
+        (r, COLS - 1 - c)
+      } else {
+        (ROWS - 1 - r, c)
+      }
+    };
+    self.placed = placed;
+    self.current = current;
+    self.mines = self.mines.iter().copied().map(cell).collect();
+    self.mine = self.mine.map(cell);
+    Ok(())
+  }
+
+  /// Places the rest of the fleet, each ship across the board from the one dropped before
+  /// it, covering as much of that ship's mirror image as it can. With none dropped yet the
+  /// current ship is dropped where it is to start from.
+  pub fn fill_symmetric(&mut self) -> Result<(), String> {
+    if self.placed.is_empty() {
+      self.drop()?;
+    }
+    while let Some(current) = &self.current {
+      let last = match self.placed.last() {
+        Some(last) => last,
+        None => return Ok(()),
+      };
+      let mirror = |(r, c): &Coordinate| (*r, COLS - 1 - c);
+      let target = last.cells().iter().map(mirror).collect::<BTreeSet<_>>();
+      let (row, col) = mirror(&last.center());
+      let taken = self
+        .placed
+        .iter()
+        .flat_map(|p| p.cells())
+        .collect::<BTreeSet<_>>();
+      let best = Placement::all(&current.ship_type)
+        .into_iter()
+        .filter(|p| p.cells().is_disjoint(&taken))
+        .min_by_key(|p| {
+          let (r, c) = p.center();
+          (
+            Reverse(p.cells().intersection(&target).count()),
+            r.abs_diff(row).pow(2) + c.abs_diff(col).pow(2),
+          )
+        });
+      match best {
+        Some(best) => {
+          self.placed.push(best);
+          self.next();
+        }
+        None => return Err(format!("No room left for the {}", current.ship_type.name())),
+      }
+    }
+    Ok(())
+  }
+
+  /// Makes the current ship the flagship, or no ship when it already is one
+  pub fn toggle_flagship(&mut self) {
+    if self.current.is_none() {
+      return;
+    }
+    let index = self.placed.len();
+    self.flagship = if self.flagship == Some(index) {
+      None
+    } else {
+      Some(index)
+    };
+  }
+
+  pub fn is_done(&self) -> bool {
+    self.current.is_none() && self.mine.is_none()
+  }
+
+  /// The board with the ships dropped and the mines hidden so far
+  pub fn board(&self) -> Board {
+    Board::with_fleet(&self.placed).with_mines(&self.mines)
+  }
+
+  /// Cells of the current ship, or the cell of the current mine
+  pub fn cells(&self) -> BTreeSet<Coordinate> {
+    match (&self.current, self.mine) {
+      (Some(current), _) => current.cells(),
+      (None, Some(mine)) => [mine].into(),
+      (None, None) => BTreeSet::new(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::check_fleet;
+
+  #[test]
+  fn test_placer() {
+    let mut placer = Placer::new(&FleetType::Standard);
+    assert_eq!(placer.current.as_ref().unwrap().ship_type, ShipType::X);
+    placer.toggle_flagship();
+    assert_eq!(placer.flagship, Some(0));
+    placer.toggle_flagship();
+    assert_eq!(placer.flagship, None);
+    placer.shift(-1, 20);
+    assert_eq!(placer.current.as_ref().unwrap().origin, (0, COLS - 3));
+    placer.drop().unwrap();
+
+    // the next ship starts over the first one
+    placer.shift(0, 20);
+    assert!(!placer.overlaps().is_empty());
+    assert_eq!(
+      placer.drop().unwrap_err(),
+      "The Destroyer overlaps another ship"
+    );
+    placer.shift(3, 0);
+    placer.rotate();
+    assert_eq!(placer.current.as_ref().unwrap().rotation, 180);
+    placer.drop().unwrap();
+
+    placer.undo();
+    assert_eq!(placer.placed.len(), 1);
+    assert_eq!(placer.current.as_ref().unwrap().rotation, 180);
+
+    placer.drop().unwrap();
+    placer.shift(6, 0);
+    placer.drop().unwrap();
+    placer.shift(6, 6);
+    placer.drop().unwrap();
+    assert!(placer.is_done());
+    assert_eq!(placer.board().placements(), placer.placed);
+  }
+
+  #[test]
+  fn test_placer_mines() {
+    let fleet = FleetType::custom(vec![ShipType::P]).unwrap();
+    let mut placer = Placer::new(&fleet).with_mines(2);
+    placer.drop().unwrap();
+    assert!(placer.is_laying_mines());
+    assert!(!placer.is_done());
+
+    // the mine starts where the patrol boat lies
+    assert_eq!(placer.overlaps(), [(0, 0)].into());
+    assert_eq!(placer.drop().unwrap_err(), "A mine goes in an empty cell");
+    placer.shift(20, 20);
+    assert_eq!(placer.cells(), [(ROWS - 1, COLS - 1)].into());
+    placer.drop().unwrap();
+    assert_eq!(placer.drop().unwrap_err(), "There is a mine there already");
+    placer.mirror(true).unwrap();
+    assert_eq!(placer.mines, vec![(ROWS - 1, 0)]);
+    assert_eq!(placer.placed[0].origin, (0, COLS - 2));
+
+    // taking back the mine then the ship
+    placer.undo();
+    assert!(placer.mines.is_empty());
+    placer.undo();
+    assert!(!placer.is_laying_mines());
+    assert!(placer.current.is_some());
+    placer.drop().unwrap();
+    placer.shift(-20, -20);
+    placer.drop().unwrap();
+    placer.shift(1, 0);
+    placer.drop().unwrap();
+    assert!(placer.is_done());
+    assert!(placer.board().is_mined((1, 0)));
+  }
+
+  #[test]
+  fn test_placer_mirror() {
+    let mut placer = Placer::new(&FleetType::Classic);
+    placer.drop().unwrap();
+    placer.rotate();
+    assert!(placer.mirror(true).is_ok());
+    assert_eq!(placer.placed[0].origin, (0, COLS - 5));
+    assert_eq!(placer.current.as_ref().unwrap().origin, (0, COLS - 1));
+    placer.mirror(false).unwrap();
+    assert_eq!(placer.placed[0].origin, (ROWS - 1, COLS - 5));
+    assert_eq!(
+      placer.current.as_ref().unwrap().origin,
+      (ROWS - 4, COLS - 1)
+    );
+
+    // the cruiser and the submarine are the same shape, so one mirrors the other exactly
+    let fleet = FleetType::custom(vec![ShipType::C, ShipType::S, ShipType::A]).unwrap();
+    let mut placer = Placer::new(&fleet);
+    placer.shift(2, 1);
+    placer.fill_symmetric().unwrap();
+    assert!(placer.is_done());
+    assert!(check_fleet(&placer.placed).is_ok());
+    assert_eq!(placer.placed[1].cells(), [(2, 6), (2, 7), (2, 8)].into());
+    // with the cruiser in the way, the carrier lies as near its mirror image as it can
+    assert_eq!(placer.placed[2].origin, (1, 0));
+    assert_eq!(placer.placed[2].rotation, 90);
+  }
+
+  #[test]
+  fn test_placer_classic() {
+    let mut placer = Placer::new(&FleetType::Classic);
+    assert_eq!(placer.cells().len(), 5);
+    placer.shift(20, 20);
+    assert_eq!(
+      placer.current.as_ref().unwrap().origin,
+      (ROWS - 1, COLS - 5)
+    );
+    // standing up keeps the whole ship on the board
+    placer.rotate();
+    assert_eq!(
+      placer.current.as_ref().unwrap().origin,
+      (ROWS - 5, COLS - 5)
+    );
+    assert!(placer.cells().iter().all(|(r, c)| *r < ROWS && *c < COLS));
+    placer.drop().unwrap();
+    assert_eq!(
+      placer.current.as_ref().unwrap().ship_type.name(),
+      "Battleship"
+    );
+  }
+}
@@ -0,0 +1,205 @@
+//! Named combinations of rule, difficulty and mode. Some are bundled with the game and
+//! more are saved in a TOML file in the config directory, so a combination found in the
+//! sandbox can be played again with `--preset`:
+//!
+//! ```toml
+//! [blitz]
+//! rule = "Fury"
+//! difficulty = "Hard"
+//! mode = "TimeAttack"
+//! time_limit = 120
+//! ```
+
+use std::{
+  collections::BTreeMap,
+  fs,
+  io::{self, ErrorKind},
+  path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+use structopt::clap::arg_enum;
+
+use super::game::{Difficulty, Rule};
+
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Mode {
+        Normal,
+        TimeAttack, // the computer doesn't fire back
+        Gauntlet,   // a fresh fleet after every sunk one
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Preset {
+  #[serde(with = "text")]
+  pub rule: Rule,
+  #[serde(with = "text")]
+  pub difficulty: Difficulty,
+  #[serde(with = "text", default = "normal")]
+  pub mode: Mode,
+  /// seconds allowed for a time attack
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub time_limit: Option<u64>,
+  #[serde(default)]
+  pub strict: bool,
+}
+
+fn normal() -> Mode {
+  Mode::Normal
+}
+
+impl Default for Preset {
+  fn default() -> Self {
+    Self {
+      rule: Rule::Default,
+      difficulty: Difficulty::Hard,
+      mode: Mode::Normal,
+      time_limit: None,
+      strict: false,
+    }
+  }
+}
+
+/// The presets bundled with the game
+const BUNDLED: &str = include_str!("presets.toml");
+
+/// All the presets, the user's taking the place of bundled ones of the same name
+pub fn presets(user: Option<&PresetFile>) -> io::Result<BTreeMap<String, Preset>> {
+  let mut presets = parse(BUNDLED)?;
+  if let Some(user) = user {
+    presets.extend(user.load()?);
+  }
+  Ok(presets)
+}
+
+fn parse(content: &str) -> io::Result<BTreeMap<String, Preset>> {
+  toml::from_str(content).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+/// The file with the user's presets
+pub struct PresetFile {
+  path: PathBuf,
+}
+
+impl PresetFile {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  /// The presets file in the user's config directory, if the platform has one
+  pub fn in_config_dir() -> Option<Self> {
+    dirs::config_dir().map(|dir| Self::new(dir.join("battleship-rs").join("presets.toml")))
+  }
+
+  pub fn load(&self) -> io::Result<BTreeMap<String, Preset>> {
+    if !self.path.exists() {
+      return Ok(BTreeMap::new());
+    }
+    parse(&fs::read_to_string(&self.path)?)
+  }
+
+  /// Adds the preset to the file, replacing any preset of the same name
+  pub fn save(&self, name: &str, preset: &Preset) -> io::Result<()> {
+    let mut presets = self.load()?;
+    presets.insert(name.into(), preset.clone());
+    let content =
+      toml::to_string(&presets).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    if let Some(dir) = self.path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    fs::write(&self.path, content)
+  }
+}
+
+/// Serializes the enums by their names, as they're spelled on the command line
+mod text {
+  use std::{fmt::Display, str::FromStr};
+
+  use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+  pub fn serialize<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+  }
+
+  pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+  where
+    T: FromStr<Err = String>,
+    D: Deserializer<'de>,
+  {
+    String::deserialize(deserializer)?
+      .parse()
+      .map_err(D::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use uuid::Uuid;
+
+  use super::*;
+
+  #[test]
+  fn test_presets_bundled() {
+    let presets = presets(None).unwrap();
+    assert_eq!(
+      presets.keys().collect::<Vec<_>>(),
+      vec!["blitz", "chaos", "classic"]
+    );
+    assert_eq!(presets["classic"], Preset::default());
+    assert_eq!(presets["blitz"].mode, Mode::TimeAttack);
+    assert_eq!(presets["chaos"].mode, Mode::Gauntlet);
+  }
+
+  #[test]
+  fn test_presets_user_overrides() {
+    let dir = env::temp_dir().join(format!("battleship-{}", Uuid::new_v4()));
+    let file = PresetFile::new(dir.join("presets.toml"));
+    let easy = Preset {
+      difficulty: Difficulty::Easy,
+      ..Preset::default()
+    };
+    file.save("classic", &easy).unwrap();
+    file.save("mine", &easy).unwrap();
+
+    let presets = presets(Some(&file)).unwrap();
+    assert_eq!(presets.len(), 4);
+    assert_eq!(presets["classic"], easy);
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn test_preset_file_save() {
+    let dir = env::temp_dir().join(format!("battleship-{}", Uuid::new_v4()));
+    let file = PresetFile::new(dir.join("presets.toml"));
+    assert!(file.load().unwrap().is_empty());
+
+    let blitz = Preset {
+      rule: Rule::Fury,
+      mode: Mode::TimeAttack,
+      time_limit: Some(120),
+      ..Preset::default()
+    };
+    file.save("blitz", &blitz).unwrap();
+    file.save("plain", &Preset::default()).unwrap();
+    let presets = file.load().unwrap();
+    assert_eq!(presets.len(), 2);
+    assert_eq!(presets["blitz"], blitz);
+
+    let content = fs::read_to_string(&file.path).unwrap();
+    assert!(
+      content.contains("[blitz]\nrule = \"Fury\"\ndifficulty = \"Hard\"\nmode = \"TimeAttack\"")
+    );
+
+    fs::write(
+      &file.path,
+      "[broken]\nrule = \"Sudden\"\ndifficulty = \"Hard\"\n",
+    )
+    .unwrap();
+    assert_eq!(file.load().unwrap_err().kind(), ErrorKind::InvalidData);
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,293 @@
+//! Steps through a recorded match turn by turn, showing both boards as they were after
+//! each turn, and checks that a match dealt from a seed still plays out as recorded.
+
+use std::collections::BTreeSet;
+
+use super::game::{a1_notation, write_power_up, Coordinate, Game, Status, Turn};
+
+/// Turns the scrubber jumps by at once
+pub const SCRUB_STEP: isize = 10;
+
+/// What the tick of a turn on the scrubber shows of both fleets after the turn
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Thumbnail {
+  /// cells of each fleet hit so far, the user's first
+  pub hits: [usize; 2],
+  /// cells around each fleet missed so far
+  pub misses: [usize; 2],
+  /// the fleet the turn fired upon
+  pub target: usize,
+  /// whether the turn hit it
+  pub hit: bool,
+}
+
+impl Thumbnail {
+  /// Cells of the fleet fired upon so far
+  pub fn fired(&self, fleet: usize) -> usize {
+    self.hits[fleet] + self.misses[fleet]
+  }
+}
+
+/// The thumbnails of every turn, added up from what each turn changed on the fleets
+pub fn thumbnails(timeline: &[Turn]) -> Vec<Thumbnail> {
+  let mut last = Thumbnail::default();
+  timeline
+    .iter()
+    .map(|turn| {
+      let target = 1 - turn.player;
+      let mut thumbnail = Thumbnail {
+        target,
+        hit: turn
+          .response
+          .values()
+          .any(|status| matches!(status, Status::Hit | Status::Kill | Status::Dent)),
+        ..last
+      };
+      for change in &turn.fleet_changes {
+        if change.to == Status::Hit && change.from != Status::Hit {
+          thumbnail.hits[target] += 1;
+        } else if matches!(change.to, Status::Miss | Status::Mine) && change.from != change.to {
+          thumbnail.misses[target] += 1;
+        }
+      }
+      // the shooter's own ship cells its mines blew up
+      thumbnail.hits[turn.player] += turn.blasts.len();
+      last = thumbnail;
+      thumbnail
+    })
+    .collect()
+}
+
+pub struct Replay {
+  /// the match as it was recorded
+  full: Game,
+  /// turns played on the boards shown
+  pub step: usize,
+  /// the tick of every turn on the scrubber
+  pub thumbnails: Vec<Thumbnail>,
+}
+
+impl Replay {
+  pub fn new(full: Game, step: usize) -> Self {
+    let step = step.min(full.timeline().len());
+    let thumbnails = thumbnails(full.timeline());
+    Self {
+      full,
+      step,
+      thumbnails,
+    }
+  }
+
+  /// What the user wrote down about the match when it was played
+  pub fn note(&self) -> Option<&str> {
+    self.full.note()
+  }
+
+  pub fn len(&self) -> usize {
+    self.full.timeline().len()
+  }
+
+  /// Moves by the given number of turns, staying within the match
+  pub fn go(&mut self, by: isize) {
+    self.step = (self.step as isize + by).clamp(0, self.len() as isize) as usize;
+  }
+
+  pub fn go_to(&mut self, step: usize) {
+    self.step = step.min(self.len());
+  }
+
+  /// The match after the current step
+  pub fn at(&self) -> Game {
+    if self.step == self.len() {
+      // the whole match, with a result that may not come from its turns like a forfeit
+      return self.full.clone();
+    }
+    let full = &self.full;
+    let mut game = Game::restore(
+      full.rule,
+      *full.difficulty(),
+      [
+        full.player().player_board().placements(),
+        full.computer().player_board().placements(),
+      ],
+      &[],
+    )
+    .with_setup_of(full);
+    game.set_first(full.first());
+    game.set_balanced(full.is_balanced());
+    game.replay_turns(&full.timeline()[..self.step])
+  }
+}
+
+/// Plays a recorded match again from its seed and returns the number of turns played, or
+/// where the engine no longer deals the computer's fleet or picks its shots as it did
+pub fn verify(recorded: &Game) -> Result<usize, String> {
+  let seed = recorded
+    .seed()
+    .ok_or("The match names no seed to play it again from")?;
+  let mut game = Game::seeded(
+    recorded.rule,
+    *recorded.difficulty(),
+    recorded.fleet_type().clone(),
+    seed,
+  );
+  if game.computer().player_board().placements() != recorded.computer().player_board().placements()
+  {
+    return Err(format!("Seed {} deals the computer another fleet", seed));
+  }
+  // the user may have placed their own fleet
+  game.place_fleet(&recorded.player().player_board().placements())?;
+  game.set_first(recorded.first());
+  game.set_balanced(recorded.is_balanced());
+  for (index, turn) in recorded.timeline().iter().enumerate() {
+    if game.is_user_turn() {
+      game.play(turn, false)?;
+      continue;
+    }
+    game.bot_fire();
+    let played = &game.timeline()[index];
+    if played.power_up != turn.power_up {
+      return Err(format!(
+        "Turn {}: the computer plays {} instead of {}",
+        index + 1,
+        cells_or_power_up(played),
+        cells_or_power_up(turn)
+      ));
+    }
+    if played.shots != turn.shots {
+      return Err(format!(
+        "Turn {}: the computer fires at {} instead of {}",
+        index + 1,
+        cells(&played.shots),
+        cells(&turn.shots)
+      ));
+    }
+  }
+  Ok(recorded.timeline().len())
+}
+
+fn cells_or_power_up(turn: &Turn) -> String {
+  turn
+    .power_up
+    .map_or_else(|| cells(&turn.shots), write_power_up)
+}
+
+fn cells(shots: &BTreeSet<Coordinate>) -> String {
+  shots
+    .iter()
+    .map(|c| a1_notation(*c))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    game::{Difficulty, FleetType, Rule},
+    notation,
+  };
+
+  #[test]
+  fn test_replay_steps() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    for _ in 0..3 {
+      let shots = game.legal_shots(0).into_iter().take(1).collect();
+      game.fire(&shots, false);
+      game.bot_fire();
+    }
+    game.forfeit(0);
+
+    let mut replay = Replay::new(game, 0);
+    assert!(replay.at().timeline().is_empty());
+    replay.go(-1);
+    assert_eq!(replay.step, 0);
+    replay.go(3);
+    assert_eq!(replay.at().timeline(), &replay.full.timeline()[..3]);
+    assert!(!replay.at().is_won());
+    replay.go(10);
+    assert_eq!(replay.step, 6);
+    // the forfeit shows once the end is reached
+    assert_eq!(replay.at().winner(), Some(1));
+  }
+
+  #[test]
+  fn test_replay_thumbnails() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    let cells = game.computer().player_board().placements()[0].cells();
+    let ship = *cells.iter().next().unwrap();
+    let water = game
+      .legal_shots(0)
+      .into_iter()
+      .find(|c| game.computer().player_board().status_at(*c) == Status::Space)
+      .unwrap();
+    game.fire(&[ship].iter().cloned().collect(), false);
+    game.bot_fire();
+    game.fire(&[water].iter().cloned().collect(), false);
+
+    let replay = Replay::new(game, 0);
+    let thumbnails = &replay.thumbnails;
+    assert_eq!(thumbnails.len(), 3);
+    assert_eq!((thumbnails[0].target, thumbnails[0].hit), (1, true));
+    assert_eq!((thumbnails[0].hits, thumbnails[0].misses), ([0, 1], [0, 0]));
+    assert_eq!(thumbnails[1].target, 0);
+    assert_eq!(thumbnails[1].fired(0), 1);
+    assert_eq!(thumbnails[1].hits[1], 1);
+    assert!(!thumbnails[2].hit);
+    assert_eq!((thumbnails[2].hits[1], thumbnails[2].misses[1]), (1, 1));
+  }
+
+  #[test]
+  fn test_verify() {
+    let mut game = Game::seeded(Rule::Fury, Difficulty::Expert, FleetType::Standard, 11);
+    for _ in 0..4 {
+      let shots = game.legal_shots(0).into_iter().take(2).collect();
+      game.fire(&shots, false);
+      game.bot_fire();
+    }
+    let text = notation::write(&game);
+    assert!(text.contains("[Seed \"11\"]\n\n"));
+    assert_eq!(verify(&notation::parse(&text).unwrap()), Ok(8));
+
+    // the computer plays on from an imported match as it would have
+    let mut imported = notation::parse(&text).unwrap();
+    imported.fire(&game.legal_shots(0).into_iter().take(2).collect(), false);
+    game.fire(&game.legal_shots(0).into_iter().take(2).collect(), false);
+    imported.bot_fire();
+    game.bot_fire();
+    assert_eq!(imported.timeline(), game.timeline());
+
+    let other = notation::write(&Game::seeded(
+      Rule::Fury,
+      Difficulty::Expert,
+      FleetType::Standard,
+      12,
+    ));
+    let computer = |text: &str| {
+      let start = text.find("[Computer").unwrap();
+      text[start..text.find("[Seed").unwrap()].to_string()
+    };
+    let tampered = text.replacen(&computer(&text), &computer(&other), 1);
+    assert_eq!(
+      verify(&notation::parse(&tampered).unwrap()),
+      Err("Seed 11 deals the computer another fleet".into())
+    );
+    let unseeded = text.replace("[Seed \"11\"]\n", "");
+    assert!(verify(&notation::parse(&unseeded).unwrap()).is_err());
+
+    // a match the computer opened plays out the same
+    let mut game = Game::seeded(Rule::Default, Difficulty::Hard, FleetType::Standard, 5);
+    game.set_first(1);
+    game.set_balanced(true);
+    for _ in 0..3 {
+      game.bot_fire();
+      game.fire(&game.legal_shots(0).into_iter().take(1).collect(), false);
+    }
+    let recorded = notation::parse(&notation::write(&game)).unwrap();
+    assert_eq!(verify(&recorded), Ok(6));
+    let mut replay = Replay::new(recorded, 0);
+    assert!(!replay.at().is_user_turn());
+    replay.go(1);
+    assert_eq!(replay.at().timeline(), &game.timeline()[..1]);
+  }
+}
@@ -0,0 +1,136 @@
+//! A screen to try out combinations of rule, difficulty and mode in a throwaway game.
+
+use super::{
+  clock::format_duration,
+  game::{Difficulty, Rule},
+  preset::{Mode, Preset},
+};
+
+/// Time limits to choose from for a time attack, in seconds
+const TIME_LIMITS: [Option<u64>; 4] = [None, Some(60), Some(120), Some(300)];
+
+pub struct Sandbox {
+  pub preset: Preset,
+  /// the option under the cursor
+  pub option: usize,
+  /// name typed so far when saving the combination as a preset
+  pub naming: Option<String>,
+}
+
+impl Sandbox {
+  const OPTIONS: usize = 5;
+
+  pub fn new(preset: Preset) -> Self {
+    Self {
+      preset,
+      option: 0,
+      naming: None,
+    }
+  }
+
+  pub fn up(&mut self) {
+    self.option = self.option.checked_sub(1).unwrap_or(Self::OPTIONS - 1);
+  }
+
+  pub fn down(&mut self) {
+    self.option = (self.option + 1) % Self::OPTIONS;
+  }
+
+  /// Moves the option under the cursor to its next or previous value, wrapping around
+  pub fn change(&mut self, forward: bool) {
+    let preset = &mut self.preset;
+    match self.option {
+      0 => {
+        let rules = [
+          Rule::Default,
+          Rule::Fury,
+          Rule::Charge,
+          Rule::Salvo,
+          Rule::Chain,
+          Rule::Drift,
+          Rule::Mines,
+          Rule::Critical,
+          Rule::Armored,
+          Rule::Hazards,
+          Rule::Night,
+        ];
+        preset.rule = cycle(&rules, preset.rule, forward);
+      }
+      1 => {
+        let difficulties = [Difficulty::Easy, Difficulty::Hard, Difficulty::Expert];
+        preset.difficulty = cycle(&difficulties, preset.difficulty, forward);
+      }
+      2 => {
+        let modes = [Mode::Normal, Mode::TimeAttack, Mode::Gauntlet];
+        preset.mode = cycle(&modes, preset.mode, forward);
+      }
+      3 => preset.time_limit = cycle(&TIME_LIMITS, preset.time_limit, forward),
+      _ => preset.strict = !preset.strict,
+    }
+  }
+
+  /// The options with their values, the one under the cursor marked
+  pub fn lines(&self) -> Vec<String> {
+    let preset = &self.preset;
+    let time_limit = match preset.time_limit {
+      Some(secs) => format_duration(std::time::Duration::from_secs(secs)),
+      None => "none".into(),
+    };
+    let options = [
+      format!("Rule: {}", preset.rule),
+      format!("Difficulty: {}", preset.difficulty),
+      format!("Mode: {}", preset.mode),
+      format!("Time limit: {}", time_limit),
+      format!("Strict: {}", if preset.strict { "on" } else { "off" }),
+    ];
+    let mut lines = options
+      .iter()
+      .enumerate()
+      .map(|(i, o)| format!("{} {}", if i == self.option { ">" } else { " " }, o))
+      .collect::<Vec<_>>();
+    lines.push(String::new());
+    match &self.naming {
+      Some(name) => lines.push(format!("Preset name: {}_", name)),
+      None => {
+        lines.push("<h/l> change | <enter> play".into());
+        lines.push("<w> save preset | <esc> close".into());
+      }
+    }
+    lines
+  }
+}
+
+fn cycle<T: PartialEq + Copy>(values: &[T], current: T, forward: bool) -> T {
+  let index = values.iter().position(|v| *v == current).unwrap_or(0);
+  let next = if forward {
+    (index + 1) % values.len()
+  } else {
+    (index + values.len() - 1) % values.len()
+  };
+  values[next]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sandbox_change() {
+    let mut sandbox = Sandbox::new(Preset::default());
+    sandbox.change(true);
+    assert_eq!(sandbox.preset.rule, Rule::Fury);
+    sandbox.change(false);
+    sandbox.change(false);
+    assert_eq!(sandbox.preset.rule, Rule::Night);
+
+    sandbox.up();
+    assert_eq!(sandbox.option, 4);
+    sandbox.change(true);
+    assert!(sandbox.preset.strict);
+
+    sandbox.up();
+    sandbox.change(true);
+    assert_eq!(sandbox.preset.time_limit, Some(60));
+    assert!(sandbox.lines().contains(&"> Time limit: 01:00".to_string()));
+  }
+}
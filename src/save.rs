@@ -0,0 +1,271 @@
+//! A match saved on request to be resumed later, as JSON in `~/.battleship-rs/save.json`.
+//! Like the turn log it keeps the fleets and the shots of every turn, and the match is
+//! rebuilt by replaying them.
+
+use std::{
+  fs,
+  io::{self, ErrorKind},
+  path::PathBuf,
+  time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+  features,
+  game::{
+    a1_notation, collect_salvo, parse_a1, parse_power_up, write_power_up, Difficulty, Game, Turn,
+  },
+  notation::{parse_fleet, write_fleet},
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Save {
+  pub rule: String,
+  pub difficulty: String,
+  /// the fleets of the user and the computer, `X@A1/90 V@D1/90 ...`
+  pub fleets: [String; 2],
+  /// the cells fired upon in every turn, `["B7", "C3"]`, or the power-up used, `["C4?"]`
+  pub turns: Vec<Vec<String>>,
+  /// seconds on the clock
+  pub elapsed: u64,
+  /// the player who fired first, the computer's turns come first when it's 1
+  #[serde(default)]
+  pub first: usize,
+  /// whether the player firing second got a shot more in their first turn
+  #[serde(default)]
+  pub balanced: bool,
+  /// optional features the match depends on, which a build has to have to resume it
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub features: Vec<String>,
+  /// what the user wrote down about the match
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub note: Option<String>,
+  /// where the flagships are in the fleets of the user and the computer, under the flagship
+  /// rule
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub flagships: Option<[usize; 2]>,
+  /// the cells the user and the computer hid their mines in, `["E5", "B8"]`, under the mines
+  /// rule
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub mines: Option<[Vec<String>; 2]>,
+}
+
+impl Save {
+  pub fn of(game: &Game, elapsed: Duration) -> Self {
+    Self {
+      rule: game.rule.to_string(),
+      difficulty: game.difficulty().to_string(),
+      fleets: [
+        write_fleet(&game.player().player_board().placements()),
+        write_fleet(&game.computer().player_board().placements()),
+      ],
+      turns: game
+        .timeline()
+        .iter()
+        .map(|turn| match turn.power_up {
+          Some(power_up) => vec![write_power_up(power_up)],
+          None => turn.shots.iter().map(|c| a1_notation(*c)).collect(),
+        })
+        .collect(),
+      elapsed: elapsed.as_secs(),
+      first: game.first(),
+      balanced: game.is_balanced(),
+      features: vec![],
+      note: game.note().map(String::from),
+      flagships: match [game.flagship(0), game.flagship(1)] {
+        [Some(you), Some(computer)] => Some([you, computer]),
+        _ => None,
+      },
+      mines: match [game.mines(0), game.mines(1)] {
+        [you, computer] if !you.is_empty() && !computer.is_empty() => {
+          Some([you, computer].map(|mines| mines.iter().map(|c| a1_notation(*c)).collect()))
+        }
+        _ => None,
+      },
+    }
+  }
+
+  /// The saved match and the time on its clock
+  pub fn restore(&self) -> io::Result<(Game, Duration)> {
+    let invalid = |msg: String| io::Error::new(ErrorKind::InvalidData, format!("save: {}", msg));
+    features::check(&self.features).map_err(invalid)?;
+    let rule = features::parse_rule(&self.rule).map_err(invalid)?;
+    let difficulty = self.difficulty.parse::<Difficulty>().map_err(invalid)?;
+    if self.first > 1 {
+      return Err(invalid(format!("invalid first player {}", self.first)));
+    }
+    let fleets = [
+      parse_fleet(&self.fleets[0]).map_err(invalid)?,
+      parse_fleet(&self.fleets[1]).map_err(invalid)?,
+    ];
+    let turns = self
+      .turns
+      .iter()
+      .enumerate()
+      .map(|(index, shots)| {
+        if let [power_up] = shots.as_slice() {
+          if let Some(power_up) = parse_power_up(power_up) {
+            return Ok(Turn {
+              player: (self.first + index) % 2,
+              power_up: Some(power_up),
+              ..Default::default()
+            });
+          }
+        }
+        let shots = shots
+          .iter()
+          .map(|s| parse_a1(s).ok_or_else(|| invalid(format!("invalid cell {}", s))))
+          .collect::<io::Result<Vec<_>>>()?;
+        Ok(Turn {
+          player: (self.first + index) % 2,
+          shots: collect_salvo(&shots).map_err(|err| invalid(err.to_string()))?,
+          ..Default::default()
+        })
+      })
+      .collect::<io::Result<Vec<_>>>()?;
+    let mut game = Game::restore(rule, difficulty, fleets, &[]);
+    game.set_first(self.first);
+    game.set_balanced(self.balanced);
+    for (player, index) in self.flagships.iter().flatten().enumerate() {
+      game.set_flagship(player, *index).map_err(invalid)?;
+    }
+    for (player, cells) in self.mines.iter().flatten().enumerate() {
+      let cells = cells
+        .iter()
+        .map(|s| parse_a1(s).ok_or_else(|| invalid(format!("invalid mine {}", s))))
+        .collect::<io::Result<_>>()?;
+      game.set_mines(player, &cells).map_err(invalid)?;
+    }
+    let mut game = game.replay_turns(&turns);
+    if game.timeline().len() < turns.len() {
+      return Err(invalid("the match is over before its last turn".into()));
+    }
+    game.set_note(self.note.clone());
+    Ok((game, Duration::from_secs(self.elapsed)))
+  }
+}
+
+pub struct SaveFile {
+  path: PathBuf,
+}
+
+impl SaveFile {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  pub fn in_home_dir() -> Option<Self> {
+    dirs::home_dir().map(|dir| Self::new(dir.join(".battleship-rs").join("save.json")))
+  }
+
+  pub fn load(&self) -> io::Result<Save> {
+    let text = fs::read_to_string(&self.path).map_err(|err| match err.kind() {
+      ErrorKind::NotFound => io::Error::new(ErrorKind::NotFound, "No saved match to resume"),
+      _ => err,
+    })?;
+    serde_json::from_str(&text).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+  }
+
+  /// Saves the match, replacing the one saved before
+  pub fn save(&self, save: &Save) -> io::Result<()> {
+    if let Some(dir) = self.path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(save).map_err(io::Error::other)?;
+    fs::write(&self.path, json)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::game::{PowerUp, Rule, MINES};
+
+  #[test]
+  fn test_save_round_trip() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Expert, 0);
+    for _ in 0..3 {
+      let shots = game.legal_shots(0).into_iter().take(2).collect();
+      game.fire(&shots, false);
+      game.bot_fire();
+    }
+    game.use_power_up(PowerUp::Sonar, (4, 4), false).unwrap();
+    game.set_note(Some("sonar on E5".into()));
+    let save = Save::of(&game, Duration::from_secs(75));
+    assert_eq!(save.turns[0], vec!["A1", "B1"]);
+    assert_eq!(save.turns[6], vec!["E5?"]);
+
+    let dir = env::temp_dir().join(format!("battleship-{}", Uuid::new_v4()));
+    let file = SaveFile::new(dir.join("save.json"));
+    assert_eq!(
+      file.load().err().map(|err| err.to_string()),
+      Some("No saved match to resume".into())
+    );
+    file.save(&save).unwrap();
+    let (restored, elapsed) = file.load().unwrap().restore().unwrap();
+    assert_eq!(restored.timeline(), game.timeline());
+    assert_eq!(restored.difficulty(), &Difficulty::Expert);
+    assert_eq!(elapsed, Duration::from_secs(75));
+    assert_eq!(restored.note(), Some("sonar on E5"));
+    fs::remove_dir_all(dir).unwrap();
+
+    let newer = Save {
+      rule: "Torpedoes".into(),
+      ..Save::of(&game, Duration::from_secs(75))
+    };
+    assert_eq!(
+      newer.restore().err().map(|err| err.to_string()),
+      Some("save: the match is played by the Torpedoes rule, which this build lacks".into())
+    );
+    let json = serde_json::to_string(&newer).unwrap();
+    assert!(!json.contains("features"));
+    let newer = serde_json::from_str::<Save>(
+      &json.replace("\"elapsed\"", "\"features\":[\"torpedoes\"],\"elapsed\""),
+    )
+    .unwrap();
+    assert_eq!(
+      newer.restore().err().map(|err| err.to_string()),
+      Some("save: the match needs features this build lacks: torpedoes".into())
+    );
+
+    // the computer's turns come first when it fired first
+    let mut game = Game::new(Rule::Default, Difficulty::Hard, 1);
+    game.set_balanced(true);
+    game.bot_fire();
+    game.fire(&game.legal_shots(0).into_iter().take(2).collect(), false);
+    game.bot_fire();
+    let (restored, _) = Save::of(&game, Duration::ZERO).restore().unwrap();
+    assert_eq!((restored.first(), restored.is_balanced()), (1, true));
+    assert_eq!(restored.timeline(), game.timeline());
+    assert_eq!(restored.state_hash(), game.state_hash());
+    // saves from before the first player was kept are the user's first
+    let json = serde_json::to_string(&save).unwrap();
+    let json = json.replace(",\"first\":0,\"balanced\":false", "");
+    assert!(!json.contains("first"));
+    assert_eq!(serde_json::from_str::<Save>(&json).unwrap(), save);
+
+    // the mines are the ones the players hid
+    let mut game = Game::new(Rule::Mines, Difficulty::Hard, 0);
+    game.fire(&game.legal_shots(0).into_iter().take(1).collect(), false);
+    game.bot_fire();
+    let mined = Save::of(&game, Duration::ZERO);
+    assert_eq!(mined.mines.as_ref().map(|m| m[1].len()), Some(MINES));
+    let (restored, _) = mined.restore().unwrap();
+    assert_eq!(restored.mines(1), game.mines(1));
+    assert_eq!(restored.timeline(), game.timeline());
+
+    let broken = Save {
+      turns: vec![vec!["K11".into()]],
+      ..save
+    };
+    assert_eq!(
+      broken.restore().err().map(|err| err.to_string()),
+      Some("save: invalid cell K11".into())
+    );
+  }
+}
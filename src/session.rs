@@ -0,0 +1,111 @@
+//! The matches open in the tabs when the game was quit, opened again in tabs of their own
+//! at the next start, as JSON in `~/.battleship-rs/session.json`. A match against the
+//! computer is kept the way <ctrl-s> saves it, and a hosted match still waiting for the
+//! other player is hosted again on its port. A match against another player in progress
+//! ends with the connection and isn't kept.
+
+use std::{
+  fs,
+  io::{self, ErrorKind},
+  path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::save::Save;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Session {
+  pub tabs: Vec<SessionTab>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SessionTab {
+  pub save: Save,
+  /// port the match is hosted on while it waits for the other player
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub hosting: Option<u16>,
+}
+
+pub struct SessionFile {
+  path: PathBuf,
+}
+
+impl SessionFile {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  pub fn in_home_dir() -> Option<Self> {
+    dirs::home_dir().map(|dir| Self::new(dir.join(".battleship-rs").join("session.json")))
+  }
+
+  /// The session left at the last quit, an empty one when there is none
+  pub fn load(&self) -> io::Result<Session> {
+    let text = match fs::read_to_string(&self.path) {
+      Ok(text) => text,
+      Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Session::default()),
+      Err(err) => return Err(err),
+    };
+    serde_json::from_str(&text)
+      .map_err(|err| io::Error::new(ErrorKind::InvalidData, format!("session: {}", err)))
+  }
+
+  /// Keeps the session for the next start, removing the file when nothing is left open
+  pub fn save(&self, session: &Session) -> io::Result<()> {
+    if session.tabs.is_empty() {
+      return match fs::remove_file(&self.path) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+      };
+    }
+    if let Some(dir) = self.path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(session).map_err(io::Error::other)?;
+    fs::write(&self.path, json)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{env, time::Duration};
+
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::game::{Difficulty, Game, Rule};
+
+  #[test]
+  fn test_session_file() {
+    let dir = env::temp_dir().join(format!("battleship-{}", Uuid::new_v4()));
+    let file = SessionFile::new(dir.join("session.json"));
+    assert_eq!(file.load().unwrap(), Session::default());
+
+    let game = Game::new(Rule::Fury, Difficulty::Hard, 0);
+    let session = Session {
+      tabs: vec![
+        SessionTab {
+          save: Save::of(&game, Duration::from_secs(30)),
+          hosting: None,
+        },
+        SessionTab {
+          save: Save::of(&game, Duration::ZERO),
+          hosting: Some(4000),
+        },
+      ],
+    };
+    file.save(&session).unwrap();
+    assert_eq!(file.load().unwrap(), session);
+
+    file.save(&Session::default()).unwrap();
+    assert!(!dir.join("session.json").exists());
+    fs::write(dir.join("session.json"), "{").unwrap();
+    assert!(file
+      .load()
+      .unwrap_err()
+      .to_string()
+      .starts_with("session: "));
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
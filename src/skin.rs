@@ -0,0 +1,122 @@
+//! Skins, picked with `--skin`, dress the fleets up as something else: they rename the
+//! ships, draw the cells with emoji of their own and tint the boards. They're cosmetic
+//! only, the ships keep their shapes and a match written down with a skin reads the same
+//! as one without.
+
+use structopt::clap::arg_enum;
+use tui::style::Color;
+
+use super::{
+  game::{ShipType, Status},
+  widget::BoardTheme,
+};
+
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Skin {
+        Naval,  // the ships of a navy, as the game was made
+        Space,  // starships in the dark
+        Pirate, // sails on the high seas
+    }
+}
+
+impl Skin {
+  /// What the skin calls the ship, ships the user drew keeping the names they were given
+  pub fn ship_name<'a>(&self, ship_type: &'a ShipType) -> &'a str {
+    let name = match (self, ship_type) {
+      (Skin::Naval, _) | (_, ShipType::Custom(_)) => None,
+      (Skin::Space, ShipType::X) => Some("Interceptor"),
+      (Skin::Space, ShipType::V) => Some("Star destroyer"),
+      (Skin::Space, ShipType::H) => Some("Mothership"),
+      (Skin::Space, ShipType::I) => Some("Probe"),
+      (Skin::Space, ShipType::A) => Some("Dreadnought"),
+      (Skin::Space, ShipType::B) => Some("Battlecruiser"),
+      (Skin::Space, ShipType::C) => Some("Corvette"),
+      (Skin::Space, ShipType::S) => Some("Cloaked raider"),
+      (Skin::Space, ShipType::P) => Some("Shuttle"),
+      (Skin::Pirate, ShipType::X) => Some("Brigantine"),
+      (Skin::Pirate, ShipType::V) => Some("Privateer"),
+      (Skin::Pirate, ShipType::H) => Some("Treasure ship"),
+      (Skin::Pirate, ShipType::I) => Some("Sloop"),
+      (Skin::Pirate, ShipType::A) => Some("Galleon"),
+      (Skin::Pirate, ShipType::B) => Some("Man-o'-war"),
+      (Skin::Pirate, ShipType::C) => Some("Frigate"),
+      (Skin::Pirate, ShipType::S) => Some("Corsair"),
+      (Skin::Pirate, ShipType::P) => Some("Jolly boat"),
+    };
+    name.unwrap_or_else(|| ship_type.name())
+  }
+
+  /// The text with the ship's own name swapped for the one the skin calls it
+  pub fn rename(&self, text: &str, ship_type: &ShipType) -> String {
+    text.replace(ship_type.name(), self.ship_name(ship_type))
+  }
+
+  /// The emoji the skin draws a cell of the status with, where it has one of its own. The
+  /// plain glyphs stay the same under every skin, so boards can still be read back.
+  pub fn emoji(&self, status: Status) -> Option<&'static str> {
+    match (self, status) {
+      (Skin::Space, Status::Live) => Some("🛸"),
+      (Skin::Space, Status::Miss) => Some("✨"),
+      (Skin::Space, Status::Kill) => Some("☄"),
+      (Skin::Pirate, Status::Live) => Some("⛵"),
+      (Skin::Pirate, Status::Miss) => Some("⚓"),
+      (Skin::Pirate, Status::Kill) => Some("☠"),
+      _ => None,
+    }
+  }
+
+  /// The board colours of the theme, tinted by the skin unless colours are off
+  pub fn board(&self, theme: BoardTheme) -> BoardTheme {
+    if theme.no_color {
+      return theme;
+    }
+    match self {
+      Skin::Naval => theme,
+      Skin::Space => BoardTheme {
+        background: Color::Rgb(8, 8, 28),
+        live: Color::LightMagenta,
+        miss: Color::Gray,
+        ..theme
+      },
+      Skin::Pirate => BoardTheme {
+        background: Color::Rgb(36, 24, 12),
+        live: Color::LightYellow,
+        miss: Color::Rgb(190, 150, 90),
+        ..theme
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::theme::Theme;
+
+  #[test]
+  fn test_skin_ship_names() {
+    assert_eq!(Skin::Naval.ship_name(&ShipType::H), "Carrier");
+    assert_eq!(Skin::Space.ship_name(&ShipType::H), "Mothership");
+    assert_eq!(Skin::Pirate.ship_name(&ShipType::P), "Jolly boat");
+    let tug = ShipType::custom("Tug", &["**"]).unwrap();
+    assert_eq!(Skin::Pirate.ship_name(&tug), "Tug");
+
+    assert_eq!(
+      Skin::Space.rename("You got my Carrier", &ShipType::H),
+      "You got my Mothership"
+    );
+  }
+
+  #[test]
+  fn test_skin_board() {
+    let theme = Theme::default().board;
+    assert_eq!(Skin::Naval.board(theme), theme);
+    assert_ne!(Skin::Pirate.board(theme).background, theme.background);
+    assert_eq!(Skin::Pirate.board(theme).hit, theme.hit);
+
+    let plain = Theme::no_color().board;
+    assert_eq!(Skin::Space.board(plain), plain);
+    assert_eq!(Skin::Naval.emoji(Status::Live), None);
+  }
+}
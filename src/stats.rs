@@ -0,0 +1,709 @@
+//! Lifetime statistics over all matches played, kept in a plain text file in the data
+//! directory unless `--stats` names another. The user's shots and hits, their fastest win in
+//! seconds and the wins and losses of the matches they fired first in follow the counts,
+//! then the wins, losses and fastest win of every rule
+//! played, then the fastest wins with their rule, difficulty and day, then the fastest fleets
+//! sunk in time attacks with the shots they took, their rule and day, then the recent fleets
+//! of the user, each with the number of shots it took the computer to find every ship, or
+//! `-` for a ship it never found:
+//!
+//! ```text
+//! won 3
+//! lost 2
+//! abandoned 1
+//! criticals 4
+//! shots 212
+//! hits 61
+//! fastest 95
+//! first_won 2
+//! first_lost 0
+//! rule Default 2 1 95
+//! rule Fury 1 1 -
+//! best 95 Default Hard 2026-10-16
+//! attack 42 23 Default 2026-10-16
+//! fleet 12,30,7,- X@A1/90 V@D1/90 H@A6/90 I@F6/90
+//! ```
+
+use std::{
+  collections::BTreeMap,
+  fs::{self, File},
+  io::{self, Write},
+  path::PathBuf,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+  clock::format_duration,
+  game::{Game, Placement},
+  notation::{parse_fleet, write_fleet},
+};
+
+/// Fleets kept in the file, older ones make way for new ones
+const RECENT_FLEETS: usize = 20;
+/// Fastest wins on the leaderboard, and fleets sunk on the one of the time attacks
+const LEADERBOARD: usize = 10;
+
+/// How a match ended for the user
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Outcome {
+  Won,
+  Lost,
+  /// quit before the match was decided
+  Abandoned,
+}
+
+#[derive(PartialEq, Debug, Default)]
+pub struct Stats {
+  pub won: usize,
+  pub lost: usize,
+  pub abandoned: usize,
+  /// ships the user sank all at once with a shot on their center
+  pub criticals: usize,
+  /// the user's shots in the decided matches, and those that hit
+  pub shots: usize,
+  pub hits: usize,
+  /// seconds of the quickest win
+  pub fastest: Option<u64>,
+  /// the decided matches the user fired first in, won and lost
+  pub first_won: usize,
+  pub first_lost: usize,
+  /// how the matches went by the rule they were played by
+  pub rules: BTreeMap<String, RuleRecord>,
+  /// the fastest wins, fastest first
+  pub leaderboard: Vec<FastWin>,
+  /// the fastest fleets sunk in time attacks, fastest first
+  pub attacks: Vec<FastSink>,
+  /// the user's fleets of the recent matches against the computer, oldest first
+  pub fleets: Vec<FleetRecord>,
+}
+
+/// The wins, losses and quickest win in seconds of the matches by a rule
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct RuleRecord {
+  pub won: usize,
+  pub lost: usize,
+  pub fastest: Option<u64>,
+}
+
+impl RuleRecord {
+  fn record(&mut self, won: bool, time: Duration) {
+    if won {
+      self.won += 1;
+      self.fastest = Some(faster(self.fastest, time));
+    } else {
+      self.lost += 1;
+    }
+  }
+}
+
+fn faster(fastest: Option<u64>, time: Duration) -> u64 {
+  fastest.map_or(time.as_secs(), |secs| secs.min(time.as_secs()))
+}
+
+/// A win on the leaderboard
+#[derive(PartialEq, Debug, Clone)]
+pub struct FastWin {
+  pub secs: u64,
+  pub rule: String,
+  pub difficulty: String,
+  /// the day it was won, `YYYY-MM-DD`
+  pub date: String,
+}
+
+/// A fleet sunk in a time attack on its leaderboard
+#[derive(PartialEq, Debug, Clone)]
+pub struct FastSink {
+  pub secs: u64,
+  pub shots: usize,
+  pub rule: String,
+  /// the day it was sunk, `YYYY-MM-DD`
+  pub date: String,
+}
+
+/// The fastest matches on the end screen, wins or time attacks
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Leaderboard<'a> {
+  Wins(&'a [FastWin]),
+  Attacks(&'a [FastSink]),
+}
+
+/// What the user did in a decided match, for the totals and the records of its rule
+#[derive(PartialEq, Debug)]
+pub struct Played {
+  pub rule: String,
+  pub difficulty: String,
+  pub won: bool,
+  /// whether the user fired the first shot
+  pub first: bool,
+  pub shots: usize,
+  pub hits: usize,
+  pub time: Duration,
+  pub date: String,
+}
+
+impl Played {
+  pub fn of(game: &Game, time: Duration) -> Self {
+    let (shots, hits) = game.shots_and_hits(0);
+    let days = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_or(0, |since| since.as_secs() / 86_400);
+    Self {
+      rule: game.rule.to_string(),
+      difficulty: game.difficulty().to_string(),
+      won: game.winner() == Some(0),
+      first: game.first() == 0,
+      shots,
+      hits,
+      time,
+      date: date(days as i64),
+    }
+  }
+}
+
+/// The day as `YYYY-MM-DD`, from the days since the Unix epoch, after the civil_from_days
+/// algorithm of Howard Hinnant
+fn date(days: i64) -> String {
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let day_of_era = z.rem_euclid(146_097);
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  // months counted from March, for the leap day to come last
+  let month = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month + 2) / 5 + 1;
+  let month = if month < 10 { month + 3 } else { month - 9 };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+  format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// How the computer hunted down a fleet of the user
+#[derive(PartialEq, Debug, Clone)]
+pub struct FleetRecord {
+  pub fleet: Vec<Placement>,
+  /// shots the computer fired until it first hit each ship of the fleet, if it did
+  pub found: Vec<Option<usize>>,
+}
+
+impl FleetRecord {
+  pub fn of(game: &Game) -> Self {
+    let fleet = game.player().player_board().placements();
+    let cells = fleet.iter().map(|p| p.cells()).collect::<Vec<_>>();
+    let mut found = vec![None; fleet.len()];
+    let mut fired = 0;
+    for turn in game.timeline().iter().filter(|t| t.player == 1) {
+      fired += turn.shots.len();
+      let landed = turn.landed();
+      for (index, ship) in cells.iter().enumerate() {
+        if found[index].is_none() && !ship.is_disjoint(&landed) {
+          found[index] = Some(fired);
+        }
+      }
+    }
+    Self { fleet, found }
+  }
+}
+
+impl Stats {
+  pub fn record(&mut self, outcome: Outcome) {
+    match outcome {
+      Outcome::Won => self.won += 1,
+      Outcome::Lost => self.lost += 1,
+      Outcome::Abandoned => self.abandoned += 1,
+    }
+  }
+
+  /// Losses including the matches quit before losing them
+  pub fn losses(&self) -> usize {
+    self.lost + self.abandoned
+  }
+
+  /// Adds the match to the totals and the records of its rule, and returns its place on
+  /// the leaderboard if the win made it there
+  pub fn record_played(&mut self, played: &Played) -> Option<usize> {
+    self.shots += played.shots;
+    self.hits += played.hits;
+    if played.won {
+      self.fastest = Some(faster(self.fastest, played.time));
+    }
+    match (played.first, played.won) {
+      (true, true) => self.first_won += 1,
+      (true, false) => self.first_lost += 1,
+      (false, _) => {}
+    }
+    self
+      .rules
+      .entry(played.rule.clone())
+      .or_default()
+      .record(played.won, played.time);
+    if !played.won {
+      return None;
+    }
+    let secs = played.time.as_secs();
+    // a tie goes after the wins that got there first
+    let place = self.leaderboard.iter().filter(|w| w.secs <= secs).count();
+    if place >= LEADERBOARD {
+      return None;
+    }
+    self.leaderboard.insert(
+      place,
+      FastWin {
+        secs,
+        rule: played.rule.clone(),
+        difficulty: played.difficulty.clone(),
+        date: played.date.clone(),
+      },
+    );
+    self.leaderboard.truncate(LEADERBOARD);
+    Some(place)
+  }
+
+  /// Adds a time attack to its leaderboard if the fleet was sunk fast enough, and returns
+  /// the place it took
+  pub fn record_attack(&mut self, played: &Played) -> Option<usize> {
+    if !played.won {
+      return None;
+    }
+    let (secs, shots) = (played.time.as_secs(), played.shots);
+    // fewer shots break a tie on the time, and a full tie goes after the one there first
+    let place = self
+      .attacks
+      .iter()
+      .filter(|a| (a.secs, a.shots) <= (secs, shots))
+      .count();
+    if place >= LEADERBOARD {
+      return None;
+    }
+    self.attacks.insert(
+      place,
+      FastSink {
+        secs,
+        shots,
+        rule: played.rule.clone(),
+        date: played.date.clone(),
+      },
+    );
+    self.attacks.truncate(LEADERBOARD);
+    Some(place)
+  }
+
+  /// Share of the user's shots that hit, in percent
+  pub fn accuracy(&self) -> Option<usize> {
+    match (self.shots, self.hits) {
+      (0, _) => None,
+      (shots, hits) => Some(hits * 100 / shots),
+    }
+  }
+
+  /// The statistics as lines to show, the totals first and the rules after them
+  pub fn lines(&self) -> Vec<String> {
+    let fastest = |fastest: Option<u64>| {
+      fastest.map_or("-".into(), |secs| {
+        format_duration(Duration::from_secs(secs))
+      })
+    };
+    let mut lines = vec![
+      format!("Wins {} | Losses {}", self.won, self.losses()),
+      format!("Abandoned {}", self.abandoned),
+      format!("Shots fired {}", self.shots),
+      format!(
+        "Accuracy {}",
+        self
+          .accuracy()
+          .map_or("-".into(), |accuracy| format!("{}%", accuracy))
+      ),
+      format!("Fastest win {}", fastest(self.fastest)),
+      format!(
+        "Firing first: won {} | lost {}",
+        self.first_won, self.first_lost
+      ),
+      format!("Critical hits {}", self.criticals),
+    ];
+    if let Some(best) = self.attacks.first() {
+      lines.push(format!(
+        "Fastest time attack {} in {} shots",
+        fastest(Some(best.secs)),
+        best.shots
+      ));
+    }
+    if !self.rules.is_empty() {
+      lines.push(String::new());
+    }
+    for (rule, record) in &self.rules {
+      lines.push(format!(
+        "{}: won {} | lost {} | fastest {}",
+        rule,
+        record.won,
+        record.lost,
+        fastest(record.fastest)
+      ));
+    }
+    lines
+  }
+}
+
+pub struct StatsFile {
+  path: PathBuf,
+}
+
+impl StatsFile {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  /// The stats file in the user's data directory, if the platform has one
+  pub fn in_data_dir() -> Option<Self> {
+    dirs::data_dir().map(|dir| Self::new(dir.join("battleship-rs").join("stats")))
+  }
+
+  pub fn load(&self) -> io::Result<Stats> {
+    if !self.path.exists() {
+      return Ok(Stats::default());
+    }
+    parse(&fs::read_to_string(&self.path)?)
+  }
+
+  /// Adds the outcome of a match to the file and returns the updated statistics
+  pub fn record(&self, outcome: Outcome) -> io::Result<Stats> {
+    let mut stats = self.load()?;
+    stats.record(outcome);
+    self.write(&stats)?;
+    Ok(stats)
+  }
+
+  /// Adds the ships the user sank with critical hits in a match
+  pub fn record_criticals(&self, count: usize) -> io::Result<()> {
+    let mut stats = self.load()?;
+    stats.criticals += count;
+    self.write(&stats)
+  }
+
+  /// Adds the shots and the time of a decided match to the totals and the records of its
+  /// rule
+  pub fn record_played(&self, played: &Played) -> io::Result<Option<usize>> {
+    let mut stats = self.load()?;
+    let place = stats.record_played(played);
+    self.write(&stats)?;
+    Ok(place)
+  }
+
+  /// Adds a time attack to its leaderboard and returns the place it took
+  pub fn record_attack(&self, played: &Played) -> io::Result<Option<usize>> {
+    let mut stats = self.load()?;
+    let place = stats.record_attack(played);
+    self.write(&stats)?;
+    Ok(place)
+  }
+
+  /// Adds the fleet of a match to the recent ones
+  pub fn record_fleet(&self, record: FleetRecord) -> io::Result<()> {
+    let mut stats = self.load()?;
+    stats.fleets.push(record);
+    let excess = stats.fleets.len().saturating_sub(RECENT_FLEETS);
+    stats.fleets.drain(..excess);
+    self.write(&stats)
+  }
+
+  fn write(&self, stats: &Stats) -> io::Result<()> {
+    if let Some(dir) = self.path.parent() {
+      fs::create_dir_all(dir)?;
+    }
+    let mut file = File::create(&self.path)?;
+    writeln!(file, "won {}", stats.won)?;
+    writeln!(file, "lost {}", stats.lost)?;
+    writeln!(file, "abandoned {}", stats.abandoned)?;
+    writeln!(file, "criticals {}", stats.criticals)?;
+    writeln!(file, "shots {}", stats.shots)?;
+    writeln!(file, "hits {}", stats.hits)?;
+    if let Some(fastest) = stats.fastest {
+      writeln!(file, "fastest {}", fastest)?;
+    }
+    writeln!(file, "first_won {}", stats.first_won)?;
+    writeln!(file, "first_lost {}", stats.first_lost)?;
+    for (rule, record) in &stats.rules {
+      let fastest = record.fastest.map_or("-".into(), |secs| secs.to_string());
+      writeln!(
+        file,
+        "rule {} {} {} {}",
+        rule, record.won, record.lost, fastest
+      )?;
+    }
+    for win in &stats.leaderboard {
+      writeln!(
+        file,
+        "best {} {} {} {}",
+        win.secs, win.rule, win.difficulty, win.date
+      )?;
+    }
+    for attack in &stats.attacks {
+      writeln!(
+        file,
+        "attack {} {} {} {}",
+        attack.secs, attack.shots, attack.rule, attack.date
+      )?;
+    }
+    for record in &stats.fleets {
+      let found = record
+        .found
+        .iter()
+        .map(|f| f.map_or("-".into(), |f| f.to_string()))
+        .collect::<Vec<_>>();
+      writeln!(
+        file,
+        "fleet {} {}",
+        found.join(","),
+        write_fleet(&record.fleet)
+      )?;
+    }
+    Ok(())
+  }
+}
+
+fn parse(s: &str) -> io::Result<Stats> {
+  let mut stats = Stats::default();
+  for (index, line) in s.lines().enumerate() {
+    let invalid = || {
+      io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("stats line {}: invalid record {}", index + 1, line),
+      )
+    };
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let (key, count) = match words.as_slice() {
+      [] => continue,
+      ["fleet", found, fleet @ ..] => {
+        stats
+          .fleets
+          .push(parse_fleet_record(found, fleet).ok_or_else(invalid)?);
+        continue;
+      }
+      ["rule", rule, won, lost, fastest] => {
+        let record = parse_rule_record(won, lost, fastest).ok_or_else(invalid)?;
+        stats.rules.insert(rule.to_string(), record);
+        continue;
+      }
+      ["best", secs, rule, difficulty, date] => {
+        stats.leaderboard.push(FastWin {
+          secs: secs.parse().map_err(|_| invalid())?,
+          rule: rule.to_string(),
+          difficulty: difficulty.to_string(),
+          date: date.to_string(),
+        });
+        continue;
+      }
+      ["attack", secs, shots, rule, date] => {
+        stats.attacks.push(FastSink {
+          secs: secs.parse().map_err(|_| invalid())?,
+          shots: shots.parse().map_err(|_| invalid())?,
+          rule: rule.to_string(),
+          date: date.to_string(),
+        });
+        continue;
+      }
+      [key, count] => (*key, count.parse::<usize>().map_err(|_| invalid())?),
+      _ => return Err(invalid()),
+    };
+    match key {
+      "won" => stats.won = count,
+      "lost" => stats.lost = count,
+      "abandoned" => stats.abandoned = count,
+      "criticals" => stats.criticals = count,
+      "shots" => stats.shots = count,
+      "hits" => stats.hits = count,
+      "fastest" => stats.fastest = Some(count as u64),
+      "first_won" => stats.first_won = count,
+      "first_lost" => stats.first_lost = count,
+      _ => return Err(invalid()),
+    }
+  }
+  Ok(stats)
+}
+
+fn parse_rule_record(won: &str, lost: &str, fastest: &str) -> Option<RuleRecord> {
+  Some(RuleRecord {
+    won: won.parse().ok()?,
+    lost: lost.parse().ok()?,
+    fastest: match fastest {
+      "-" => None,
+      _ => Some(fastest.parse().ok()?),
+    },
+  })
+}
+
+fn parse_fleet_record(found: &str, fleet: &[&str]) -> Option<FleetRecord> {
+  let fleet = parse_fleet(&fleet.join(" ")).ok()?;
+  let found = found
+    .split(',')
+    .map(|f| match f {
+      "-" => Some(None),
+      _ => f.parse().ok().map(Some),
+    })
+    .collect::<Option<Vec<_>>>()?;
+  if found.len() != fleet.len() {
+    return None;
+  }
+  Some(FleetRecord { fleet, found })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use uuid::Uuid;
+
+  use super::*;
+  use crate::game::{Difficulty, Rule};
+
+  #[test]
+  fn test_stats_file_record() {
+    let file = StatsFile::new(env::temp_dir().join(format!("battleship-{}.stats", Uuid::new_v4())));
+    assert_eq!(file.load().unwrap(), Stats::default());
+
+    file.record(Outcome::Won).unwrap();
+    file.record(Outcome::Abandoned).unwrap();
+    file.record_criticals(2).unwrap();
+    let stats = file.record(Outcome::Lost).unwrap();
+    assert_eq!(stats, file.load().unwrap());
+    assert_eq!((stats.won, stats.lost, stats.abandoned), (1, 1, 1));
+    assert_eq!(stats.criticals, 2);
+    assert_eq!(stats.losses(), 2);
+
+    let played = |rule: &str, won, secs| Played {
+      rule: rule.into(),
+      difficulty: "Easy".into(),
+      date: "2026-10-16".into(),
+      won,
+      // the computer fired first in the slower win
+      first: secs != 130,
+      shots: 40,
+      hits: 17,
+      time: Duration::from_secs(secs),
+    };
+    file.record_played(&played("Fury", true, 95)).unwrap();
+    file.record_played(&played("Fury", true, 130)).unwrap();
+    file.record_played(&played("Default", false, 60)).unwrap();
+    let stats = file.load().unwrap();
+    assert_eq!(
+      (stats.shots, stats.hits, stats.fastest),
+      (120, 51, Some(95))
+    );
+    assert_eq!(
+      stats.rules["Fury"],
+      RuleRecord {
+        won: 2,
+        lost: 0,
+        fastest: Some(95)
+      }
+    );
+    assert_eq!(stats.rules["Default"].fastest, None);
+    let lines = stats.lines();
+    assert_eq!(lines[0], "Wins 1 | Losses 2");
+    assert_eq!(lines[3], "Accuracy 42%");
+    assert_eq!(lines[4], "Fastest win 01:35");
+    assert_eq!(lines[5], "Firing first: won 1 | lost 1");
+    assert_eq!(lines[8], "Default: won 0 | lost 1 | fastest -");
+    assert_eq!(lines[9], "Fury: won 2 | lost 0 | fastest 01:35");
+    assert_eq!(stats.leaderboard.len(), 2);
+    assert_eq!(stats.leaderboard[1].secs, 130);
+    assert_eq!(stats.leaderboard[0].difficulty, "Easy");
+
+    fs::write(&file.path, "won 1\nlost many\n").unwrap();
+    assert_eq!(file.load().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    fs::write(&file.path, "won 1\nfleet 3,- X@A1/90\n").unwrap();
+    assert_eq!(file.load().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    fs::remove_file(&file.path).unwrap();
+  }
+
+  #[test]
+  fn test_stats_leaderboard() {
+    let mut stats = Stats::default();
+    let win = |secs| Played {
+      rule: "Default".into(),
+      difficulty: "Hard".into(),
+      won: true,
+      first: true,
+      shots: 0,
+      hits: 0,
+      time: Duration::from_secs(secs),
+      date: "2026-10-16".into(),
+    };
+    for secs in (100..110).rev() {
+      stats.record_played(&win(secs));
+    }
+    assert_eq!(stats.leaderboard[0].secs, 100);
+    assert_eq!(stats.record_played(&win(110)), None);
+    // a tie goes after the win that got there first
+    assert_eq!(stats.record_played(&win(100)), Some(1));
+    assert_eq!(stats.leaderboard.len(), LEADERBOARD);
+    assert_eq!(stats.leaderboard[9].secs, 108);
+    let lost = Played {
+      won: false,
+      ..win(1)
+    };
+    assert_eq!(stats.record_played(&lost), None);
+    assert!(stats.attacks.is_empty());
+
+    assert_eq!(date(0), "1970-01-01");
+    assert_eq!(date(11_016), "2000-02-29");
+    assert_eq!(date(20_742), "2026-10-16");
+    assert_eq!(date(-1), "1969-12-31");
+  }
+
+  #[test]
+  fn test_stats_file_record_attack() {
+    let file = StatsFile::new(env::temp_dir().join(format!("battleship-{}.stats", Uuid::new_v4())));
+    let attack = |secs, shots, won| Played {
+      rule: "Default".into(),
+      difficulty: "Easy".into(),
+      won,
+      first: true,
+      shots,
+      hits: 17,
+      time: Duration::from_secs(secs),
+      date: "2026-10-17".into(),
+    };
+    assert_eq!(file.record_attack(&attack(60, 30, true)).unwrap(), Some(0));
+    // fewer shots go first on the same time
+    assert_eq!(file.record_attack(&attack(60, 25, true)).unwrap(), Some(0));
+    assert_eq!(file.record_attack(&attack(42, 40, true)).unwrap(), Some(0));
+    // out of time, the fleet wasn't sunk
+    assert_eq!(file.record_attack(&attack(10, 5, false)).unwrap(), None);
+
+    let stats = file.load().unwrap();
+    let shots = stats.attacks.iter().map(|a| a.shots).collect::<Vec<_>>();
+    assert_eq!(shots, vec![40, 25, 30]);
+    assert_eq!(stats.leaderboard, vec![]);
+    assert_eq!(
+      stats.lines().last().unwrap(),
+      "Fastest time attack 00:42 in 40 shots"
+    );
+    fs::remove_file(&file.path).unwrap();
+  }
+
+  #[test]
+  fn test_stats_file_record_fleet() {
+    let file = StatsFile::new(env::temp_dir().join(format!("battleship-{}.stats", Uuid::new_v4())));
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    while !game.is_won() {
+      let shots = game.legal_shots(0).into_iter().take(1).collect();
+      game.fire(&shots, false);
+      if !game.is_won() {
+        game.bot_fire();
+      }
+    }
+    let record = FleetRecord::of(&game);
+    assert_eq!(record.found.len(), 4);
+    let fired = game.timeline().iter().filter(|t| t.player == 1).count();
+    assert!(record.found.iter().flatten().all(|f| *f <= fired));
+
+    file.record(Outcome::Won).unwrap();
+    for _ in 0..RECENT_FLEETS + 2 {
+      file.record_fleet(record.clone()).unwrap();
+    }
+    let stats = file.record(Outcome::Lost).unwrap();
+    assert_eq!((stats.won, stats.lost), (1, 1));
+    assert_eq!(stats.fleets.len(), RECENT_FLEETS);
+    assert_eq!(stats.fleets[0], record);
+    fs::remove_file(&file.path).unwrap();
+  }
+}
@@ -0,0 +1,191 @@
+//! A read-only HTTP endpoint with a summary of the match, for stream overlays and the like.
+//! Every request gets the latest summary as JSON, whatever its path. Web pages can only read
+//! it from the origin the user lets read it, as it shows where the user's ships are.
+
+use std::{
+  io::{self, Read, Write},
+  net::{TcpListener, TcpStream},
+  sync::{Arc, Mutex},
+  thread,
+  time::Duration,
+};
+
+use serde::Serialize;
+
+use super::game::{Board, Game, COLS, ROWS};
+
+/// How long a client may take to send its request and read the response, as the clients
+/// are served one after the other
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Summary {
+  pub rule: String,
+  pub difficulty: String,
+  pub turn: usize,
+  /// the side that won once the match is decided, "you", "computer" or, over the
+  /// network, "opponent", or "host" and "guest" for a spectator
+  pub winner: Option<String>,
+  pub you: Side,
+  pub computer: Side,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Side {
+  pub shots: usize,
+  pub hits: usize,
+  pub ships_afloat: usize,
+  /// rows of cells as this side's opponent knows them, so the user's ships are shown
+  /// and the computer's only where they were hit
+  pub board: Vec<Vec<&'static str>>,
+}
+
+impl Summary {
+  /// The summary of the match between the sides of the names, the user's first
+  pub fn of(game: &Game, names: [&str; 2]) -> Self {
+    let side = |player: usize, board: &Board| {
+      let (shots, hits) = game.shots_and_hits(player);
+      Side {
+        shots,
+        hits,
+        ships_afloat: game.fleet(player).alive,
+        board: (0..ROWS)
+          .map(|r| {
+            (0..COLS)
+              .map(|c| board.status_at((r, c)).describe())
+              .collect()
+          })
+          .collect(),
+      }
+    };
+    Self {
+      rule: game.rule.to_string(),
+      difficulty: game.difficulty().to_string(),
+      turn: game.round(),
+      winner: game.winner().map(|w| names[w].to_lowercase()),
+      you: side(0, game.player().player_board()),
+      computer: side(1, game.player().opponent_board()),
+    }
+  }
+}
+
+/// Serves the latest summary on a local port from a thread of its own
+pub struct StatusServer {
+  summary: Arc<Mutex<String>>,
+}
+
+impl StatusServer {
+  /// Listens on the port of localhost only, as the endpoint has no authentication, and
+  /// lets web pages of the origin read it, if any
+  pub fn start(port: u16, origin: Option<String>) -> io::Result<Self> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    let summary = Arc::new(Mutex::new(String::from("{}")));
+    let shared = Arc::clone(&summary);
+    thread::spawn(move || {
+      for stream in listener.incoming().flatten() {
+        let body = shared.lock().map(|s| s.clone()).unwrap_or_default();
+        // a client that hangs up early or idles only loses its own response
+        let _ = respond(stream, &body, origin.as_deref());
+      }
+    });
+    Ok(Self { summary })
+  }
+
+  pub fn publish(&self, summary: &Summary) {
+    if let (Ok(json), Ok(mut current)) = (serde_json::to_string(summary), self.summary.lock()) {
+      *current = json;
+    }
+  }
+}
+
+fn respond(mut stream: TcpStream, body: &str, origin: Option<&str>) -> io::Result<()> {
+  stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+  stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+  // the request line and headers don't matter, read them so the client sees a clean close
+  let mut request = [0; 1024];
+  let _ = stream.read(&mut request)?;
+  let cors = origin.map_or(String::new(), |origin| {
+    format!("Access-Control-Allow-Origin: {}\r\n", origin)
+  });
+  write!(
+    stream,
+    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+    cors,
+    body.len(),
+    body
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::{Difficulty, Rule};
+
+  #[test]
+  fn test_summary_fog() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy, 0);
+    let ship = *game.computer().player_board().placements()[0]
+      .cells()
+      .iter()
+      .next()
+      .unwrap();
+    game.fire(&[ship].iter().cloned().collect(), false);
+
+    let summary = Summary::of(&game, ["You", "Computer"]);
+    assert_eq!(summary.turn, 1);
+    assert_eq!(summary.winner, None);
+    assert_eq!((summary.you.shots, summary.you.hits), (1, 1));
+    assert_eq!(summary.computer.ships_afloat, 4);
+    assert_eq!(summary.computer.board[ship.0][ship.1], "hit");
+    // the computer's other ships stay hidden while all of the user's are shown
+    let count = |side: &Side, status| {
+      side
+        .board
+        .iter()
+        .flatten()
+        .filter(|s| **s == status)
+        .count()
+    };
+    assert_eq!(count(&summary.computer, "ship"), 0);
+    let fleet = game.player().player_board().placements();
+    let cells = fleet.iter().map(|p| p.cells().len()).sum::<usize>();
+    assert_eq!(count(&summary.you, "ship"), cells);
+
+    // over the network the other side isn't the computer
+    game.forfeit(0);
+    let summary = Summary::of(&game, ["You", "Opponent"]);
+    assert_eq!(summary.winner.as_deref(), Some("opponent"));
+  }
+
+  #[test]
+  fn test_status_server() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+
+    let server = StatusServer::start(port, None).unwrap();
+    let game = Game::new(Rule::Fury, Difficulty::Hard, 0);
+    server.publish(&Summary::of(&game, ["You", "Computer"]));
+
+    let get = |port| {
+      let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+      stream.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+      let mut response = String::new();
+      stream.read_to_string(&mut response).unwrap();
+      response
+    };
+    // a client that never sends its request holds up the others for a moment only
+    let _idle = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let response = get(port);
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("\r\n\r\n{\"rule\":\"Fury\",\"difficulty\":\"Hard\",\"turn\":1,"));
+    // no web page may read the user's fleet unless its origin is let in
+    assert!(!response.contains("Access-Control-Allow-Origin"));
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    StatusServer::start(port, Some("http://localhost:8080".into())).unwrap();
+    assert!(get(port).contains("\r\nAccess-Control-Allow-Origin: http://localhost:8080\r\n"));
+  }
+}
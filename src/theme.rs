@@ -0,0 +1,278 @@
+//! The colours the screen is drawn in, picked with `--theme`. Some themes are bundled with
+//! the game and more can be added in `themes.toml` in the config directory, each naming
+//! only the colours it changes from the classic theme:
+//!
+//! ```toml
+//! [dusk]
+//! background = "#1c1c28"
+//! cursor = "light-magenta"
+//! hit = "208"
+//! ```
+//!
+//! A colour is one of the 16 terminal colours by name, an index into the 256 colour
+//! palette or an RGB hex code.
+
+use std::{
+  collections::BTreeMap,
+  fs,
+  io::{self, ErrorKind},
+  path::PathBuf,
+};
+
+use tui::style::Color;
+
+use super::widget::BoardTheme;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+  pub board: BoardTheme,
+  /// the border of the screen and the popups
+  pub frame: Color,
+  /// key hints and board labels
+  pub text: Color,
+  /// board titles
+  pub title: Color,
+  /// power-ups that can't be used yet
+  pub muted: Color,
+  /// the turn log and the pending count
+  pub accent: Color,
+  pub info: Color,
+  pub success: Color,
+  pub warn: Color,
+  pub error: Color,
+  /// what the user did, in the log and the alerts
+  pub you: Color,
+  /// what the opponent did
+  pub opponent: Color,
+}
+
+impl Default for Theme {
+  fn default() -> Self {
+    Self {
+      board: BoardTheme::default(),
+      frame: Color::Cyan,
+      text: Color::Gray,
+      title: Color::Green,
+      muted: Color::DarkGray,
+      accent: Color::Yellow,
+      info: Color::Magenta,
+      success: Color::LightGreen,
+      warn: Color::Yellow,
+      error: Color::Red,
+      you: Color::LightCyan,
+      opponent: Color::LightRed,
+    }
+  }
+}
+
+impl Theme {
+  /// Every colour left to the terminal, cells and power-ups told apart by their characters,
+  /// borders and text styles alone
+  pub fn no_color() -> Self {
+    Self {
+      board: BoardTheme {
+        background: Color::Reset,
+        live: Color::Reset,
+        hit: Color::Reset,
+        miss: Color::Reset,
+        cursor: Color::Reset,
+        selected: Color::Reset,
+        muted: Color::Reset,
+        no_color: true,
+      },
+      frame: Color::Reset,
+      text: Color::Reset,
+      title: Color::Reset,
+      muted: Color::Reset,
+      accent: Color::Reset,
+      info: Color::Reset,
+      success: Color::Reset,
+      warn: Color::Reset,
+      error: Color::Reset,
+      you: Color::Reset,
+      opponent: Color::Reset,
+    }
+  }
+
+  fn set(&mut self, key: &str, color: Color) -> Result<(), String> {
+    let slot = match key {
+      "background" => &mut self.board.background,
+      "live" => &mut self.board.live,
+      "hit" => &mut self.board.hit,
+      "miss" => &mut self.board.miss,
+      "cursor" => &mut self.board.cursor,
+      "selected" => &mut self.board.selected,
+      "frame" => &mut self.frame,
+      "text" => &mut self.text,
+      "title" => &mut self.title,
+      "muted" => {
+        // the board tints the cells left out with it too
+        self.board.muted = color;
+        &mut self.muted
+      }
+      "accent" => &mut self.accent,
+      "info" => &mut self.info,
+      "success" => &mut self.success,
+      "warn" => &mut self.warn,
+      "error" => &mut self.error,
+      "you" => &mut self.you,
+      "opponent" => &mut self.opponent,
+      _ => return Err(format!("unknown colour key {}", key)),
+    };
+    *slot = color;
+    Ok(())
+  }
+}
+
+/// A colour by name, like `light-red`, by palette index or as `#rrggbb`
+fn parse_color(text: &str) -> Result<Color, String> {
+  let unknown = || format!("unknown colour {:?}", text);
+  if let Some(hex) = text.strip_prefix('#') {
+    if hex.len() != 6 || !hex.is_ascii() {
+      return Err(unknown());
+    }
+    let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).map_err(|_| unknown());
+    return Ok(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+  }
+  if let Ok(index) = text.parse::<u8>() {
+    return Ok(Color::Indexed(index));
+  }
+  let name = text
+    .chars()
+    .filter(|c| *c != '-' && *c != '_' && *c != ' ')
+    .collect::<String>()
+    .to_lowercase();
+  Ok(match name.as_str() {
+    "reset" => Color::Reset,
+    "black" => Color::Black,
+    "red" => Color::Red,
+    "green" => Color::Green,
+    "yellow" => Color::Yellow,
+    "blue" => Color::Blue,
+    "magenta" => Color::Magenta,
+    "cyan" => Color::Cyan,
+    "gray" | "grey" => Color::Gray,
+    "darkgray" | "darkgrey" => Color::DarkGray,
+    "lightred" => Color::LightRed,
+    "lightgreen" => Color::LightGreen,
+    "lightyellow" => Color::LightYellow,
+    "lightblue" => Color::LightBlue,
+    "lightmagenta" => Color::LightMagenta,
+    "lightcyan" => Color::LightCyan,
+    "white" => Color::White,
+    _ => return Err(unknown()),
+  })
+}
+
+/// The themes bundled with the game
+const BUNDLED: &str = include_str!("themes.toml");
+
+/// All the themes, the user's taking the place of bundled ones of the same name
+pub fn themes(user: Option<&ThemeFile>) -> io::Result<BTreeMap<String, Theme>> {
+  let mut themes = parse(BUNDLED)?;
+  if let Some(user) = user {
+    themes.extend(user.load()?);
+  }
+  Ok(themes)
+}
+
+fn parse(content: &str) -> io::Result<BTreeMap<String, Theme>> {
+  let invalid = |err: String| io::Error::new(ErrorKind::InvalidData, format!("themes: {}", err));
+  let entries: BTreeMap<String, BTreeMap<String, String>> =
+    toml::from_str(content).map_err(|err| invalid(err.to_string()))?;
+  entries
+    .into_iter()
+    .map(|(name, colors)| {
+      let mut theme = Theme::default();
+      for (key, value) in &colors {
+        parse_color(value)
+          .and_then(|color| theme.set(key, color))
+          .map_err(|err| invalid(format!("{} in the {} theme", err, name)))?;
+      }
+      Ok((name, theme))
+    })
+    .collect()
+}
+
+/// The file with the user's themes
+pub struct ThemeFile {
+  path: PathBuf,
+}
+
+impl ThemeFile {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  /// The themes file in the user's config directory, if the platform has one
+  pub fn in_config_dir() -> Option<Self> {
+    dirs::config_dir().map(|dir| Self::new(dir.join("battleship-rs").join("themes.toml")))
+  }
+
+  pub fn load(&self) -> io::Result<BTreeMap<String, Theme>> {
+    if !self.path.exists() {
+      return Ok(BTreeMap::new());
+    }
+    parse(&fs::read_to_string(&self.path)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use uuid::Uuid;
+
+  use super::*;
+
+  #[test]
+  fn test_themes_bundled() {
+    let themes = themes(None).unwrap();
+    assert_eq!(
+      themes.keys().collect::<Vec<_>>(),
+      vec!["classic", "high-contrast", "monochrome", "ocean"]
+    );
+    assert_eq!(themes["classic"], Theme::default());
+    assert_eq!(themes["monochrome"].board.hit, Color::White);
+    assert_eq!(themes["monochrome"].opponent, Color::Gray);
+    assert_ne!(themes["ocean"].board.background, Color::Black);
+  }
+
+  #[test]
+  fn test_themes_user_file() {
+    let dir = env::temp_dir().join(format!("battleship-{}", Uuid::new_v4()));
+    let file = ThemeFile::new(dir.join("themes.toml"));
+    assert!(file.load().unwrap().is_empty());
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+      &file.path,
+      "[classic]\ncursor = \"Light-Magenta\"\n\n[dusk]\nbackground = \"#1c1c28\"\nhit = \"208\"\n",
+    )
+    .unwrap();
+    let themes = themes(Some(&file)).unwrap();
+    assert_eq!(themes.len(), 5);
+    assert_eq!(themes["classic"].board.cursor, Color::LightMagenta);
+    assert_eq!(
+      themes["dusk"].board.background,
+      Color::Rgb(0x1c, 0x1c, 0x28)
+    );
+    assert_eq!(themes["dusk"].board.hit, Color::Indexed(208));
+    assert_eq!(themes["dusk"].frame, Theme::default().frame);
+
+    let err = |content: &str| {
+      fs::write(&file.path, content).unwrap();
+      file.load().unwrap_err().to_string()
+    };
+    assert_eq!(
+      err("[dusk]\nsky = \"blue\"\n"),
+      "themes: unknown colour key sky in the dusk theme"
+    );
+    assert_eq!(
+      err("[dusk]\nhit = \"#12345\"\n"),
+      "themes: unknown colour \"#12345\" in the dusk theme"
+    );
+    assert!(err("[dusk]\nhit = 3\n").starts_with("themes: "));
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
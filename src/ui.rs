@@ -1,71 +1,349 @@
+use std::time::Duration;
+
+use structopt::clap::arg_enum;
 use tui::{
   backend::Backend,
   layout::{Alignment, Constraint, Direction, Layout, Rect},
-  style::{Color, Modifier, Style},
-  widgets::{Block, BorderType, Borders, Clear, Paragraph},
+  style::{Modifier, Style},
+  text::{Span, Spans},
+  widgets::{Block, BorderType, Borders, Clear, Paragraph, Row, Table, Wrap},
   Frame,
 };
 
+#[cfg(feature = "chat")]
+use super::chat::ChatVote;
 use super::{
-  game::{COLS, ROWS},
+  alert::{Actor, Alerts, Severity},
+  app::{Focus, InputMode},
+  clock::format_duration,
+  game::{a1_notation, Rule, COLS, ROWS},
+  lobby::Lobby,
+  stats::Leaderboard,
+  theme::Theme,
+  widget::{
+    AbilityBar, BoardState, BoardTheme, BoardWidget, Scrubber, CELL_HEIGHT, CELL_WIDTH,
+    GRID_HEIGHT, GRID_WIDTH,
+  },
   App,
 };
 
-const CELL_WIDTH: u16 = 5;
-const CELL_HEIGHT: u16 = 3;
-const PADDING: u16 = 1;
-const GRID_WIDTH: u16 = CELL_WIDTH * (COLS as u16) + 2 * PADDING;
-const GRID_HEIGHT: u16 = CELL_HEIGHT * (ROWS as u16) + 2 * PADDING;
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum LayoutMode {
+        Auto,     // picked from the size of the terminal
+        Standard,
+        Wide,     // a log panel next to the boards
+        Vertical, // the opponent's board below the user's
+    }
+}
+
+/// Terminals at least this wide fit the log panel next to both boards
+const WIDE_WIDTH: u16 = 3 * (GRID_WIDTH + 4);
+/// Terminals narrower than this can't fit the boards side by side
+const STANDARD_WIDTH: u16 = 2 * (GRID_WIDTH + 2);
+/// Terminals at least this tall fit the boards on top of each other, with the keys below
+const VERTICAL_HEIGHT: u16 = 2 * (GRID_HEIGHT + 1) + 4;
+
+impl LayoutMode {
+  /// The layout to draw in a terminal of the given size
+  fn resolve(self, size: Rect) -> Self {
+    match self {
+      LayoutMode::Auto if size.width >= WIDE_WIDTH => LayoutMode::Wide,
+      LayoutMode::Auto if size.width < STANDARD_WIDTH && size.height >= VERTICAL_HEIGHT => {
+        LayoutMode::Vertical
+      }
+      LayoutMode::Auto => LayoutMode::Standard,
+      layout => layout,
+    }
+  }
+}
 
 pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+  let theme = app.theme;
   let main_block = Block::default()
     .borders(Borders::ALL)
-    .style(Style::default().bg(Color::Black).fg(Color::Cyan))
-    .title(format!(
-      "{} | Rule: {} ({}s)",
-      app.title,
-      app.rule(),
-      app.elapsed_duration(),
+    .style(Style::default().bg(theme.board.background).fg(theme.frame))
+    .title(title_spans(
+      app,
+      &theme,
+      title(app, f.size().width.saturating_sub(4)),
     ));
 
   f.render_widget(main_block, f.size());
 
-  let vertical_pad_block_height = f.size().height.checked_sub(GRID_HEIGHT).unwrap_or_default() / 2;
+  let layout = app.layout.resolve(f.size());
+  // the boards stack in a vertical layout, with both rows sharing the padding
+  let rows = if layout == LayoutMode::Vertical { 2 } else { 1 };
+  let vertical_pad_block_height = f.size().height.saturating_sub(GRID_HEIGHT * rows) / 2;
+  let mut constraints = vec![Constraint::Min(vertical_pad_block_height)];
+  constraints.extend((0..rows).map(|_| Constraint::Length(GRID_HEIGHT + 1)));
+  constraints.push(Constraint::Min(vertical_pad_block_height));
   let v_chunks = Layout::default()
     .direction(Direction::Vertical)
-    .constraints(vec![
-      Constraint::Min(vertical_pad_block_height),
-      Constraint::Length(GRID_HEIGHT + 1),
-      Constraint::Min(vertical_pad_block_height),
-    ])
+    .constraints(constraints)
     .split(f.size());
 
-  let header = Paragraph::new(
-    "move: 🠔 🠗 🠕 🠖 (or) hjkl | select/unselect: <space> | fire: <enter> | quit: <q>",
-  )
-  .style(Style::default().fg(Color::Gray))
-  .block(Block::default().borders(Borders::NONE))
-  .alignment(Alignment::Center);
+  let game_over = app.game_over();
+  let keys = if app.tab().replay.is_some() {
+    "turn: 🠔 🠖 hl | 10 turns: 🠕 🠗 kj | first: 0 g | last: $ G | quit: <q>"
+  } else if app.is_spectating() {
+    "watching the match | tabs: <F1>… | quit: <q>"
+  } else if app.is_waiting() {
+    "waiting for an opponent | tabs: <F1>… | quit: <q>"
+  } else if game_over.is_some() {
+    "turns: <D> | tabs: <F1>… | quit: <q>"
+  } else if app.tab().placing.is_some() && *app.rule() == Rule::Flagship {
+    "move: 🠔 🠗 🠕 🠖 hjkl | rotate: <r> | drop: <space> | undo: <esc> | mirror: <m> <M> | fill: <S> | flagship: <F> | quit: <q>"
+  } else if app.tab().placing.is_some() {
+    "move: 🠔 🠗 🠕 🠖 hjkl | rotate: <r> | drop: <space> | undo: <esc> | mirror: <m> <M> | fill: <S> | quit: <q>"
+  } else if let InputMode::Targeting(_) = app.input_mode() {
+    "move: 🠔 🠗 🠕 🠖 hjkl | turn: <r> | use: <enter> | put away: <esc> | quit: <q>"
+  } else if layout == LayoutMode::Vertical {
+    // only the keys needed to play fit under stacked boards
+    "hjkl move | <tab> board | <space> select | <enter> fire | <q> quit"
+  } else {
+    "move: 🠔 🠗 🠕 🠖 hjkl | jump: 0 $ g G c | board: <tab> | inspect: <i> | help: <?> | select: <space> | fire: <enter> | power-ups: <alt-1>…<alt-4> | sandbox: <o> | tabs: <F1>… | quit: <q>"
+  };
+  // the mode the keys are in goes first, so it's plain what they do
+  let keys = match app.mode_indicator() {
+    Some(mode) => format!("-- {} -- | {}", mode, keys),
+    None => keys.to_string(),
+  };
+  let header = Paragraph::new(keys)
+    .style(Style::default().fg(theme.text))
+    .block(Block::default().borders(Borders::NONE))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
 
-  f.render_widget(header, v_chunks[2]);
+  // the cell under the cursor sits in a fixed slot right above the boards
+  if let Some(cursor) = app.cursor_status() {
+    let above = v_chunks[0];
+    // the first line of the padding is the title bar
+    if above.height > 1 {
+      let slot = Rect {
+        y: above.y + above.height - 1,
+        height: 1,
+        ..above
+      };
+      let cursor = Paragraph::new(cursor)
+        .style(Style::default().fg(theme.text))
+        .alignment(Alignment::Center);
+      f.render_widget(cursor, slot);
+    }
+  }
 
-  let board_chunks = Layout::default()
-    .direction(Direction::Horizontal)
-    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-    .split(v_chunks[1]);
+  let footer = v_chunks[v_chunks.len() - 1];
+  // the power-ups sit right under the boards while the match is played
+  let playing = app.tab().replay.is_none()
+    && !app.is_spectating()
+    && !app.is_waiting()
+    && app.input_mode() != InputMode::Placing
+    && game_over.is_none();
+  if playing && footer.height > 1 {
+    let slots = app.ability_slots();
+    let bar = Rect {
+      height: 1,
+      ..footer
+    };
+    f.render_widget(AbilityBar::new(&slots).theme(theme), bar);
+    let keys = Rect {
+      y: footer.y + 1,
+      height: footer.height - 1,
+      ..footer
+    };
+    f.render_widget(header, keys);
+  } else if let (Some(replay), true) = (&app.tab().replay, footer.height > 3) {
+    // a tick for every turn right under the boards, to find a moment of a long match
+    let scrubber = Rect {
+      height: 3,
+      ..footer
+    };
+    f.render_widget(
+      Scrubber::new(&replay.thumbnails, replay.step)
+        .emoji(app.emoji)
+        .theme(theme),
+      scrubber,
+    );
+    let keys = Rect {
+      y: footer.y + 3,
+      height: footer.height - 3,
+      ..footer
+    };
+    f.render_widget(header, keys);
+  } else {
+    f.render_widget(header, footer);
+  }
+
+  let board_chunks = match layout {
+    LayoutMode::Vertical => vec![v_chunks[1], v_chunks[2]],
+    _ => {
+      let constraints = if layout == LayoutMode::Wide {
+        vec![Constraint::Ratio(1, 3); 3]
+      } else {
+        vec![Constraint::Percentage(50), Constraint::Percentage(50)]
+      };
+      Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(v_chunks[1])
+    }
+  };
 
   let player_chunk = board_chunks[0];
   let opponent_chunk = board_chunks[1];
 
-  draw_board(f, player_chunk, "You", app, true);
-  draw_board(f, opponent_chunk, "Computer", app, false);
+  // the end screen has the outcome where the user's board was
+  let player = app.player_name();
+  match &game_over {
+    Some(lines) => draw_game_over(f, &theme, lines.clone(), app.leaderboard(), player_chunk),
+    None => draw_board(f, player_chunk, player, app, true),
+  }
+  let opponent = app.opponent_name();
+  draw_board(f, opponent_chunk, opponent, app, false);
+  if layout == LayoutMode::Wide {
+    let fleets = app.fleet_status();
+    let side_chunks = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints(vec![
+        Constraint::Length(fleets.len() as u16 + 2),
+        Constraint::Min(0),
+      ])
+      .split(board_chunks[2]);
+    draw_fleets(f, fleets, side_chunks[0]);
+    draw_log(f, &theme, app, side_chunks[1]);
+  } else if app.tab().log_scroll.is_some() {
+    // with no side panel the log opens over the boards while it's scrolled back
+    let area = centered_rect(60, 20, f.size());
+    draw_log(f, &theme, app, area);
+  }
+
+  #[cfg(feature = "chat")]
+  if let Some(chat) = &app.chat {
+    draw_chat_votes(f, chat, f.size());
+  }
+
+  if let Some(count) = app.tab().pending_count {
+    draw_pending_count(f, &theme, count, f.size());
+  }
+
+  if let Some(offset) = app.tab().devtools {
+    draw_devtools(f, &theme, app.turn_changes(), offset, f.size());
+  } else if let Some(view) = &app.tab().bot_view {
+    draw_popup(
+      f,
+      &theme,
+      "How the computer saw you",
+      view.lines(),
+      f.size(),
+    );
+  } else if let Some(sandbox) = &app.sandbox {
+    draw_popup(f, &theme, "Sandbox", sandbox.lines(), f.size());
+  } else if app.is_paused() {
+    draw_popup(f, &theme, "Paused", app.help(), f.size());
+  } else if app.tab().inspecting {
+    draw_popup(f, &theme, "Inspect", app.inspect(), f.size());
+  }
+
+  // show alerts, unless the end screen says it all
+  if game_over.is_none() {
+    draw_alerts(f, &theme, &app.tab().alerts, app.names(), f.size());
+  }
+}
 
-  // show alerts
-  if app.frame_count % 8 != 0 || app.is_won() {
-    draw_alert(f, app.message.clone(), f.size());
+/// The matches found on the local network, to pick one to join
+pub fn draw_lobby<B: Backend>(f: &mut Frame<B>, theme: &Theme, title: &str, lobby: &Lobby) {
+  let main_block = Block::default()
+    .borders(Borders::ALL)
+    .style(Style::default().bg(theme.board.background).fg(theme.frame))
+    .title(title);
+  f.render_widget(main_block, f.size());
+
+  let mut lines = lobby.lines();
+  lines.push(String::new());
+  lines.push("<enter> join | <r> search | <q> quit".into());
+  draw_popup(f, theme, "Matches on the network", lines, f.size());
+}
+
+/// Title bar sections in order of importance, the least important ones are left out
+/// when the terminal is too narrow to fit them all
+fn title(app: &App, width: u16) -> String {
+  let (shots_left, budget) = app.shots_left();
+  let clock = match app.remaining() {
+    Some(remaining) => format!("{} left", format_duration(remaining)),
+    None => format_duration(app.elapsed()),
+  };
+  let mode = if app.is_time_attack() {
+    "Time attack"
   } else {
-    // reset messages
-    app.message = String::default();
+    "Rule"
+  };
+  let mut sections = vec![
+    format!("{}: {} ({})", mode, app.rule(), clock),
+    format!("Turn {}", app.round()),
+    format!("Shots {}/{}", shots_left, budget),
+  ];
+  if app.tab().replay.is_none() && app.tab().placing.is_none() {
+    sections.push(a1_notation(app.active()));
+  }
+  if app.is_night() {
+    sections.insert(2, "Night".into());
+  }
+  if let Some(chance) = app.drift_chance() {
+    sections.insert(3, format!("Drift {}%", chance));
+  }
+  if let Some(replay) = &app.tab().replay {
+    sections.insert(1, format!("Replay {}/{}", replay.step, replay.len()));
+  }
+  let tabs = app.tabs();
+  if tabs.len() > 1 {
+    sections.insert(1, tabs.join(" "));
+  }
+  if let Some(current) = app.tab().placing.as_ref().and_then(|p| p.current.as_ref()) {
+    sections.insert(
+      1,
+      format!("Place your {}", app.skin.ship_name(&current.ship_type)),
+    );
+  }
+  if let (Some(gauntlet), Some(misses_left)) = (&app.tab().gauntlet, app.misses_left()) {
+    sections.push(format!("Fleet {}", gauntlet.cleared + 1));
+    sections.push(format!("Misses left {}", misses_left));
+  }
+  if let Some(accuracy) = app.accuracy() {
+    sections.push(format!("Accuracy {}%", accuracy));
+  }
+  if let Some(note) = app.note() {
+    sections.push(format!("Note: {}", note));
+  }
+
+  let with_title = |sections: &[String]| {
+    std::iter::once(app.title.clone())
+      .chain(sections.iter().cloned())
+      .collect::<Vec<_>>()
+      .join(" | ")
+  };
+  let fits = |s: &String| s.chars().count() <= usize::from(width);
+
+  (1..=sections.len())
+    .rev()
+    .map(|len| with_title(&sections[..len]))
+    .find(fits)
+    .unwrap_or_else(|| sections[0].clone())
+}
+
+/// The title with the shot counter standing out once the selection is full
+fn title_spans(app: &App, theme: &Theme, title: String) -> Spans<'static> {
+  let (shots_left, budget) = app.shots_left();
+  let counter = format!("Shots {}/{}", shots_left, budget);
+  match title.find(&counter) {
+    Some(at) if app.is_selection_full() => Spans::from(vec![
+      Span::raw(title[..at].to_string()),
+      Span::styled(
+        counter.clone(),
+        Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
+      ),
+      Span::raw(title[at + counter.len()..].to_string()),
+    ]),
+    _ => Spans::from(title),
   }
 }
 
@@ -76,14 +354,7 @@ fn draw_board<B: Backend>(
   app: &mut App,
   is_self: bool,
 ) {
-  let row_constraints = std::iter::repeat(Constraint::Length(CELL_HEIGHT))
-    .take(ROWS)
-    .collect::<Vec<_>>();
-  let col_constraints = std::iter::repeat(Constraint::Length(CELL_WIDTH))
-    .take(COLS)
-    .collect::<Vec<_>>();
-
-  let horizontal_pad_block_width = (player_chunk.width - GRID_WIDTH) / 2;
+  let horizontal_pad_block_width = player_chunk.width.saturating_sub(GRID_WIDTH) / 2;
   let h_main_rects = Layout::default()
     .direction(Direction::Horizontal)
     .constraints(vec![
@@ -98,91 +369,320 @@ fn draw_board<B: Backend>(
     .constraints(vec![Constraint::Min(1), Constraint::Length(GRID_HEIGHT)])
     .split(h_main_rects[1]);
 
+  let focused = app.tab().focus
+    == if is_self {
+      Focus::Player
+    } else {
+      Focus::Opponent
+    };
+  let title_style = Style::default()
+    .fg(app.theme.title)
+    .add_modifier(Modifier::BOLD);
   let title = Paragraph::new(title)
-    .style(
-      Style::default()
-        .fg(Color::Green)
-        .add_modifier(Modifier::BOLD),
-    )
+    .style(if focused {
+      title_style.add_modifier(Modifier::UNDERLINED)
+    } else {
+      title_style
+    })
     .block(Block::default().borders(Borders::NONE))
     .alignment(Alignment::Center);
 
   f.render_widget(title, v_main_rects[0]);
 
-  let board_block = Block::default()
-    .borders(Borders::ALL)
-    .border_type(BorderType::Plain);
-
-  let board_rect = v_main_rects[1];
-  f.render_widget(board_block, board_rect);
+  match &app.tab().placing {
+    // the ship being placed is outlined, in red where it sits on another one
+    Some(placer) if is_self => {
+      let board = placer.board();
+      let theme = BoardTheme {
+        selected: if placer.overlaps().is_empty() {
+          app.theme.board.selected
+        } else {
+          app.theme.board.hit
+        },
+        ..app.theme.board
+      };
+      let mut state = BoardState {
+        cursor: None,
+        selected: placer.cells(),
+        ..BoardState::default()
+      };
+      let widget = BoardWidget::new(&board)
+        .emoji(app.emoji)
+        .skin(app.skin)
+        .theme(theme);
+      f.render_stateful_widget(widget, v_main_rects[1], &mut state);
+    }
+    _ => {
+      let board = BoardWidget::new(app.board(is_self))
+        .emoji(app.emoji)
+        .skin(app.skin)
+        .theme(app.theme.board);
+      f.render_stateful_widget(board, v_main_rects[1], &mut app.board_state(is_self));
+    }
+  }
+  draw_labels(f, &app.theme, v_main_rects[1], player_chunk);
+}
 
-  let row_rects = Layout::default()
-    .direction(Direction::Vertical)
-    .vertical_margin(1)
-    .horizontal_margin(0)
-    .constraints(row_constraints)
-    .split(board_rect);
-
-  for (r, row_rect) in row_rects.into_iter().enumerate() {
-    let col_rects = Layout::default()
-      .direction(Direction::Horizontal)
-      .vertical_margin(0)
-      .horizontal_margin(1)
-      .constraints(col_constraints.clone())
-      .split(row_rect);
-
-    for (c, cell_rect) in col_rects.into_iter().enumerate() {
-      let cell = app.cell((r, c), is_self);
-      let single_row_text = format!(
-        "{:^length$}",
-        cell.to_string(),
-        length = usize::from(CELL_WIDTH - 2)
+/// Column letters along the top edge of the board and row numbers to its left, where the
+/// chunk leaves room for them, to name the cells like the physical game does
+fn draw_labels<B: Backend>(f: &mut Frame<B>, theme: &Theme, grid: Rect, chunk: Rect) {
+  let padding = (GRID_WIDTH - CELL_WIDTH * COLS as u16) / 2;
+  let style = Style::default().fg(theme.text);
+  for col in 0..COLS as u16 {
+    let letter = ((b'A' + col as u8) as char).to_string();
+    let x = grid.x + padding + col * CELL_WIDTH + CELL_WIDTH / 2;
+    if x < grid.right() {
+      f.render_widget(
+        Paragraph::new(letter).style(style),
+        Rect::new(x, grid.y, 1, 1),
       );
-      let pad_line = " ".repeat(usize::from(CELL_WIDTH));
-
-      // 1 line for the text, 1 line each for the top and bottom of the cell == 3 lines
-      // that are not eligible for padding
-      let num_pad_lines = usize::from(CELL_HEIGHT.checked_sub(3).unwrap_or_default());
-
-      // text is:
-      //   pad with half the pad lines budget
-      //   the interesting text
-      //   pad with half the pad lines budget
-      //   join with newlines
-      let text = std::iter::repeat(pad_line.clone())
-        .take(num_pad_lines / 2)
-        .chain(std::iter::once(single_row_text.clone()))
-        .chain(std::iter::repeat(pad_line).take(num_pad_lines / 2))
-        .collect::<Vec<_>>()
-        .join("\n");
-
-      let cell_text = Paragraph::new(text)
-        .block(cell.block())
-        .style(cell.text_style());
-      f.render_widget(cell_text, cell_rect);
     }
   }
+  // the numbers need two columns clear of the border of the screen
+  if grid.x < chunk.x + 3 {
+    return;
+  }
+  for row in 0..ROWS as u16 {
+    let y = grid.y + padding + row * CELL_HEIGHT + CELL_HEIGHT / 2;
+    if y < grid.bottom() {
+      f.render_widget(
+        Paragraph::new(format!("{:>2}", row + 1)).style(style),
+        Rect::new(grid.x - 2, y, 2, 1),
+      );
+    }
+  }
+}
+
+/// Which ships of both fleets are afloat and which are sunk, next to the boards on wide
+/// terminals
+fn draw_fleets<B: Backend>(f: &mut Frame<B>, lines: Vec<String>, area: Rect) {
+  let area = Rect {
+    width: area.width.saturating_sub(2),
+    ..area
+  };
+  f.render_widget(
+    Paragraph::new(lines.join("\n")).block(
+      Block::default()
+        .title("Fleets")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded),
+    ),
+    area,
+  );
+}
+
+/// The score and the latest turns, next to the boards on wide terminals
+/// The scoreboard and the latest turns, each side's lines in its colour
+fn draw_log<B: Backend>(f: &mut Frame<B>, theme: &Theme, app: &App, area: Rect) {
+  let area = Rect {
+    width: area.width.saturating_sub(2),
+    ..area
+  };
+  let line = |player: usize, text: String| {
+    let color = if player == 0 {
+      theme.you
+    } else {
+      theme.opponent
+    };
+    Spans::from(Span::styled(text, Style::default().fg(color)))
+  };
+  let mut lines = app
+    .scoreboard()
+    .into_iter()
+    .enumerate()
+    .map(|(player, text)| line(player, text))
+    .collect::<Vec<_>>();
+  lines.push(Spans::default());
+  // the turns that fit up to where the log is scrolled back to, oldest first
+  let log = app.log();
+  let scroll = app.tab().log_scroll.unwrap_or(0).min(log.len());
+  let room = usize::from(area.height.saturating_sub(2)).saturating_sub(lines.len());
+  let end = log.len() - scroll;
+  let skip = end.saturating_sub(room);
+  lines.extend(
+    log
+      .into_iter()
+      .take(end)
+      .skip(skip)
+      .map(|(player, text)| line(player, text)),
+  );
+  let title = if scroll > 0 {
+    format!("Log, {} newer <pgdn>", scroll)
+  } else {
+    "Log <pgup>".into()
+  };
+  f.render_widget(Clear, area);
+  f.render_widget(
+    Paragraph::new(lines)
+      .block(
+        Block::default()
+          .title(title)
+          .borders(Borders::ALL)
+          .border_type(BorderType::Rounded),
+      )
+      .style(Style::default().bg(theme.board.background)),
+    area,
+  );
 }
 
-fn draw_alert<B: Backend>(f: &mut Frame<B>, message: String, area: Rect) {
-  if !message.is_empty() {
-    let area = top_centered_rect(50, 4, area);
+/// How the match went, in a panel the size of a board, with the fastest wins or time attacks
+/// under it after a win
+fn draw_game_over<B: Backend>(
+  f: &mut Frame<B>,
+  theme: &Theme,
+  lines: Vec<String>,
+  leaderboard: Option<(Leaderboard<'_>, Option<usize>)>,
+  chunk: Rect,
+) {
+  let mut area = centered_rect(GRID_WIDTH, GRID_HEIGHT, chunk);
+  if let Some((board, place)) = leaderboard {
+    let width = usize::from(area.width.saturating_sub(2)).max(1);
+    let height = lines
+      .iter()
+      .map(|line| line.chars().count().max(1).div_ceil(width))
+      .sum::<usize>() as u16;
+    let rects = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints(vec![Constraint::Length(height + 2), Constraint::Min(0)])
+      .split(area);
+    area = rects[0];
+    draw_leaderboard(f, theme, board, place, rects[1]);
+  }
+  // the outcome sits in the middle of the panel
+  let padding = usize::from(area.height.saturating_sub(lines.len() as u16 + 2) / 2);
+  let text = std::iter::repeat_n(String::new(), padding)
+    .chain(lines)
+    .collect::<Vec<_>>()
+    .join("\n");
+  f.render_widget(
+    Paragraph::new(text)
+      .block(
+        Block::default()
+          .title("Game over")
+          .borders(Borders::ALL)
+          .border_type(BorderType::Rounded)
+          .border_style(Style::default().fg(theme.title)),
+      )
+      .style(Style::default().fg(theme.text))
+      .alignment(Alignment::Center)
+      .wrap(Wrap { trim: true }),
+    area,
+  );
+}
+
+/// The fastest wins or time attacks, the one just won marked with a `*`
+fn draw_leaderboard<B: Backend>(
+  f: &mut Frame<B>,
+  theme: &Theme,
+  board: Leaderboard<'_>,
+  place: Option<usize>,
+  area: Rect,
+) {
+  let time = |secs| format_duration(Duration::from_secs(secs));
+  let (title, header, cells) = match board {
+    Leaderboard::Wins(wins) => (
+      "Fastest wins",
+      ["Time", "Rule", "Level", "Date"],
+      wins
+        .iter()
+        .map(|win| {
+          [
+            time(win.secs),
+            win.rule.clone(),
+            win.difficulty.clone(),
+            win.date.clone(),
+          ]
+        })
+        .collect::<Vec<_>>(),
+    ),
+    Leaderboard::Attacks(attacks) => (
+      "Fastest time attacks",
+      ["Time", "Rule", "Shots", "Date"],
+      attacks
+        .iter()
+        .map(|attack| {
+          [
+            time(attack.secs),
+            attack.rule.clone(),
+            attack.shots.to_string(),
+            attack.date.clone(),
+          ]
+        })
+        .collect(),
+    ),
+  };
+  let rows = cells.into_iter().enumerate().map(|(index, cells)| {
+    let mark = if place == Some(index) { "*" } else { "" };
+    let row = Row::new(std::iter::once(format!("{}{}", index + 1, mark)).chain(cells));
+    if place == Some(index) {
+      row.style(
+        Style::default()
+          .fg(theme.accent)
+          .add_modifier(Modifier::BOLD),
+      )
+    } else {
+      row
+    }
+  });
+  let widths = [
+    Constraint::Length(3),
+    Constraint::Length(6),
+    Constraint::Length(10),
+    Constraint::Length(6),
+    Constraint::Length(10),
+  ];
+  let table = Table::new(rows)
+    .header(Row::new(std::iter::once("#").chain(header)).style(Style::default().fg(theme.title)))
+    .block(
+      Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.title)),
+    )
+    .style(Style::default().fg(theme.text))
+    .widths(&widths)
+    .column_spacing(1);
+  f.render_widget(table, area);
+}
+
+/// The alerts stacked at the top, the ones about a move named after and coloured like the
+/// side that made it
+fn draw_alerts<B: Backend>(
+  f: &mut Frame<B>,
+  theme: &Theme,
+  alerts: &Alerts,
+  names: [&str; 2],
+  area: Rect,
+) {
+  let mut top = area.y;
+  for alert in alerts.iter() {
+    let height = alert.text.lines().count() as u16 + 2;
+    let rest = Rect {
+      y: top,
+      height: area.bottom().saturating_sub(top),
+      ..area
+    };
+    let area = top_centered_rect(50, height, rest);
+    top = area.bottom();
+
+    let (color, title) = match (alert.actor, alert.severity) {
+      (Actor::Player(0), _) => (theme.you, names[0]),
+      (Actor::Player(_), _) => (theme.opponent, names[1]),
+      (Actor::System, Severity::Info) => (theme.info, ""),
+      (Actor::System, Severity::Success) => (theme.success, ""),
+      (Actor::System, Severity::Warn) => (theme.warn, ""),
+      (Actor::System, Severity::Error) => (theme.error, ""),
+    };
     f.render_widget(Clear, area); //this clears out the background
     f.render_widget(
-      Paragraph::new(message)
+      Paragraph::new(alert.text.as_str())
         .block(
           Block::default()
+            .title(title)
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .border_style(
-              Style::default()
-                .fg(if true {
-                  Color::Magenta
-                } else {
-                  Color::LightGreen
-                })
-                .add_modifier(Modifier::BOLD),
-            )
+            .border_style(Style::default().fg(color).add_modifier(Modifier::BOLD))
             .style(Style::default().add_modifier(Modifier::BOLD)),
         )
         .alignment(Alignment::Center)
@@ -192,6 +692,117 @@ fn draw_alert<B: Backend>(f: &mut Frame<B>, message: String, area: Rect) {
   }
 }
 
+/// The running tally of the chat's votes, in the top right corner
+#[cfg(feature = "chat")]
+fn draw_chat_votes<B: Backend>(f: &mut Frame<B>, chat: &ChatVote, area: Rect) {
+  let mut lines = chat
+    .tally()
+    .into_iter()
+    .take(5)
+    .map(|(cell, votes)| format!("{:<4}{:>3}", a1_notation(cell), votes))
+    .collect::<Vec<_>>();
+  if lines.is_empty() {
+    lines.push("no votes".into());
+  }
+  let width = 20.min(area.width);
+  let area = Rect::new(
+    area.right().saturating_sub(width + 1),
+    area.y + 1,
+    width,
+    (lines.len() as u16 + 2).min(area.height.saturating_sub(1)),
+  );
+  let title = format!("Votes ({}s)", chat.ticks_left.div_ceil(4));
+  f.render_widget(Clear, area);
+  f.render_widget(
+    Paragraph::new(lines.join("\n"))
+      .block(Block::default().title(title).borders(Borders::ALL))
+      .alignment(Alignment::Center),
+    area,
+  );
+}
+
+fn draw_popup<B: Backend>(
+  f: &mut Frame<B>,
+  theme: &Theme,
+  title: &str,
+  details: Vec<String>,
+  area: Rect,
+) {
+  let area = centered_rect(40, details.len() as u16 + 2, area);
+  f.render_widget(Clear, area);
+  f.render_widget(
+    Paragraph::new(details.join("\n"))
+      .block(
+        Block::default()
+          .title(title)
+          .borders(Borders::ALL)
+          .border_type(BorderType::Rounded)
+          .border_style(Style::default().fg(theme.frame)),
+      )
+      .alignment(Alignment::Center)
+      .style(Style::default().bg(theme.board.background)),
+    area,
+  );
+}
+
+fn draw_devtools<B: Backend>(
+  f: &mut Frame<B>,
+  theme: &Theme,
+  lines: Vec<String>,
+  offset: usize,
+  area: Rect,
+) {
+  let area = centered_rect(
+    area.width.saturating_sub(8),
+    area.height.saturating_sub(4),
+    area,
+  );
+  // keep the last lines in view when scrolled past them
+  let visible = usize::from(area.height.saturating_sub(2));
+  let offset = offset.min(lines.len().saturating_sub(visible));
+  f.render_widget(Clear, area);
+  f.render_widget(
+    Paragraph::new(lines.join("\n"))
+      .block(
+        Block::default()
+          .title("Turns <j/k> scroll | <esc> close")
+          .borders(Borders::ALL)
+          .border_type(BorderType::Rounded)
+          .border_style(Style::default().fg(theme.accent)),
+      )
+      .scroll((offset as u16, 0))
+      .style(Style::default().bg(theme.board.background)),
+    area,
+  );
+}
+
+fn draw_pending_count<B: Backend>(f: &mut Frame<B>, theme: &Theme, count: usize, area: Rect) {
+  let text = count.to_string();
+  let width = text.len() as u16;
+  // bottom right corner, inside the main block border
+  let area = Rect::new(
+    area.right().saturating_sub(width + 2),
+    area.bottom().saturating_sub(2),
+    width,
+    1,
+  );
+  f.render_widget(
+    Paragraph::new(text).style(Style::default().fg(theme.accent)),
+    area,
+  );
+}
+
+fn centered_rect(width: u16, height: u16, r: Rect) -> Rect {
+  let width = width.min(r.width);
+  let height = height.min(r.height);
+  Rect::new(
+    r.x + (r.width - width) / 2,
+    r.y + (r.height - height) / 2,
+    width,
+    height,
+  )
+}
+
 fn top_centered_rect(width: u16, height: u16, r: Rect) -> Rect {
   let Rect {
     width: grid_width,
@@ -199,13 +810,13 @@ fn top_centered_rect(width: u16, height: u16, r: Rect) -> Rect {
     ..
   } = r;
 
-  let outer_height = grid_height.checked_sub(height).unwrap_or_default();
+  let outer_height = grid_height.saturating_sub(height);
   let popup_layout = Layout::default()
     .direction(Direction::Vertical)
     .constraints([Constraint::Length(height), Constraint::Length(outer_height)].as_ref())
     .split(r);
 
-  let outer_width = (grid_width / 2).checked_sub(width / 2).unwrap_or_default();
+  let outer_width = (grid_width / 2).saturating_sub(width / 2);
 
   Layout::default()
     .direction(Direction::Horizontal)
@@ -0,0 +1,677 @@
+//! A board drawn as a grid of cells, usable in any `Rect` of a tui layout, the bar of
+//! power-ups drawn under it and the scrubber of a replay.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use tui::{
+  buffer::Buffer,
+  layout::{Constraint, Direction, Layout, Rect},
+  style::{Color, Modifier, Style},
+  widgets::{Block, BorderType, Borders, Paragraph, StatefulWidget, Widget},
+};
+
+use super::{
+  game::{Board, Coordinate, Status, COLS, ROWS},
+  replay::Thumbnail,
+  skin::Skin,
+  theme::Theme,
+};
+
+pub const CELL_WIDTH: u16 = 5;
+pub const CELL_HEIGHT: u16 = 3;
+const PADDING: u16 = 1;
+/// Ticks the animation of a shot landing lasts, before the cell shows what it found
+pub const IMPACT_FRAMES: u16 = 3;
+/// Size of the rect a whole board needs
+pub const GRID_WIDTH: u16 = CELL_WIDTH * (COLS as u16) + 2 * PADDING;
+pub const GRID_HEIGHT: u16 = CELL_HEIGHT * (ROWS as u16) + 2 * PADDING;
+
+/// Border colours of the cells
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardTheme {
+  pub background: Color,
+  pub live: Color,
+  pub hit: Color,
+  pub miss: Color,
+  pub cursor: Color,
+  pub selected: Color,
+  /// the cells left out once the selection is full
+  pub muted: Color,
+  /// whether cells are told apart by the style of their borders too, for players and
+  /// terminals that can't rely on colours
+  pub no_color: bool,
+}
+
+impl Default for BoardTheme {
+  fn default() -> Self {
+    Self {
+      background: Color::Black,
+      live: Color::Yellow,
+      hit: Color::Red,
+      miss: Color::White,
+      cursor: Color::Cyan,
+      selected: Color::Yellow,
+      muted: Color::DarkGray,
+      no_color: false,
+    }
+  }
+}
+
+impl BoardTheme {
+  fn status_color(&self, status: Status) -> Color {
+    match status {
+      Status::Live | Status::Echo | Status::Splash => self.live,
+      Status::Hit | Status::Kill | Status::Mine | Status::Dent => self.hit,
+      Status::Miss | Status::Space | Status::Clear => self.miss,
+    }
+  }
+}
+
+/// What a shot that just landed did to the cell, played as a short animation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Impact {
+  Hit,
+  Kill,
+}
+
+impl Impact {
+  /// The explosion growing over the frames of the animation
+  fn glyph(&self, frame: u16, emoji: bool) -> &'static str {
+    let frames: [&str; IMPACT_FRAMES as usize] = match (self, emoji) {
+      (Impact::Hit, false) => [".", "+", "*"],
+      (Impact::Hit, true) => ["·", "✦", "💥"],
+      (Impact::Kill, false) => ["*", "***", "*X*"],
+      (Impact::Kill, true) => ["✦", "💥", "🔥"],
+    };
+    frames[usize::from(frame).min(frames.len() - 1)]
+  }
+}
+
+/// Cells to highlight on the board
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BoardState {
+  pub cursor: Option<Coordinate>,
+  pub selected: BTreeSet<Coordinate>,
+  /// cells worked out to hold no ship, drawn as ruled out
+  pub ruled_out: BTreeSet<Coordinate>,
+  /// ship cells a mine just blew up, drawn as an explosion
+  pub blasts: BTreeSet<Coordinate>,
+  /// shots whose results are still held back, drawn as splashes
+  pub hidden: BTreeSet<Coordinate>,
+  /// cells a shot just landed on, with the frame their animation is at
+  pub impacts: BTreeMap<Coordinate, (Impact, u16)>,
+  /// whether the selection used up the shots of the turn, tinting the cells left out
+  pub full: bool,
+}
+
+pub struct BoardWidget<'a> {
+  board: &'a Board,
+  emoji: bool,
+  skin: Skin,
+  theme: BoardTheme,
+}
+
+impl<'a> BoardWidget<'a> {
+  pub fn new(board: &'a Board) -> Self {
+    Self {
+      board,
+      emoji: false,
+      skin: Skin::Naval,
+      theme: BoardTheme::default(),
+    }
+  }
+
+  pub fn emoji(mut self, emoji: bool) -> Self {
+    self.emoji = emoji;
+    self
+  }
+
+  pub fn skin(mut self, skin: Skin) -> Self {
+    self.skin = skin;
+    self
+  }
+
+  pub fn theme(mut self, theme: BoardTheme) -> Self {
+    self.theme = theme;
+    self
+  }
+
+  /// The status the cell is drawn with, a splash for a shot whose result is held back
+  fn status_at(&self, coordinate: Coordinate, state: &BoardState) -> Status {
+    if state.hidden.contains(&coordinate) {
+      Status::Splash
+    } else {
+      self.board.status_at(coordinate)
+    }
+  }
+
+  /// The frame of the animation of a shot landing on the cell, unless its result is still
+  /// held back
+  fn impact_at(&self, coordinate: Coordinate, state: &BoardState) -> Option<(Impact, u16)> {
+    state
+      .impacts
+      .get(&coordinate)
+      .copied()
+      .filter(|(_, frame)| *frame < IMPACT_FRAMES && !state.hidden.contains(&coordinate))
+  }
+
+  fn glyph(&self, coordinate: Coordinate, state: &BoardState) -> String {
+    let status = self.status_at(coordinate, state);
+    if let Some((impact, frame)) = self.impact_at(coordinate, state) {
+      impact.glyph(frame, self.emoji).into()
+    } else if state.blasts.contains(&coordinate) {
+      if self.emoji { "🔥" } else { "!" }.into()
+    } else if self.board.is_hazard(coordinate) {
+      // mines adrift in the player's own waters
+      if self.emoji { "🌀" } else { "%" }.into()
+    } else if status == Status::Space && state.ruled_out.contains(&coordinate) {
+      "·".into()
+    } else if status == Status::Space && self.board.is_mined(coordinate) {
+      // the player's own mines, still waiting
+      let mine = Status::Mine;
+      if self.emoji {
+        mine.to_string()
+      } else {
+        mine.as_char().to_string()
+      }
+    } else if self.board.is_armored(coordinate) {
+      // the player's own ship cells whose armor is still whole
+      if self.emoji { "🛡" } else { "@" }.into()
+    } else if self.emoji {
+      self
+        .skin
+        .emoji(status)
+        .map_or_else(|| status.to_string(), String::from)
+    } else {
+      status.as_char().to_string()
+    }
+  }
+
+  fn border_color(&self, coordinate: Coordinate, state: &BoardState) -> Color {
+    if let Some((_, frame)) = self.impact_at(coordinate, state) {
+      // the border flashes while the shot lands
+      if frame % 2 == 0 {
+        self.theme.selected
+      } else {
+        self.theme.hit
+      }
+    } else if state.selected.contains(&coordinate) {
+      self.theme.selected
+    } else if state.cursor == Some(coordinate) {
+      self.theme.cursor
+    } else if state.full && self.status_at(coordinate, state) == Status::Space {
+      self.theme.muted
+    } else {
+      self.theme.status_color(self.status_at(coordinate, state))
+    }
+  }
+
+  /// Double borders for the selection, thick ones for the cursor and square ones for
+  /// the cells hit, when colours can't be relied on
+  fn border_type(&self, coordinate: Coordinate, state: &BoardState) -> BorderType {
+    if let Some((_, frame)) = self.impact_at(coordinate, state) {
+      if frame % 2 == 0 {
+        BorderType::Thick
+      } else {
+        BorderType::Double
+      }
+    } else if !self.theme.no_color {
+      BorderType::Rounded
+    } else if state.selected.contains(&coordinate) {
+      BorderType::Double
+    } else if state.cursor == Some(coordinate) {
+      BorderType::Thick
+    } else {
+      match self.status_at(coordinate, state) {
+        Status::Hit | Status::Kill | Status::Mine | Status::Dent => BorderType::Plain,
+        _ => BorderType::Rounded,
+      }
+    }
+  }
+}
+
+impl StatefulWidget for BoardWidget<'_> {
+  type State = BoardState;
+
+  fn render(self, area: Rect, buf: &mut Buffer, state: &mut BoardState) {
+    Block::default()
+      .borders(Borders::ALL)
+      .border_type(BorderType::Plain)
+      .render(area, buf);
+
+    let row_constraints =
+      std::iter::repeat_n(Constraint::Length(CELL_HEIGHT), ROWS).collect::<Vec<_>>();
+    let col_constraints =
+      std::iter::repeat_n(Constraint::Length(CELL_WIDTH), COLS).collect::<Vec<_>>();
+
+    let row_rects = Layout::default()
+      .direction(Direction::Vertical)
+      .vertical_margin(1)
+      .horizontal_margin(0)
+      .constraints(row_constraints)
+      .split(area);
+
+    for (r, row_rect) in row_rects.into_iter().enumerate() {
+      let col_rects = Layout::default()
+        .direction(Direction::Horizontal)
+        .vertical_margin(0)
+        .horizontal_margin(1)
+        .constraints(col_constraints.clone())
+        .split(row_rect);
+
+      for (c, cell_rect) in col_rects.into_iter().enumerate() {
+        let single_row_text = format!(
+          "{:^length$}",
+          self.glyph((r, c), state),
+          length = usize::from(CELL_WIDTH - 2)
+        );
+        let pad_line = " ".repeat(usize::from(CELL_WIDTH));
+
+        // 1 line for the text, 1 line each for the top and bottom of the cell == 3 lines
+        // that are not eligible for padding
+        let num_pad_lines = usize::from(CELL_HEIGHT.saturating_sub(3));
+
+        // text is:
+        //   pad with half the pad lines budget
+        //   the interesting text
+        //   pad with half the pad lines budget
+        //   join with newlines
+        let text = std::iter::repeat_n(pad_line.clone(), num_pad_lines / 2)
+          .chain(std::iter::once(single_row_text))
+          .chain(std::iter::repeat_n(pad_line, num_pad_lines / 2))
+          .collect::<Vec<_>>()
+          .join("\n");
+
+        let block = Block::default()
+          .borders(Borders::ALL)
+          .style(
+            Style::default()
+              .bg(self.theme.background)
+              .fg(self.border_color((r, c), state)),
+          )
+          .border_type(self.border_type((r, c), state));
+        Paragraph::new(text)
+          .block(block)
+          .style(Style::default().bg(self.theme.background))
+          .render(cell_rect, buf);
+      }
+    }
+  }
+}
+
+impl Widget for BoardWidget<'_> {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    StatefulWidget::render(self, area, buf, &mut BoardState::default());
+  }
+}
+
+/// A power-up on the ability bar
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbilitySlot {
+  /// digit pressed with <alt> to aim it
+  pub hotkey: usize,
+  pub label: String,
+  /// whether it can be used this turn
+  pub ready: bool,
+  /// whether it's being aimed
+  pub aimed: bool,
+}
+
+/// The power-ups in a row with their hotkeys, centered on a single line, those ready to use
+/// lit up and the one being aimed highlighted
+pub struct AbilityBar<'a> {
+  slots: &'a [AbilitySlot],
+  theme: Theme,
+}
+
+impl<'a> AbilityBar<'a> {
+  pub fn new(slots: &'a [AbilitySlot]) -> Self {
+    Self {
+      slots,
+      theme: Theme::default(),
+    }
+  }
+
+  pub fn theme(mut self, theme: Theme) -> Self {
+    self.theme = theme;
+    self
+  }
+}
+
+impl Widget for AbilityBar<'_> {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    const SEPARATOR: &str = " | ";
+    let texts = self
+      .slots
+      .iter()
+      .map(|slot| format!("[{}] {}", slot.hotkey, slot.label))
+      .collect::<Vec<_>>();
+    let width = texts.iter().map(|t| t.chars().count()).sum::<usize>()
+      + SEPARATOR.len() * texts.len().saturating_sub(1);
+    let mut x = area.x + area.width.saturating_sub(width as u16) / 2;
+    for (index, (slot, text)) in self.slots.iter().zip(&texts).enumerate() {
+      // what doesn't fit a narrow terminal is cut off
+      if x >= area.right() {
+        break;
+      }
+      if index > 0 {
+        x = buf
+          .set_stringn(
+            x,
+            area.y,
+            SEPARATOR,
+            usize::from(area.right().saturating_sub(x)),
+            Style::default().fg(self.theme.text),
+          )
+          .0;
+        if x >= area.right() {
+          break;
+        }
+      }
+      let (color, modifier) = if slot.aimed {
+        (self.theme.board.selected, Modifier::REVERSED)
+      } else if slot.ready {
+        (self.theme.board.cursor, Modifier::BOLD)
+      } else {
+        (self.theme.muted, Modifier::empty())
+      };
+      let mut style = Style::default().fg(color);
+      if self.theme.board.no_color {
+        style = style.add_modifier(modifier);
+      }
+      x = buf
+        .set_stringn(
+          x,
+          area.y,
+          text,
+          usize::from(area.right().saturating_sub(x)),
+          style,
+        )
+        .0;
+    }
+  }
+}
+
+/// A tick for every turn of a replay, the user's fleet in the top row and the computer's
+/// below, each as dense as the cells fired upon it so far, with a marker under the turn
+/// shown. A long match scrolls with the marker.
+pub struct Scrubber<'a> {
+  thumbnails: &'a [Thumbnail],
+  step: usize,
+  emoji: bool,
+  theme: Theme,
+}
+
+impl<'a> Scrubber<'a> {
+  pub fn new(thumbnails: &'a [Thumbnail], step: usize) -> Self {
+    Self {
+      thumbnails,
+      step,
+      emoji: false,
+      theme: Theme::default(),
+    }
+  }
+
+  pub fn emoji(mut self, emoji: bool) -> Self {
+    self.emoji = emoji;
+    self
+  }
+
+  pub fn theme(mut self, theme: Theme) -> Self {
+    self.theme = theme;
+    self
+  }
+
+  /// The glyph of a fleet with the given number of cells fired upon
+  fn density(&self, fired: usize) -> char {
+    let ramp: &[char] = if self.emoji {
+      &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█']
+    } else {
+      &['.', ':', '-', '=', '+', '*', '#', '@']
+    };
+    let level = (fired * ramp.len() / (ROWS * COLS)).min(ramp.len() - 1);
+    ramp[level]
+  }
+}
+
+impl Widget for Scrubber<'_> {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    let width = usize::from(area.width);
+    if width == 0 || area.height < 3 || self.thumbnails.is_empty() {
+      return;
+    }
+    let len = self.thumbnails.len();
+    // the tick of the turn shown stays in view, in the middle once the match is long
+    let current = self.step.checked_sub(1);
+    let start = current
+      .unwrap_or(0)
+      .saturating_sub(width / 2)
+      .min(len.saturating_sub(width));
+    let shown = &self.thumbnails[start..len.min(start + width)];
+    let x = area.x + (area.width - shown.len() as u16) / 2;
+    for (offset, thumbnail) in shown.iter().enumerate() {
+      let x = x + offset as u16;
+      for fleet in 0..2 {
+        let color = if thumbnail.target != fleet {
+          self.theme.muted
+        } else if thumbnail.hit {
+          self.theme.board.hit
+        } else {
+          self.theme.board.miss
+        };
+        let mut style = Style::default().fg(color);
+        if current == Some(start + offset) {
+          style = style.add_modifier(Modifier::REVERSED);
+        }
+        buf
+          .get_mut(x, area.y + fleet as u16)
+          .set_char(self.density(thumbnail.fired(fleet)))
+          .set_style(style);
+      }
+      if current == Some(start + offset) {
+        buf
+          .get_mut(x, area.y + 2)
+          .set_char('^')
+          .set_style(Style::default().fg(self.theme.board.cursor));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // the fixture of the game tests: ships at A1 and F1, a hit at B6, a sunk ship in column I
+  const BOARD: &str = "# #  # #  
+ #   x #  
+# #   #   
+   .      
+          
+ # #      
+ #x#    X 
+ # #    X 
+        X 
+.         ";
+
+  /// Glyph and border colour of the cell at the coordinate
+  fn cell(buf: &Buffer, (r, c): Coordinate) -> (String, Color) {
+    let (x, y) = (1 + CELL_WIDTH * c as u16, 1 + CELL_HEIGHT * r as u16);
+    (buf.get(x + 2, y + 1).symbol.clone(), buf.get(x, y).fg)
+  }
+
+  #[test]
+  fn test_board_widget() {
+    let board = BOARD.parse::<Board>().unwrap();
+    let area = Rect::new(0, 0, GRID_WIDTH, GRID_HEIGHT);
+    let mut buf = Buffer::empty(area);
+    let mut state = BoardState {
+      cursor: Some((0, 1)),
+      selected: vec![(0, 2)].into_iter().collect(),
+      ruled_out: vec![(4, 4), (0, 0)].into_iter().collect(),
+      blasts: vec![(6, 2)].into_iter().collect(),
+      hidden: vec![(6, 8)].into_iter().collect(),
+      impacts: BTreeMap::new(),
+      full: false,
+    };
+    StatefulWidget::render(BoardWidget::new(&board), area, &mut buf, &mut state);
+
+    assert_eq!(cell(&buf, (0, 0)), ("#".into(), Color::Yellow));
+    assert_eq!(cell(&buf, (0, 1)), (" ".into(), Color::Cyan));
+    assert_eq!(cell(&buf, (1, 5)), ("x".into(), Color::Red));
+    assert_eq!(cell(&buf, (3, 3)), (".".into(), Color::White));
+    // a kill whose shot is still being revealed
+    assert_eq!(cell(&buf, (6, 8)), ("?".into(), Color::Yellow));
+    assert_eq!(cell(&buf, (4, 4)).0, "·");
+    assert_eq!(cell(&buf, (6, 2)), ("!".into(), Color::Red));
+
+    let theme = BoardTheme {
+      selected: Color::Magenta,
+      ..BoardTheme::default()
+    };
+    let mut buf = Buffer::empty(area);
+    let widget = BoardWidget::new(&board).emoji(true).theme(theme);
+    StatefulWidget::render(widget, area, &mut buf, &mut state);
+    assert_eq!(cell(&buf, (0, 0)).0, "🚀");
+    assert_eq!(cell(&buf, (0, 2)).1, Color::Magenta);
+
+    // without colours the borders set the cells apart
+    let mut buf = Buffer::empty(area);
+    let widget = BoardWidget::new(&board).theme(Theme::no_color().board);
+    StatefulWidget::render(widget, area, &mut buf, &mut state);
+    let corner = |(r, c): Coordinate| {
+      let (x, y) = (1 + CELL_WIDTH * c as u16, 1 + CELL_HEIGHT * r as u16);
+      buf.get(x, y).symbol.clone()
+    };
+    assert_eq!(corner((0, 0)), "╭");
+    assert_eq!(corner((0, 1)), "┏");
+    assert_eq!(corner((0, 2)), "╔");
+    assert_eq!(corner((1, 5)), "┌");
+    assert_eq!(cell(&buf, (1, 5)), ("x".into(), Color::Reset));
+
+    // a full selection tints the cells that could still be picked
+    state.full = true;
+    let mut buf = Buffer::empty(area);
+    StatefulWidget::render(BoardWidget::new(&board), area, &mut buf, &mut state);
+    assert_eq!(cell(&buf, (5, 5)), (" ".into(), Color::DarkGray));
+    assert_eq!(cell(&buf, (0, 2)).1, Color::Yellow);
+    assert_eq!(cell(&buf, (3, 3)), (".".into(), Color::White));
+  }
+
+  #[test]
+  fn test_board_widget_impacts() {
+    let board = BOARD.parse::<Board>().unwrap();
+    let area = Rect::new(0, 0, GRID_WIDTH, GRID_HEIGHT);
+    let render = |frame: u16| {
+      let mut state = BoardState {
+        impacts: vec![
+          ((1, 5), (Impact::Hit, frame)),
+          ((6, 8), (Impact::Kill, frame)),
+        ]
+        .into_iter()
+        .collect(),
+        hidden: vec![(6, 8)].into_iter().collect(),
+        ..BoardState::default()
+      };
+      let mut buf = Buffer::empty(area);
+      StatefulWidget::render(BoardWidget::new(&board), area, &mut buf, &mut state);
+      buf
+    };
+
+    let buf = render(0);
+    assert_eq!(cell(&buf, (1, 5)), (".".into(), Color::Yellow));
+    // a shot still held back doesn't give itself away
+    assert_eq!(cell(&buf, (6, 8)).0, "?");
+    let buf = render(1);
+    assert_eq!(cell(&buf, (1, 5)), ("+".into(), Color::Red));
+    // once the animation is over the cell shows what the shot found
+    let buf = render(IMPACT_FRAMES);
+    assert_eq!(cell(&buf, (1, 5)), ("x".into(), Color::Red));
+    assert_eq!(Impact::Kill.glyph(2, false), "*X*");
+  }
+
+  #[test]
+  fn test_ability_bar() {
+    let slot = |hotkey, label: &str, ready, aimed| AbilitySlot {
+      hotkey,
+      label: label.into(),
+      ready,
+      aimed,
+    };
+    let slots = vec![
+      slot(1, "sonar 1", true, false),
+      slot(2, "ping in 2", false, false),
+      slot(3, "airstrike row 1", true, true),
+    ];
+    let area = Rect::new(0, 0, 60, 1);
+    let mut buf = Buffer::empty(area);
+    AbilityBar::new(&slots).render(area, &mut buf);
+    let line = (0..area.width)
+      .map(|x| buf.get(x, 0).symbol.clone())
+      .collect::<String>();
+    assert_eq!(
+      line.trim(),
+      "[1] sonar 1 | [2] ping in 2 | [3] airstrike row 1"
+    );
+    let start = line.find('[').unwrap() as u16;
+    assert_eq!(buf.get(start, 0).fg, Color::Cyan);
+    assert_eq!(buf.get(start + 14, 0).fg, Color::DarkGray);
+    assert_eq!(buf.get(start + 30, 0).fg, Color::Yellow);
+
+    let theme = Theme {
+      muted: Color::Blue,
+      ..Theme::default()
+    };
+    AbilityBar::new(&slots).theme(theme).render(area, &mut buf);
+    assert_eq!(buf.get(start + 14, 0).fg, Color::Blue);
+
+    AbilityBar::new(&slots)
+      .theme(Theme::no_color())
+      .render(area, &mut buf);
+    assert!(buf.get(start, 0).modifier.contains(Modifier::BOLD));
+    assert!(buf.get(start + 30, 0).modifier.contains(Modifier::REVERSED));
+  }
+
+  #[test]
+  fn test_scrubber() {
+    let thumbnail = |target, hit, fired| Thumbnail {
+      hits: [0, 0],
+      misses: [fired, 60],
+      target,
+      hit,
+    };
+    let thumbnails = (0..30)
+      .map(|turn| thumbnail(turn % 2, turn % 4 == 1, turn))
+      .collect::<Vec<_>>();
+    let area = Rect::new(0, 0, 10, 3);
+    let mut buf = Buffer::empty(area);
+    let row = |buf: &Buffer, y| {
+      (0..area.width)
+        .map(|x| buf.get(x, y).symbol.clone())
+        .collect::<String>()
+    };
+
+    // before the first turn the scrubber starts at it, with no marker
+    Scrubber::new(&thumbnails, 0).render(area, &mut buf);
+    assert_eq!(row(&buf, 0), "..........");
+    assert_eq!(row(&buf, 1), "++++++++++");
+    assert_eq!(row(&buf, 2).trim(), "");
+    assert_eq!(buf.get(0, 0).fg, Color::White);
+    assert_eq!(buf.get(0, 1).fg, Color::DarkGray);
+    assert_eq!(buf.get(1, 1).fg, Color::Red);
+
+    // a long match scrolls to keep the turn shown in the middle
+    let mut buf = Buffer::empty(area);
+    Scrubber::new(&thumbnails, 21).render(area, &mut buf);
+    assert_eq!(row(&buf, 2), "     ^    ");
+    assert!(buf.get(5, 0).modifier.contains(Modifier::REVERSED));
+    assert_eq!(row(&buf, 0), "::::::::::");
+
+    let mut buf = Buffer::empty(area);
+    Scrubber::new(&thumbnails, 30)
+      .emoji(true)
+      .render(area, &mut buf);
+    assert_eq!(row(&buf, 2), "         ^");
+    assert_eq!(row(&buf, 1), "▅▅▅▅▅▅▅▅▅▅");
+  }
+}